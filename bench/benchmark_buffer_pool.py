@@ -0,0 +1,27 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import pytest
+
+from orjson import configure, dumps_str
+
+DATA = {"id": 1, "name": "example", "tags": ["a", "b"], "active": True}
+
+pool_sizes = {
+    "pool": 4,
+    "no pool": 0,
+}
+
+
+@pytest.mark.parametrize("approach", pool_sizes)
+def test_dumps_str_small(benchmark, approach):
+    """
+    dumps_str() of a small object with and without the buffer pool
+    """
+    benchmark.group = "buffer pool"
+    benchmark.extra_info["approach"] = approach
+    configure(buffer_pool_size=pool_sizes[approach])
+    try:
+        benchmark(dumps_str, DATA)
+    finally:
+        configure(buffer_pool_size=4)
@@ -0,0 +1,26 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import pytest
+
+from orjson import OPT_CACHE_TUPLES, dumps
+
+DATA = tuple((f"key{i}", i, i / 7, i % 2 == 0, None) for i in range(1000))
+
+options = {
+    "OPT_CACHE_TUPLES": OPT_CACHE_TUPLES,
+    "no cache": None,
+}
+
+
+@pytest.mark.parametrize("approach", options)
+def test_dumps_same_tuple(benchmark, approach):
+    """
+    dumps() of the same tuple of one thousand tuples repeatedly
+    """
+    benchmark.group = "tuple cache"
+    benchmark.extra_info["approach"] = approach
+    benchmark.extra_info["correct"] = dumps(DATA, option=options[approach]) == dumps(
+        DATA
+    )
+    benchmark(dumps, DATA, option=options[approach])
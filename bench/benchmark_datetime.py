@@ -0,0 +1,34 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import datetime
+
+import pytest
+
+from orjson import OPT_NAIVE_UTC, OPT_OMIT_MICROSECONDS, OPT_UTC_Z, dumps
+
+BASE = datetime.datetime(2020, 1, 1, tzinfo=datetime.timezone.utc)
+
+AWARE = [
+    BASE + datetime.timedelta(seconds=i, microseconds=i) for i in range(100_000)
+]
+
+NAIVE = [obj.replace(tzinfo=None) for obj in AWARE]
+
+cases = {
+    "aware": (AWARE, 0),
+    "aware_utc_z": (AWARE, OPT_UTC_Z),
+    "naive": (NAIVE, 0),
+    "naive_utc_omit_microseconds": (NAIVE, OPT_NAIVE_UTC | OPT_OMIT_MICROSECONDS),
+}
+
+
+@pytest.mark.parametrize("case", cases)
+def test_dumps_datetime(benchmark, case):
+    """
+    100,000 datetimes with options
+    """
+    benchmark.group = "datetime"
+    benchmark.extra_info["case"] = case
+    data, option = cases[case]
+    benchmark(dumps, data, option=option)
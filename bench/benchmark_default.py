@@ -0,0 +1,26 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+from orjson import dumps
+
+
+class Point:
+    __slots__ = ("x",)
+
+    def __init__(self, x):
+        self.x = x
+
+
+DATA = [Point(i) for i in range(100_000)]
+
+
+def default(obj):
+    return obj.x
+
+
+def test_dumps_default_same_type(benchmark):
+    """
+    dumps() of a list of 100,000 objects of one type passed to default
+    """
+    benchmark.group = "default"
+    benchmark(dumps, DATA, default=default)
@@ -0,0 +1,31 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import pytest
+
+from orjson import dumps, dumps_many
+
+DATA = [
+    {"id": i, "topic": "events", "payload": {"value": i * 1.5, "ok": True}}
+    for i in range(1000)
+]
+
+
+def dumps_loop(objs):
+    return [dumps(obj) for obj in objs]
+
+
+approaches = {
+    "dumps_many": dumps_many,
+    "loop": dumps_loop,
+}
+
+
+@pytest.mark.parametrize("approach", approaches)
+def test_dumps_many(benchmark, approach):
+    """
+    1,000 small messages with dumps_many() and a loop of dumps()
+    """
+    benchmark.group = "dumps_many"
+    benchmark.extra_info["approach"] = approach
+    benchmark(approaches[approach], DATA)
@@ -0,0 +1,31 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import pytest
+
+from orjson import dumps, loads, loads_many
+
+DATA = [
+    dumps({"id": i, "topic": "events", "payload": {"value": i * 1.5, "ok": True}})
+    for i in range(1000)
+]
+
+
+def loads_loop(docs):
+    return [loads(doc) for doc in docs]
+
+
+approaches = {
+    "loads_many": loads_many,
+    "loop": loads_loop,
+}
+
+
+@pytest.mark.parametrize("approach", approaches)
+def test_loads_many(benchmark, approach):
+    """
+    1,000 small messages with loads_many() and a loop of loads()
+    """
+    benchmark.group = "loads_many"
+    benchmark.extra_info["approach"] = approach
+    benchmark(approaches[approach], DATA)
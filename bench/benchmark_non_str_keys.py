@@ -0,0 +1,34 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import pytest
+
+from orjson import OPT_NON_STR_KEYS, dumps
+
+DATA = {i * 7 - 35000: [i, str(i)] for i in range(10000)}
+
+
+def _non_str_keys(obj):
+    return dumps(obj, option=OPT_NON_STR_KEYS)
+
+
+def _stringify(obj):
+    return dumps({str(key): val for key, val in obj.items()})
+
+
+approaches = {
+    "OPT_NON_STR_KEYS": _non_str_keys,
+    "str() keys": _stringify,
+}
+
+
+@pytest.mark.parametrize("approach", approaches)
+def test_dumps_int_keys(benchmark, approach):
+    """
+    a dict of ten thousand int keys
+    """
+    dumper = approaches[approach]
+    benchmark.group = "int keys serialization"
+    benchmark.extra_info["approach"] = approach
+    benchmark.extra_info["correct"] = dumper(DATA) == _stringify(DATA)
+    benchmark(dumper, DATA)
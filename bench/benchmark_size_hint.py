@@ -0,0 +1,30 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import pytest
+
+from orjson import dumps
+
+DATA = {
+    "items": [
+        {"id": i, "name": f"item {i}", "tags": ["a", "b", "c"], "price": i * 1.25}
+        for i in range(20_000)
+    ]
+}
+
+SIZE = len(dumps(DATA))
+
+size_hints = {
+    "no hint": None,
+    "accurate hint": SIZE,
+}
+
+
+@pytest.mark.parametrize("approach", size_hints)
+def test_dumps_size_hint(benchmark, approach):
+    """
+    dumps() of a large object with and without an accurate size_hint
+    """
+    benchmark.group = "size_hint"
+    benchmark.extra_info["approach"] = approach
+    benchmark(dumps, DATA, size_hint=size_hints[approach])
@@ -0,0 +1,27 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import threading
+
+import pytest
+
+from .data import libraries
+
+DATA = {"id": 1, "name": "a", "tags": ["b", "c"], "score": 1.5, "ok": True}
+
+
+def _first_call_in_new_thread(dumper):
+    thread = threading.Thread(target=dumper, args=(DATA,))
+    thread.start()
+    thread.join()
+
+
+@pytest.mark.parametrize("library", libraries)
+def test_thread_first_call(benchmark, library):
+    """
+    latency of a thread's first dumps(), including starting the thread
+    """
+    dumper, _ = libraries[library]
+    benchmark.group = "new thread first call"
+    benchmark.extra_info["lib"] = library
+    benchmark(_first_call_in_new_thread, dumper)
@@ -6,23 +6,89 @@ from .hyperjson import __version__
 
 __all__ = (
     "__version__",
+    "ArrayStream",
+    "build_info",
+    "configure",
+    "disable_numpy",
+    "dump",
     "dumps",
+    "dumps_many",
+    "dumps_str",
+    "dumps_view",
     "Fragment",
     "JSONDecodeError",
     "JSONEncodeError",
+    "key_cache_stats",
+    "load_file",
     "loads",
+    "loads_array_stream",
+    "loads_many",
     "OPT_APPEND_NEWLINE",
+    "OPT_BIG_NUMBER_AS_STRING",
+    "OPT_CACHE_TUPLES",
+    "OPT_DATETIME_AS_UNIX_MILLIS",
+    "OPT_DATETIME_AS_UNIX_SECONDS",
+    "OPT_DATE_AS_EPOCH_DAYS",
+    "OPT_DUPLICATE_KEYS_AS_LIST",
+    "OPT_ENSURE_ASCII",
+    "OPT_ESCAPE_FORWARD_SLASH",
+    "OPT_FORBID_DUPLICATE_KEYS",
+    "OPT_FORBID_NAIVE_DATETIME",
+    "OPT_FRACTION_AS_FLOAT",
     "OPT_INDENT_2",
+    "OPT_INTERN_VALUES",
+    "OPT_JSON5_NONFINITE",
+    "OPT_MEMORYVIEW_AS_NESTED",
     "OPT_NAIVE_UTC",
     "OPT_NON_STR_KEYS",
+    "OPT_NON_STR_KEYS_STRICT",
     "OPT_OMIT_MICROSECONDS",
+    "OPT_PARSE_RELAXED",
+    "OPT_PASSTHROUGH_BYTES",
     "OPT_PASSTHROUGH_DATACLASS",
     "OPT_PASSTHROUGH_DATETIME",
+    "OPT_PASSTHROUGH_NUMPY",
     "OPT_PASSTHROUGH_SUBCLASS",
+    "OPT_RANGE_AS_LIST",
+    "OPT_REPR_FALLBACK",
+    "OPT_RFC3339_STRICT",
+    "OPT_SELF_CHECK",
+    "OPT_SERIALIZE_BYTES_BASE64",
+    "OPT_SERIALIZE_BYTES_HEX",
+    "OPT_SERIALIZE_BYTES_LATIN1",
     "OPT_SERIALIZE_DATACLASS",
+    "OPT_SERIALIZE_FRACTION",
+    "OPT_SERIALIZE_FSPATH",
+    "OPT_SERIALIZE_IPADDRESS",
+    "OPT_SERIALIZE_ITERABLES",
     "OPT_SERIALIZE_NUMPY",
+    "OPT_SERIALIZE_PROPERTIES",
+    "OPT_SERIALIZE_RANGE",
     "OPT_SERIALIZE_UUID",
+    "OPT_SKIP_UNSERIALIZABLE",
     "OPT_SORT_KEYS",
     "OPT_STRICT_INTEGER",
+    "OPT_SURROGATES_ESCAPE",
+    "OPT_TZ_OFFSET_NO_COLON",
+    "OPT_USE_JSON_DUNDER",
     "OPT_UTC_Z",
+    "OPT_UUID_AS_BYTES",
+    "OPT_VALIDATE_FRAGMENTS",
+    "OPT_WEAKREF_DEAD_AS_NULL",
+    "Options",
+    "register_type",
+    "reset_state",
+    "set_default_options",
+    "shutdown",
+    "warm_keys",
 )
+
+
+def __getattr__(name):
+    # JSONDecodeError is created on first use, so it is not imported above
+    if name == "JSONDecodeError":
+        from .hyperjson import JSONDecodeError
+
+        globals()[name] = JSONDecodeError
+        return JSONDecodeError
+    raise AttributeError(f"module {__name__!r} has no attribute {name!r}")
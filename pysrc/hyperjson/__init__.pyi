@@ -2,16 +2,141 @@
 # Copyright ijl (2019-2025), Eric Jolibois (2022), Anders Kaseorg (2020)
 
 import json
-from typing import Any, Callable
+from os import PathLike
+from typing import Any, Callable, Literal, Sequence, TypedDict
 
 __version__: str
 
 def dumps(
     __obj: Any,
-    default: Callable[[Any], Any] | None = ...,
-    option: int | None = ...,
+    default: Callable[[Any], Any] | Sequence[Callable[[Any], Any]] | None = ...,
+    option: int | Options | None = ...,
+    *,
+    envelope: str | None = ...,
+    tuple_fields: Sequence[str] | None = ...,
+    max_output_bytes: int | None = ...,
+    key_filter: Callable[[Any], Any] | None = ...,
+    float_precision: int | None = ...,
+    on_skip: Callable[[Any, JSONEncodeError], Any] | None = ...,
+    skip_key_prefix: str | None = ...,
+    size_hint: int | None = ...,
 ) -> bytes: ...
-def loads(__obj: bytes | bytearray | memoryview | str) -> Any: ...
+def dump(
+    __obj: Any,
+    __fp: Any,
+    default: Callable[[Any], Any] | Sequence[Callable[[Any], Any]] | None = ...,
+    option: int | Options | None = ...,
+    *,
+    envelope: str | None = ...,
+    tuple_fields: Sequence[str] | None = ...,
+    max_output_bytes: int | None = ...,
+    key_filter: Callable[[Any], Any] | None = ...,
+    chunk_size: int | None = ...,
+    float_precision: int | None = ...,
+    on_skip: Callable[[Any, JSONEncodeError], Any] | None = ...,
+    skip_key_prefix: str | None = ...,
+) -> None: ...
+def dumps_many(
+    __objs: list[Any],
+    default: Callable[[Any], Any] | Sequence[Callable[[Any], Any]] | None = ...,
+    option: int | Options | None = ...,
+    *,
+    envelope: str | None = ...,
+    max_output_bytes: int | None = ...,
+    key_filter: Callable[[Any], Any] | None = ...,
+    float_precision: int | None = ...,
+    on_skip: Callable[[Any, JSONEncodeError], Any] | None = ...,
+    skip_key_prefix: str | None = ...,
+) -> list[bytes]: ...
+def dumps_str(
+    __obj: Any,
+    default: Callable[[Any], Any] | Sequence[Callable[[Any], Any]] | None = ...,
+    option: int | Options | None = ...,
+    *,
+    envelope: str | None = ...,
+    tuple_fields: Sequence[str] | None = ...,
+    max_output_bytes: int | None = ...,
+    key_filter: Callable[[Any], Any] | None = ...,
+    float_precision: int | None = ...,
+    on_skip: Callable[[Any, JSONEncodeError], Any] | None = ...,
+    skip_key_prefix: str | None = ...,
+    size_hint: int | None = ...,
+) -> str: ...
+def dumps_view(
+    __obj: Any,
+    default: Callable[[Any], Any] | Sequence[Callable[[Any], Any]] | None = ...,
+    option: int | Options | None = ...,
+    *,
+    envelope: str | None = ...,
+    tuple_fields: Sequence[str] | None = ...,
+    max_output_bytes: int | None = ...,
+    key_filter: Callable[[Any], Any] | None = ...,
+    float_precision: int | None = ...,
+    on_skip: Callable[[Any, JSONEncodeError], Any] | None = ...,
+    skip_key_prefix: str | None = ...,
+    size_hint: int | None = ...,
+) -> memoryview: ...
+def loads(
+    __obj: bytes | bytearray | memoryview | str,
+    option: int | Options | None = ...,
+    *,
+    object_hook: Callable[[dict[str, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = ...,
+) -> Any: ...
+def load_file(
+    __path: str | bytes | PathLike[str] | PathLike[bytes],
+    option: int | Options | None = ...,
+    *,
+    object_hook: Callable[[dict[str, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = ...,
+) -> Any: ...
+def loads_array_stream(
+    __obj: bytes | bytearray | memoryview | str,
+    option: int | Options | None = ...,
+    *,
+    object_hook: Callable[[dict[str, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = ...,
+) -> ArrayStream: ...
+def loads_many(
+    __objs: list[bytes | bytearray | memoryview | str],
+    option: int | Options | None = ...,
+    *,
+    object_hook: Callable[[dict[str, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = ...,
+) -> list[Any]: ...
+
+def register_type(
+    __tp: type,
+    __handler: Callable[[Any], Any] | None,
+) -> None: ...
+def reset_state() -> None: ...
+def set_default_options(__option: int | Options | None) -> None: ...
+def warm_keys(__keys: list[str]) -> None: ...
+
+class KeyCacheStats(TypedDict):
+    hits: int
+    misses: int
+    bypasses: int
+
+def key_cache_stats(*, reset: bool = ...) -> KeyCacheStats: ...
+
+class BuildInfo(TypedDict):
+    version: str
+    debug: bool
+    gil_disabled: bool
+    key_cache: bool
+    numpy_available: bool
+    self_check: bool
+    subinterpreter_support: bool
+
+def build_info() -> BuildInfo: ...
+def configure(
+    *,
+    bytes_mode: Literal["base64", "hex", "latin1", "passthrough"] | None = ...,
+    buffer_pool_size: int | None = ...,
+) -> None: ...
+def disable_numpy() -> None: ...
+def shutdown() -> None: ...
 
 class JSONDecodeError(json.JSONDecodeError): ...
 class JSONEncodeError(TypeError): ...
@@ -19,17 +144,68 @@ class JSONEncodeError(TypeError): ...
 class Fragment(tuple):
     contents: bytes | str
 
+class Options:
+    def __new__(cls, *flags: int | Options) -> Options: ...
+    def with_option(self, flag: int | Options, /) -> Options: ...
+    def without_option(self, flag: int | Options, /) -> Options: ...
+    def __index__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __or__(self, other: int | Options) -> Options: ...
+    def __ror__(self, other: int | Options) -> Options: ...
+
+class ArrayStream:
+    def __iter__(self) -> ArrayStream: ...
+    def __next__(self) -> Any: ...
+
 OPT_APPEND_NEWLINE: int
+OPT_BIG_NUMBER_AS_STRING: int
+OPT_CACHE_TUPLES: int
+OPT_DATETIME_AS_UNIX_MILLIS: int
+OPT_DATETIME_AS_UNIX_SECONDS: int
+OPT_DATE_AS_EPOCH_DAYS: int
+OPT_DUPLICATE_KEYS_AS_LIST: int
+OPT_ENSURE_ASCII: int
+OPT_ESCAPE_FORWARD_SLASH: int
+OPT_FORBID_DUPLICATE_KEYS: int
+OPT_FORBID_NAIVE_DATETIME: int
+OPT_FRACTION_AS_FLOAT: int
 OPT_INDENT_2: int
+OPT_INTERN_VALUES: int
+OPT_JSON5_NONFINITE: int
+OPT_MEMORYVIEW_AS_NESTED: int
 OPT_NAIVE_UTC: int
 OPT_NON_STR_KEYS: int
+OPT_NON_STR_KEYS_STRICT: int
 OPT_OMIT_MICROSECONDS: int
+OPT_PARSE_RELAXED: int
+OPT_PASSTHROUGH_BYTES: int
 OPT_PASSTHROUGH_DATACLASS: int
 OPT_PASSTHROUGH_DATETIME: int
+OPT_PASSTHROUGH_NUMPY: int
 OPT_PASSTHROUGH_SUBCLASS: int
+OPT_RANGE_AS_LIST: int
+OPT_REPR_FALLBACK: int
+OPT_RFC3339_STRICT: int
+OPT_SELF_CHECK: int
+OPT_SERIALIZE_BYTES_BASE64: int
+OPT_SERIALIZE_BYTES_HEX: int
+OPT_SERIALIZE_BYTES_LATIN1: int
 OPT_SERIALIZE_DATACLASS: int
+OPT_SERIALIZE_FRACTION: int
+OPT_SERIALIZE_FSPATH: int
+OPT_SERIALIZE_IPADDRESS: int
+OPT_SERIALIZE_ITERABLES: int
 OPT_SERIALIZE_NUMPY: int
+OPT_SERIALIZE_PROPERTIES: int
+OPT_SERIALIZE_RANGE: int
 OPT_SERIALIZE_UUID: int
+OPT_SKIP_UNSERIALIZABLE: int
 OPT_SORT_KEYS: int
 OPT_STRICT_INTEGER: int
+OPT_SURROGATES_ESCAPE: int
+OPT_TZ_OFFSET_NO_COLON: int
+OPT_USE_JSON_DUNDER: int
 OPT_UTC_Z: int
+OPT_UUID_AS_BYTES: int
+OPT_VALIDATE_FRAGMENTS: int
+OPT_WEAKREF_DEAD_AS_NULL: int
@@ -14,3 +14,71 @@
 //
 // If PyMem_* allocation is needed in the future, it should be done explicitly
 // with interpreter context, not through a global allocator.
+//
+// The exception is the `pymalloc` feature, for applications with a single
+// interpreter that want allocations tracked by tracemalloc and served from
+// pymalloc's arenas. `assert_single_interpreter()` makes using the module in
+// a second interpreter a panic rather than undefined behavior.
+
+#[cfg(feature = "pymalloc")]
+use core::alloc::{GlobalAlloc, Layout};
+#[cfg(feature = "pymalloc")]
+use core::ffi::c_void;
+#[cfg(feature = "pymalloc")]
+use core::sync::atomic::{AtomicI64, Ordering};
+
+#[cfg(feature = "pymalloc")]
+struct PyMemAllocator {}
+
+#[cfg(feature = "pymalloc")]
+#[global_allocator]
+static ALLOCATOR: PyMemAllocator = PyMemAllocator {};
+
+#[cfg(feature = "pymalloc")]
+unsafe impl Sync for PyMemAllocator {}
+
+#[cfg(feature = "pymalloc")]
+unsafe impl GlobalAlloc for PyMemAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        debug_assert!(layout.align() <= 16);
+        unsafe { crate::ffi::PyMem_Malloc(layout.size()).cast::<u8>() }
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        unsafe { crate::ffi::PyMem_Free(ptr.cast::<c_void>()) }
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        debug_assert!(layout.align() <= 16);
+        unsafe { crate::ffi::PyMem_Calloc(1, layout.size()).cast::<u8>() }
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, _layout: Layout, new_size: usize) -> *mut u8 {
+        unsafe { crate::ffi::PyMem_Realloc(ptr.cast::<c_void>(), new_size).cast::<u8>() }
+    }
+}
+
+/// The ID of the interpreter the module was first initialized in, or -1.
+#[cfg(feature = "pymalloc")]
+static INTERPRETER_ID: AtomicI64 = AtomicI64::new(-1);
+
+/// Panic if the module state is created for an interpreter other than the
+/// first, as with the `pymalloc` feature memory allocated in one
+/// interpreter's heap would be used and freed by another.
+#[cfg(feature = "pymalloc")]
+#[cold]
+pub(crate) fn assert_single_interpreter(interpreter_id: i64) {
+    if let Err(first) =
+        INTERPRETER_ID.compare_exchange(-1, interpreter_id, Ordering::Relaxed, Ordering::Relaxed)
+    {
+        if first != interpreter_id {
+            panic!(
+                "hyperjson was built with the pymalloc feature, which supports only a single interpreter, but was initialized in interpreter {interpreter_id} after interpreter {first}"
+            );
+        }
+    }
+}
@@ -5,15 +5,19 @@ use super::ffi::{
     YYJSON_READ_SUCCESS, yyjson_alc, yyjson_alc_pool_init, yyjson_doc, yyjson_read_err,
     yyjson_read_opts, yyjson_val,
 };
-use crate::deserialize::DeserializeError;
 use crate::deserialize::pyobject::{
-    get_unicode_key, parse_f64, parse_false, parse_i64, parse_none, parse_true, parse_u64,
+    get_unicode_key, get_unicode_value, parse_f64, parse_false, parse_i64, parse_none, parse_true,
+    parse_u64,
 };
+use crate::deserialize::relaxed::NonFinite;
+use crate::deserialize::{DeserializeError, ObjectHook};
+use crate::opt::{DUPLICATE_KEYS_AS_LIST, FORBID_DUPLICATE_KEYS, INTERN_VALUES, Opt};
 use crate::str::PyStr;
 use crate::util::usize_to_isize;
 use core::ffi::c_char;
 use core::ptr::{NonNull, null, null_mut};
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 const YYJSON_TAG_BIT: u8 = 8;
 
@@ -68,8 +72,13 @@ fn unsafe_yyjson_get_next_non_container(val: *mut yyjson_val) -> *mut yyjson_val
     unsafe { (val.cast::<u8>().add(YYJSON_VAL_SIZE)).cast::<yyjson_val>() }
 }
 
+/// Deserialize `data`, reading the string at the offset of each of
+/// `nonfinite` as its float.
 pub(crate) fn deserialize(
     data: &'static str,
+    opts: Opt,
+    hook: Option<ObjectHook>,
+    nonfinite: &[NonFinite],
 ) -> Result<NonNull<crate::ffi::PyObject>, DeserializeError<'static>> {
     assume!(!data.is_empty());
     let buffer_capacity = buffer_capacity_to_allocate(data.len());
@@ -111,11 +120,47 @@ pub(crate) fn deserialize(
         return Err(DeserializeError::from_yyjson(msg, err.pos as i64, data));
     }
     let val = yyjson_doc_get_root(doc);
+    if !nonfinite.is_empty() {
+        cold_path!();
+        if let Err(pos) = replace_nonfinite(val, unsafe { (*doc).str_pool }, nonfinite) {
+            ffi!(PyMem_Free(buffer_ptr));
+            return Err(DeserializeError::from_yyjson(
+                Cow::Borrowed("unexpected character, expected a string for the key"),
+                pos as i64,
+                data,
+            ));
+        }
+    }
+    if opt_enabled!(opts, FORBID_DUPLICATE_KEYS) && unsafe_yyjson_is_ctn(val) {
+        cold_path!();
+        if let Some(key) = find_duplicate_key(val) {
+            let key_str = str_from_slice!((*key).uni.str_.cast::<u8>(), unsafe_yyjson_get_len(key));
+            let msg = Cow::Owned(format!("Duplicate object key: {key_str:?}"));
+            // strings are unescaped in place in the copy of the input, so the
+            // offset is that of the key's content; step back to its quote.
+            let pos = unsafe { (*key).uni.str_.offset_from((*doc).str_pool) } - 1;
+            ffi!(PyMem_Free(buffer_ptr));
+            return Err(DeserializeError::from_yyjson(msg, pos as i64, data));
+        }
+    }
     let pyval = {
-        if !unsafe_yyjson_is_ctn(val) {
+        if hook.is_some() || opt_enabled!(opts, DUPLICATE_KEYS_AS_LIST) && unsafe_yyjson_is_ctn(val)
+        {
+            cold_path!();
+            match parse_yy_slow(val, opts, hook) {
+                Some(pyval) => pyval,
+                None => {
+                    ffi!(PyMem_Free(buffer_ptr));
+                    return Err(DeserializeError::raised());
+                }
+            }
+        } else if !unsafe_yyjson_is_ctn(val) {
             cold_path!();
             match ElementType::from_tag(val) {
-                ElementType::String => parse_yy_string(val),
+                ElementType::String if opt_enabled!(opts, INTERN_VALUES) => {
+                    parse_yy_string::<true>(val)
+                }
+                ElementType::String => parse_yy_string::<false>(val),
                 ElementType::Uint64 => parse_yy_u64(val),
                 ElementType::Int64 => parse_yy_i64(val),
                 ElementType::Double => parse_yy_f64(val),
@@ -127,7 +172,11 @@ pub(crate) fn deserialize(
         } else if is_yyjson_tag!(val, TAG_ARRAY) {
             let pyval = nonnull!(ffi!(PyList_New(usize_to_isize(unsafe_yyjson_get_len(val)))));
             if unsafe_yyjson_get_len(val) > 0 {
-                populate_yy_array(pyval.as_ptr(), val);
+                if opt_enabled!(opts, INTERN_VALUES) {
+                    populate_yy_array::<true>(pyval.as_ptr(), val);
+                } else {
+                    populate_yy_array::<false>(pyval.as_ptr(), val);
+                }
             }
             pyval
         } else {
@@ -135,7 +184,11 @@ pub(crate) fn deserialize(
                 unsafe_yyjson_get_len(val)
             ))));
             if unsafe_yyjson_get_len(val) > 0 {
-                populate_yy_object(pyval.as_ptr(), val);
+                if opt_enabled!(opts, INTERN_VALUES) {
+                    populate_yy_object::<true>(pyval.as_ptr(), val);
+                } else {
+                    populate_yy_object::<false>(pyval.as_ptr(), val);
+                }
             }
             pyval
         }
@@ -174,12 +227,13 @@ impl ElementType {
 }
 
 #[inline(always)]
-fn parse_yy_string(elem: *mut yyjson_val) -> NonNull<crate::ffi::PyObject> {
-    PyStr::from_str(str_from_slice!(
-        (*elem).uni.str_.cast::<u8>(),
-        unsafe_yyjson_get_len(elem)
-    ))
-    .as_non_null_ptr()
+fn parse_yy_string<const INTERN: bool>(elem: *mut yyjson_val) -> NonNull<crate::ffi::PyObject> {
+    let val_str = str_from_slice!((*elem).uni.str_.cast::<u8>(), unsafe_yyjson_get_len(elem));
+    if INTERN {
+        get_unicode_value(val_str).as_non_null_ptr()
+    } else {
+        PyStr::from_str(val_str).as_non_null_ptr()
+    }
 }
 
 #[inline(always)]
@@ -207,7 +261,7 @@ macro_rules! append_to_list {
 }
 
 #[inline(never)]
-fn populate_yy_array(list: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
+fn populate_yy_array<const INTERN: bool>(list: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
     unsafe {
         let len = unsafe_yyjson_get_len(elem);
         assume!(len >= 1);
@@ -223,7 +277,7 @@ fn populate_yy_array(list: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
                     let pyval = ffi!(PyList_New(usize_to_isize(unsafe_yyjson_get_len(val))));
                     append_to_list!(dptr, pyval);
                     if unsafe_yyjson_get_len(val) > 0 {
-                        populate_yy_array(pyval, val);
+                        populate_yy_array::<INTERN>(pyval, val);
                     }
                 } else {
                     let pyval = ffi!(_PyDict_NewPresized(usize_to_isize(unsafe_yyjson_get_len(
@@ -231,13 +285,13 @@ fn populate_yy_array(list: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
                     ))));
                     append_to_list!(dptr, pyval);
                     if unsafe_yyjson_get_len(val) > 0 {
-                        populate_yy_object(pyval, val);
+                        populate_yy_object::<INTERN>(pyval, val);
                     }
                 }
             } else {
                 next = unsafe_yyjson_get_next_non_container(val);
                 let pyval = match ElementType::from_tag(val) {
-                    ElementType::String => parse_yy_string(val),
+                    ElementType::String => parse_yy_string::<INTERN>(val),
                     ElementType::Uint64 => parse_yy_u64(val),
                     ElementType::Int64 => parse_yy_i64(val),
                     ElementType::Double => parse_yy_f64(val),
@@ -253,7 +307,7 @@ fn populate_yy_array(list: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
 }
 
 #[inline(never)]
-fn populate_yy_object(dict: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
+fn populate_yy_object<const INTERN: bool>(dict: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
     unsafe {
         let len = unsafe_yyjson_get_len(elem);
         assume!(len >= 1);
@@ -276,7 +330,7 @@ fn populate_yy_object(dict: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
                     let pyval = ffi!(PyList_New(usize_to_isize(unsafe_yyjson_get_len(val))));
                     pydict_setitem!(dict, pykey.as_ptr(), pyval);
                     if unsafe_yyjson_get_len(val) > 0 {
-                        populate_yy_array(pyval, val);
+                        populate_yy_array::<INTERN>(pyval, val);
                     }
                 } else {
                     let pyval = ffi!(_PyDict_NewPresized(usize_to_isize(unsafe_yyjson_get_len(
@@ -284,14 +338,14 @@ fn populate_yy_object(dict: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
                     ))));
                     pydict_setitem!(dict, pykey.as_ptr(), pyval);
                     if unsafe_yyjson_get_len(val) > 0 {
-                        populate_yy_object(pyval, val);
+                        populate_yy_object::<INTERN>(pyval, val);
                     }
                 }
             } else {
                 next_key = unsafe_yyjson_get_next_non_container(val);
                 next_val = next_key.add(1);
                 let pyval = match ElementType::from_tag(val) {
-                    ElementType::String => parse_yy_string(val),
+                    ElementType::String => parse_yy_string::<INTERN>(val),
                     ElementType::Uint64 => parse_yy_u64(val),
                     ElementType::Int64 => parse_yy_i64(val),
                     ElementType::Double => parse_yy_f64(val),
@@ -305,3 +359,270 @@ fn populate_yy_object(dict: *mut crate::ffi::PyObject, elem: *mut yyjson_val) {
         }
     }
 }
+
+/// The offset in the input of the opening quote of the string `elem`.
+/// Strings are unescaped in place in the copy of the input.
+fn string_offset(elem: *mut yyjson_val, str_pool: *mut c_char) -> usize {
+    unsafe { (*elem).uni.str_.offset_from(str_pool) as usize - 1 }
+}
+
+/// Replace each string value of `elem` that `strip_relaxed()` put in place
+/// of a token of `nonfinite` by its float, so that it is deserialized as any
+/// other. Returns the offset of such a token used as a key.
+#[cold]
+#[inline(never)]
+fn replace_nonfinite(
+    elem: *mut yyjson_val,
+    str_pool: *mut c_char,
+    nonfinite: &[NonFinite],
+) -> Result<(), usize> {
+    unsafe {
+        if !unsafe_yyjson_is_ctn(elem) {
+            if is_yyjson_tag!(elem, TAG_STRING) {
+                let offset = string_offset(elem, str_pool);
+                if let Ok(idx) = nonfinite.binary_search_by_key(&offset, |each| each.0) {
+                    (*elem).tag = u64::from(TAG_DOUBLE);
+                    (*elem).uni.f64_ = nonfinite[idx].1;
+                }
+            }
+            return Ok(());
+        }
+        let len = unsafe_yyjson_get_len(elem);
+        let is_object = is_yyjson_tag!(elem, TAG_OBJECT);
+        let mut next = unsafe_yyjson_get_first(elem);
+        for _ in 0..len {
+            if is_object {
+                let offset = string_offset(next, str_pool);
+                if nonfinite
+                    .binary_search_by_key(&offset, |each| each.0)
+                    .is_ok()
+                {
+                    return Err(offset);
+                }
+                next = next.add(1);
+            }
+            let val = next;
+            if unsafe_yyjson_is_ctn(val) {
+                next = unsafe_yyjson_get_next_container(val);
+            } else {
+                next = unsafe_yyjson_get_next_non_container(val);
+            }
+            replace_nonfinite(val, str_pool, nonfinite)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find the first key repeated within an object, for OPT_FORBID_DUPLICATE_KEYS.
+///
+/// This is a separate pass over the document so that building Python objects
+/// is unaffected if the option is not specified.
+#[cold]
+#[inline(never)]
+fn find_duplicate_key(elem: *mut yyjson_val) -> Option<*mut yyjson_val> {
+    unsafe {
+        let len = unsafe_yyjson_get_len(elem);
+        if is_yyjson_tag!(elem, TAG_ARRAY) {
+            let mut next = unsafe_yyjson_get_first(elem);
+            for _ in 0..len {
+                let val = next;
+                if unsafe_yyjson_is_ctn(val) {
+                    next = unsafe_yyjson_get_next_container(val);
+                    if let Some(key) = find_duplicate_key(val) {
+                        return Some(key);
+                    }
+                } else {
+                    next = unsafe_yyjson_get_next_non_container(val);
+                }
+            }
+        } else {
+            let mut keys: HashSet<&str> = HashSet::with_capacity(len);
+            let mut next_key = unsafe_yyjson_get_first(elem);
+            for _ in 0..len {
+                let key = next_key;
+                let val = key.add(1);
+                let key_str =
+                    str_from_slice!((*key).uni.str_.cast::<u8>(), unsafe_yyjson_get_len(key));
+                if !keys.insert(key_str) {
+                    return Some(key);
+                }
+                if unsafe_yyjson_is_ctn(val) {
+                    next_key = unsafe_yyjson_get_next_container(val);
+                    if let Some(key) = find_duplicate_key(val) {
+                        return Some(key);
+                    }
+                } else {
+                    next_key = unsafe_yyjson_get_next_non_container(val);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Call `object_hook` or `object_pairs_hook` with `arg`, returning a new
+/// reference or null if it raised.
+#[cold]
+#[inline(never)]
+fn call_hook(
+    hook: NonNull<crate::ffi::PyObject>,
+    arg: *mut crate::ffi::PyObject,
+) -> *mut crate::ffi::PyObject {
+    #[cfg(not(Py_3_10))]
+    let ret = ffi!(PyObject_CallFunctionObjArgs(
+        hook.as_ptr(),
+        arg,
+        core::ptr::null_mut::<crate::ffi::PyObject>()
+    ));
+    #[cfg(Py_3_10)]
+    #[allow(clippy::cast_sign_loss)]
+    let nargs = ffi!(PyVectorcall_NARGS(1)) as usize;
+    #[cfg(Py_3_10)]
+    let ret = unsafe {
+        crate::ffi::PyObject_Vectorcall(hook.as_ptr(), &raw const arg, nargs, null_mut())
+    };
+    ret
+}
+
+/// Deserialize an element for OPT_DUPLICATE_KEYS_AS_LIST, `object_hook`, or
+/// `object_pairs_hook`, returning `None` if a hook raised.
+///
+/// For OPT_DUPLICATE_KEYS_AS_LIST, the first occurrence of a key is set as
+/// usual. On the second, the value is replaced by a list of both values and
+/// later occurrences are appended to it. A value that is itself a list is
+/// therefore nested, not extended. `object_hook` is called with the result.
+/// `object_pairs_hook` is called with every pair, so the option does not
+/// apply to it.
+#[cold]
+#[inline(never)]
+fn parse_yy_slow(
+    elem: *mut yyjson_val,
+    opts: Opt,
+    hook: Option<ObjectHook>,
+) -> Option<NonNull<crate::ffi::PyObject>> {
+    unsafe {
+        let len = unsafe_yyjson_get_len(elem);
+        match ElementType::from_tag(elem) {
+            ElementType::String if opt_enabled!(opts, INTERN_VALUES) => {
+                Some(parse_yy_string::<true>(elem))
+            }
+            ElementType::String => Some(parse_yy_string::<false>(elem)),
+            ElementType::Uint64 => Some(parse_yy_u64(elem)),
+            ElementType::Int64 => Some(parse_yy_i64(elem)),
+            ElementType::Double => Some(parse_yy_f64(elem)),
+            ElementType::Null => Some(parse_none()),
+            ElementType::True => Some(parse_true()),
+            ElementType::False => Some(parse_false()),
+            ElementType::Array => {
+                let list = ffi!(PyList_New(usize_to_isize(len)));
+                let mut dptr = (*list.cast::<crate::ffi::PyListObject>()).ob_item;
+                let mut next = unsafe_yyjson_get_first(elem);
+                for _ in 0..len {
+                    let val = next;
+                    if unsafe_yyjson_is_ctn(val) {
+                        next = unsafe_yyjson_get_next_container(val);
+                    } else {
+                        next = unsafe_yyjson_get_next_non_container(val);
+                    }
+                    match parse_yy_slow(val, opts, hook) {
+                        Some(pyval) => append_to_list!(dptr, pyval.as_ptr()),
+                        None => {
+                            // unset items are null, which dealloc skips
+                            ffi!(Py_DECREF(list));
+                            return None;
+                        }
+                    }
+                }
+                Some(nonnull!(list))
+            }
+            ElementType::Object if matches!(hook, Some(ObjectHook::Pairs(_))) => {
+                let pairs = ffi!(PyList_New(usize_to_isize(len)));
+                let mut dptr = (*pairs.cast::<crate::ffi::PyListObject>()).ob_item;
+                let mut next_key = unsafe_yyjson_get_first(elem);
+                for _ in 0..len {
+                    let key = next_key;
+                    let val = key.add(1);
+                    if unsafe_yyjson_is_ctn(val) {
+                        next_key = unsafe_yyjson_get_next_container(val);
+                    } else {
+                        next_key = unsafe_yyjson_get_next_non_container(val);
+                    }
+                    let Some(pyval) = parse_yy_slow(val, opts, hook) else {
+                        ffi!(Py_DECREF(pairs));
+                        return None;
+                    };
+                    let key_str =
+                        str_from_slice!((*key).uni.str_.cast::<u8>(), unsafe_yyjson_get_len(key));
+                    let pair = ffi!(PyTuple_New(2));
+                    ffi!(PyTuple_SET_ITEM(pair, 0, get_unicode_key(key_str).as_ptr()));
+                    ffi!(PyTuple_SET_ITEM(pair, 1, pyval.as_ptr()));
+                    append_to_list!(dptr, pair);
+                }
+                let Some(ObjectHook::Pairs(callable)) = hook else {
+                    unreachable_unchecked!()
+                };
+                let ret = call_hook(callable, pairs);
+                ffi!(Py_DECREF(pairs));
+                NonNull::new(ret)
+            }
+            ElementType::Object => {
+                let collect_duplicates = opt_enabled!(opts, DUPLICATE_KEYS_AS_LIST);
+                let dict = ffi!(_PyDict_NewPresized(usize_to_isize(len)));
+                // value currently set for each key and whether it is a list
+                // created to collect duplicates
+                let mut seen: HashMap<&str, (*mut crate::ffi::PyObject, bool)> =
+                    HashMap::with_capacity(if collect_duplicates { len } else { 0 });
+                let mut next_key = unsafe_yyjson_get_first(elem);
+                for _ in 0..len {
+                    let key = next_key;
+                    let val = key.add(1);
+                    if unsafe_yyjson_is_ctn(val) {
+                        next_key = unsafe_yyjson_get_next_container(val);
+                    } else {
+                        next_key = unsafe_yyjson_get_next_non_container(val);
+                    }
+                    let Some(pyval) = parse_yy_slow(val, opts, hook) else {
+                        ffi!(Py_DECREF(dict));
+                        return None;
+                    };
+                    let pyval = pyval.as_ptr();
+                    let key_str =
+                        str_from_slice!((*key).uni.str_.cast::<u8>(), unsafe_yyjson_get_len(key));
+                    let pykey = get_unicode_key(key_str);
+                    if !collect_duplicates {
+                        pydict_setitem!(dict, pykey.as_ptr(), pyval);
+                        continue;
+                    }
+                    match seen.get_mut(key_str) {
+                        None => {
+                            seen.insert(key_str, (pyval, false));
+                            pydict_setitem!(dict, pykey.as_ptr(), pyval);
+                        }
+                        Some((existing, collected)) if !*collected => {
+                            let list = ffi!(PyList_New(2));
+                            ffi!(Py_INCREF(*existing));
+                            ffi!(PyList_SET_ITEM(list, 0, *existing));
+                            ffi!(PyList_SET_ITEM(list, 1, pyval));
+                            *existing = list;
+                            *collected = true;
+                            pydict_setitem!(dict, pykey.as_ptr(), list);
+                        }
+                        Some((existing, _)) => {
+                            ffi!(PyList_Append(*existing, pyval));
+                            ffi!(Py_DECREF(pyval));
+                            ffi!(Py_DECREF(pykey.as_ptr()));
+                        }
+                    }
+                }
+                match hook {
+                    Some(ObjectHook::Object(callable)) => {
+                        let ret = call_hook(callable, dict);
+                        ffi!(Py_DECREF(dict));
+                        NonNull::new(ret)
+                    }
+                    _ => Some(nonnull!(dict)),
+                }
+            }
+        }
+    }
+}
@@ -3,7 +3,6 @@
 
 use crate::str::PyStr;
 use associative_cache::{AssociativeCache, Capacity2048, HashDirectMapped, RoundRobinReplacement};
-use core::cell::OnceCell;
 
 #[repr(transparent)]
 pub(crate) struct CachedKey {
@@ -31,7 +30,18 @@ impl Drop for CachedKey {
     }
 }
 
+/// Strings longer than this are not cached.
+pub(crate) const CACHED_STR_MAX_LEN: usize = 64;
+
+/// How keys were looked up in the `KeyMap` of an interpreter, for
+/// `key_cache_stats()`: found in it, inserted into it, or too long to be
+/// cached.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct KeyCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub bypasses: u64,
+}
+
 pub(crate) type KeyMap =
     AssociativeCache<u64, CachedKey, Capacity2048, HashDirectMapped, RoundRobinReplacement>;
-
-pub(crate) static mut KEY_MAP: OnceCell<KeyMap> = OnceCell::new();
@@ -2,18 +2,100 @@
 // Copyright ijl (2018-2025), Aarni Koskela (2021), Eric Jolibois (2021)
 
 use crate::deserialize::DeserializeError;
-use crate::deserialize::utf8::read_input_to_buf;
+use crate::deserialize::relaxed::strip_relaxed;
+use crate::deserialize::utf8::{read_input_to_buf, validate_buf};
+use crate::opt::{Opt, PARSE_RELAXED};
+use crate::util::{isize_to_usize, usize_to_isize};
 // EMPTY_UNICODE now accessed via typeref::get_empty_unicode()
 use core::ptr::NonNull;
+use std::borrow::Cow;
+
+/// The `object_hook` or `object_pairs_hook` given to `loads()`, called with
+/// each object as a `dict` or as a `list` of `(key, value)` tuples.
+#[derive(Clone, Copy)]
+pub(crate) enum ObjectHook {
+    Object(NonNull<crate::ffi::PyObject>),
+    Pairs(NonNull<crate::ffi::PyObject>),
+}
 
 pub(crate) fn deserialize(
     ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
+    hook: Option<ObjectHook>,
 ) -> Result<NonNull<crate::ffi::PyObject>, DeserializeError<'static>> {
     debug_assert!(ffi!(Py_REFCNT(ptr)) >= 1);
     let buffer = read_input_to_buf(ptr)?;
     debug_assert!(!buffer.is_empty());
+    deserialize_valid(buffer, opts, hook)
+}
+
+/// Deserialize each element of `ptr`, a `list`, as `deserialize()` would,
+/// for `loads_many()`, returning a `list` of the objects. The first element
+/// that fails is reported with its index at the end of the message.
+#[cold]
+#[inline(never)]
+pub(crate) fn deserialize_many(
+    ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
+    hook: Option<ObjectHook>,
+) -> Result<NonNull<crate::ffi::PyObject>, DeserializeError<'static>> {
+    let mut items: Vec<*mut crate::ffi::PyObject> =
+        Vec::with_capacity(isize_to_usize(ffi!(Py_SIZE(ptr))));
+    // the length is read on each iteration, as a hook may modify the list
+    let mut idx = 0;
+    while idx < isize_to_usize(ffi!(Py_SIZE(ptr))) {
+        let item = ffi!(PyList_GET_ITEM(ptr, usize_to_isize(idx)));
+        ffi!(Py_INCREF(item));
+        let res = deserialize(item, opts, hook).map_err(|mut err| {
+            cold_path!();
+            if !err.raised {
+                err.message = Cow::Owned(format!("{} (at index {idx})", err.message));
+                // the document may not outlive the reference to it
+                err.data = err.data.map(|data| Cow::Owned(data.into_owned()));
+            }
+            err
+        });
+        ffi!(Py_DECREF(item));
+        match res {
+            Ok(val) => items.push(val.as_ptr()),
+            Err(err) => {
+                for each in items {
+                    ffi!(Py_DECREF(each));
+                }
+                return Err(err);
+            }
+        }
+        idx += 1;
+    }
+    let list = ffi!(PyList_New(usize_to_isize(items.len())));
+    for (idx, each) in items.into_iter().enumerate() {
+        ffi!(PyList_SET_ITEM(list, usize_to_isize(idx), each));
+    }
+    Ok(nonnull!(list))
+}
 
-    if buffer.len() == 2 {
+/// Deserialize a buffer not owned by a Python object, e.g., the contents of
+/// a file read by `load_file()`.
+pub(crate) fn deserialize_buf(
+    buffer: &[u8],
+    opts: Opt,
+    hook: Option<ObjectHook>,
+) -> Result<NonNull<crate::ffi::PyObject>, DeserializeError<'_>> {
+    validate_buf(buffer)?;
+    // the error may borrow the buffer, so it does not outlive it
+    let buffer: &'static [u8] =
+        unsafe { core::slice::from_raw_parts(buffer.as_ptr(), buffer.len()) };
+    deserialize_valid(buffer, opts, hook)
+}
+
+/// Deserialize a buffer that is non-empty and valid UTF-8.
+#[inline(always)]
+pub(crate) fn deserialize_valid(
+    buffer: &'static [u8],
+    opts: Opt,
+    hook: Option<ObjectHook>,
+) -> Result<NonNull<crate::ffi::PyObject>, DeserializeError<'static>> {
+    if buffer.len() == 2 && hook.is_none() {
         cold_path!();
         if buffer == b"[]" {
             return Ok(nonnull!(ffi!(PyList_New(0))));
@@ -26,5 +108,41 @@ pub(crate) fn deserialize(
 
     let buffer_str = unsafe { core::str::from_utf8_unchecked(buffer) };
 
-    crate::deserialize::backend::deserialize(buffer_str)
+    if opt_enabled!(opts, PARSE_RELAXED) {
+        cold_path!();
+        return deserialize_relaxed(buffer_str, opts, hook);
+    }
+
+    crate::deserialize::backend::deserialize(buffer_str, opts, hook, &[])
+}
+
+/// Deserialize `data` with comments and trailing commas removed. An error
+/// is reported against `data`, as offsets are unchanged.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn deserialize_relaxed(
+    data: &'static str,
+    opts: Opt,
+    hook: Option<ObjectHook>,
+) -> Result<NonNull<crate::ffi::PyObject>, DeserializeError<'static>> {
+    let (buffer, nonfinite) = strip_relaxed(data)?;
+    // the error may borrow the buffer, so it is replaced before it is dropped
+    let buffer_str: &'static str = unsafe {
+        core::str::from_utf8_unchecked(core::slice::from_raw_parts(buffer.as_ptr(), buffer.len()))
+    };
+    crate::deserialize::backend::deserialize(buffer_str, opts, hook, &nonfinite).map_err(
+        |mut err| {
+            if err.data.is_some() {
+                let mut pos = err.pos as usize;
+                // a multibyte character in a comment is spaces in the buffer
+                while !data.is_char_boundary(pos) {
+                    pos -= 1;
+                }
+                err.pos = pos as i64;
+                err.data = Some(Cow::Borrowed(data));
+            }
+            err
+        },
+    )
 }
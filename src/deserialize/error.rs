@@ -5,8 +5,11 @@ use std::borrow::Cow;
 
 pub(crate) struct DeserializeError<'a> {
     pub message: Cow<'a, str>,
-    pub data: Option<&'a str>,
+    pub data: Option<Cow<'a, str>>,
     pub pos: i64,
+    /// An exception, e.g., from `object_hook`, is already set and is raised
+    /// instead of `JSONDecodeError`.
+    pub raised: bool,
 }
 
 impl<'a> DeserializeError<'a> {
@@ -16,6 +19,17 @@ impl<'a> DeserializeError<'a> {
             message: message,
             data: None,
             pos: 0,
+            raised: false,
+        }
+    }
+
+    #[cold]
+    pub fn raised() -> Self {
+        DeserializeError {
+            message: Cow::Borrowed(""),
+            data: None,
+            pos: 0,
+            raised: true,
         }
     }
 
@@ -23,8 +37,21 @@ impl<'a> DeserializeError<'a> {
     pub fn from_yyjson(message: Cow<'a, str>, pos: i64, data: &'a str) -> Self {
         DeserializeError {
             message: message,
-            data: Some(data),
+            data: Some(Cow::Borrowed(data)),
             pos: pos,
+            raised: false,
+        }
+    }
+
+    /// The input is valid UTF-8 up to byte `pos`. `data` is the input with
+    /// invalid sequences replaced, so that it is identical up to `pos`.
+    #[cold]
+    pub fn from_utf8(message: Cow<'a, str>, pos: usize, data: String) -> Self {
+        DeserializeError {
+            message: message,
+            data: Some(Cow::Owned(data)),
+            pos: pos as i64,
+            raised: false,
         }
     }
 
@@ -33,7 +60,7 @@ impl<'a> DeserializeError<'a> {
     #[cfg_attr(feature = "optimize", optimize(size))]
     pub fn pos(&self) -> i64 {
         match self.data {
-            Some(as_str) => as_str[0..self.pos as usize].chars().count() as i64,
+            Some(ref as_str) => as_str[0..self.pos as usize].chars().count() as i64,
             None => 0,
         }
     }
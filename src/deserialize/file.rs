@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::deserialize::DeserializeError;
+use crate::ffi::{PyBytes_AS_STRING, PyBytes_GET_SIZE, PyObject};
+use crate::util::{isize_to_usize, usize_to_isize};
+use core::ffi::{c_char, c_void};
+use core::ptr::null_mut;
+use std::path::PathBuf;
+
+/// Read the file at `path`, a `str`, `bytes`, or `os.PathLike`, for
+/// `load_file()`.
+///
+/// The GIL is released while reading unless the `pymalloc` feature is
+/// enabled, as the buffer is then allocated using `PyMem_Malloc()`. A
+/// `TypeError` or `OSError`, e.g., `FileNotFoundError`, is raised as by
+/// `open()`.
+pub(crate) fn read_file(path: *mut PyObject) -> Result<Vec<u8>, DeserializeError<'static>> {
+    let mut encoded: *mut PyObject = null_mut();
+    if ffi!(PyUnicode_FSConverter(
+        path,
+        (&raw mut encoded).cast::<c_void>()
+    )) == 0
+    {
+        cold_path!();
+        return Err(DeserializeError::raised());
+    }
+    let fspath = fspath_from_bytes(unsafe {
+        core::slice::from_raw_parts(
+            PyBytes_AS_STRING(encoded).cast::<u8>(),
+            isize_to_usize(PyBytes_GET_SIZE(encoded)),
+        )
+    });
+
+    #[cfg(not(feature = "pymalloc"))]
+    let ret = {
+        let tstate = ffi!(PyEval_SaveThread());
+        let ret = std::fs::read(&fspath);
+        ffi!(PyEval_RestoreThread(tstate));
+        ret
+    };
+    #[cfg(feature = "pymalloc")]
+    let ret = std::fs::read(&fspath);
+
+    let ret = ret.map_err(|err| raise_os_error(&err, encoded));
+    ffi!(Py_DECREF(encoded));
+    ret
+}
+
+#[cfg(unix)]
+fn fspath_from_bytes(encoded: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(encoded))
+}
+
+#[cfg(not(unix))]
+fn fspath_from_bytes(encoded: &[u8]) -> PathBuf {
+    // the filesystem encoding is UTF-8 on Windows
+    PathBuf::from(String::from_utf8_lossy(encoded).into_owned())
+}
+
+/// Raise `OSError(errno, strerror, filename)`, which is the subclass for
+/// `errno`, e.g., `PermissionError` for `EACCES`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn raise_os_error(err: &std::io::Error, filename: *mut PyObject) -> DeserializeError<'static> {
+    let msg = err.to_string();
+    let strerror = match err.raw_os_error() {
+        Some(errno) => msg.trim_end_matches(&format!(" (os error {errno})")),
+        None => msg.as_str(),
+    };
+    let strerror_obj = ffi!(PyUnicode_FromStringAndSize(
+        strerror.as_ptr().cast::<c_char>(),
+        usize_to_isize(strerror.len())
+    ));
+    let errno_obj = ffi!(PyLong_FromLongLong(i64::from(
+        err.raw_os_error().unwrap_or(0)
+    )));
+    let filename_obj = ffi!(PyUnicode_DecodeFSDefaultAndSize(
+        PyBytes_AS_STRING(filename),
+        PyBytes_GET_SIZE(filename)
+    ));
+    if !strerror_obj.is_null() && !errno_obj.is_null() && !filename_obj.is_null() {
+        let args = [errno_obj, strerror_obj, filename_obj];
+        let exc = ffi!(PyObject_Vectorcall(
+            crate::ffi::PyExc_OSError,
+            args.as_ptr(),
+            3,
+            null_mut()
+        ));
+        if !exc.is_null() {
+            ffi!(PyErr_SetObject(ob_type!(exc).cast::<PyObject>(), exc));
+            ffi!(Py_DECREF(exc));
+        }
+    }
+    ffi!(Py_XDECREF(strerror_obj));
+    ffi!(Py_XDECREF(errno_obj));
+    ffi!(Py_XDECREF(filename_obj));
+    DeserializeError::raised()
+}
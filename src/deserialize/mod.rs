@@ -6,10 +6,17 @@ mod backend;
 pub(crate) mod cache;
 mod deserializer;
 mod error;
+mod file;
 mod pyobject;
+mod relaxed;
+mod stream;
 mod utf8;
 
 #[cfg(not(Py_GIL_DISABLED))]
-pub(crate) use cache::{KEY_MAP, KeyMap};
-pub(crate) use deserializer::deserialize;
+pub(crate) use cache::KeyCacheStats;
+pub(crate) use deserializer::{ObjectHook, deserialize, deserialize_buf, deserialize_many};
 pub(crate) use error::DeserializeError;
+pub(crate) use file::read_file;
+pub(crate) use pyobject::warm_key;
+pub(crate) use stream::ArrayScanner;
+pub(crate) use utf8::read_input_to_buf;
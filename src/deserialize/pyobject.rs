@@ -2,7 +2,7 @@
 // Copyright ijl (2022-2025)
 
 #[cfg(not(Py_GIL_DISABLED))]
-use crate::deserialize::cache::CachedKey;
+use crate::deserialize::cache::{CACHED_STR_MAX_LEN, CachedKey};
 use crate::str::PyStr;
 // NONE, TRUE, FALSE now accessed via typeref accessor functions
 use core::ptr::NonNull;
@@ -10,21 +10,33 @@ use core::ptr::NonNull;
 #[cfg(not(Py_GIL_DISABLED))]
 #[inline(always)]
 pub(crate) fn get_unicode_key(key_str: &str) -> PyStr {
-    if key_str.len() > 64 {
+    if key_str.len() > CACHED_STR_MAX_LEN {
         cold_path!();
+        unsafe {
+            let state = crate::interpreter_state::get_current_state().as_ref().unwrap();
+            (*state.key_cache_stats.get()).bypasses += 1;
+        }
         PyStr::from_str_with_hash(key_str)
     } else {
-        assume!(key_str.len() <= 64);
+        assume!(key_str.len() <= CACHED_STR_MAX_LEN);
         let hash = xxhash_rust::xxh3::xxh3_64(key_str.as_bytes());
         unsafe {
             let state = crate::interpreter_state::get_current_state().as_ref().unwrap();
             let key_map = &mut *state.key_map.get();
-            let entry = key_map
-                .entry(&hash)
-                .or_insert_with(
-                    || hash,
-                    || CachedKey::new(PyStr::from_str_with_hash(key_str)),
-                );
+            let stats = &mut *state.key_cache_stats.get();
+            let mut missed = false;
+            let entry = key_map.entry(&hash).or_insert_with(
+                || hash,
+                || {
+                    missed = true;
+                    CachedKey::new(PyStr::from_str_with_hash(key_str))
+                },
+            );
+            if missed {
+                stats.misses += 1;
+            } else {
+                stats.hits += 1;
+            }
             entry.get()
         }
     }
@@ -36,6 +48,55 @@ pub(crate) fn get_unicode_key(key_str: &str) -> PyStr {
     PyStr::from_str_with_hash(key_str)
 }
 
+/// Cache `key`, whose contents are `key_str`, as the `str` of that key when
+/// deserialized, for `warm_keys()`. It replaces a `str` already cached for
+/// the key. Keys too long to be cached are ignored.
+#[cfg(not(Py_GIL_DISABLED))]
+#[cold]
+pub(crate) fn warm_key(key: PyStr, key_str: &str) {
+    if key_str.len() > CACHED_STR_MAX_LEN {
+        return;
+    }
+    // as `PyStr::from_str_with_hash()`, so that it is not hashed again
+    if ffi!(PyObject_Hash(key.as_ptr())) == -1 {
+        cold_path!();
+        ffi!(PyErr_Clear());
+        return;
+    }
+    ffi!(Py_INCREF(key.as_ptr()));
+    let hash = xxhash_rust::xxh3::xxh3_64(key_str.as_bytes());
+    unsafe {
+        let state = crate::interpreter_state::get_current_state()
+            .as_ref()
+            .unwrap();
+        let key_map = &mut *state.key_map.get();
+        key_map.insert(hash, CachedKey::new(key));
+    }
+}
+
+#[cfg(Py_GIL_DISABLED)]
+#[cold]
+pub(crate) fn warm_key(_key: PyStr, _key_str: &str) {}
+
+/// Get a string value for OPT_INTERN_VALUES. Values short enough to be
+/// cached as keys share the cache with keys. Longer values are likely
+/// free-form text and are not cached or hashed.
+#[cfg(not(Py_GIL_DISABLED))]
+#[inline(always)]
+pub(crate) fn get_unicode_value(val_str: &str) -> PyStr {
+    if val_str.len() > CACHED_STR_MAX_LEN {
+        PyStr::from_str(val_str)
+    } else {
+        get_unicode_key(val_str)
+    }
+}
+
+#[cfg(Py_GIL_DISABLED)]
+#[inline(always)]
+pub(crate) fn get_unicode_value(val_str: &str) -> PyStr {
+    PyStr::from_str(val_str)
+}
+
 #[allow(dead_code)]
 #[inline(always)]
 pub(crate) fn parse_bool(val: bool) -> NonNull<crate::ffi::PyObject> {
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::deserialize::DeserializeError;
+use std::borrow::Cow;
+
+/// The offset of a `NaN`, `Infinity`, or `-Infinity` token of JSON5 that
+/// `strip_relaxed()` replaced by a string of the same width, and its value.
+pub(crate) type NonFinite = (usize, f64);
+
+const NONFINITE_TOKENS: [(&[u8], f64); 4] = [
+    (b"NaN", f64::NAN),
+    (b"Infinity", f64::INFINITY),
+    (b"+Infinity", f64::INFINITY),
+    (b"-Infinity", f64::NEG_INFINITY),
+];
+
+/// The non-finite token at the start of `rest`, if it is not followed by
+/// another letter or digit.
+fn nonfinite_token(rest: &[u8]) -> Option<(usize, f64)> {
+    NONFINITE_TOKENS.iter().find_map(|&(token, value)| {
+        (rest.starts_with(token)
+            && !rest
+                .get(token.len())
+                .is_some_and(|each| each.is_ascii_alphanumeric() || *each == b'_'))
+        .then_some((token.len(), value))
+    })
+}
+
+/// Copy `data` for `OPT_PARSE_RELAXED` with each `//` and `/* */` comment
+/// and each comma before a closing `]` or `}` replaced by spaces, so that it
+/// is standard JSON, if otherwise valid, with every byte at the same offset.
+///
+/// A newline in a comment is kept, so that a line number is unchanged, and
+/// a comma that does not follow a value, e.g., `[,]`, is left to be rejected.
+///
+/// A `NaN`, `Infinity`, or `-Infinity` value is replaced by a string of
+/// spaces of the same width, whose offset is returned so that the string
+/// is read as the float instead.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn strip_relaxed(data: &str) -> Result<(Vec<u8>, Vec<NonFinite>), DeserializeError<'_>> {
+    let mut buf = data.as_bytes().to_vec();
+    let mut nonfinite: Vec<NonFinite> = Vec::new();
+    let len = buf.len();
+    // the last byte other than whitespace or a comment
+    let mut previous: u8 = 0;
+    let mut comma: Option<usize> = None;
+    let mut idx = 0;
+    while idx < len {
+        match buf[idx] {
+            b'"' => {
+                idx += 1;
+                while idx < len {
+                    match buf[idx] {
+                        b'\\' => idx += 2,
+                        b'"' => break,
+                        _ => idx += 1,
+                    }
+                }
+                previous = b'"';
+                comma = None;
+            }
+            b'/' if buf.get(idx + 1) == Some(&b'/') => {
+                while idx < len && buf[idx] != b'\n' {
+                    buf[idx] = b' ';
+                    idx += 1;
+                }
+                continue;
+            }
+            b'/' if buf.get(idx + 1) == Some(&b'*') => {
+                let start = idx;
+                let Some(end) = data.as_bytes()[idx + 2..]
+                    .windows(2)
+                    .position(|each| each == b"*/")
+                else {
+                    return Err(DeserializeError::from_yyjson(
+                        Cow::Borrowed("unclosed multiline comment"),
+                        start as i64,
+                        data,
+                    ));
+                };
+                let end = idx + 2 + end + 2;
+                for each in &mut buf[start..end] {
+                    if *each != b'\n' {
+                        *each = b' ';
+                    }
+                }
+                idx = end;
+                continue;
+            }
+            b' ' | b'\t' | b'\n' | b'\r' => {}
+            b',' => {
+                comma = match previous {
+                    b'[' | b'{' | b',' | b':' => None,
+                    _ => Some(idx),
+                };
+                previous = b',';
+            }
+            val @ (b']' | b'}') => {
+                if let Some(pos) = comma.take() {
+                    buf[pos] = b' ';
+                }
+                previous = val;
+            }
+            b'N' | b'I' | b'+' | b'-' if matches!(previous, 0 | b'[' | b',' | b':') => {
+                if let Some((len, value)) = nonfinite_token(&buf[idx..]) {
+                    buf[idx] = b'"';
+                    buf[idx + 1..idx + len - 1].fill(b' ');
+                    buf[idx + len - 1] = b'"';
+                    nonfinite.push((idx, value));
+                    previous = b'"';
+                    comma = None;
+                    idx += len;
+                    continue;
+                }
+                previous = buf[idx];
+                comma = None;
+            }
+            val => {
+                previous = val;
+                comma = None;
+            }
+        }
+        idx += 1;
+    }
+    Ok((buf, nonfinite))
+}
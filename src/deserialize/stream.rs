@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::deserialize::DeserializeError;
+use crate::deserialize::deserializer::{ObjectHook, deserialize_valid};
+use crate::opt::Opt;
+use core::ptr::NonNull;
+use std::borrow::Cow;
+
+#[derive(Clone, Copy)]
+enum ScanState {
+    /// Before the `[` of the array.
+    Start,
+    /// After an element, before a `,` or the `]`.
+    Next,
+    /// After the `]` or an error.
+    Done,
+}
+
+/// Finds the elements of a top-level JSON array one at a time, for
+/// `loads_array_stream()`. Only the bytes of each element are scanned to
+/// find where it ends, by its brackets and strings; the element is then
+/// deserialized on its own, so that it is validated as by `loads()`.
+#[derive(Clone, Copy)]
+pub(crate) struct ArrayScanner {
+    pos: usize,
+    state: ScanState,
+}
+
+#[inline(always)]
+fn is_whitespace(each: u8) -> bool {
+    matches!(each, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+impl ArrayScanner {
+    pub const fn new() -> Self {
+        ArrayScanner {
+            pos: 0,
+            state: ScanState::Start,
+        }
+    }
+
+    /// Find the next element of the array of `data`, which is non-empty and
+    /// valid UTF-8, returning its start and end, or `Ok(None)` at its end.
+    /// After an error, it returns `Ok(None)`.
+    pub fn next_span(
+        &mut self,
+        data: &'static str,
+    ) -> Result<Option<(usize, usize)>, DeserializeError<'static>> {
+        let res = self.next_element(data.as_bytes());
+        if res.is_err() {
+            self.finish();
+        }
+        res
+    }
+
+    /// Return `Ok(None)` from then on, e.g., after an element fails to
+    /// deserialize.
+    pub fn finish(&mut self) {
+        self.state = ScanState::Done;
+    }
+
+    /// Deserialize the element of `data` at `span`, as returned by
+    /// `next_span()`. It does not borrow the scanner, as a hook it calls may
+    /// advance it.
+    pub fn deserialize_span(
+        data: &'static str,
+        span: (usize, usize),
+        opts: Opt,
+        hook: Option<ObjectHook>,
+    ) -> Result<NonNull<crate::ffi::PyObject>, DeserializeError<'static>> {
+        let (start, end) = span;
+        deserialize_valid(&data.as_bytes()[start..end], opts, hook).map_err(|mut err| {
+            if err.data.is_some() {
+                err.pos += start as i64;
+                err.data = Some(Cow::Borrowed(data));
+            }
+            err
+        })
+    }
+
+    /// The start and end of the next element of `buffer`.
+    fn next_element(
+        &mut self,
+        buffer: &'static [u8],
+    ) -> Result<Option<(usize, usize)>, DeserializeError<'static>> {
+        match self.state {
+            ScanState::Done => return Ok(None),
+            ScanState::Start => {
+                self.skip_whitespace(buffer);
+                if buffer.get(self.pos) != Some(&b'[') {
+                    return Err(self.error("Input is not an array", buffer));
+                }
+                self.pos += 1;
+                self.skip_whitespace(buffer);
+                if buffer.get(self.pos) == Some(&b']') {
+                    return self.end(buffer);
+                }
+            }
+            ScanState::Next => {
+                self.skip_whitespace(buffer);
+                match buffer.get(self.pos) {
+                    Some(b']') => return self.end(buffer),
+                    Some(b',') => {
+                        let comma = self.pos;
+                        self.pos += 1;
+                        self.skip_whitespace(buffer);
+                        if buffer.get(self.pos) == Some(&b']') {
+                            self.pos = comma;
+                            return Err(self.error("trailing comma is not allowed", buffer));
+                        }
+                    }
+                    Some(_) => return Err(self.error("unexpected character", buffer)),
+                    None => return Err(self.error("unexpected end of data", buffer)),
+                }
+            }
+        }
+        self.state = ScanState::Next;
+        let start = self.pos;
+        self.skip_element(buffer);
+        if self.pos == start {
+            return Err(self.error(
+                if start == buffer.len() {
+                    "unexpected end of data"
+                } else {
+                    "unexpected character"
+                },
+                buffer,
+            ));
+        }
+        Ok(Some((start, self.pos)))
+    }
+
+    /// Advance past the element at `pos`, to the `,` or `]` after it at the
+    /// same depth, whitespace, or the end of `buffer`.
+    fn skip_element(&mut self, buffer: &[u8]) {
+        let mut depth: usize = 0;
+        let mut in_string = false;
+        while let Some(&each) = buffer.get(self.pos) {
+            if in_string {
+                match each {
+                    b'\\' => self.pos += 1,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+            } else {
+                match each {
+                    b'"' => in_string = true,
+                    b'[' | b'{' => depth += 1,
+                    b']' | b'}' if depth == 0 => return,
+                    b']' | b'}' => depth -= 1,
+                    b',' if depth == 0 => return,
+                    _ if depth == 0 && is_whitespace(each) => return,
+                    _ => {}
+                }
+            }
+            self.pos += 1;
+        }
+        self.pos = buffer.len();
+    }
+
+    /// At the `]` of the array, which must be followed only by whitespace.
+    fn end(
+        &mut self,
+        buffer: &'static [u8],
+    ) -> Result<Option<(usize, usize)>, DeserializeError<'static>> {
+        self.pos += 1;
+        self.skip_whitespace(buffer);
+        if self.pos != buffer.len() {
+            return Err(self.error("unexpected content after document", buffer));
+        }
+        self.state = ScanState::Done;
+        Ok(None)
+    }
+
+    #[inline(always)]
+    fn skip_whitespace(&mut self, buffer: &[u8]) {
+        while buffer
+            .get(self.pos)
+            .is_some_and(|each| is_whitespace(*each))
+        {
+            self.pos += 1;
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn error(&self, message: &'static str, buffer: &'static [u8]) -> DeserializeError<'static> {
+        let data = unsafe { core::str::from_utf8_unchecked(buffer) };
+        #[allow(clippy::cast_possible_wrap)]
+        DeserializeError::from_yyjson(Cow::Borrowed(message), self.pos as i64, data)
+    }
+}
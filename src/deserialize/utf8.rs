@@ -34,6 +34,81 @@ fn is_valid_utf8(buf: &[u8]) -> bool {
     std::str::from_utf8(buf).is_ok()
 }
 
+/// Locate the first invalid sequence of `buffer` so that `JSONDecodeError`
+/// has its `pos`, `lineno`, and `colno`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn invalid_utf8(buffer: &[u8]) -> DeserializeError<'static> {
+    let valid_up_to = match core::str::from_utf8(buffer) {
+        Ok(_) => buffer.len(),
+        Err(err) => err.valid_up_to(),
+    };
+    DeserializeError::from_utf8(
+        Cow::Borrowed(INVALID_STR),
+        valid_up_to,
+        String::from_utf8_lossy(buffer).into_owned(),
+    )
+}
+
+/// Locate the first surrogate of the `str` at `ptr`. `doc` has each
+/// surrogate replaced by `?`, as by `str.encode(errors="replace")`, so that
+/// it has the same number of characters as the input.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn invalid_str(ptr: *mut crate::ffi::PyObject) -> DeserializeError<'static> {
+    ffi!(PyErr_Clear());
+    let encoded = ffi!(PyUnicode_AsEncodedString(
+        ptr,
+        c"utf-8".as_ptr(),
+        c"surrogatepass".as_ptr()
+    ));
+    let replaced = ffi!(PyUnicode_AsEncodedString(
+        ptr,
+        c"utf-8".as_ptr(),
+        c"replace".as_ptr()
+    ));
+    let err = if encoded.is_null() || replaced.is_null() {
+        ffi!(PyErr_Clear());
+        DeserializeError::invalid(Cow::Borrowed(INVALID_STR))
+    } else {
+        let bytes_slice = |obj| unsafe {
+            core::slice::from_raw_parts(
+                PyBytes_AS_STRING(obj).cast::<u8>(),
+                isize_to_usize(PyBytes_GET_SIZE(obj)),
+            )
+        };
+        let valid_up_to = match core::str::from_utf8(bytes_slice(encoded)) {
+            Ok(_) => 0,
+            Err(err) => err.valid_up_to(),
+        };
+        DeserializeError::from_utf8(
+            Cow::Borrowed(INVALID_STR),
+            valid_up_to,
+            String::from_utf8_lossy(bytes_slice(replaced)).into_owned(),
+        )
+    };
+    ffi!(Py_XDECREF(encoded));
+    ffi!(Py_XDECREF(replaced));
+    err
+}
+
+/// Validate a buffer not owned by a Python object, e.g., the contents of a
+/// file, as `read_input_to_buf()` does `bytes`.
+pub(crate) fn validate_buf(buffer: &[u8]) -> Result<(), DeserializeError<'static>> {
+    if !is_valid_utf8(buffer) {
+        return Err(invalid_utf8(buffer));
+    }
+    if buffer.is_empty() {
+        cold_path!();
+        return Err(DeserializeError::invalid(Cow::Borrowed(
+            "Input is a zero-length, empty document",
+        )));
+    }
+    Ok(())
+}
+
 pub(crate) fn read_input_to_buf(
     ptr: *mut crate::ffi::PyObject,
 ) -> Result<&'static [u8], DeserializeError<'static>> {
@@ -47,13 +122,13 @@ pub(crate) fn read_input_to_buf(
             )
         };
         if !is_valid_utf8(buffer) {
-            return Err(DeserializeError::invalid(Cow::Borrowed(INVALID_STR)));
+            return Err(invalid_utf8(buffer));
         }
     } else if is_type!(obj_type_ptr, crate::typeref::get_str_type()) {
         let pystr = unsafe { PyStr::from_ptr_unchecked(ptr) };
         let uni = pystr.to_str();
         if uni.is_none() {
-            return Err(DeserializeError::invalid(Cow::Borrowed(INVALID_STR)));
+            return Err(invalid_str(ptr));
         }
         let as_str = uni.unwrap();
         buffer = unsafe { core::slice::from_raw_parts(as_str.as_ptr(), as_str.len()) };
@@ -72,7 +147,7 @@ pub(crate) fn read_input_to_buf(
             )
         };
         if !is_valid_utf8(buffer) {
-            return Err(DeserializeError::invalid(Cow::Borrowed(INVALID_STR)));
+            return Err(invalid_utf8(buffer));
         }
     } else if is_type!(obj_type_ptr, crate::typeref::get_bytearray_type()) {
         cold_path!();
@@ -83,7 +158,7 @@ pub(crate) fn read_input_to_buf(
             )
         };
         if !is_valid_utf8(buffer) {
-            return Err(DeserializeError::invalid(Cow::Borrowed(INVALID_STR)));
+            return Err(invalid_utf8(buffer));
         }
     } else {
         return Err(DeserializeError::invalid(Cow::Borrowed(
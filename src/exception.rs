@@ -6,8 +6,8 @@ use core::ptr::null_mut;
 
 use crate::deserialize::DeserializeError;
 use crate::ffi::{
-    Py_DECREF, PyErr_SetObject, PyLong_FromLongLong, PyObject, PyTuple_New,
-    PyUnicode_FromStringAndSize,
+    Py_DECREF, PyErr_SetObject, PyExc_RuntimeError, PyExc_TypeError, PyExc_ValueError,
+    PyLong_FromLongLong, PyObject, PyTuple_New, PyUnicode_FromStringAndSize,
 };
 // EMPTY_UNICODE, JsonDecodeError, JsonEncodeError now accessed via typeref accessor functions
 use crate::util::usize_to_isize;
@@ -16,7 +16,15 @@ use crate::util::usize_to_isize;
 #[inline(never)]
 #[cfg_attr(feature = "optimize", optimize(size))]
 pub(crate) fn raise_loads_exception(err: DeserializeError) -> *mut PyObject {
+    if err.raised {
+        debug_assert!(!ffi!(PyErr_Occurred()).is_null());
+        return null_mut();
+    }
     unsafe {
+        let json_decode_error = crate::typeref::get_json_decode_error();
+        if json_decode_error.is_null() {
+            return null_mut();
+        }
         let err_pos = err.pos();
         let msg = err.message;
         let doc = match err.data {
@@ -35,12 +43,59 @@ pub(crate) fn raise_loads_exception(err: DeserializeError) -> *mut PyObject {
         crate::ffi::PyTuple_SET_ITEM(args, 0, err_msg);
         crate::ffi::PyTuple_SET_ITEM(args, 1, doc);
         crate::ffi::PyTuple_SET_ITEM(args, 2, pos);
-        PyErr_SetObject(crate::typeref::get_json_decode_error(), args);
+        PyErr_SetObject(json_decode_error, args);
         Py_DECREF(args);
     }
     null_mut()
 }
 
+/// Raised when the interpreter state cannot be resolved, e.g., because the
+/// module can no longer be imported during interpreter finalization. This
+/// must not use any accessor in `typeref` as they require the state.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn raise_state_exception() -> *mut PyObject {
+    unsafe {
+        let msg = "hyperjson interpreter state is unavailable";
+        let err_msg =
+            PyUnicode_FromStringAndSize(msg.as_ptr().cast::<c_char>(), usize_to_isize(msg.len()));
+        PyErr_SetObject(PyExc_RuntimeError, err_msg);
+        Py_DECREF(err_msg);
+    }
+    null_mut()
+}
+
+/// Raised on invalid arguments to a function other than `dumps()`, which
+/// instead raises `JSONEncodeError`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn raise_args_exception(msg: &str) -> *mut PyObject {
+    unsafe {
+        let err_msg =
+            PyUnicode_FromStringAndSize(msg.as_ptr().cast::<c_char>(), usize_to_isize(msg.len()));
+        PyErr_SetObject(PyExc_TypeError, err_msg);
+        Py_DECREF(err_msg);
+    }
+    null_mut()
+}
+
+/// Raised on an argument of the right type but an unsupported value to a
+/// function other than `dumps()`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn raise_value_exception(msg: &str) -> *mut PyObject {
+    unsafe {
+        let err_msg =
+            PyUnicode_FromStringAndSize(msg.as_ptr().cast::<c_char>(), usize_to_isize(msg.len()));
+        PyErr_SetObject(PyExc_ValueError, err_msg);
+        Py_DECREF(err_msg);
+    }
+    null_mut()
+}
+
 #[cold]
 #[inline(never)]
 #[cfg_attr(feature = "optimize", optimize(size))]
@@ -117,3 +172,29 @@ pub(crate) fn raise_dumps_exception_dynamic(err: &str) -> *mut PyObject {
     }
     null_mut()
 }
+
+/// The `JSONEncodeError` that `raise_dumps_exception_dynamic()` raises for
+/// `err`, with any exception already set as its cause, returned as a new
+/// reference rather than raised.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn dumps_exception(err: &str) -> *mut PyObject {
+    raise_dumps_exception_dynamic(err);
+    #[cfg(Py_3_12)]
+    let exc = unsafe { crate::ffi::PyErr_GetRaisedException() };
+    #[cfg(not(Py_3_12))]
+    let exc = unsafe {
+        let mut tp: *mut PyObject = null_mut();
+        let mut val: *mut PyObject = null_mut();
+        let mut traceback: *mut PyObject = null_mut();
+        crate::ffi::PyErr_Fetch(&mut tp, &mut val, &mut traceback);
+        crate::ffi::PyErr_NormalizeException(&mut tp, &mut val, &mut traceback);
+        Py_DECREF(tp);
+        if !traceback.is_null() {
+            Py_DECREF(traceback);
+        }
+        val
+    };
+    exc
+}
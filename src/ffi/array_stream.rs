@@ -0,0 +1,317 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use core::ffi::c_char;
+
+use core::ptr::null_mut;
+use pyo3_ffi::{
+    Py_DECREF, Py_INCREF, Py_TPFLAGS_DEFAULT, PyObject, PyObject_SelfIter, PyType_Ready,
+    PyType_Type, PyTypeObject, PyVarObject,
+};
+
+use crate::deserialize::{ArrayScanner, ObjectHook, read_input_to_buf};
+use crate::exception::raise_loads_exception;
+use crate::opt::Opt;
+use crate::util::isize_to_usize;
+
+#[cfg(Py_GIL_DISABLED)]
+use super::atomiculong::AtomicCULong;
+#[cfg(Py_GIL_DISABLED)]
+use core::sync::atomic::{AtomicIsize, AtomicU32};
+
+#[cfg(Py_GIL_DISABLED)]
+macro_rules! pymutex_new {
+    () => {
+        unsafe { core::mem::zeroed() }
+    };
+}
+
+/// The iterator `loads_array_stream()` returns, deserializing one element
+/// of the array of `source`, a `bytes` or `str`, per `next()`.
+#[repr(C)]
+pub(crate) struct ArrayStream {
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_tid: usize,
+    #[cfg(all(Py_GIL_DISABLED, Py_3_14))]
+    pub ob_flags: u16,
+    #[cfg(all(Py_GIL_DISABLED, not(Py_3_14)))]
+    pub _padding: u16,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_mutex: pyo3_ffi::PyMutex,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_gc_bits: u8,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_ref_local: AtomicU32,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_ref_shared: AtomicIsize,
+    #[cfg(not(Py_GIL_DISABLED))]
+    pub ob_refcnt: pyo3_ffi::Py_ssize_t,
+    #[cfg(PyPy)]
+    pub ob_pypy_link: pyo3_ffi::Py_ssize_t,
+    pub ob_type: *mut pyo3_ffi::PyTypeObject,
+    source: *mut PyObject,
+    data: &'static str,
+    opts: Opt,
+    hook: Option<ObjectHook>,
+    scanner: ArrayScanner,
+    /// Whether an element is being deserialized, so that `next()` from a
+    /// hook, or from another thread without the GIL, is rejected.
+    running: bool,
+}
+
+#[inline(always)]
+fn hook_callable(hook: ObjectHook) -> *mut PyObject {
+    match hook {
+        ObjectHook::Object(callable) | ObjectHook::Pairs(callable) => callable.as_ptr(),
+    }
+}
+
+/// Create the iterator of the elements of the array of `obj`. A `bytearray`
+/// or `memoryview` is copied, as it may be modified while iterating. Returns
+/// null with `JSONDecodeError` set if `obj` is not valid input.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe fn array_stream_new(
+    obj: *mut PyObject,
+    opts: Opt,
+    hook: Option<ObjectHook>,
+) -> *mut PyObject {
+    unsafe {
+        let mut buffer = match read_input_to_buf(obj) {
+            Ok(buffer) => buffer,
+            Err(err) => return raise_loads_exception(err),
+        };
+        let source = if is_type!(ob_type!(obj), crate::typeref::get_bytes_type())
+            || is_type!(ob_type!(obj), crate::typeref::get_str_type())
+        {
+            Py_INCREF(obj);
+            obj
+        } else {
+            let copy = crate::ffi::PyBytes_FromStringAndSize(
+                buffer.as_ptr().cast::<c_char>(),
+                crate::util::usize_to_isize(buffer.len()),
+            );
+            if copy.is_null() {
+                return null_mut();
+            }
+            buffer = core::slice::from_raw_parts(
+                crate::ffi::PyBytes_AS_STRING(copy).cast::<u8>(),
+                isize_to_usize(crate::ffi::PyBytes_GET_SIZE(copy)),
+            );
+            copy
+        };
+        if let Some(hook) = hook {
+            Py_INCREF(hook_callable(hook));
+        }
+        let obj = Box::new(ArrayStream {
+            #[cfg(Py_GIL_DISABLED)]
+            ob_tid: 0,
+            #[cfg(all(Py_GIL_DISABLED, Py_3_14))]
+            ob_flags: 0,
+            #[cfg(all(Py_GIL_DISABLED, not(Py_3_14)))]
+            _padding: 0,
+            #[cfg(Py_GIL_DISABLED)]
+            ob_mutex: pymutex_new!(),
+            #[cfg(Py_GIL_DISABLED)]
+            ob_gc_bits: 0,
+            #[cfg(Py_GIL_DISABLED)]
+            ob_ref_local: AtomicU32::new(0),
+            #[cfg(Py_GIL_DISABLED)]
+            ob_ref_shared: AtomicIsize::new(0),
+            #[cfg(not(Py_GIL_DISABLED))]
+            ob_refcnt: 1,
+            #[cfg(PyPy)]
+            ob_pypy_link: 0,
+            ob_type: crate::typeref::get_array_stream_type(),
+            source: source,
+            // validated by `read_input_to_buf()` and owned by `source`
+            data: core::str::from_utf8_unchecked(buffer),
+            opts: opts,
+            hook: hook,
+            scanner: ArrayScanner::new(),
+            running: false,
+        });
+        Box::into_raw(obj).cast::<PyObject>()
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe extern "C" fn orjson_arraystream_iternext(
+    object: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        let Some(_state) = crate::interpreter_state::StateGuard::new() else {
+            return crate::exception::raise_state_exception();
+        };
+        let stream = object.cast::<ArrayStream>();
+        // The scanner is advanced past the element before it is deserialized
+        // rather than borrowed while hooks run.
+        #[cfg(Py_GIL_DISABLED)]
+        let mut critical_section: pyo3_ffi::PyCriticalSection = core::mem::zeroed();
+        #[cfg(Py_GIL_DISABLED)]
+        pyo3_ffi::PyCriticalSection_Begin(&raw mut critical_section, object);
+        let span = if (*stream).running {
+            None
+        } else {
+            let mut scanner = (*stream).scanner;
+            let span = scanner.next_span((*stream).data);
+            (*stream).scanner = scanner;
+            (*stream).running = matches!(span, Ok(Some(_)));
+            Some(span)
+        };
+        #[cfg(Py_GIL_DISABLED)]
+        pyo3_ffi::PyCriticalSection_End(&raw mut critical_section);
+        let res = match span {
+            None => {
+                cold_path!();
+                return crate::exception::raise_value_exception("ArrayStream is already executing");
+            }
+            Some(Ok(Some(span))) => {
+                let res = ArrayScanner::deserialize_span(
+                    (*stream).data,
+                    span,
+                    (*stream).opts,
+                    (*stream).hook,
+                );
+                #[cfg(Py_GIL_DISABLED)]
+                pyo3_ffi::PyCriticalSection_Begin(&raw mut critical_section, object);
+                (*stream).running = false;
+                if res.is_err() {
+                    (*stream).scanner.finish();
+                }
+                #[cfg(Py_GIL_DISABLED)]
+                pyo3_ffi::PyCriticalSection_End(&raw mut critical_section);
+                res.map(Some)
+            }
+            Some(Ok(None)) => Ok(None),
+            Some(Err(err)) => Err(err),
+        };
+        match res {
+            Ok(Some(element)) => element.as_ptr(),
+            // null without an exception set ends iteration
+            Ok(None) => null_mut(),
+            Err(err) => raise_loads_exception(err),
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe extern "C" fn orjson_arraystream_dealloc(object: *mut PyObject) {
+    unsafe {
+        let stream = Box::from_raw(object.cast::<ArrayStream>());
+        Py_DECREF(stream.source);
+        if let Some(hook) = stream.hook {
+            Py_DECREF(hook_callable(hook));
+        }
+    }
+}
+
+/// Returns null with an exception set if the type cannot be readied.
+#[unsafe(no_mangle)]
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe extern "C" fn orjson_arraystreamtype_new() -> *mut PyTypeObject {
+    unsafe {
+        #[cfg(Py_GIL_DISABLED)]
+        let tp_flags: AtomicCULong =
+            AtomicCULong::new(Py_TPFLAGS_DEFAULT | pyo3_ffi::Py_TPFLAGS_IMMUTABLETYPE);
+        #[cfg(all(Py_3_10, not(Py_GIL_DISABLED)))]
+        let tp_flags: core::ffi::c_ulong = Py_TPFLAGS_DEFAULT | pyo3_ffi::Py_TPFLAGS_IMMUTABLETYPE;
+        #[cfg(not(Py_3_10))]
+        let tp_flags: core::ffi::c_ulong = Py_TPFLAGS_DEFAULT;
+        let ob = Box::new(PyTypeObject {
+            ob_base: PyVarObject {
+                ob_base: PyObject {
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_tid: 0,
+                    #[cfg(all(Py_GIL_DISABLED, Py_3_14))]
+                    ob_flags: 0,
+                    #[cfg(all(Py_GIL_DISABLED, not(Py_3_14)))]
+                    _padding: 0,
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_mutex: pymutex_new!(),
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_gc_bits: 0,
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_ref_local: AtomicU32::new(crate::ffi::compat::_Py_IMMORTAL_REFCNT_LOCAL),
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_ref_shared: AtomicIsize::new(0),
+                    #[cfg(all(Py_3_12, not(Py_GIL_DISABLED)))]
+                    ob_refcnt: pyo3_ffi::PyObjectObRefcnt { ob_refcnt: 0 },
+                    #[cfg(not(Py_3_12))]
+                    ob_refcnt: 0,
+                    #[cfg(PyPy)]
+                    ob_pypy_link: 0,
+                    ob_type: &raw mut PyType_Type,
+                },
+                #[cfg(not(GraalPy))]
+                ob_size: 0,
+                #[cfg(GraalPy)]
+                _ob_size_graalpy: 0,
+            },
+            tp_name: c"orjson.ArrayStream".as_ptr(),
+            tp_basicsize: core::mem::size_of::<ArrayStream>() as isize,
+            tp_itemsize: 0,
+            tp_dealloc: Some(orjson_arraystream_dealloc),
+            tp_init: None,
+            // created only by `loads_array_stream()`
+            tp_new: None,
+            tp_flags: tp_flags,
+            // ...
+            tp_bases: null_mut(),
+            tp_cache: null_mut(),
+            tp_del: None,
+            tp_finalize: None,
+            tp_free: None,
+            tp_is_gc: None,
+            tp_mro: null_mut(),
+            tp_subclasses: null_mut(),
+            tp_vectorcall: None,
+            tp_version_tag: 0,
+            tp_weaklist: null_mut(),
+            tp_vectorcall_offset: 0,
+            tp_getattr: None,
+            tp_setattr: None,
+            tp_as_async: null_mut(),
+            tp_repr: None,
+            tp_as_number: null_mut(),
+            tp_as_sequence: null_mut(),
+            tp_as_mapping: null_mut(),
+            tp_hash: None,
+            tp_call: None,
+            tp_str: None,
+            tp_getattro: None,
+            tp_setattro: None,
+            tp_as_buffer: null_mut(),
+            tp_doc: core::ptr::null_mut(),
+            tp_traverse: None,
+            tp_clear: None,
+            tp_richcompare: None,
+            tp_weaklistoffset: 0,
+            tp_iter: Some(PyObject_SelfIter),
+            tp_iternext: Some(orjson_arraystream_iternext),
+            tp_methods: null_mut(),
+            tp_members: null_mut(),
+            tp_getset: null_mut(),
+            tp_base: null_mut(),
+            tp_dict: null_mut(),
+            tp_descr_get: None,
+            tp_descr_set: None,
+            tp_dictoffset: 0,
+            tp_alloc: None,
+            #[cfg(Py_3_12)]
+            tp_watched: 0,
+        });
+        let ob_ptr = Box::into_raw(ob);
+        if PyType_Ready(ob_ptr) < 0 {
+            drop(Box::from_raw(ob_ptr));
+            return null_mut();
+        }
+        ob_ptr
+    }
+}
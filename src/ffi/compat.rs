@@ -194,6 +194,12 @@ pub(crate) unsafe fn PyTuple_SET_ITEM(
 }
 
 unsafe extern "C" {
+    #[cfg(all(CPython, not(Py_3_13)))]
+    pub(crate) fn _PyThreadState_UncheckedGet() -> *mut pyo3_ffi::PyThreadState;
+
+    #[cfg(all(CPython, Py_3_13))]
+    pub(crate) fn PyThreadState_GetUnchecked() -> *mut pyo3_ffi::PyThreadState;
+
     #[cfg(CPython)]
     pub fn _PyDict_Next(
         mp: *mut pyo3_ffi::PyObject,
@@ -247,3 +253,24 @@ unsafe extern "C" {
         is_signed: core::ffi::c_int,
     ) -> core::ffi::c_int;
 }
+
+/// Whether the current thread has a thread state, i.e., holds the GIL of an
+/// interpreter. Unlike `PyGILState_Check()`, this is accurate once
+/// subinterpreters have been created.
+#[cfg(CPython)]
+#[inline]
+pub(crate) unsafe fn thread_state_is_attached() -> bool {
+    unsafe {
+        #[cfg(not(Py_3_13))]
+        let tstate = _PyThreadState_UncheckedGet();
+        #[cfg(Py_3_13)]
+        let tstate = PyThreadState_GetUnchecked();
+        !tstate.is_null()
+    }
+}
+
+#[cfg(not(CPython))]
+#[inline]
+pub(crate) unsafe fn thread_state_is_attached() -> bool {
+    unsafe { pyo3_ffi::PyGILState_Check() == 1 }
+}
@@ -70,6 +70,8 @@ pub(crate) unsafe extern "C" fn orjson_fragment_tp_new(
         if Py_SIZE(args) != 1 || !kwds.is_null() {
             raise_args_exception();
             null_mut()
+        } else if crate::interpreter_state::get_current_state().is_null() {
+            crate::exception::raise_state_exception()
         } else {
             let contents = crate::ffi::PyTuple_GET_ITEM(args, 0);
             Py_INCREF(contents);
@@ -110,6 +112,7 @@ pub(crate) unsafe extern "C" fn orjson_fragment_dealloc(object: *mut PyObject) {
     }
 }
 
+/// Returns null with an exception set if the type cannot be readied.
 #[unsafe(no_mangle)]
 #[cold]
 #[cfg_attr(feature = "optimize", optimize(size))]
@@ -205,7 +208,10 @@ pub(crate) unsafe extern "C" fn orjson_fragmenttype_new() -> *mut PyTypeObject {
             tp_watched: 0,
         });
         let ob_ptr = Box::into_raw(ob);
-        PyType_Ready(ob_ptr);
+        if PyType_Ready(ob_ptr) < 0 {
+            drop(Box::from_raw(ob_ptr));
+            return null_mut();
+        }
         ob_ptr
     }
 }
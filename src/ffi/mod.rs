@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2022-2025)
 
+mod array_stream;
 #[cfg(Py_GIL_DISABLED)]
 mod atomiculong;
 mod buffer;
@@ -8,7 +9,10 @@ mod bytes;
 pub(crate) mod compat;
 mod fragment;
 mod long;
+mod options;
+mod timezone;
 
+pub(crate) use array_stream::{array_stream_new, orjson_arraystreamtype_new};
 pub(crate) use buffer::*;
 pub(crate) use bytes::{PyBytes_AS_STRING, PyBytes_GET_SIZE, PyBytesObject};
 pub(crate) use compat::*;
@@ -17,39 +21,57 @@ pub(crate) use fragment::{Fragment, orjson_fragmenttype_new};
 pub(crate) use long::pylong_is_unsigned;
 #[cfg(feature = "inline_int")]
 pub(crate) use long::{pylong_fits_in_i32, pylong_get_inline_value, pylong_is_zero};
+pub(crate) use options::{Options, flags_of, orjson_optionstype_new};
+#[cfg(CPython)]
+pub(crate) use timezone::PyTimeZone_GET_OFFSET;
 
 #[allow(unused_imports, deprecated)]
 pub(crate) use pyo3_ffi::{
-    _PyBytes_Resize, METH_FASTCALL, METH_KEYWORDS, METH_O, Py_DECREF, Py_False, Py_INCREF, Py_None,
-    Py_REFCNT, Py_SIZE, Py_TPFLAGS_DEFAULT, Py_TPFLAGS_DICT_SUBCLASS, Py_TPFLAGS_LIST_SUBCLASS,
+    _PyBytes_Resize, METH_FASTCALL, METH_KEYWORDS, METH_NOARGS, METH_O, Py_DECREF, Py_False,
+    Py_INCREF, Py_None, Py_NotImplemented, Py_REFCNT, Py_SIZE, Py_TPFLAGS_BYTES_SUBCLASS,
+    Py_TPFLAGS_DEFAULT, Py_TPFLAGS_DICT_SUBCLASS, Py_TPFLAGS_LIST_SUBCLASS,
     Py_TPFLAGS_LONG_SUBCLASS, Py_TPFLAGS_TUPLE_SUBCLASS, Py_TPFLAGS_UNICODE_SUBCLASS, Py_TYPE,
     Py_True, Py_XDECREF, Py_buffer, Py_hash_t, Py_intptr_t, Py_mod_exec, Py_ssize_t, PyASCIIObject,
     PyBool_Type, PyBuffer_IsContiguous, PyByteArray_AsString, PyByteArray_Size, PyByteArray_Type,
-    PyBytes_FromStringAndSize, PyBytes_Type, PyCFunction_NewEx, PyCapsule_Import,
+    PyBytes_FromStringAndSize, PyBytes_Type, PyCFunction_NewEx, PyCallable_Check, PyCapsule_Import,
     PyCompactUnicodeObject, PyDateTime_CAPI, PyDateTime_DATE_GET_HOUR,
     PyDateTime_DATE_GET_MICROSECOND, PyDateTime_DATE_GET_MINUTE, PyDateTime_DATE_GET_SECOND,
     PyDateTime_DATE_GET_TZINFO, PyDateTime_DELTA_GET_DAYS, PyDateTime_DELTA_GET_SECONDS,
     PyDateTime_DateTime, PyDateTime_GET_DAY, PyDateTime_GET_MONTH, PyDateTime_GET_YEAR,
     PyDateTime_IMPORT, PyDateTime_TIME_GET_HOUR, PyDateTime_TIME_GET_MICROSECOND,
-    PyDateTime_TIME_GET_MINUTE, PyDateTime_TIME_GET_SECOND, PyDateTime_Time, PyDict_Contains,
-    PyDict_New, PyDict_Next, PyDict_SetItem, PyDict_Type, PyDictObject, PyErr_Clear,
-    PyErr_NewException, PyErr_Occurred, PyErr_Restore, PyErr_SetObject, PyExc_TypeError,
+    PyDateTime_TIME_GET_MINUTE, PyDateTime_TIME_GET_SECOND, PyDateTime_TIME_GET_TZINFO,
+    PyDateTime_Time, PyDict_Contains, PyDict_DelItem, PyDict_GetItem, PyDict_GetItemString,
+    PyDict_Merge, PyDict_New, PyDict_Next, PyDict_SetItem, PyDict_SetItemString, PyDict_Size,
+    PyDict_Type, PyDictObject, PyDictProxy_Type, PyErr_Clear, PyErr_Format, PyErr_NewException,
+    PyErr_Occurred, PyErr_Restore, PyErr_SetObject, PyEval_RestoreThread, PyEval_SaveThread,
+    PyExc_AttributeError, PyExc_OSError, PyExc_RuntimeError, PyExc_TypeError, PyExc_ValueError,
     PyException_SetCause, PyFloat_AS_DOUBLE, PyFloat_FromDouble, PyFloat_Type,
-    PyImport_ImportModule, PyList_GET_ITEM, PyList_New, PyList_SET_ITEM, PyList_Type, PyListObject,
-    PyLong_AsLong, PyLong_AsLongLong, PyLong_AsUnsignedLongLong, PyLong_FromLongLong,
-    PyLong_FromUnsignedLongLong, PyLong_Type, PyLongObject, PyMapping_GetItemString, PyMem_Free,
-    PyMem_Malloc, PyMem_Realloc, PyMemoryView_Type, PyMethodDef, PyMethodDefPointer,
-    PyModule_AddIntConstant, PyModuleDef, PyModuleDef_HEAD_INIT, PyModuleDef_Init,
-    PyModuleDef_Slot, PyObject, PyObject_CallMethodObjArgs, PyObject_GenericGetDict,
-    PyObject_GetAttr, PyObject_HasAttr, PyObject_Hash, PyObject_Vectorcall, PyTuple_New,
-    PyTuple_Type, PyTupleObject, PyType_Ready, PyType_Type, PyTypeObject, PyUnicode_AsUTF8AndSize,
-    PyUnicode_FromStringAndSize, PyUnicode_InternFromString, PyUnicode_New, PyUnicode_Type,
-    PyVarObject, PyVectorcall_NARGS,
+    PyImport_GetModuleDict, PyImport_ImportModule, PyIter_Next, PyList_Append, PyList_GET_ITEM,
+    PyList_New, PyList_SET_ITEM, PyList_Type, PyListObject, PyLong_AsLong, PyLong_AsLongLong,
+    PyLong_AsLongLongAndOverflow, PyLong_AsSsize_t, PyLong_AsUnsignedLongLong, PyLong_FromLongLong,
+    PyLong_FromUnsignedLongLong, PyLong_Type, PyLongObject, PyMapping_GetItemString, PyMem_Calloc,
+    PyMem_Free, PyMem_Malloc, PyMem_Realloc, PyMemoryView_FromObject, PyMemoryView_Type,
+    PyMethodDef, PyMethodDefPointer, PyModule_AddIntConstant, PyModule_GetDict, PyModule_GetName,
+    PyModule_GetNameObject, PyModuleDef, PyModuleDef_HEAD_INIT, PyModuleDef_Init, PyModuleDef_Slot,
+    PyNumber_Float, PyNumber_ToBase, PyOS_FSPath, PyObject, PyObject_CallMethodObjArgs,
+    PyObject_GenericGetDict, PyObject_GetAttr, PyObject_GetAttrString, PyObject_GetIter,
+    PyObject_HasAttr, PyObject_Hash, PyObject_Repr, PyObject_Size, PyObject_Str,
+    PyObject_Vectorcall, PyProperty_Type, PyRange_Type, PyTuple_New, PyTuple_Type, PyTupleObject,
+    PyType_Check, PyType_IsSubtype, PyType_Ready, PyType_Type, PyTypeObject,
+    PyUnicode_AsEncodedString, PyUnicode_AsUTF8AndSize, PyUnicode_CompareWithASCIIString,
+    PyUnicode_DecodeFSDefaultAndSize, PyUnicode_FSConverter, PyUnicode_FromStringAndSize,
+    PyUnicode_GetLength, PyUnicode_InternFromString, PyUnicode_New, PyUnicode_ReadChar,
+    PyUnicode_Type, PyVarObject, PyVectorcall_NARGS,
 };
 
+pub(crate) use pyo3_ffi::Py_IsInitialized;
+
 #[cfg(CPython)]
 pub(crate) use pyo3_ffi::{PyObject_CallMethodNoArgs, PyObject_CallMethodOneArg};
 
+#[cfg(not(PyPy))]
+pub(crate) use pyo3_ffi::{PyInterpreterState_Get, PyInterpreterState_GetID};
+
 #[cfg(all(CPython, not(target_endian = "little")))]
 pub(crate) use pyo3_ffi::{PyUnicode_DATA, PyUnicode_KIND};
 
@@ -0,0 +1,347 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use core::ffi::c_char;
+
+use core::ptr::null_mut;
+use pyo3_ffi::{
+    METH_O, Py_DECREF, Py_INCREF, Py_NotImplemented, Py_SIZE, Py_TPFLAGS_DEFAULT, PyErr_Occurred,
+    PyErr_SetObject, PyExc_TypeError, PyExc_ValueError, PyLong_AsLongLong,
+    PyLong_FromUnsignedLongLong, PyMethodDef, PyMethodDefPointer, PyNumberMethods, PyObject,
+    PyType_Ready, PyType_Type, PyTypeObject, PyUnicode_FromStringAndSize, PyVarObject,
+};
+
+use crate::opt::{MAX_OPT, Opt};
+
+#[cfg(Py_GIL_DISABLED)]
+use super::atomiculong::AtomicCULong;
+#[cfg(Py_GIL_DISABLED)]
+use core::sync::atomic::{AtomicIsize, AtomicU32};
+
+#[cfg(Py_GIL_DISABLED)]
+macro_rules! pymutex_new {
+    () => {
+        unsafe { core::mem::zeroed() }
+    };
+}
+
+/// An immutable, validated set of `OPT_*` flags. `dumps()` and `loads()`
+/// accept it wherever an `int` option is accepted.
+#[repr(C)]
+pub(crate) struct Options {
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_tid: usize,
+    #[cfg(all(Py_GIL_DISABLED, Py_3_14))]
+    pub ob_flags: u16,
+    #[cfg(all(Py_GIL_DISABLED, not(Py_3_14)))]
+    pub _padding: u16,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_mutex: pyo3_ffi::PyMutex,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_gc_bits: u8,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_ref_local: AtomicU32,
+    #[cfg(Py_GIL_DISABLED)]
+    pub ob_ref_shared: AtomicIsize,
+    #[cfg(not(Py_GIL_DISABLED))]
+    pub ob_refcnt: pyo3_ffi::Py_ssize_t,
+    #[cfg(PyPy)]
+    pub ob_pypy_link: pyo3_ffi::Py_ssize_t,
+    pub ob_type: *mut pyo3_ffi::PyTypeObject,
+    pub opts: Opt,
+}
+
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn raise_exception(exc: *mut PyObject, msg: &str) -> *mut PyObject {
+    unsafe {
+        let err_msg =
+            PyUnicode_FromStringAndSize(msg.as_ptr().cast::<c_char>(), msg.len() as isize);
+        PyErr_SetObject(exc, err_msg);
+        Py_DECREF(err_msg);
+    };
+    null_mut()
+}
+
+/// The flags of an `int` or `Options`, or `None` if `ob` is neither or is
+/// not a valid combination of flags.
+pub(crate) unsafe fn flags_of(ob: *mut PyObject) -> Option<Opt> {
+    unsafe {
+        if core::ptr::eq((*ob).ob_type, crate::typeref::get_options_type()) {
+            Some((*ob.cast::<Options>()).opts)
+        } else if core::ptr::eq((*ob).ob_type, crate::typeref::get_int_type()) {
+            let val = PyLong_AsLongLong(ob);
+            if !PyErr_Occurred().is_null() {
+                pyo3_ffi::PyErr_Clear();
+                None
+            } else if (0..=MAX_OPT).contains(&val) {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                Some(val as Opt)
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+}
+
+unsafe fn options_new(opts: Opt) -> *mut PyObject {
+    if let Some(msg) = crate::opt::incompatible(opts) {
+        return raise_exception(unsafe { PyExc_ValueError }, msg);
+    }
+    let obj = Box::new(Options {
+        #[cfg(Py_GIL_DISABLED)]
+        ob_tid: 0,
+        #[cfg(all(Py_GIL_DISABLED, Py_3_14))]
+        ob_flags: 0,
+        #[cfg(all(Py_GIL_DISABLED, not(Py_3_14)))]
+        _padding: 0,
+        #[cfg(Py_GIL_DISABLED)]
+        ob_mutex: pymutex_new!(),
+        #[cfg(Py_GIL_DISABLED)]
+        ob_gc_bits: 0,
+        #[cfg(Py_GIL_DISABLED)]
+        ob_ref_local: AtomicU32::new(0),
+        #[cfg(Py_GIL_DISABLED)]
+        ob_ref_shared: AtomicIsize::new(0),
+        #[cfg(not(Py_GIL_DISABLED))]
+        ob_refcnt: 1,
+        #[cfg(PyPy)]
+        ob_pypy_link: 0,
+        ob_type: crate::typeref::get_options_type(),
+        opts: opts,
+    });
+    Box::into_raw(obj).cast::<PyObject>()
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe extern "C" fn orjson_options_tp_new(
+    _subtype: *mut PyTypeObject,
+    args: *mut PyObject,
+    kwds: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        if !kwds.is_null() {
+            return raise_exception(
+                PyExc_TypeError,
+                "orjson.Options() takes no keyword arguments",
+            );
+        }
+        if crate::interpreter_state::get_current_state().is_null() {
+            return crate::exception::raise_state_exception();
+        }
+        let mut opts: Opt = 0;
+        for i in 0..Py_SIZE(args) {
+            match flags_of(crate::ffi::PyTuple_GET_ITEM(args, i)) {
+                Some(flags) => opts |= flags,
+                None => return raise_exception(PyExc_ValueError, "Invalid opts"),
+            }
+        }
+        options_new(opts)
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe extern "C" fn orjson_options_dealloc(object: *mut PyObject) {
+    unsafe {
+        drop(Box::from_raw(object.cast::<Options>()));
+    }
+}
+
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+unsafe extern "C" fn orjson_options_with_option(
+    slf: *mut PyObject,
+    arg: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        match flags_of(arg) {
+            Some(flags) => options_new((*slf.cast::<Options>()).opts | flags),
+            None => raise_exception(PyExc_ValueError, "Invalid opts"),
+        }
+    }
+}
+
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+unsafe extern "C" fn orjson_options_without_option(
+    slf: *mut PyObject,
+    arg: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        match flags_of(arg) {
+            Some(flags) => options_new((*slf.cast::<Options>()).opts & !flags),
+            None => raise_exception(PyExc_ValueError, "Invalid opts"),
+        }
+    }
+}
+
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+unsafe extern "C" fn orjson_options_index(slf: *mut PyObject) -> *mut PyObject {
+    unsafe { PyLong_FromUnsignedLongLong((*slf.cast::<Options>()).opts) }
+}
+
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+unsafe extern "C" fn orjson_options_or(lhs: *mut PyObject, rhs: *mut PyObject) -> *mut PyObject {
+    unsafe {
+        let is_int = |ob: *mut PyObject| {
+            core::ptr::eq((*ob).ob_type, crate::typeref::get_options_type())
+                || core::ptr::eq((*ob).ob_type, crate::typeref::get_int_type())
+        };
+        if !is_int(lhs) || !is_int(rhs) {
+            let ret = Py_NotImplemented();
+            Py_INCREF(ret);
+            return ret;
+        }
+        match (flags_of(lhs), flags_of(rhs)) {
+            (Some(lhs), Some(rhs)) => options_new(lhs | rhs),
+            _ => raise_exception(PyExc_ValueError, "Invalid opts"),
+        }
+    }
+}
+
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+unsafe extern "C" fn orjson_options_repr(slf: *mut PyObject) -> *mut PyObject {
+    unsafe {
+        let repr = format!("orjson.Options({})", (*slf.cast::<Options>()).opts);
+        PyUnicode_FromStringAndSize(repr.as_ptr().cast::<c_char>(), repr.len() as isize)
+    }
+}
+
+/// Returns null with an exception set if the type cannot be readied.
+#[unsafe(no_mangle)]
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe extern "C" fn orjson_optionstype_new() -> *mut PyTypeObject {
+    unsafe {
+        #[cfg(Py_GIL_DISABLED)]
+        let tp_flags: AtomicCULong =
+            AtomicCULong::new(Py_TPFLAGS_DEFAULT | pyo3_ffi::Py_TPFLAGS_IMMUTABLETYPE);
+        #[cfg(all(Py_3_10, not(Py_GIL_DISABLED)))]
+        let tp_flags: core::ffi::c_ulong = Py_TPFLAGS_DEFAULT | pyo3_ffi::Py_TPFLAGS_IMMUTABLETYPE;
+        #[cfg(not(Py_3_10))]
+        let tp_flags: core::ffi::c_ulong = Py_TPFLAGS_DEFAULT;
+        let number_methods = Box::new(PyNumberMethods {
+            nb_or: Some(orjson_options_or),
+            nb_int: Some(orjson_options_index),
+            nb_index: Some(orjson_options_index),
+            ..core::mem::zeroed()
+        });
+        let methods = Box::new([
+            PyMethodDef {
+                ml_name: c"with_option".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    PyCFunction: orjson_options_with_option,
+                },
+                ml_flags: METH_O,
+                ml_doc: c"with_option(flag, /)\n--\n\nReturn a copy with flag set.".as_ptr(),
+            },
+            PyMethodDef {
+                ml_name: c"without_option".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    PyCFunction: orjson_options_without_option,
+                },
+                ml_flags: METH_O,
+                ml_doc: c"without_option(flag, /)\n--\n\nReturn a copy with flag cleared.".as_ptr(),
+            },
+            core::mem::zeroed(),
+        ]);
+        let ob = Box::new(PyTypeObject {
+            ob_base: PyVarObject {
+                ob_base: PyObject {
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_tid: 0,
+                    #[cfg(all(Py_GIL_DISABLED, Py_3_14))]
+                    ob_flags: 0,
+                    #[cfg(all(Py_GIL_DISABLED, not(Py_3_14)))]
+                    _padding: 0,
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_mutex: pymutex_new!(),
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_gc_bits: 0,
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_ref_local: AtomicU32::new(crate::ffi::compat::_Py_IMMORTAL_REFCNT_LOCAL),
+                    #[cfg(Py_GIL_DISABLED)]
+                    ob_ref_shared: AtomicIsize::new(0),
+                    #[cfg(all(Py_3_12, not(Py_GIL_DISABLED)))]
+                    ob_refcnt: pyo3_ffi::PyObjectObRefcnt { ob_refcnt: 0 },
+                    #[cfg(not(Py_3_12))]
+                    ob_refcnt: 0,
+                    #[cfg(PyPy)]
+                    ob_pypy_link: 0,
+                    ob_type: &raw mut PyType_Type,
+                },
+                #[cfg(not(GraalPy))]
+                ob_size: 0,
+                #[cfg(GraalPy)]
+                _ob_size_graalpy: 0,
+            },
+            tp_name: c"orjson.Options".as_ptr(),
+            tp_basicsize: core::mem::size_of::<Options>() as isize,
+            tp_itemsize: 0,
+            tp_dealloc: Some(orjson_options_dealloc),
+            tp_init: None,
+            tp_new: Some(orjson_options_tp_new),
+            tp_flags: tp_flags,
+            // ...
+            tp_bases: null_mut(),
+            tp_cache: null_mut(),
+            tp_del: None,
+            tp_finalize: None,
+            tp_free: None,
+            tp_is_gc: None,
+            tp_mro: null_mut(),
+            tp_subclasses: null_mut(),
+            tp_vectorcall: None,
+            tp_version_tag: 0,
+            tp_weaklist: null_mut(),
+            tp_vectorcall_offset: 0,
+            tp_getattr: None,
+            tp_setattr: None,
+            tp_as_async: null_mut(),
+            tp_repr: Some(orjson_options_repr),
+            tp_as_number: Box::into_raw(number_methods),
+            tp_as_sequence: null_mut(),
+            tp_as_mapping: null_mut(),
+            tp_hash: None,
+            tp_call: None,
+            tp_str: None,
+            tp_getattro: None,
+            tp_setattro: None,
+            tp_as_buffer: null_mut(),
+            tp_doc: core::ptr::null_mut(),
+            tp_traverse: None,
+            tp_clear: None,
+            tp_richcompare: None,
+            tp_weaklistoffset: 0,
+            tp_iter: None,
+            tp_iternext: None,
+            tp_methods: Box::into_raw(methods).cast::<PyMethodDef>(),
+            tp_members: null_mut(),
+            tp_getset: null_mut(),
+            tp_base: null_mut(),
+            tp_dict: null_mut(),
+            tp_descr_get: None,
+            tp_descr_set: None,
+            tp_dictoffset: 0,
+            tp_alloc: None,
+            #[cfg(Py_3_12)]
+            tp_watched: 0,
+        });
+        let ob_ptr = Box::into_raw(ob);
+        if PyType_Ready(ob_ptr) < 0 {
+            drop(Box::from_raw(ob_ptr));
+            return null_mut();
+        }
+        ob_ptr
+    }
+}
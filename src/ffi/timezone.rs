@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::ffi::PyObject;
+
+/// The layout of `datetime.timezone`, which is not part of the C API but has
+/// been unchanged since the type was added.
+#[cfg(CPython)]
+#[repr(C)]
+pub(crate) struct PyDateTime_TimeZone {
+    pub ob_base: PyObject,
+    pub offset: *mut PyObject,
+    pub name: *mut PyObject,
+}
+
+/// The `datetime.timedelta` offset of a `datetime.timezone`, borrowed.
+#[cfg(CPython)]
+#[allow(non_snake_case)]
+#[inline(always)]
+pub(crate) unsafe fn PyTimeZone_GET_OFFSET(op: *mut PyObject) -> *mut PyObject {
+    unsafe { (*op.cast::<PyDateTime_TimeZone>()).offset }
+}
@@ -7,19 +7,31 @@
 //! Each interpreter has its own instance of all PyObject pointers and caches.
 
 use core::ffi::CStr;
-use core::ptr::{NonNull, null_mut};
+use core::ptr::{null, null_mut};
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, AtomicU64, AtomicUsize, Ordering};
 use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
-use std::thread::LocalKey;
+use std::collections::hash_map::Entry;
+use std::ffi::CString;
+use std::sync::{Mutex, OnceLock, RwLock};
 
-use crate::deserialize::cache::KeyMap;
+use crate::deserialize::cache::{KeyCacheStats, KeyMap};
 use crate::ffi::{
     Py_DECREF, Py_False, Py_INCREF, Py_None, Py_True, Py_XDECREF, PyBool_Type, PyByteArray_Type,
-    PyBytes_Type, PyDict_Type, PyErr_Clear, PyErr_NewException, PyExc_TypeError, PyFloat_Type,
-    PyImport_ImportModule, PyList_Type, PyLong_Type, PyMapping_GetItemString, PyMemoryView_Type,
-    PyObject, PyObject_GenericGetDict, PyTuple_Type, PyTypeObject, PyUnicode_InternFromString,
-    PyUnicode_New, PyUnicode_Type, orjson_fragmenttype_new,
+    PyBytes_Type, PyDict_GetItemString, PyDict_New, PyDict_SetItemString, PyDict_Type,
+    PyDictProxy_Type, PyErr_Clear, PyErr_NewException, PyExc_TypeError, PyExc_ValueError,
+    PyFloat_Type, PyImport_ImportModule, PyList_Type, PyLong_Type, PyMapping_GetItemString,
+    PyMemoryView_Type, PyModule_GetDict, PyModule_GetName, PyObject, PyObject_GenericGetDict,
+    PyObject_GetAttrString, PyProperty_Type, PyRange_Type, PyTuple_Type, PyTypeObject,
+    PyUnicode_InternFromString, PyUnicode_New, PyUnicode_Type, orjson_arraystreamtype_new,
+    orjson_fragmenttype_new, orjson_optionstype_new,
 };
+#[cfg(not(PyPy))]
+use crate::ffi::{PyInterpreterState_Get, PyInterpreterState_GetID};
+use crate::serialize::BytesMode;
+#[cfg(not(Py_GIL_DISABLED))]
+use crate::serialize::cache::{NonStrKeyMap, TupleMap};
+#[cfg(not(Py_GIL_DISABLED))]
+use crate::serialize::writer::BufferPool;
 
 /// Per-interpreter state containing all interpreter-specific PyObject pointers and caches.
 /// This struct is Send + Sync because:
@@ -33,6 +45,20 @@ pub(crate) struct InterpreterState {
     // Keyword argument strings
     pub default: *mut PyObject,
     pub option: *mut PyObject,
+    pub envelope: *mut PyObject,
+    pub tuple_fields: *mut PyObject,
+    pub max_output_bytes: *mut PyObject,
+    pub float_precision: *mut PyObject,
+    pub on_skip: *mut PyObject,
+    pub skip_key_prefix: *mut PyObject,
+    pub chunk_size: *mut PyObject,
+    pub size_hint: *mut PyObject,
+    pub key_filter: *mut PyObject,
+    pub object_hook: *mut PyObject,
+    pub object_pairs_hook: *mut PyObject,
+    pub bytes_mode_str: *mut PyObject,
+    pub buffer_pool_size_str: *mut PyObject,
+    pub reset_str: *mut PyObject,
 
     // Builtin objects
     pub none: *mut PyObject,
@@ -55,18 +81,45 @@ pub(crate) struct InterpreterState {
     pub datetime_type: *mut PyTypeObject,
     pub date_type: *mut PyTypeObject,
     pub time_type: *mut PyTypeObject,
+    /// `datetime.timezone`, i.e., a fixed offset such as `timezone.utc`.
+    pub timezone_type: *mut PyTypeObject,
     pub uuid_type: *mut PyTypeObject,
     pub enum_type: *mut PyTypeObject,
     pub field_type: *mut PyTypeObject,
     pub fragment_type: *mut PyTypeObject,
-    pub zoneinfo_type: *mut PyTypeObject,
+    pub options_type: *mut PyTypeObject,
+    pub array_stream_type: *mut PyTypeObject,
+    pub chainmap_type: *mut PyTypeObject,
+    pub mappingproxy_type: *mut PyTypeObject,
+    pub property_type: *mut PyTypeObject,
+    pub range_type: *mut PyTypeObject,
+    pub simple_namespace_type: *mut PyTypeObject,
+    pub weakref_type: *mut PyTypeObject,
+    pub weakproxy_type: *mut PyTypeObject,
+    pub weakcallableproxy_type: *mut PyTypeObject,
+    pub array_type: *mut PyTypeObject,
+    pub dict_keys_type: *mut PyTypeObject,
+    pub dict_values_type: *mut PyTypeObject,
+    pub dict_items_type: *mut PyTypeObject,
+    /// Resolved on first use by `DateTime::offset()` rather than imported.
+    pub zoneinfo_type: AtomicPtr<PyTypeObject>,
+    /// The type of `pandas.NaT`, resolved like `zoneinfo_type`.
+    pub pandas_nat_type: AtomicPtr<PyTypeObject>,
+    /// `ipaddress.IPv4Address`, `IPv6Address`, `IPv4Network`, and
+    /// `IPv6Network`, resolved together like `zoneinfo_type`.
+    pub ipaddress_types: [AtomicPtr<PyTypeObject>; 4],
+    /// `fractions.Fraction`, resolved like `zoneinfo_type`.
+    pub fraction_type: AtomicPtr<PyTypeObject>,
 
     // Interned strings
     pub utcoffset_method_str: *mut PyObject,
     pub normalize_method_str: *mut PyObject,
     pub convert_method_str: *mut PyObject,
+    pub write_method_str: *mut PyObject,
     pub dst_str: *mut PyObject,
     pub dict_str: *mut PyObject,
+    pub json_dunder_str: *mut PyObject,
+    pub fspath_str: *mut PyObject,
     pub dataclass_fields_str: *mut PyObject,
     pub slots_str: *mut PyObject,
     pub field_type_str: *mut PyObject,
@@ -78,12 +131,41 @@ pub(crate) struct InterpreterState {
 
     // Exception types
     pub json_encode_error: *mut PyObject,
-    pub json_decode_error: *mut PyObject,
+    /// Created on first use by `json_decode_error()`, as it imports `json`.
+    pub json_decode_error: AtomicPtr<PyObject>,
+    /// The `__dict__` of the module, borrowed, which `JSONDecodeError` is
+    /// added to when created.
+    pub module_dict: *mut PyObject,
+
+    // Settings from configure(), per-interpreter
+    pub bytes_mode: AtomicU8,
+    /// The flags given to `set_default_options()`.
+    pub default_opts: AtomicU64,
+    /// Whether `disable_numpy()` was called.
+    pub numpy_disabled: AtomicBool,
+    /// Handlers given to `register_type()`, keyed by type.
+    pub type_handlers: *mut PyObject,
+
+    /// The interpreter the state belongs to, whose GIL releases it.
+    pub interpreter_id: i64,
+    /// Calls in progress holding the state with a `StateGuard`.
+    pub calls: AtomicUsize,
+    /// Whether the state was dropped by `reset_state()` or `shutdown()` and
+    /// is released once `calls` is 0.
+    pub retired: AtomicBool,
 
     // Cache - per-interpreter (using UnsafeCell for interior mutability)
     // Safe because GIL ensures single-threaded access within an interpreter
     #[cfg(not(Py_GIL_DISABLED))]
     pub key_map: core::cell::UnsafeCell<KeyMap>,
+    #[cfg(not(Py_GIL_DISABLED))]
+    pub key_cache_stats: core::cell::UnsafeCell<KeyCacheStats>,
+    #[cfg(not(Py_GIL_DISABLED))]
+    pub non_str_key_map: core::cell::UnsafeCell<NonStrKeyMap>,
+    #[cfg(not(Py_GIL_DISABLED))]
+    pub tuple_map: core::cell::UnsafeCell<TupleMap>,
+    #[cfg(not(Py_GIL_DISABLED))]
+    pub buffer_pool: core::cell::UnsafeCell<BufferPool>,
 }
 
 unsafe fn look_up_type_object(module_name: &CStr, member_name: &CStr) -> *mut PyTypeObject {
@@ -97,23 +179,50 @@ unsafe fn look_up_type_object(module_name: &CStr, member_name: &CStr) -> *mut Py
     }
 }
 
+/// The base of `JSONDecodeError`, as a new reference: `json.JSONDecodeError`
+/// or, if it cannot be imported, e.g., in an embedded interpreter without the
+/// `json` module, `ValueError`, which `json.JSONDecodeError` derives from.
+#[cold]
+unsafe fn look_up_json_decode_error_base() -> *mut PyObject {
+    unsafe {
+        let module = PyImport_ImportModule(c"json".as_ptr());
+        if !module.is_null() {
+            let base = PyObject_GetAttrString(module, c"JSONDecodeError".as_ptr());
+            Py_DECREF(module);
+            if !base.is_null() {
+                return base;
+            }
+        }
+        PyErr_Clear();
+        Py_INCREF(PyExc_ValueError);
+        PyExc_ValueError
+    }
+}
+
 #[cfg(not(PyPy))]
 unsafe fn look_up_datetime(
     datetime_type: &mut *mut PyTypeObject,
     date_type: &mut *mut PyTypeObject,
     time_type: &mut *mut PyTypeObject,
-    zoneinfo_type: &mut *mut PyTypeObject,
+    timezone_type: &mut *mut PyTypeObject,
 ) {
     unsafe {
         crate::ffi::PyDateTime_IMPORT();
         let datetime_capsule = crate::ffi::PyCapsule_Import(c"datetime.datetime_CAPI".as_ptr(), 1)
             .cast::<crate::ffi::PyDateTime_CAPI>();
-        debug_assert!(!datetime_capsule.is_null());
+        if datetime_capsule.is_null() {
+            // e.g., an embedded interpreter without the datetime module. The
+            // types stay null, so datetime objects are not recognized, and
+            // reset_state() retries.
+            cold_path!();
+            PyErr_Clear();
+            return;
+        }
 
         *datetime_type = (*datetime_capsule).DateTimeType;
         *date_type = (*datetime_capsule).DateType;
         *time_type = (*datetime_capsule).TimeType;
-        *zoneinfo_type = (*datetime_capsule).TZInfoType;
+        *timezone_type = ob_type!((*datetime_capsule).TimeZone_UTC);
     }
 }
 
@@ -122,27 +231,47 @@ unsafe fn look_up_datetime(
     datetime_type: &mut *mut PyTypeObject,
     date_type: &mut *mut PyTypeObject,
     time_type: &mut *mut PyTypeObject,
-    zoneinfo_type: &mut *mut PyTypeObject,
+    timezone_type: &mut *mut PyTypeObject,
 ) {
     unsafe {
         *datetime_type = look_up_type_object(c"datetime", c"datetime");
         *date_type = look_up_type_object(c"datetime", c"date");
         *time_type = look_up_type_object(c"datetime", c"time");
-        *zoneinfo_type = look_up_type_object(c"zoneinfo", c"ZoneInfo");
+        *timezone_type = look_up_type_object(c"datetime", c"timezone");
     }
 }
 
 impl InterpreterState {
     /// Initialize a new interpreter state for the current interpreter.
+    ///
+    /// If the module already exports `Fragment` and `JSONDecodeError`, i.e.,
+    /// the state is being rebuilt after `reset_state()`, those are reused so
+    /// that the objects users hold remain the ones recognized and raised.
+    ///
+    /// Returns `None` with an exception set if the `Fragment` or `Options`
+    /// type cannot be created, so that no state has a null type to compare
+    /// against or construct.
     #[cold]
     #[cfg_attr(feature = "optimize", optimize(size))]
-    pub(crate) unsafe fn new() -> Self {
+    pub(crate) unsafe fn new(module: *mut PyObject) -> Option<Self> {
         unsafe {
-            debug_assert!(crate::opt::MAX_OPT < i32::from(u16::MAX));
-
             let mut state = InterpreterState {
                 default: null_mut(),
                 option: null_mut(),
+                envelope: null_mut(),
+                tuple_fields: null_mut(),
+                max_output_bytes: null_mut(),
+                float_precision: null_mut(),
+                on_skip: null_mut(),
+                skip_key_prefix: null_mut(),
+                chunk_size: null_mut(),
+                size_hint: null_mut(),
+                key_filter: null_mut(),
+                object_hook: null_mut(),
+                object_pairs_hook: null_mut(),
+                bytes_mode_str: null_mut(),
+                buffer_pool_size_str: null_mut(),
+                reset_str: null_mut(),
                 none: Py_None(),
                 true_: Py_True(),
                 false_: Py_False(),
@@ -161,16 +290,37 @@ impl InterpreterState {
                 datetime_type: null_mut(),
                 date_type: null_mut(),
                 time_type: null_mut(),
+                timezone_type: null_mut(),
                 uuid_type: null_mut(),
                 enum_type: null_mut(),
                 field_type: null_mut(),
                 fragment_type: null_mut(),
-                zoneinfo_type: null_mut(),
+                options_type: null_mut(),
+                array_stream_type: null_mut(),
+                chainmap_type: null_mut(),
+                mappingproxy_type: &raw mut PyDictProxy_Type,
+                property_type: &raw mut PyProperty_Type,
+                range_type: &raw mut PyRange_Type,
+                simple_namespace_type: null_mut(),
+                weakref_type: null_mut(),
+                weakproxy_type: null_mut(),
+                weakcallableproxy_type: null_mut(),
+                array_type: null_mut(),
+                dict_keys_type: null_mut(),
+                dict_values_type: null_mut(),
+                dict_items_type: null_mut(),
+                zoneinfo_type: AtomicPtr::new(null_mut()),
+                pandas_nat_type: AtomicPtr::new(null_mut()),
+                ipaddress_types: [const { AtomicPtr::new(null_mut()) }; 4],
+                fraction_type: AtomicPtr::new(null_mut()),
                 utcoffset_method_str: null_mut(),
                 normalize_method_str: null_mut(),
                 convert_method_str: null_mut(),
+                write_method_str: null_mut(),
                 dst_str: null_mut(),
                 dict_str: null_mut(),
+                json_dunder_str: null_mut(),
+                fspath_str: null_mut(),
                 dataclass_fields_str: null_mut(),
                 slots_str: null_mut(),
                 field_type_str: null_mut(),
@@ -180,9 +330,25 @@ impl InterpreterState {
                 value_str: null_mut(),
                 int_attr_str: null_mut(),
                 json_encode_error: null_mut(),
-                json_decode_error: null_mut(),
+                json_decode_error: AtomicPtr::new(null_mut()),
+                module_dict: null_mut(),
+                bytes_mode: AtomicU8::new(BytesMode::Passthrough as u8),
+                default_opts: AtomicU64::new(0),
+                numpy_disabled: AtomicBool::new(false),
+                type_handlers: null_mut(),
+                interpreter_id: current_interpreter_id(),
+                calls: AtomicUsize::new(0),
+                retired: AtomicBool::new(false),
                 #[cfg(not(Py_GIL_DISABLED))]
                 key_map: core::cell::UnsafeCell::new(KeyMap::default()),
+                #[cfg(not(Py_GIL_DISABLED))]
+                key_cache_stats: core::cell::UnsafeCell::new(KeyCacheStats::default()),
+                #[cfg(not(Py_GIL_DISABLED))]
+                non_str_key_map: core::cell::UnsafeCell::new(NonStrKeyMap::default()),
+                #[cfg(not(Py_GIL_DISABLED))]
+                tuple_map: core::cell::UnsafeCell::new(TupleMap::default()),
+                #[cfg(not(Py_GIL_DISABLED))]
+                buffer_pool: core::cell::UnsafeCell::new(BufferPool::new()),
             };
 
             state.none_type = unsafe { (*state.none).ob_type };
@@ -191,21 +357,61 @@ impl InterpreterState {
                 &mut state.datetime_type,
                 &mut state.date_type,
                 &mut state.time_type,
-                &mut state.zoneinfo_type,
+                &mut state.timezone_type,
             );
 
             state.uuid_type = look_up_type_object(c"uuid", c"UUID");
             state.enum_type = look_up_type_object(c"enum", c"EnumMeta");
             state.field_type = look_up_type_object(c"dataclasses", c"_FIELD");
-
-            state.fragment_type = orjson_fragmenttype_new();
+            state.chainmap_type = look_up_type_object(c"collections", c"ChainMap");
+            state.simple_namespace_type = look_up_type_object(c"types", c"SimpleNamespace");
+            state.weakref_type = look_up_type_object(c"_weakref", c"ReferenceType");
+            state.weakproxy_type = look_up_type_object(c"_weakref", c"ProxyType");
+            state.weakcallableproxy_type = look_up_type_object(c"_weakref", c"CallableProxyType");
+            state.array_type = look_up_type_object(c"array", c"array");
+            state.dict_keys_type = look_up_type_object(c"_collections_abc", c"dict_keys");
+            state.dict_values_type = look_up_type_object(c"_collections_abc", c"dict_values");
+            state.dict_items_type = look_up_type_object(c"_collections_abc", c"dict_items");
+
+            let module_dict = PyModule_GetDict(module);
+            state.module_dict = module_dict;
+            let fragment_type = PyDict_GetItemString(module_dict, c"Fragment".as_ptr());
+            if fragment_type.is_null() {
+                state.fragment_type = orjson_fragmenttype_new();
+                if state.fragment_type.is_null() {
+                    return None;
+                }
+            } else {
+                state.fragment_type = fragment_type.cast::<PyTypeObject>();
+            }
+            let options_type = PyDict_GetItemString(module_dict, c"Options".as_ptr());
+            if options_type.is_null() {
+                state.options_type = orjson_optionstype_new();
+                if state.options_type.is_null() {
+                    return None;
+                }
+            } else {
+                state.options_type = options_type.cast::<PyTypeObject>();
+            }
+            let array_stream_type = PyDict_GetItemString(module_dict, c"ArrayStream".as_ptr());
+            if array_stream_type.is_null() {
+                state.array_stream_type = orjson_arraystreamtype_new();
+                if state.array_stream_type.is_null() {
+                    return None;
+                }
+            } else {
+                state.array_stream_type = array_stream_type.cast::<PyTypeObject>();
+            }
 
             state.int_attr_str = PyUnicode_InternFromString(c"int".as_ptr());
             state.utcoffset_method_str = PyUnicode_InternFromString(c"utcoffset".as_ptr());
             state.normalize_method_str = PyUnicode_InternFromString(c"normalize".as_ptr());
             state.convert_method_str = PyUnicode_InternFromString(c"convert".as_ptr());
+            state.write_method_str = PyUnicode_InternFromString(c"write".as_ptr());
             state.dst_str = PyUnicode_InternFromString(c"dst".as_ptr());
             state.dict_str = PyUnicode_InternFromString(c"__dict__".as_ptr());
+            state.json_dunder_str = PyUnicode_InternFromString(c"__json__".as_ptr());
+            state.fspath_str = PyUnicode_InternFromString(c"__fspath__".as_ptr());
             state.dataclass_fields_str = PyUnicode_InternFromString(c"__dataclass_fields__".as_ptr());
             state.slots_str = PyUnicode_InternFromString(c"__slots__".as_ptr());
             state.field_type_str = PyUnicode_InternFromString(c"_field_type".as_ptr());
@@ -215,21 +421,192 @@ impl InterpreterState {
             state.value_str = PyUnicode_InternFromString(c"value".as_ptr());
             state.default = PyUnicode_InternFromString(c"default".as_ptr());
             state.option = PyUnicode_InternFromString(c"option".as_ptr());
+            state.envelope = PyUnicode_InternFromString(c"envelope".as_ptr());
+            state.tuple_fields = PyUnicode_InternFromString(c"tuple_fields".as_ptr());
+            state.max_output_bytes = PyUnicode_InternFromString(c"max_output_bytes".as_ptr());
+            state.float_precision = PyUnicode_InternFromString(c"float_precision".as_ptr());
+            state.on_skip = PyUnicode_InternFromString(c"on_skip".as_ptr());
+            state.skip_key_prefix = PyUnicode_InternFromString(c"skip_key_prefix".as_ptr());
+            state.chunk_size = PyUnicode_InternFromString(c"chunk_size".as_ptr());
+            state.size_hint = PyUnicode_InternFromString(c"size_hint".as_ptr());
+            state.key_filter = PyUnicode_InternFromString(c"key_filter".as_ptr());
+            state.object_hook = PyUnicode_InternFromString(c"object_hook".as_ptr());
+            state.object_pairs_hook = PyUnicode_InternFromString(c"object_pairs_hook".as_ptr());
+            state.bytes_mode_str = PyUnicode_InternFromString(c"bytes_mode".as_ptr());
+            state.buffer_pool_size_str = PyUnicode_InternFromString(c"buffer_pool_size".as_ptr());
+            state.reset_str = PyUnicode_InternFromString(c"reset".as_ptr());
+
+            state.type_handlers = PyDict_New();
 
             state.json_encode_error = PyExc_TypeError;
             Py_INCREF(state.json_encode_error);
-            let json_jsondecodeerror =
-                look_up_type_object(c"json", c"JSONDecodeError").cast::<PyObject>();
-            debug_assert!(!json_jsondecodeerror.is_null());
-            state.json_decode_error = PyErr_NewException(
-                c"hyperjson.JSONDecodeError".as_ptr(),
-                json_jsondecodeerror,
+            let json_decode_error = PyDict_GetItemString(module_dict, c"JSONDecodeError".as_ptr());
+            if !json_decode_error.is_null() {
+                Py_INCREF(json_decode_error);
+            }
+            state.json_decode_error = AtomicPtr::new(json_decode_error);
+
+            Some(state)
+        }
+    }
+
+    /// `JSONDecodeError`, created and added to the module on first use so
+    /// that an interpreter that does not deserialize invalid input never
+    /// imports `json`. Returns null with an exception set if it cannot be
+    /// created.
+    #[inline(always)]
+    pub(crate) unsafe fn json_decode_error(&self) -> *mut PyObject {
+        let json_decode_error = self.json_decode_error.load(Ordering::Acquire);
+        if !json_decode_error.is_null() {
+            return json_decode_error;
+        }
+        unsafe { self.create_json_decode_error() }
+    }
+
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    unsafe fn create_json_decode_error(&self) -> *mut PyObject {
+        unsafe {
+            let base = look_up_json_decode_error_base();
+            let json_decode_error_name = CString::new(format!(
+                "{}.JSONDecodeError",
+                MODULE_NAME
+                    .get()
+                    .map_or(c"hyperjson", CString::as_c_str)
+                    .to_string_lossy()
+            ))
+            .unwrap();
+            let json_decode_error =
+                PyErr_NewException(json_decode_error_name.as_ptr(), base, null_mut());
+            Py_DECREF(base);
+            if json_decode_error.is_null() {
+                return null_mut();
+            }
+            // Importing `json` may release the GIL, so another thread may have
+            // created it in the meantime. The first one is kept.
+            match self.json_decode_error.compare_exchange(
                 null_mut(),
-            );
-            debug_assert!(!state.json_decode_error.is_null());
-            Py_XDECREF(json_jsondecodeerror);
+                json_decode_error,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // The state keeps its reference and the module its own,
+                    // so that it is reused when the state is rebuilt.
+                    if PyDict_SetItemString(
+                        self.module_dict,
+                        c"JSONDecodeError".as_ptr(),
+                        json_decode_error,
+                    ) != 0
+                    {
+                        PyErr_Clear();
+                    }
+                    json_decode_error
+                }
+                Err(existing) => {
+                    Py_DECREF(json_decode_error);
+                    existing
+                }
+            }
+        }
+    }
 
-            state
+    /// Release the caches of a retired state, which a call may still hold.
+    /// Unlike the rest of the state, they are used only between calls into
+    /// Python, so no caller holds them.
+    #[cfg(not(Py_GIL_DISABLED))]
+    #[cold]
+    unsafe fn release_caches(&self) {
+        unsafe {
+            *self.key_map.get() = KeyMap::default();
+            *self.non_str_key_map.get() = NonStrKeyMap::default();
+            *self.tuple_map.get() = TupleMap::default();
+            *self.buffer_pool.get() = BufferPool::new();
+        }
+    }
+}
+
+/// Release the references the state owns, with the GIL of its interpreter.
+/// `Fragment` is owned by the module and carried over to the rebuilt state,
+/// as is `JSONDecodeError` once the module has it. Builtin objects and the
+/// types from the datetime CAPI are borrowed.
+impl Drop for InterpreterState {
+    fn drop(&mut self) {
+        unsafe {
+            for ptr in [
+                self.default,
+                self.option,
+                self.envelope,
+                self.tuple_fields,
+                self.max_output_bytes,
+                self.float_precision,
+                self.on_skip,
+                self.skip_key_prefix,
+                self.chunk_size,
+                self.size_hint,
+                self.key_filter,
+                self.object_hook,
+                self.object_pairs_hook,
+                self.bytes_mode_str,
+                self.buffer_pool_size_str,
+                self.reset_str,
+                self.empty_unicode,
+                self.uuid_type.cast::<PyObject>(),
+                self.enum_type.cast::<PyObject>(),
+                self.field_type.cast::<PyObject>(),
+                self.chainmap_type.cast::<PyObject>(),
+                self.simple_namespace_type.cast::<PyObject>(),
+                self.weakref_type.cast::<PyObject>(),
+                self.weakproxy_type.cast::<PyObject>(),
+                self.weakcallableproxy_type.cast::<PyObject>(),
+                self.array_type.cast::<PyObject>(),
+                self.dict_keys_type.cast::<PyObject>(),
+                self.dict_values_type.cast::<PyObject>(),
+                self.dict_items_type.cast::<PyObject>(),
+                self.zoneinfo_type
+                    .load(Ordering::Relaxed)
+                    .cast::<PyObject>(),
+                self.pandas_nat_type
+                    .load(Ordering::Relaxed)
+                    .cast::<PyObject>(),
+                self.fraction_type
+                    .load(Ordering::Relaxed)
+                    .cast::<PyObject>(),
+                self.utcoffset_method_str,
+                self.normalize_method_str,
+                self.convert_method_str,
+                self.write_method_str,
+                self.dst_str,
+                self.dict_str,
+                self.json_dunder_str,
+                self.fspath_str,
+                self.dataclass_fields_str,
+                self.slots_str,
+                self.field_type_str,
+                self.array_struct_str,
+                self.dtype_str,
+                self.descr_str,
+                self.value_str,
+                self.int_attr_str,
+                self.json_encode_error,
+                self.json_decode_error.load(Ordering::Relaxed),
+                self.type_handlers,
+            ] {
+                Py_XDECREF(ptr);
+            }
+            for ptr in &self.ipaddress_types {
+                Py_XDECREF(ptr.load(Ordering::Relaxed).cast::<PyObject>());
+            }
+            #[cfg(PyPy)]
+            for ptr in [
+                self.datetime_type,
+                self.date_type,
+                self.time_type,
+                self.timezone_type,
+            ] {
+                Py_XDECREF(ptr.cast::<PyObject>());
+            }
         }
     }
 }
@@ -240,72 +617,366 @@ impl InterpreterState {
 static INTERPRETER_STATES: OnceLock<Mutex<HashMap<usize, Box<InterpreterState>>>> =
     OnceLock::new();
 
+/// The module's `__name__` as given on first initialization, e.g., "hyperjson"
+/// or "hyperjson.hyperjson" if loaded as a submodule of the package. This is
+/// what `get_current_state()` imports to find the module of the interpreter.
+static MODULE_NAME: OnceLock<CString> = OnceLock::new();
+
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+unsafe fn module_name(module: *mut PyObject) -> &'static CStr {
+    MODULE_NAME.get_or_init(|| unsafe {
+        let name = PyModule_GetName(module);
+        if name.is_null() {
+            PyErr_Clear();
+            CString::from(c"hyperjson")
+        } else {
+            CString::from(CStr::from_ptr(name))
+        }
+    })
+}
+
+/// States resolved so far, keyed by interpreter ID (as usize for Send+Sync),
+/// so that a thread without `CACHED_STATE` finds the state of its interpreter
+/// without importing the module. Unlike module pointers, interpreter IDs are
+/// never reused within a process, so one interpreter's state is never handed
+/// to another.
+static STATES_BY_INTERPRETER: OnceLock<RwLock<HashMap<i64, usize>>> = OnceLock::new();
+
+#[inline(always)]
+fn current_interpreter_id() -> i64 {
+    #[cfg(not(PyPy))]
+    unsafe {
+        PyInterpreterState_GetID(PyInterpreterState_Get())
+    }
+    #[cfg(PyPy)]
+    0
+}
+
 /// Get or create the interpreter state for the given module.
-/// The module pointer uniquely identifies the interpreter.
+/// The module pointer uniquely identifies the interpreter. Returns null with
+/// an exception set if the state cannot be created.
 #[inline(always)]
 pub(crate) unsafe fn get_or_init_state(module: *mut PyObject) -> *const InterpreterState {
     unsafe {
+        #[cfg(feature = "pymalloc")]
+        crate::alloc::assert_single_interpreter(current_interpreter_id());
+
+        // `JSONDecodeError` is qualified by the name given on first
+        // initialization when it is created
+        module_name(module);
         let states = INTERPRETER_STATES.get_or_init(|| Mutex::new(HashMap::new()));
         let mut guard = states.lock().unwrap();
 
         // Use entry API for efficient lookup/insert
         // Convert pointer to usize for HashMap key (safe for comparison only)
         let module_key = module as usize;
-        let state_ptr = guard
-            .entry(module_key)
-            .or_insert_with(|| Box::new(InterpreterState::new()))
-            .as_ref() as *const InterpreterState;
+        let state_ptr = match guard.entry(module_key) {
+            Entry::Occupied(entry) => entry.into_mut().as_ref() as *const InterpreterState,
+            Entry::Vacant(entry) => match InterpreterState::new(module) {
+                Some(state) => entry.insert(Box::new(state)).as_ref() as *const InterpreterState,
+                None => return null(),
+            },
+        };
+        drop(guard);
+
+        // The module is executed in its own interpreter, so this is the
+        // state of the current interpreter.
+        STATES_BY_INTERPRETER
+            .get_or_init(|| RwLock::new(HashMap::new()))
+            .write()
+            .unwrap()
+            .insert(current_interpreter_id(), state_ptr as usize);
 
         // Leak the pointer - the state lives as long as the interpreter
         state_ptr
     }
 }
 
-/// Thread-local cache for the current interpreter's state pointer.
-/// This avoids repeated module imports for performance.
+/// Incremented by `reset_current_state()` so that every thread's
+/// `CACHED_STATE` from before the reset is treated as a miss.
+static STATE_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
 thread_local! {
-    static CACHED_STATE: std::cell::Cell<(*mut PyObject, *const InterpreterState)> = 
-        std::cell::Cell::new((null_mut(), null_mut()));
+    /// Thread-local cache for the current interpreter's state pointer, with
+    /// the generation and the ID of the interpreter it was resolved for, as a
+    /// thread may run more than one interpreter. This avoids locking
+    /// `STATES_BY_INTERPRETER` on every lookup.
+    static CACHED_STATE: core::cell::Cell<(*const InterpreterState, usize, i64)> =
+        const { core::cell::Cell::new((null(), 0, -1)) };
+}
+
+/// Look up the state of the current interpreter in `STATES_BY_INTERPRETER`.
+#[cold]
+#[inline(never)]
+fn find_interpreter_state() -> *const InterpreterState {
+    STATES_BY_INTERPRETER
+        .get()
+        .and_then(|states| {
+            states
+                .read()
+                .unwrap()
+                .get(&current_interpreter_id())
+                .copied()
+        })
+        .map_or(null(), |state| state as *const InterpreterState)
 }
 
 /// Get the current interpreter's state, using thread-local cache for performance.
-/// This imports the orjson module if not cached.
+/// A thread's first call finds the state by interpreter ID and imports the
+/// module by its `__name__` only if the interpreter has not resolved a state
+/// yet. Returns null if the module cannot be imported.
 #[inline(always)]
 pub(crate) unsafe fn get_current_state() -> *const InterpreterState {
     unsafe {
         // Try to get from cache first
-        let cached = CACHED_STATE.with(|cell| {
-            let (cached_module, cached_state) = cell.get();
-            if !cached_module.is_null() && !cached_state.is_null() {
-                Some((cached_module, cached_state))
-            } else {
-                None
+        let generation = STATE_GENERATION.load(Ordering::Relaxed);
+        let interpreter_id = current_interpreter_id();
+        let (cached_state, cached_generation, cached_interpreter_id) =
+            CACHED_STATE.with(core::cell::Cell::get);
+        if !cached_state.is_null()
+            && cached_generation == generation
+            && cached_interpreter_id == interpreter_id
+        {
+            return cached_state;
+        }
+
+        cold_path!();
+        let mut state = find_interpreter_state();
+        if state.is_null() {
+            // Interpreter not seen yet, or reset - import module
+            state = import_current_state();
+            if state.is_null() {
+                return null();
             }
-        });
+            // e.g., retired by `shutdown_states()` from another interpreter
+            release_retired_states();
+        }
 
-        if let Some((_cached_module, cached_state)) = cached {
-            // Verify the module is still valid by checking if it's the same interpreter
-            // For now, we'll just use it - in practice, the module pointer should be stable
-            // within a thread for the same interpreter
-            return cached_state;
+        CACHED_STATE.with(|cell| cell.set((state, generation, interpreter_id)));
+        state
+    }
+}
+
+/// Resolve the current interpreter's state by importing the module. An
+/// exception already set, e.g., by a callback that called `reset_state()`
+/// and raised, is kept rather than lost to the import.
+#[cold]
+#[inline(never)]
+unsafe fn import_current_state() -> *const InterpreterState {
+    unsafe {
+        #[cfg(Py_3_12)]
+        let pending = crate::ffi::PyErr_GetRaisedException();
+        #[cfg(not(Py_3_12))]
+        let (mut tp, mut val, mut traceback) = (null_mut(), null_mut(), null_mut());
+        #[cfg(not(Py_3_12))]
+        crate::ffi::PyErr_Fetch(&mut tp, &mut val, &mut traceback);
+
+        let module_name = MODULE_NAME.get().map_or(c"hyperjson", CString::as_c_str);
+        let module = PyImport_ImportModule(module_name.as_ptr());
+        let state = if module.is_null() {
+            null()
+        } else {
+            let state = get_or_init_state(module);
+            // The state is owned by INTERPRETER_STATES, not the module
+            Py_DECREF(module);
+            state
+        };
+        if state.is_null() {
+            // The module can fail to import, e.g., during interpreter
+            // finalization. Callers check for null and raise RuntimeError.
+            PyErr_Clear();
         }
 
-        // Cache miss - import module and cache it
-        let module = PyImport_ImportModule(c"hyperjson".as_ptr());
-        if module.is_null() {
-            // This shouldn't happen, but if it does, we'll crash
-            core::hint::unreachable_unchecked();
+        #[cfg(Py_3_12)]
+        if !pending.is_null() {
+            crate::ffi::PyErr_SetRaisedException(pending);
+        }
+        #[cfg(not(Py_3_12))]
+        if !tp.is_null() {
+            crate::ffi::PyErr_Restore(tp, val, traceback);
         }
-        let state = get_or_init_state(module);
-        
-        // Cache it
-        CACHED_STATE.with(|cell| {
-            cell.set((module, state));
-        });
-        
-        // Don't DECREF the module - we're keeping it alive for the cache
-        // The module will be cleaned up when the interpreter is destroyed
         state
     }
 }
 
+/// States dropped by `reset_current_state()` or `shutdown_states()`, each
+/// released by `release_retired_states()` once no call holds it. They stay
+/// boxed, as calls hold pointers to them.
+#[allow(clippy::vec_box)]
+static RETIRED_STATES: Mutex<Vec<Box<InterpreterState>>> = Mutex::new(Vec::new());
+
+/// Holds the state of the current interpreter for a call that may run Python
+/// code, so that `reset_state()` from that code, or from another thread while
+/// the GIL is released, does not release the state while the call uses it.
+pub(crate) struct StateGuard {
+    state: *const InterpreterState,
+}
+
+impl StateGuard {
+    /// Hold the state of the current interpreter, or return `None` if it
+    /// cannot be resolved, as for `get_current_state()`.
+    #[inline(always)]
+    pub(crate) unsafe fn new() -> Option<Self> {
+        unsafe {
+            let state = get_current_state();
+            if state.is_null() {
+                return None;
+            }
+            (*state).calls.fetch_add(1, Ordering::Relaxed);
+            Some(StateGuard { state: state })
+        }
+    }
+}
+
+impl core::ops::Deref for StateGuard {
+    type Target = InterpreterState;
+
+    #[inline(always)]
+    fn deref(&self) -> &InterpreterState {
+        unsafe { &*self.state }
+    }
+}
+
+impl Drop for StateGuard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe {
+            let calls = (*self.state).calls.fetch_sub(1, Ordering::Relaxed);
+            if calls == 1 && (*self.state).retired.load(Ordering::Relaxed) {
+                cold_path!();
+                release_retired_states();
+            }
+        }
+    }
+}
+
+/// Retire `state`, which is no longer found by `get_current_state()`, so
+/// that it is released once no call holds it.
+#[cfg(not(Py_GIL_DISABLED))]
+#[cold]
+fn retire_state(state: Box<InterpreterState>) {
+    state.retired.store(true, Ordering::Relaxed);
+    RETIRED_STATES.lock().unwrap().push(state);
+}
+
+/// Release the retired states of the current interpreter that no call holds.
+#[cold]
+#[inline(never)]
+unsafe fn release_retired_states() {
+    let interpreter_id = current_interpreter_id();
+    let released: Vec<Box<InterpreterState>> = {
+        let mut retired = RETIRED_STATES.lock().unwrap();
+        let (released, kept) = core::mem::take(&mut *retired)
+            .into_iter()
+            .partition(|state| {
+                state.interpreter_id == interpreter_id && state.calls.load(Ordering::Relaxed) == 0
+            });
+        *retired = kept;
+        released
+    };
+    // dropped without the lock, as releasing a reference may run Python code
+    // that calls back into the module
+    drop(released);
+}
+
+/// Drop the current interpreter's state so that the next call rebuilds it,
+/// e.g., to pick up a type that was monkeypatched after import. The old state
+/// is released once no call holds it. Returns false if the module cannot be
+/// imported.
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe fn reset_current_state() -> bool {
+    unsafe {
+        let module_name = MODULE_NAME.get().map_or(c"hyperjson", CString::as_c_str);
+        let module = PyImport_ImportModule(module_name.as_ptr());
+        if module.is_null() {
+            cold_path!();
+            PyErr_Clear();
+            return false;
+        }
+        if let Some(states) = STATES_BY_INTERPRETER.get() {
+            states.write().unwrap().remove(&current_interpreter_id());
+        }
+        // Other threads see the new generation on their next lookup and so
+        // never use their cached pointer to the dropped state.
+        STATE_GENERATION.fetch_add(1, Ordering::Relaxed);
+        CACHED_STATE.with(|cell| cell.set((null(), 0, -1)));
+        let old = INTERPRETER_STATES
+            .get()
+            .and_then(|states| states.lock().unwrap().remove(&(module as usize)));
+        Py_DECREF(module);
+        // The state is retired rather than released, as it may still be in
+        // use: a callback of a `dumps()` or `loads()` in progress may have
+        // called this, or another thread may hold it while the GIL is
+        // released, e.g., to import `json` for `JSONDecodeError`. Its caches
+        // are released now. Without the GIL, another thread may be using it
+        // without a `StateGuard`, so it is leaked.
+        #[cfg(not(Py_GIL_DISABLED))]
+        if let Some(state) = old {
+            state.release_caches();
+            retire_state(state);
+            release_retired_states();
+        }
+        #[cfg(Py_GIL_DISABLED)]
+        core::mem::forget(old);
+        true
+    }
+}
+
+/// Drop the state of every interpreter, e.g., when Python is finalized and
+/// initialized again in the same process, so that no state refers to objects
+/// of a previous initialization. Each interpreter rebuilds its state on its
+/// next call. As for `reset_current_state()`, the states are retired, as a
+/// call in progress may still use one, and each is released under the GIL of
+/// its interpreter: the current one's now if `release_current`, i.e., its GIL
+/// is held, and those of other interpreters when they next resolve a state.
+/// If Python is not initialized, the states are leaked, as their objects
+/// were freed with the interpreters.
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) unsafe fn shutdown_states(release_current: bool) {
+    let current = if release_current {
+        find_interpreter_state()
+    } else {
+        null()
+    };
+    if let Some(states) = STATES_BY_INTERPRETER.get() {
+        states.write().unwrap().clear();
+    }
+    STATE_GENERATION.fetch_add(1, Ordering::Relaxed);
+    CACHED_STATE.with(|cell| cell.set((null(), 0, -1)));
+    let old: Vec<Box<InterpreterState>> = INTERPRETER_STATES
+        .get()
+        .map(|states| {
+            states
+                .lock()
+                .unwrap()
+                .drain()
+                .map(|(_, state)| state)
+                .collect()
+        })
+        .unwrap_or_default();
+    if unsafe { crate::ffi::Py_IsInitialized() } == 0 {
+        core::mem::forget(old);
+        core::mem::forget(core::mem::take(&mut *RETIRED_STATES.lock().unwrap()));
+        return;
+    }
+    #[cfg(not(Py_GIL_DISABLED))]
+    for state in old {
+        // The caches of other interpreters cannot be released from this one.
+        if core::ptr::eq(state.as_ref(), current) {
+            unsafe {
+                state.release_caches();
+            }
+        }
+        retire_state(state);
+    }
+    #[cfg(Py_GIL_DISABLED)]
+    core::mem::forget(old);
+    if release_current {
+        unsafe {
+            release_retired_states();
+        }
+    }
+}
@@ -87,17 +87,27 @@ mod typeref;
 
 use core::ffi::{c_char, c_int, c_void};
 use core::ptr::{NonNull, null, null_mut};
+use std::borrow::Cow;
 
-use crate::deserialize::deserialize;
+use crate::deserialize::{
+    DeserializeError, ObjectHook, deserialize, deserialize_buf, deserialize_many, read_file,
+};
 use crate::exception::{
-    raise_dumps_exception_dynamic, raise_dumps_exception_fixed, raise_loads_exception,
+    raise_args_exception, raise_dumps_exception_dynamic, raise_dumps_exception_fixed,
+    raise_loads_exception, raise_state_exception, raise_value_exception,
 };
 use crate::ffi::{
-    METH_KEYWORDS, METH_O, Py_SIZE, Py_ssize_t, PyCFunction_NewEx, PyLong_AsLong, PyMethodDef,
-    PyMethodDefPointer, PyModuleDef, PyModuleDef_HEAD_INIT, PyModuleDef_Slot, PyObject,
-    PyUnicode_FromStringAndSize, PyUnicode_InternFromString, PyVectorcall_NARGS,
+    METH_KEYWORDS, METH_NOARGS, Py_SIZE, Py_XDECREF, Py_ssize_t, PyCFunction_NewEx,
+    PyLong_AsLongLong, PyMethodDef, PyMethodDefPointer, PyModule_GetNameObject, PyModuleDef,
+    PyModuleDef_HEAD_INIT, PyModuleDef_Slot, PyObject, PyUnicode_FromStringAndSize,
+    PyVectorcall_NARGS,
+};
+use crate::serialize::BytesMode;
+use crate::serialize::writer::BUFFER_POOL_MAX_SIZE;
+use crate::serialize::{
+    DEFAULT_CHUNK_SIZE, DumpOptions, serialize, serialize_many, serialize_str, serialize_to_fp,
+    serialize_to_slice, serialize_view,
 };
-use crate::serialize::serialize;
 use crate::util::{isize_to_usize, usize_to_isize};
 
 #[cfg(Py_3_13)]
@@ -121,14 +131,10 @@ macro_rules! add {
     };
 }
 
+// a C long is 32 bits on Windows and 32-bit targets, so not every flag fits
 macro_rules! opt {
     ($mptr:expr, $name:expr, $opt:expr) => {
-        #[cfg(all(not(target_os = "windows"), target_pointer_width = "64"))]
-        crate::ffi::PyModule_AddIntConstant($mptr, $name.as_ptr(), i64::from($opt));
-        #[cfg(all(not(target_os = "windows"), target_pointer_width = "32"))]
-        crate::ffi::PyModule_AddIntConstant($mptr, $name.as_ptr(), $opt as i32);
-        #[cfg(target_os = "windows")]
-        crate::ffi::PyModule_AddIntConstant($mptr, $name.as_ptr(), $opt as i32);
+        add!($mptr, $name, crate::ffi::PyLong_FromUnsignedLongLong($opt));
     };
 }
 
@@ -139,8 +145,16 @@ macro_rules! opt {
 #[cfg_attr(feature = "optimize", optimize(size))]
 pub(crate) unsafe extern "C" fn orjson_init_exec(mptr: *mut PyObject) -> c_int {
     unsafe {
-        // Initialize per-interpreter state
-        interpreter_state::get_or_init_state(mptr);
+        typeref::init_typerefs();
+
+        // Initialize per-interpreter state. If this fails, the import raises
+        // the exception instead of the module having a null type.
+        if interpreter_state::get_or_init_state(mptr).is_null() {
+            cold_path!();
+            return -1;
+        }
+
+        let module_name = PyModule_GetNameObject(mptr);
 
         {
             let version = env!("CARGO_PKG_VERSION");
@@ -152,7 +166,7 @@ pub(crate) unsafe extern "C" fn orjson_init_exec(mptr: *mut PyObject) -> c_int {
         }
 
         {
-            let dumps_doc = c"dumps(obj, /, default=None, option=None)\n--\n\nSerialize Python objects to JSON.";
+            let dumps_doc = c"dumps(obj, /, default=None, option=None, *, envelope=None, tuple_fields=None, max_output_bytes=None, key_filter=None, float_precision=None, on_skip=None, skip_key_prefix=None, size_hint=None)\n--\n\nSerialize Python objects to JSON.";
 
             let wrapped_dumps = Box::new(PyMethodDef {
                 ml_name: c"dumps".as_ptr(),
@@ -166,55 +180,445 @@ pub(crate) unsafe extern "C" fn orjson_init_exec(mptr: *mut PyObject) -> c_int {
                 ml_doc: dumps_doc.as_ptr(),
             });
 
-            let func = PyCFunction_NewEx(
-                Box::into_raw(wrapped_dumps),
-                null_mut(),
-                PyUnicode_InternFromString(c"hyperjson".as_ptr()),
-            );
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_dumps), null_mut(), module_name);
             add!(mptr, c"dumps", func);
         }
 
         {
-            let loads_doc = c"loads(obj, /)\n--\n\nDeserialize JSON to Python objects.";
+            let dumps_str_doc = c"dumps_str(obj, /, default=None, option=None, *, envelope=None, tuple_fields=None, max_output_bytes=None, key_filter=None, float_precision=None, on_skip=None, skip_key_prefix=None, size_hint=None)\n--\n\nSerialize Python objects to JSON as str.";
+
+            let wrapped_dumps_str = Box::new(PyMethodDef {
+                ml_name: c"dumps_str".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: dumps_str,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: dumps_str,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: dumps_str_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_dumps_str), null_mut(), module_name);
+            add!(mptr, c"dumps_str", func);
+        }
+
+        {
+            let dumps_view_doc = c"dumps_view(obj, /, default=None, option=None, *, envelope=None, tuple_fields=None, max_output_bytes=None, key_filter=None, float_precision=None, on_skip=None, skip_key_prefix=None, size_hint=None)\n--\n\nSerialize Python objects to JSON as a memoryview.";
+
+            let wrapped_dumps_view = Box::new(PyMethodDef {
+                ml_name: c"dumps_view".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: dumps_view,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: dumps_view,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: dumps_view_doc.as_ptr(),
+            });
+            let func =
+                PyCFunction_NewEx(Box::into_raw(wrapped_dumps_view), null_mut(), module_name);
+            add!(mptr, c"dumps_view", func);
+        }
+
+        {
+            let dumps_many_doc = c"dumps_many(objs, /, default=None, option=None, *, envelope=None, max_output_bytes=None, key_filter=None, float_precision=None, on_skip=None, skip_key_prefix=None)\n--\n\nSerialize each Python object in a list to JSON.";
+
+            let wrapped_dumps_many = Box::new(PyMethodDef {
+                ml_name: c"dumps_many".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: dumps_many,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: dumps_many,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: dumps_many_doc.as_ptr(),
+            });
+            let func =
+                PyCFunction_NewEx(Box::into_raw(wrapped_dumps_many), null_mut(), module_name);
+            add!(mptr, c"dumps_many", func);
+        }
+
+        {
+            let dump_doc = c"dump(obj, fp, /, default=None, option=None, *, envelope=None, tuple_fields=None, max_output_bytes=None, key_filter=None, chunk_size=None, float_precision=None, on_skip=None, skip_key_prefix=None)\n--\n\nSerialize Python objects to JSON and write it to fp.";
+
+            let wrapped_dump = Box::new(PyMethodDef {
+                ml_name: c"dump".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: dump,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: dump,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: dump_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_dump), null_mut(), module_name);
+            add!(mptr, c"dump", func);
+        }
+
+        {
+            let loads_doc =
+                c"loads(obj, /, option=None, *, object_hook=None, object_pairs_hook=None)\n--\n\nDeserialize JSON to Python objects.";
 
             let wrapped_loads = Box::new(PyMethodDef {
                 ml_name: c"loads".as_ptr(),
-                ml_meth: PyMethodDefPointer { PyCFunction: loads },
-                ml_flags: METH_O,
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: loads,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: loads,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
                 ml_doc: loads_doc.as_ptr(),
             });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_loads), null_mut(), module_name);
+            add!(mptr, c"loads", func);
+        }
+
+        {
+            let load_file_doc =
+                c"load_file(path, /, option=None, *, object_hook=None, object_pairs_hook=None)\n--\n\nDeserialize the JSON file at path to Python objects.";
+
+            let wrapped_load_file = Box::new(PyMethodDef {
+                ml_name: c"load_file".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: load_file,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: load_file,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: load_file_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_load_file), null_mut(), module_name);
+            add!(mptr, c"load_file", func);
+        }
+
+        {
+            let loads_array_stream_doc =
+                c"loads_array_stream(obj, /, option=None, *, object_hook=None, object_pairs_hook=None)\n--\n\nIterate over the elements of a JSON array, deserializing each as it is reached.";
+
+            let wrapped_loads_array_stream = Box::new(PyMethodDef {
+                ml_name: c"loads_array_stream".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: loads_array_stream,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: loads_array_stream,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: loads_array_stream_doc.as_ptr(),
+            });
             let func = PyCFunction_NewEx(
-                Box::into_raw(wrapped_loads),
+                Box::into_raw(wrapped_loads_array_stream),
                 null_mut(),
-                PyUnicode_InternFromString(c"hyperjson".as_ptr()),
+                module_name,
             );
-            add!(mptr, c"loads", func);
+            add!(mptr, c"loads_array_stream", func);
+        }
+
+        {
+            let loads_many_doc =
+                c"loads_many(objs, /, option=None, *, object_hook=None, object_pairs_hook=None)\n--\n\nDeserialize each JSON document in a list to Python objects.";
+
+            let wrapped_loads_many = Box::new(PyMethodDef {
+                ml_name: c"loads_many".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: loads_many,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: loads_many,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: loads_many_doc.as_ptr(),
+            });
+            let func =
+                PyCFunction_NewEx(Box::into_raw(wrapped_loads_many), null_mut(), module_name);
+            add!(mptr, c"loads_many", func);
+        }
+
+        {
+            let reset_state_doc = c"reset_state()\n--\n\nDrop the cached state of the current interpreter so it is rebuilt on the next call.";
+
+            let wrapped_reset_state = Box::new(PyMethodDef {
+                ml_name: c"reset_state".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    PyCFunction: reset_state,
+                },
+                ml_flags: METH_NOARGS,
+                ml_doc: reset_state_doc.as_ptr(),
+            });
+            let func =
+                PyCFunction_NewEx(Box::into_raw(wrapped_reset_state), null_mut(), module_name);
+            add!(mptr, c"reset_state", func);
+        }
+
+        {
+            let disable_numpy_doc = c"disable_numpy()\n--\n\nNever import numpy to serialize with OPT_SERIALIZE_NUMPY in the current interpreter.";
+
+            let wrapped_disable_numpy = Box::new(PyMethodDef {
+                ml_name: c"disable_numpy".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    PyCFunction: disable_numpy,
+                },
+                ml_flags: METH_NOARGS,
+                ml_doc: disable_numpy_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(
+                Box::into_raw(wrapped_disable_numpy),
+                null_mut(),
+                module_name,
+            );
+            add!(mptr, c"disable_numpy", func);
+        }
+
+        {
+            let shutdown_doc = c"shutdown()\n--\n\nDrop the cached state of every interpreter so each is rebuilt on its next call.";
+
+            let wrapped_shutdown = Box::new(PyMethodDef {
+                ml_name: c"shutdown".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    PyCFunction: shutdown_all,
+                },
+                ml_flags: METH_NOARGS,
+                ml_doc: shutdown_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_shutdown), null_mut(), module_name);
+            add!(mptr, c"shutdown", func);
+        }
+
+        {
+            let build_info_doc = c"build_info()\n--\n\nReturn a dict describing how the module was built and what it supports.";
+
+            let wrapped_build_info = Box::new(PyMethodDef {
+                ml_name: c"build_info".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    PyCFunction: build_info,
+                },
+                ml_flags: METH_NOARGS,
+                ml_doc: build_info_doc.as_ptr(),
+            });
+            let func =
+                PyCFunction_NewEx(Box::into_raw(wrapped_build_info), null_mut(), module_name);
+            add!(mptr, c"build_info", func);
+        }
+
+        {
+            let configure_doc =
+                c"configure(*, bytes_mode=None, buffer_pool_size=None)\n--\n\nSet defaults for the current interpreter.";
+
+            let wrapped_configure = Box::new(PyMethodDef {
+                ml_name: c"configure".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: configure,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: configure,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | METH_KEYWORDS,
+                ml_doc: configure_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_configure), null_mut(), module_name);
+            add!(mptr, c"configure", func);
+        }
+
+        {
+            let set_default_options_doc = c"set_default_options(option, /)\n--\n\nSet the option used by calls in the current interpreter in addition to their own.";
+
+            let wrapped_set_default_options = Box::new(PyMethodDef {
+                ml_name: c"set_default_options".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFast: set_default_options,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFast: set_default_options,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL,
+                ml_doc: set_default_options_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(
+                Box::into_raw(wrapped_set_default_options),
+                null_mut(),
+                module_name,
+            );
+            add!(mptr, c"set_default_options", func);
+        }
+
+        {
+            let register_type_doc = c"register_type(tp, handler, /)\n--\n\nSerialize instances of tp, and of its subclasses, by what handler returns.";
+
+            let wrapped_register_type = Box::new(PyMethodDef {
+                ml_name: c"register_type".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFast: register_type,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFast: register_type,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL,
+                ml_doc: register_type_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(
+                Box::into_raw(wrapped_register_type),
+                null_mut(),
+                module_name,
+            );
+            add!(mptr, c"register_type", func);
+        }
+
+        {
+            let warm_keys_doc = c"warm_keys(keys, /)\n--\n\nCache each str of keys as deserialized object keys are cached.";
+
+            let wrapped_warm_keys = Box::new(PyMethodDef {
+                ml_name: c"warm_keys".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFast: warm_keys,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFast: warm_keys,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL,
+                ml_doc: warm_keys_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_warm_keys), null_mut(), module_name);
+            add!(mptr, c"warm_keys", func);
+        }
+
+        {
+            let key_cache_stats_doc = c"key_cache_stats(*, reset=False)\n--\n\nReturn the number of deserialized object keys found in, added to, and too long for the key cache of the current interpreter.";
+
+            let wrapped_key_cache_stats = Box::new(PyMethodDef {
+                ml_name: c"key_cache_stats".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    #[cfg(Py_3_10)]
+                    PyCFunctionFastWithKeywords: key_cache_stats,
+                    #[cfg(not(Py_3_10))]
+                    _PyCFunctionFastWithKeywords: key_cache_stats,
+                },
+                ml_flags: crate::ffi::METH_FASTCALL | crate::ffi::METH_KEYWORDS,
+                ml_doc: key_cache_stats_doc.as_ptr(),
+            });
+            let func = PyCFunction_NewEx(
+                Box::into_raw(wrapped_key_cache_stats),
+                null_mut(),
+                module_name,
+            );
+            add!(mptr, c"key_cache_stats", func);
+        }
+
+        {
+            // JSONDecodeError is created on first use rather than here
+            let wrapped_getattr = Box::new(PyMethodDef {
+                ml_name: c"__getattr__".as_ptr(),
+                ml_meth: PyMethodDefPointer {
+                    PyCFunction: module_getattr,
+                },
+                ml_flags: crate::ffi::METH_O,
+                ml_doc: null_mut(),
+            });
+            let func = PyCFunction_NewEx(Box::into_raw(wrapped_getattr), mptr, module_name);
+            add!(mptr, c"__getattr__", func);
         }
 
         add!(mptr, c"Fragment", typeref::get_fragment_type().cast::<PyObject>());
+        add!(mptr, c"Options", typeref::get_options_type().cast::<PyObject>());
+        add!(
+            mptr,
+            c"ArrayStream",
+            typeref::get_array_stream_type().cast::<PyObject>()
+        );
 
         opt!(mptr, c"OPT_APPEND_NEWLINE", opt::APPEND_NEWLINE);
+        opt!(mptr, c"OPT_BIG_NUMBER_AS_STRING", opt::BIG_NUMBER_AS_STRING);
+        opt!(mptr, c"OPT_CACHE_TUPLES", opt::CACHE_TUPLES);
+        opt!(
+            mptr,
+            c"OPT_DATETIME_AS_UNIX_MILLIS",
+            opt::DATETIME_AS_UNIX_MILLIS
+        );
+        opt!(
+            mptr,
+            c"OPT_DATETIME_AS_UNIX_SECONDS",
+            opt::DATETIME_AS_UNIX_SECONDS
+        );
+        opt!(mptr, c"OPT_DATE_AS_EPOCH_DAYS", opt::DATE_AS_EPOCH_DAYS);
+        opt!(
+            mptr,
+            c"OPT_DUPLICATE_KEYS_AS_LIST",
+            opt::DUPLICATE_KEYS_AS_LIST
+        );
+        opt!(mptr, c"OPT_ENSURE_ASCII", opt::ENSURE_ASCII);
+        opt!(mptr, c"OPT_ESCAPE_FORWARD_SLASH", opt::ESCAPE_FORWARD_SLASH);
+        opt!(
+            mptr,
+            c"OPT_FORBID_DUPLICATE_KEYS",
+            opt::FORBID_DUPLICATE_KEYS
+        );
+        opt!(
+            mptr,
+            c"OPT_FORBID_NAIVE_DATETIME",
+            opt::FORBID_NAIVE_DATETIME
+        );
+        opt!(mptr, c"OPT_FRACTION_AS_FLOAT", opt::FRACTION_AS_FLOAT);
         opt!(mptr, c"OPT_INDENT_2", opt::INDENT_2);
+        opt!(mptr, c"OPT_INTERN_VALUES", opt::INTERN_VALUES);
+        opt!(mptr, c"OPT_JSON5_NONFINITE", opt::JSON5_NONFINITE);
+        opt!(mptr, c"OPT_MEMORYVIEW_AS_NESTED", opt::MEMORYVIEW_AS_NESTED);
         opt!(mptr, c"OPT_NAIVE_UTC", opt::NAIVE_UTC);
         opt!(mptr, c"OPT_NON_STR_KEYS", opt::NON_STR_KEYS);
+        opt!(mptr, c"OPT_NON_STR_KEYS_STRICT", opt::NON_STR_KEYS_STRICT);
         opt!(mptr, c"OPT_OMIT_MICROSECONDS", opt::OMIT_MICROSECONDS);
+        opt!(mptr, c"OPT_PARSE_RELAXED", opt::PARSE_RELAXED);
+        opt!(mptr, c"OPT_PASSTHROUGH_BYTES", opt::PASSTHROUGH_BYTES);
         opt!(
             mptr,
             c"OPT_PASSTHROUGH_DATACLASS",
             opt::PASSTHROUGH_DATACLASS
         );
         opt!(mptr, c"OPT_PASSTHROUGH_DATETIME", opt::PASSTHROUGH_DATETIME);
+        opt!(mptr, c"OPT_PASSTHROUGH_NUMPY", opt::PASSTHROUGH_NUMPY);
         opt!(mptr, c"OPT_PASSTHROUGH_SUBCLASS", opt::PASSTHROUGH_SUBCLASS);
+        opt!(mptr, c"OPT_RANGE_AS_LIST", opt::RANGE_AS_LIST);
+        opt!(mptr, c"OPT_REPR_FALLBACK", opt::REPR_FALLBACK);
+        opt!(mptr, c"OPT_RFC3339_STRICT", opt::RFC3339_STRICT);
+        opt!(mptr, c"OPT_SELF_CHECK", opt::SELF_CHECK);
+        opt!(
+            mptr,
+            c"OPT_SERIALIZE_BYTES_BASE64",
+            opt::SERIALIZE_BYTES_BASE64
+        );
+        opt!(mptr, c"OPT_SERIALIZE_BYTES_HEX", opt::SERIALIZE_BYTES_HEX);
+        opt!(
+            mptr,
+            c"OPT_SERIALIZE_BYTES_LATIN1",
+            opt::SERIALIZE_BYTES_LATIN1
+        );
         opt!(mptr, c"OPT_SERIALIZE_DATACLASS", opt::SERIALIZE_DATACLASS);
+        opt!(mptr, c"OPT_SERIALIZE_FRACTION", opt::SERIALIZE_FRACTION);
+        opt!(mptr, c"OPT_SERIALIZE_FSPATH", opt::SERIALIZE_FSPATH);
+        opt!(mptr, c"OPT_SERIALIZE_IPADDRESS", opt::SERIALIZE_IPADDRESS);
+        opt!(mptr, c"OPT_SERIALIZE_ITERABLES", opt::SERIALIZE_ITERABLES);
         opt!(mptr, c"OPT_SERIALIZE_NUMPY", opt::SERIALIZE_NUMPY);
+        opt!(mptr, c"OPT_SERIALIZE_PROPERTIES", opt::SERIALIZE_PROPERTIES);
+        opt!(mptr, c"OPT_SERIALIZE_RANGE", opt::SERIALIZE_RANGE);
         opt!(mptr, c"OPT_SERIALIZE_UUID", opt::SERIALIZE_UUID);
+        opt!(mptr, c"OPT_SKIP_UNSERIALIZABLE", opt::SKIP_UNSERIALIZABLE);
         opt!(mptr, c"OPT_SORT_KEYS", opt::SORT_KEYS);
         opt!(mptr, c"OPT_STRICT_INTEGER", opt::STRICT_INTEGER);
+        opt!(mptr, c"OPT_SURROGATES_ESCAPE", opt::SURROGATES_ESCAPE);
+        opt!(mptr, c"OPT_TZ_OFFSET_NO_COLON", opt::TZ_OFFSET_NO_COLON);
+        opt!(mptr, c"OPT_USE_JSON_DUNDER", opt::USE_JSON_DUNDER);
         opt!(mptr, c"OPT_UTC_Z", opt::UTC_Z);
+        opt!(mptr, c"OPT_UUID_AS_BYTES", opt::UUID_AS_BYTES);
+        opt!(mptr, c"OPT_VALIDATE_FRAGMENTS", opt::VALIDATE_FRAGMENTS);
+        opt!(mptr, c"OPT_WEAKREF_DEAD_AS_NULL", opt::WEAKREF_DEAD_AS_NULL);
 
-        add!(mptr, c"JSONDecodeError", typeref::get_json_decode_error());
         add!(mptr, c"JSONEncodeError", typeref::get_json_encode_error());
 
+        Py_XDECREF(module_name);
+
         0
     }
 }
@@ -270,11 +674,6 @@ pub(crate) unsafe extern "C" fn PyInit_orjson() -> *mut PyModuleDef {
     }
 }
 
-#[unsafe(no_mangle)]
-pub(crate) unsafe extern "C" fn loads(_self: *mut PyObject, obj: *mut PyObject) -> *mut PyObject {
-    deserialize(obj).map_or_else(raise_loads_exception, NonNull::as_ptr)
-}
-
 #[cfg(CPython)]
 macro_rules! matches_kwarg {
     ($val:expr, $ref:expr) => {
@@ -290,68 +689,893 @@ macro_rules! matches_kwarg {
 }
 
 #[unsafe(no_mangle)]
-pub(crate) unsafe extern "C" fn dumps(
+#[cold]
+pub(crate) unsafe extern "C" fn reset_state(
     _self: *mut PyObject,
-    args: *const *mut PyObject,
-    nargs: Py_ssize_t,
-    kwnames: *mut PyObject,
+    _args: *mut PyObject,
 ) -> *mut PyObject {
     unsafe {
-        let mut default: Option<NonNull<PyObject>> = None;
-        let mut optsptr: Option<NonNull<PyObject>> = None;
+        if !interpreter_state::reset_current_state() {
+            return raise_state_exception();
+        }
+        // not typeref::get_none(), which would rebuild the state immediately
+        use_immortal!(crate::ffi::Py_None())
+    }
+}
 
-        let num_args = PyVectorcall_NARGS(isize_to_usize(nargs));
-        if num_args == 0 {
-            cold_path!();
-            return raise_dumps_exception_fixed(
-                "dumps() missing 1 required positional argument: 'obj'",
-            );
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn disable_numpy(
+    _self: *mut PyObject,
+    _args: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        if interpreter_state::get_current_state().is_null() {
+            return raise_state_exception();
         }
-        if num_args & 2 == 2 {
-            default = Some(NonNull::new_unchecked(*args.offset(1)));
+        (*interpreter_state::get_current_state())
+            .numpy_disabled
+            .store(true, core::sync::atomic::Ordering::Relaxed);
+        use_immortal!(typeref::get_none())
+    }
+}
+
+// not named `shutdown`, as the exported symbol would interpose shutdown(2)
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn shutdown_all(
+    _self: *mut PyObject,
+    _args: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        interpreter_state::shutdown_states(true);
+        // not typeref::get_none(), which would rebuild the state immediately
+        use_immortal!(crate::ffi::Py_None())
+    }
+}
+
+/// The module's `__getattr__`, which creates `JSONDecodeError` on its first
+/// access. It is then found in the module's `__dict__` without calling this.
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn module_getattr(
+    module: *mut PyObject,
+    name: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        if is_type!(ob_type!(name), typeref::get_str_type())
+            && crate::ffi::PyUnicode_CompareWithASCIIString(name, c"JSONDecodeError".as_ptr()) == 0
+        {
+            let Some(state) = interpreter_state::StateGuard::new() else {
+                return raise_state_exception();
+            };
+            let json_decode_error = state.json_decode_error();
+            if !json_decode_error.is_null() {
+                crate::ffi::Py_INCREF(json_decode_error);
+            }
+            return json_decode_error;
         }
-        if num_args & 3 == 3 {
-            optsptr = Some(NonNull::new_unchecked(*args.offset(2)));
+        crate::ffi::PyErr_Format(
+            crate::ffi::PyExc_AttributeError,
+            c"module '%s' has no attribute '%U'".as_ptr(),
+            crate::ffi::PyModule_GetName(module),
+            name,
+        )
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn build_info(
+    _self: *mut PyObject,
+    _args: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        let info = ffi!(PyDict_New());
+        if info.is_null() {
+            return null_mut();
         }
-        if !kwnames.is_null() {
-            cold_path!();
+
+        let version = env!("CARGO_PKG_VERSION");
+        let pyversion = PyUnicode_FromStringAndSize(
+            version.as_ptr().cast::<c_char>(),
+            usize_to_isize(version.len()),
+        );
+        // numpy is imported, as on serializing with OPT_SERIALIZE_NUMPY
+        let numpy_available = typeref::get_numpy_types().is_some();
+        let pybool = |val: bool| {
+            if val {
+                use_immortal!(typeref::get_true())
+            } else {
+                use_immortal!(typeref::get_false())
+            }
+        };
+        let entries: [(&core::ffi::CStr, *mut PyObject); 7] = [
+            (c"version", pyversion),
+            (c"debug", pybool(cfg!(debug_assertions))),
+            (c"gil_disabled", pybool(cfg!(Py_GIL_DISABLED))),
+            // the key cache is not thread-safe without the GIL
+            (c"key_cache", pybool(!cfg!(Py_GIL_DISABLED))),
+            (c"numpy_available", pybool(numpy_available)),
+            (
+                c"self_check",
+                pybool(cfg!(any(debug_assertions, feature = "self_check"))),
+            ),
+            (
+                c"subinterpreter_support",
+                pybool(cfg!(Py_3_12) && !cfg!(feature = "pymalloc")),
+            ),
+        ];
+        for (key, val) in entries {
+            ffi!(PyDict_SetItemString(info, key.as_ptr(), val));
+            Py_XDECREF(val);
+        }
+        info
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn configure(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        if interpreter_state::get_current_state().is_null() {
+            return raise_state_exception();
+        }
+
+        let mut bytesmodeptr: Option<NonNull<PyObject>> = None;
+        let mut poolsizeptr: Option<NonNull<PyObject>> = None;
+
+        let num_args = PyVectorcall_NARGS(isize_to_usize(nargs));
+        if num_args != 0 {
+            return raise_args_exception("configure() takes no positional arguments");
+        }
+        if !kwnames.is_null() {
+            for i in 0..=Py_SIZE(kwnames).saturating_sub(1) {
+                let arg = crate::ffi::PyTuple_GET_ITEM(kwnames, i as Py_ssize_t);
+                if matches_kwarg!(arg, typeref::get_bytes_mode_str()) {
+                    bytesmodeptr = Some(NonNull::new_unchecked(*args.offset(i)));
+                } else if matches_kwarg!(arg, typeref::get_buffer_pool_size_str()) {
+                    poolsizeptr = Some(NonNull::new_unchecked(*args.offset(i)));
+                } else {
+                    return raise_args_exception("configure() got an unexpected keyword argument");
+                }
+            }
+        }
+
+        if let Some(mode) = bytesmodeptr {
+            if !core::ptr::eq(mode.as_ptr(), typeref::get_none()) {
+                if !core::ptr::eq((*mode.as_ptr()).ob_type, typeref::get_str_type()) {
+                    return raise_args_exception("bytes_mode must be str");
+                }
+                match crate::str::PyStr::from_ptr_unchecked(mode.as_ptr())
+                    .to_str()
+                    .and_then(BytesMode::from_name)
+                {
+                    Some(val) => val.set_configured(),
+                    None => {
+                        return raise_value_exception(
+                            "bytes_mode must be one of 'base64', 'hex', 'latin1', 'passthrough'",
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(size) = poolsizeptr {
+            if !core::ptr::eq(size.as_ptr(), typeref::get_none()) {
+                if !core::ptr::eq((*size.as_ptr()).ob_type, typeref::get_int_type()) {
+                    return raise_args_exception("buffer_pool_size must be int");
+                }
+                let mut overflow: c_int = 0;
+                let val =
+                    crate::ffi::PyLong_AsLongLongAndOverflow(size.as_ptr(), &raw mut overflow);
+                if overflow <= 0 && val < 0 {
+                    return raise_value_exception("buffer_pool_size must be non-negative");
+                }
+                let val = usize::try_from(val).unwrap_or(usize::MAX);
+                if overflow > 0 || val > BUFFER_POOL_MAX_SIZE {
+                    return raise_value_exception(&format!(
+                        "buffer_pool_size must be at most {BUFFER_POOL_MAX_SIZE}"
+                    ));
+                }
+                #[cfg(not(Py_GIL_DISABLED))]
+                (*(*interpreter_state::get_current_state()).buffer_pool.get()).set_size(val);
+            }
+        }
+
+        use_immortal!(typeref::get_none())
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn set_default_options(
+    _self: *mut PyObject,
+    args: *mut *mut PyObject,
+    nargs: Py_ssize_t,
+) -> *mut PyObject {
+    unsafe {
+        if interpreter_state::get_current_state().is_null() {
+            return raise_state_exception();
+        }
+
+        if nargs != 1 {
+            return raise_args_exception(
+                "set_default_options() takes exactly 1 positional argument",
+            );
+        }
+        let opts = if core::ptr::eq(*args, typeref::get_none()) {
+            0
+        } else if let Some(val) = crate::ffi::flags_of(*args) {
+            val
+        } else if core::ptr::eq((**args).ob_type, typeref::get_int_type()) {
+            return raise_value_exception("Invalid opts");
+        } else {
+            return raise_args_exception(
+                "set_default_options() argument must be int, Options, or None",
+            );
+        };
+        if let Some(msg) = opt::incompatible(opts) {
+            return raise_value_exception(msg);
+        }
+        (*interpreter_state::get_current_state())
+            .default_opts
+            .store(opts, core::sync::atomic::Ordering::Relaxed);
+
+        use_immortal!(typeref::get_none())
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn register_type(
+    _self: *mut PyObject,
+    args: *mut *mut PyObject,
+    nargs: Py_ssize_t,
+) -> *mut PyObject {
+    unsafe {
+        if interpreter_state::get_current_state().is_null() {
+            return raise_state_exception();
+        }
+
+        if nargs != 2 {
+            return raise_args_exception("register_type() takes exactly 2 positional arguments");
+        }
+        let tp = *args;
+        let handler = *args.offset(1);
+        if crate::ffi::PyType_Check(tp) == 0 {
+            return raise_args_exception("register_type() argument 'tp' must be a type");
+        }
+        let handlers = typeref::get_type_handlers();
+        if core::ptr::eq(handler, typeref::get_none()) {
+            if crate::ffi::PyDict_DelItem(handlers, tp) == -1 {
+                // not registered
+                crate::ffi::PyErr_Clear();
+            }
+        } else if crate::ffi::PyCallable_Check(handler) == 0 {
+            return raise_args_exception(
+                "register_type() argument 'handler' must be callable or None",
+            );
+        } else if crate::ffi::PyDict_SetItem(handlers, tp, handler) == -1 {
+            return null_mut();
+        }
+
+        use_immortal!(typeref::get_none())
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn warm_keys(
+    _self: *mut PyObject,
+    args: *mut *mut PyObject,
+    nargs: Py_ssize_t,
+) -> *mut PyObject {
+    unsafe {
+        if interpreter_state::get_current_state().is_null() {
+            return raise_state_exception();
+        }
+
+        if nargs != 1 {
+            return raise_args_exception("warm_keys() takes exactly 1 positional argument");
+        }
+        let keys = *args;
+        if !is_type!(ob_type!(keys), typeref::get_list_type()) {
+            return raise_args_exception("warm_keys() argument must be a list of str");
+        }
+        for idx in 0..Py_SIZE(keys) {
+            let key = crate::ffi::PyList_GET_ITEM(keys, idx);
+            if !is_type!(ob_type!(key), typeref::get_str_type()) {
+                return raise_args_exception("warm_keys() argument must be a list of str");
+            }
+            let key = crate::str::PyStr::from_ptr_unchecked(key);
+            match key.to_str() {
+                Some(key_str) => crate::deserialize::warm_key(key, key_str),
+                None => {
+                    return raise_args_exception("str is not valid UTF-8: surrogates not allowed");
+                }
+            }
+        }
+
+        use_immortal!(typeref::get_none())
+    }
+}
+
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn key_cache_stats(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        let state = interpreter_state::get_current_state();
+        if state.is_null() {
+            return raise_state_exception();
+        }
+
+        let mut resetptr: Option<NonNull<PyObject>> = None;
+
+        let num_args = PyVectorcall_NARGS(isize_to_usize(nargs));
+        if num_args != 0 {
+            return raise_args_exception("key_cache_stats() takes no positional arguments");
+        }
+        if !kwnames.is_null() {
+            for i in 0..=Py_SIZE(kwnames).saturating_sub(1) {
+                let arg = crate::ffi::PyTuple_GET_ITEM(kwnames, i as Py_ssize_t);
+                if matches_kwarg!(arg, typeref::get_reset_str()) {
+                    resetptr = Some(NonNull::new_unchecked(*args.offset(i)));
+                } else {
+                    return raise_args_exception(
+                        "key_cache_stats() got an unexpected keyword argument",
+                    );
+                }
+            }
+        }
+        let reset = match resetptr {
+            None => false,
+            Some(reset) if core::ptr::eq(reset.as_ptr(), typeref::get_true()) => true,
+            Some(reset) if core::ptr::eq(reset.as_ptr(), typeref::get_false()) => false,
+            Some(_) => return raise_args_exception("reset must be bool"),
+        };
+
+        #[cfg(not(Py_GIL_DISABLED))]
+        let stats = {
+            let stats = &mut *(*state).key_cache_stats.get();
+            let ret = (stats.hits, stats.misses, stats.bypasses);
+            if reset {
+                *stats = crate::deserialize::KeyCacheStats::default();
+            }
+            ret
+        };
+        // there is no key cache
+        #[cfg(Py_GIL_DISABLED)]
+        let stats = {
+            let _ = reset;
+            (0, 0, 0)
+        };
+
+        let info = ffi!(PyDict_New());
+        if info.is_null() {
+            return null_mut();
+        }
+        let entries: [(&core::ffi::CStr, u64); 3] = [
+            (c"hits", stats.0),
+            (c"misses", stats.1),
+            (c"bypasses", stats.2),
+        ];
+        for (key, val) in entries {
+            let pyval = crate::ffi::PyLong_FromUnsignedLongLong(val);
+            ffi!(PyDict_SetItemString(info, key.as_ptr(), pyval));
+            Py_XDECREF(pyval);
+        }
+        info
+    }
+}
+
+/// The input of `loads()`, an object containing a document, of
+/// `load_file()`, the path of a file containing one, of
+/// `loads_array_stream()`, an object containing an array, or of
+/// `loads_many()`, a `list` of objects each containing a document.
+#[derive(Copy, Clone)]
+enum LoadsInput {
+    Obj,
+    File,
+    ArrayStream,
+    Many,
+}
+
+impl LoadsInput {
+    fn name(self) -> &'static str {
+        match self {
+            LoadsInput::Obj => "loads",
+            LoadsInput::File => "load_file",
+            LoadsInput::ArrayStream => "loads_array_stream",
+            LoadsInput::Many => "loads_many",
+        }
+    }
+
+    fn arg_name(self) -> &'static str {
+        match self {
+            LoadsInput::Obj | LoadsInput::ArrayStream => "obj",
+            LoadsInput::File => "path",
+            LoadsInput::Many => "objs",
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn loads(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { loads_impl(args, nargs, kwnames, LoadsInput::Obj) }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn load_file(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { loads_impl(args, nargs, kwnames, LoadsInput::File) }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn loads_array_stream(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { loads_impl(args, nargs, kwnames, LoadsInput::ArrayStream) }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn loads_many(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { loads_impl(args, nargs, kwnames, LoadsInput::Many) }
+}
+
+#[inline(always)]
+unsafe fn loads_impl(
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+    input: LoadsInput,
+) -> *mut PyObject {
+    unsafe {
+        debug_assert_gil_held!(input.name());
+        let Some(_state) = interpreter_state::StateGuard::new() else {
+            cold_path!();
+            return raise_state_exception();
+        };
+
+        let mut optsptr: Option<NonNull<PyObject>> = None;
+        let mut objecthookptr: Option<NonNull<PyObject>> = None;
+        let mut pairshookptr: Option<NonNull<PyObject>> = None;
+
+        let num_args = PyVectorcall_NARGS(isize_to_usize(nargs));
+        if num_args == 0 {
+            cold_path!();
+            return raise_args_exception(&format!(
+                "{}() missing 1 required positional argument: '{}'",
+                input.name(),
+                input.arg_name()
+            ));
+        }
+        if num_args > 2 {
+            cold_path!();
+            return raise_args_exception(&format!(
+                "{}() takes at most 2 positional arguments",
+                input.name()
+            ));
+        }
+        if num_args == 2 {
+            optsptr = Some(NonNull::new_unchecked(*args.offset(1)));
+        }
+        if !kwnames.is_null() {
+            cold_path!();
+            for i in 0..=Py_SIZE(kwnames).saturating_sub(1) {
+                let arg = crate::ffi::PyTuple_GET_ITEM(kwnames, i as Py_ssize_t);
+                if matches_kwarg!(arg, typeref::get_option()) {
+                    if num_args == 2 {
+                        cold_path!();
+                        return raise_args_exception(&format!(
+                            "{}() got multiple values for argument: 'option'",
+                            input.name()
+                        ));
+                    }
+                    optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_object_hook()) {
+                    objecthookptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_object_pairs_hook()) {
+                    pairshookptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else {
+                    return raise_args_exception(&format!(
+                        "{}() got an unexpected keyword argument",
+                        input.name()
+                    ));
+                }
+            }
+        }
+
+        let mut optsbits: i64 = 0;
+        if let Some(opts) = optsptr {
+            cold_path!();
+            if core::ptr::eq((*opts.as_ptr()).ob_type, typeref::get_int_type()) {
+                optsbits = PyLong_AsLongLong(opts.as_ptr());
+                if !(0..=opt::MAX_OPT).contains(&optsbits) {
+                    cold_path!();
+                    return raise_args_exception("Invalid opts");
+                }
+            } else if core::ptr::eq((*opts.as_ptr()).ob_type, typeref::get_options_type()) {
+                #[allow(clippy::cast_possible_wrap)]
+                let tmp = (*opts.as_ptr().cast::<crate::ffi::Options>()).opts as i64;
+                optsbits = tmp;
+            } else if !core::ptr::eq(opts.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                return raise_args_exception("Invalid opts");
+            }
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        let optsbits = optsbits as opt::Opt;
+        if optsbits & !opt::LOADS_OPTS != 0 {
+            cold_path!();
+            return raise_args_exception(&format!(
+                "{}() does not support the given option",
+                input.name()
+            ));
+        }
+        // the default may hold flags for serializing, which do not apply here
+        let opts = optsbits | (typeref::get_default_opts() & opt::LOADS_OPTS);
+
+        let mut hook: Option<ObjectHook> = None;
+        if let Some(callable) = objecthookptr {
+            if !core::ptr::eq(callable.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                if crate::ffi::PyCallable_Check(callable.as_ptr()) == 0 {
+                    return raise_args_exception(&format!(
+                        "{}() argument 'object_hook' must be callable or None",
+                        input.name()
+                    ));
+                }
+                hook = Some(ObjectHook::Object(callable));
+            }
+        }
+        // object_pairs_hook takes priority, as in the standard library
+        if let Some(callable) = pairshookptr {
+            if !core::ptr::eq(callable.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                if crate::ffi::PyCallable_Check(callable.as_ptr()) == 0 {
+                    return raise_args_exception(&format!(
+                        "{}() argument 'object_pairs_hook' must be callable or None",
+                        input.name()
+                    ));
+                }
+                hook = Some(ObjectHook::Pairs(callable));
+            }
+        }
+
+        match input {
+            LoadsInput::Obj => {
+                deserialize(*args, opts, hook).map_or_else(raise_loads_exception, NonNull::as_ptr)
+            }
+            LoadsInput::File => match read_file(*args) {
+                Ok(buffer) => deserialize_buf(&buffer, opts, hook)
+                    .map_or_else(raise_loads_exception, NonNull::as_ptr),
+                Err(err) => raise_loads_exception(err),
+            },
+            LoadsInput::ArrayStream => {
+                cold_path!();
+                if opt_enabled!(opts, opt::PARSE_RELAXED) {
+                    return raise_value_exception(
+                        "loads_array_stream() does not support OPT_PARSE_RELAXED",
+                    );
+                }
+                crate::ffi::array_stream_new(*args, opts, hook)
+            }
+            LoadsInput::Many => {
+                cold_path!();
+                if !is_subclass_by_flag!(tp_flags!(ob_type!(*args)), Py_TPFLAGS_LIST_SUBCLASS) {
+                    return raise_loads_exception(DeserializeError::invalid(Cow::Borrowed(
+                        "loads_many() argument must be a list",
+                    )));
+                }
+                deserialize_many(*args, opts, hook)
+                    .map_or_else(raise_loads_exception, NonNull::as_ptr)
+            }
+        }
+    }
+}
+
+/// Validate the `tuple_fields` argument of `dumps()` against `obj`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+unsafe fn tuple_fields_from_arg(
+    obj: *mut PyObject,
+    fields: *mut PyObject,
+) -> Result<Vec<&'static str>, &'static str> {
+    unsafe {
+        let fields_type = ob_type!(fields);
+        let items: &[*mut PyObject] = if Py_SIZE(fields) == 0
+            && (is_type!(fields_type, typeref::get_list_type())
+                || is_type!(fields_type, typeref::get_tuple_type()))
+        {
+            // an empty list has no ob_item array
+            &[]
+        } else if is_type!(fields_type, typeref::get_list_type()) {
+            core::slice::from_raw_parts(
+                (*fields.cast::<crate::ffi::PyListObject>()).ob_item,
+                isize_to_usize(Py_SIZE(fields)),
+            )
+        } else if is_type!(fields_type, typeref::get_tuple_type()) {
+            core::slice::from_raw_parts(
+                (*fields.cast::<crate::ffi::PyTupleObject>())
+                    .ob_item
+                    .as_ptr(),
+                isize_to_usize(Py_SIZE(fields)),
+            )
+        } else {
+            return Err("tuple_fields must be a list or tuple of str");
+        };
+        let mut names = Vec::with_capacity(items.len());
+        for item in items {
+            if !is_type!(ob_type!(*item), typeref::get_str_type()) {
+                return Err("tuple_fields must be a list or tuple of str");
+            }
+            match crate::str::PyStr::from_ptr_unchecked(*item).to_str() {
+                Some(name) => names.push(name),
+                None => return Err("str is not valid UTF-8: surrogates not allowed"),
+            }
+        }
+        // a subclass, e.g., a namedtuple, is read by its elements as a tuple
+        if !is_subclass_by_flag!(tp_flags!(ob_type!(obj)), Py_TPFLAGS_TUPLE_SUBCLASS) {
+            return Err("tuple_fields requires obj to be a tuple");
+        }
+        if isize_to_usize(Py_SIZE(obj)) != names.len() {
+            return Err("tuple_fields length does not match tuple length");
+        }
+        Ok(names)
+    }
+}
+
+/// The object `dumps()` and `dumps_str()` return. `dump()` writes to the
+/// file as it serializes and returns `None`. `dumps_many()` returns a `list`
+/// of `bytes`, one for each element of its `list` argument.
+#[derive(Copy, Clone)]
+enum DumpsOutput {
+    Bytes,
+    Str,
+    View,
+    File(*mut PyObject),
+    Many,
+}
+
+impl DumpsOutput {
+    fn name(self) -> &'static str {
+        match self {
+            DumpsOutput::Bytes => "dumps",
+            DumpsOutput::Str => "dumps_str",
+            DumpsOutput::View => "dumps_view",
+            DumpsOutput::File(_) => "dump",
+            DumpsOutput::Many => "dumps_many",
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn dumps(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { dumps_impl(args, nargs, kwnames, DumpsOutput::Bytes) }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn dumps_str(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { dumps_impl(args, nargs, kwnames, DumpsOutput::Str) }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn dumps_view(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { dumps_impl(args, nargs, kwnames, DumpsOutput::View) }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn dumps_many(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe { dumps_impl(args, nargs, kwnames, DumpsOutput::Many) }
+}
+
+#[unsafe(no_mangle)]
+pub(crate) unsafe extern "C" fn dump(
+    _self: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    unsafe {
+        debug_assert_gil_held!("dump");
+        let num_args = PyVectorcall_NARGS(isize_to_usize(nargs));
+        if num_args < 2 {
+            cold_path!();
+            return raise_dumps_exception_fixed(if num_args == 0 {
+                "dump() missing 2 required positional arguments: 'obj' and 'fp'"
+            } else {
+                "dump() missing 1 required positional argument: 'fp'"
+            });
+        }
+        let fp = *args.offset(1);
+        // drop fp so the remaining arguments are laid out as for dumps()
+        let num_kwargs = if kwnames.is_null() {
+            0
+        } else {
+            Py_SIZE(kwnames)
+        };
+        let mut dumps_args: Vec<*mut PyObject> =
+            Vec::with_capacity(isize_to_usize(num_args + num_kwargs - 1));
+        dumps_args.push(*args);
+        for i in 2..num_args + num_kwargs {
+            dumps_args.push(*args.offset(i));
+        }
+        dumps_impl(
+            dumps_args.as_ptr(),
+            num_args - 1,
+            kwnames,
+            DumpsOutput::File(fp),
+        )
+    }
+}
+
+#[inline(always)]
+unsafe fn dumps_impl(
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+    output: DumpsOutput,
+) -> *mut PyObject {
+    unsafe {
+        debug_assert_gil_held!(output.name());
+        let Some(_state) = interpreter_state::StateGuard::new() else {
+            cold_path!();
+            return raise_state_exception();
+        };
+
+        let mut default: Option<NonNull<PyObject>> = None;
+        let mut optsptr: Option<NonNull<PyObject>> = None;
+        let mut envelopeptr: Option<NonNull<PyObject>> = None;
+        let mut tuplefieldsptr: Option<NonNull<PyObject>> = None;
+        let mut maxoutputptr: Option<NonNull<PyObject>> = None;
+        let mut floatprecisionptr: Option<NonNull<PyObject>> = None;
+        let mut keyfilterptr: Option<NonNull<PyObject>> = None;
+        let mut onskipptr: Option<NonNull<PyObject>> = None;
+        let mut skipkeyprefixptr: Option<NonNull<PyObject>> = None;
+        let mut chunksizeptr: Option<NonNull<PyObject>> = None;
+        let mut sizehintptr: Option<NonNull<PyObject>> = None;
+
+        let num_args = PyVectorcall_NARGS(isize_to_usize(nargs));
+        if num_args == 0 {
+            cold_path!();
+            return raise_dumps_exception_fixed(&format!(
+                "{}() missing 1 required positional argument: 'obj'",
+                output.name()
+            ));
+        }
+        if num_args & 2 == 2 {
+            default = Some(NonNull::new_unchecked(*args.offset(1)));
+        }
+        if num_args & 3 == 3 {
+            optsptr = Some(NonNull::new_unchecked(*args.offset(2)));
+        }
+        if !kwnames.is_null() {
+            cold_path!();
             for i in 0..=Py_SIZE(kwnames).saturating_sub(1) {
                 let arg = crate::ffi::PyTuple_GET_ITEM(kwnames, i as Py_ssize_t);
                 if matches_kwarg!(arg, typeref::get_option()) {
                     if num_args & 3 == 3 {
                         cold_path!();
-                        return raise_dumps_exception_fixed(
-                            "dumps() got multiple values for argument: 'option'",
-                        );
+                        return raise_dumps_exception_fixed(&format!(
+                            "{}() got multiple values for argument: 'option'",
+                            output.name()
+                        ));
                     }
                     optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
                 } else if matches_kwarg!(arg, typeref::get_default()) {
                     if num_args & 2 == 2 {
                         cold_path!();
-                        return raise_dumps_exception_fixed(
-                            "dumps() got multiple values for argument: 'default'",
-                        );
+                        return raise_dumps_exception_fixed(&format!(
+                            "{}() got multiple values for argument: 'default'",
+                            output.name()
+                        ));
                     }
                     default = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_envelope()) {
+                    envelopeptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if !matches!(output, DumpsOutput::Many)
+                    && matches_kwarg!(arg, typeref::get_tuple_fields())
+                {
+                    tuplefieldsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_max_output_bytes()) {
+                    maxoutputptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_float_precision()) {
+                    floatprecisionptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_key_filter()) {
+                    keyfilterptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_on_skip()) {
+                    onskipptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches_kwarg!(arg, typeref::get_skip_key_prefix()) {
+                    skipkeyprefixptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches!(output, DumpsOutput::File(_))
+                    && matches_kwarg!(arg, typeref::get_chunk_size())
+                {
+                    chunksizeptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+                } else if matches!(
+                    output,
+                    DumpsOutput::Bytes | DumpsOutput::Str | DumpsOutput::View
+                ) && matches_kwarg!(arg, typeref::get_size_hint())
+                {
+                    sizehintptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
                 } else {
-                    return raise_dumps_exception_fixed(
-                        "dumps() got an unexpected keyword argument",
-                    );
+                    return raise_dumps_exception_fixed(&format!(
+                        "{}() got an unexpected keyword argument",
+                        output.name()
+                    ));
                 }
             }
         }
 
-        let mut optsbits: i32 = 0;
+        if matches!(output, DumpsOutput::Many)
+            && !is_subclass_by_flag!(tp_flags!(ob_type!(*args)), Py_TPFLAGS_LIST_SUBCLASS)
+        {
+            cold_path!();
+            return raise_dumps_exception_fixed("dumps_many() argument must be a list");
+        }
+
+        let mut optsbits: i64 = 0;
         if let Some(opts) = optsptr {
             cold_path!();
             if core::ptr::eq((*opts.as_ptr()).ob_type, typeref::get_int_type()) {
-                #[allow(clippy::cast_possible_truncation)]
-                let tmp = PyLong_AsLong(optsptr.unwrap().as_ptr()) as i32; // stmt_expr_attributes
-                optsbits = tmp;
+                optsbits = PyLong_AsLongLong(optsptr.unwrap().as_ptr());
                 if !(0..=opt::MAX_OPT).contains(&optsbits) {
                     cold_path!();
                     return raise_dumps_exception_fixed("Invalid opts");
                 }
+            } else if core::ptr::eq((*opts.as_ptr()).ob_type, typeref::get_options_type()) {
+                #[allow(clippy::cast_possible_wrap)]
+                let tmp = (*opts.as_ptr().cast::<crate::ffi::Options>()).opts as i64;
+                optsbits = tmp;
             } else if !core::ptr::eq(opts.as_ptr(), typeref::get_none()) {
                 cold_path!();
                 return raise_dumps_exception_fixed("Invalid opts");
@@ -359,11 +1583,299 @@ pub(crate) unsafe extern "C" fn dumps(
         }
 
         #[allow(clippy::cast_sign_loss)]
-        let opts = optsbits as opt::Opt;
+        let opts = opt::with_default(optsbits as opt::Opt, typeref::get_default_opts());
+
+        if let Some(msg) = opt::incompatible(opts) {
+            cold_path!();
+            return raise_dumps_exception_fixed(msg);
+        }
+
+        let mut envelope: Option<&str> = None;
+        if let Some(key) = envelopeptr {
+            cold_path!();
+            if core::ptr::eq((*key.as_ptr()).ob_type, typeref::get_str_type()) {
+                envelope = crate::str::PyStr::from_ptr_unchecked(key.as_ptr()).to_str();
+                if envelope.is_none() {
+                    return raise_dumps_exception_fixed(
+                        "str is not valid UTF-8: surrogates not allowed",
+                    );
+                }
+            } else if !core::ptr::eq(key.as_ptr(), typeref::get_none()) {
+                return raise_dumps_exception_fixed("envelope must be str");
+            }
+        }
+
+        let mut tuple_fields: Option<Vec<&str>> = None;
+        if let Some(fields) = tuplefieldsptr {
+            if !core::ptr::eq(fields.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                match tuple_fields_from_arg(*args, fields.as_ptr()) {
+                    Ok(val) => tuple_fields = Some(val),
+                    Err(msg) => return raise_dumps_exception_fixed(msg),
+                }
+            }
+        }
+
+        let mut max_output_bytes: Option<usize> = None;
+        if let Some(limit) = maxoutputptr {
+            if !core::ptr::eq(limit.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                let val = if core::ptr::eq((*limit.as_ptr()).ob_type, typeref::get_int_type()) {
+                    crate::ffi::PyLong_AsSsize_t(limit.as_ptr())
+                } else {
+                    -1
+                };
+                if val < 0 {
+                    crate::ffi::PyErr_Clear();
+                    return raise_dumps_exception_fixed(
+                        "max_output_bytes must be a non-negative int",
+                    );
+                }
+                max_output_bytes = Some(isize_to_usize(val));
+            }
+        }
+
+        let mut float_precision: Option<u8> = None;
+        if let Some(precision) = floatprecisionptr {
+            if !core::ptr::eq(precision.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                let val = if core::ptr::eq((*precision.as_ptr()).ob_type, typeref::get_int_type()) {
+                    crate::ffi::PyLong_AsSsize_t(precision.as_ptr())
+                } else {
+                    -1
+                };
+                match u8::try_from(val) {
+                    Ok(val) if val <= 20 => float_precision = Some(val),
+                    _ => {
+                        crate::ffi::PyErr_Clear();
+                        return raise_dumps_exception_fixed(
+                            "float_precision must be an int from 0 to 20",
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(callables) = default {
+            if core::ptr::eq((*callables.as_ptr()).ob_type, typeref::get_list_type())
+                || core::ptr::eq((*callables.as_ptr()).ob_type, typeref::get_tuple_type())
+            {
+                cold_path!();
+                if !serialize::is_callable_sequence(callables.as_ptr()) {
+                    return raise_dumps_exception_fixed(
+                        "default must be callable or a list or tuple of callables",
+                    );
+                }
+            }
+        }
+
+        let mut key_filter: Option<NonNull<PyObject>> = None;
+        if let Some(callable) = keyfilterptr {
+            if !core::ptr::eq(callable.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                if crate::ffi::PyCallable_Check(callable.as_ptr()) == 0 {
+                    return raise_dumps_exception_fixed("key_filter must be callable");
+                }
+                key_filter = Some(callable);
+            }
+        }
+
+        let mut on_skip: Option<NonNull<PyObject>> = None;
+        if let Some(callable) = onskipptr {
+            if !core::ptr::eq(callable.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                if crate::ffi::PyCallable_Check(callable.as_ptr()) == 0 {
+                    return raise_dumps_exception_fixed("on_skip must be callable");
+                }
+                on_skip = Some(callable);
+            }
+        }
 
-        serialize(*args, default, opts).map_or_else(
+        let mut skip_key_prefix: Option<NonNull<PyObject>> = None;
+        if let Some(prefix) = skipkeyprefixptr {
+            if !core::ptr::eq(prefix.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                if !core::ptr::eq((*prefix.as_ptr()).ob_type, typeref::get_str_type()) {
+                    return raise_dumps_exception_fixed("skip_key_prefix must be str");
+                }
+                if crate::str::PyStr::from_ptr_unchecked(prefix.as_ptr())
+                    .to_str()
+                    .is_none()
+                {
+                    return raise_dumps_exception_fixed(
+                        "str is not valid UTF-8: surrogates not allowed",
+                    );
+                }
+                skip_key_prefix = Some(prefix);
+            }
+        }
+
+        let mut chunk_size = DEFAULT_CHUNK_SIZE;
+        if let Some(size) = chunksizeptr {
+            if !core::ptr::eq(size.as_ptr(), typeref::get_none()) {
+                let val = if core::ptr::eq((*size.as_ptr()).ob_type, typeref::get_int_type()) {
+                    crate::ffi::PyLong_AsSsize_t(size.as_ptr())
+                } else {
+                    -1
+                };
+                if val < 1 {
+                    crate::ffi::PyErr_Clear();
+                    return raise_dumps_exception_fixed("chunk_size must be a positive int");
+                }
+                chunk_size = isize_to_usize(val);
+            }
+        }
+
+        let mut size_hint: usize = 0;
+        if let Some(hint) = sizehintptr {
+            if !core::ptr::eq(hint.as_ptr(), typeref::get_none()) {
+                cold_path!();
+                // a hint too large for an int64 is capped like any other
+                let mut overflow: core::ffi::c_int = 0;
+                let val = if core::ptr::eq((*hint.as_ptr()).ob_type, typeref::get_int_type()) {
+                    crate::ffi::PyLong_AsLongLongAndOverflow(hint.as_ptr(), &raw mut overflow)
+                } else {
+                    -1
+                };
+                if val < 0 && overflow != 1 {
+                    crate::ffi::PyErr_Clear();
+                    return raise_dumps_exception_fixed("size_hint must be a non-negative int");
+                }
+                size_hint = if overflow == 1 {
+                    usize::MAX
+                } else {
+                    usize::try_from(val).unwrap_or(usize::MAX)
+                };
+            }
+        }
+
+        let options = DumpOptions {
+            opts: opts,
+            default: default,
+            key_filter: key_filter,
+            envelope: envelope,
+            tuple_fields: tuple_fields.as_deref(),
+            max_output_bytes: max_output_bytes,
+            float_precision: float_precision,
+            on_skip: on_skip,
+            skip_key_prefix: skip_key_prefix,
+            size_hint: size_hint,
+        };
+        match output {
+            DumpsOutput::Bytes => serialize(*args, &options),
+            DumpsOutput::Str => {
+                cold_path!();
+                serialize_str(*args, &options)
+            }
+            DumpsOutput::View => {
+                cold_path!();
+                serialize_view(*args, &options)
+            }
+            DumpsOutput::File(fp) => {
+                cold_path!();
+                return match serialize_to_fp(*args, &options, fp, chunk_size) {
+                    Ok(true) => use_immortal!(typeref::get_none()),
+                    Ok(false) => null_mut(),
+                    Err(err) => raise_dumps_exception_dynamic(err.as_str()),
+                };
+            }
+            DumpsOutput::Many => {
+                cold_path!();
+                serialize_many(*args, &options)
+            }
+        }
+        .map_or_else(
             |err| raise_dumps_exception_dynamic(err.as_str()),
             NonNull::as_ptr,
         )
     }
 }
+
+/// Drop the state of every interpreter, as `shutdown()` does, for native
+/// code that finalizes Python and initializes it again in the same process.
+/// Called after `Py_FinalizeEx()`, when no interpreter is active, it also
+/// forgets the numpy types, and the states are leaked rather than released,
+/// as their objects were freed with the interpreters.
+///
+/// # Safety
+///
+/// While Python is initialized, the caller must either hold the GIL of an
+/// interpreter or not be attached to any.
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn orjson_shutdown() {
+    unsafe {
+        if crate::ffi::Py_IsInitialized() == 0 {
+            interpreter_state::shutdown_states(false);
+            typeref::reset_numpy_types();
+        } else {
+            interpreter_state::shutdown_states(crate::ffi::thread_state_is_attached());
+        }
+    }
+}
+
+/// `orjson_dumps_into()` failed and an exception is set, e.g.,
+/// `JSONEncodeError`.
+pub(crate) const DUMPS_INTO_ERROR: Py_ssize_t = -1;
+
+/// `orjson_dumps_into()` failed as the output is longer than the buffer. No
+/// exception is set.
+pub(crate) const DUMPS_INTO_BUFFER_TOO_SMALL: Py_ssize_t = -2;
+
+/// Serialize `obj` into the `capacity` bytes at `buf` as `dumps(obj,
+/// option=opts)` would, for callers in native code. Returns the number of
+/// bytes written, `DUMPS_INTO_ERROR` with an exception set, or
+/// `DUMPS_INTO_BUFFER_TOO_SMALL`. The output is not NUL-terminated.
+///
+/// # Safety
+///
+/// The caller must hold the GIL of the interpreter that imported this
+/// module, `obj` must be a valid object, and `buf` must be valid for writes
+/// of `capacity` bytes. `buf` may be null if `capacity` is 0.
+#[unsafe(no_mangle)]
+#[cold]
+pub(crate) unsafe extern "C" fn orjson_dumps_into(
+    obj: *mut PyObject,
+    buf: *mut u8,
+    capacity: usize,
+    opts: u64,
+) -> Py_ssize_t {
+    unsafe {
+        debug_assert_gil_held!("orjson_dumps_into");
+        let Some(_state) = interpreter_state::StateGuard::new() else {
+            cold_path!();
+            raise_state_exception();
+            return DUMPS_INTO_ERROR;
+        };
+        if obj.is_null() || (buf.is_null() && capacity != 0) {
+            cold_path!();
+            raise_dumps_exception_fixed("orjson_dumps_into() argument is null");
+            return DUMPS_INTO_ERROR;
+        }
+        #[allow(clippy::cast_sign_loss)]
+        if opts > opt::MAX_OPT as opt::Opt {
+            cold_path!();
+            raise_dumps_exception_fixed("Invalid opts");
+            return DUMPS_INTO_ERROR;
+        }
+        let opts = opt::with_default(opts, typeref::get_default_opts());
+        if let Some(msg) = opt::incompatible(opts) {
+            cold_path!();
+            raise_dumps_exception_fixed(msg);
+            return DUMPS_INTO_ERROR;
+        }
+        let out: &mut [u8] = if capacity == 0 {
+            &mut []
+        } else {
+            core::slice::from_raw_parts_mut(buf, capacity)
+        };
+        match serialize_to_slice(obj, opts, out) {
+            Ok(Some(len)) => usize_to_isize(len),
+            Ok(None) => DUMPS_INTO_BUFFER_TOO_SMALL,
+            Err(err) => {
+                raise_dumps_exception_dynamic(err.as_str());
+                DUMPS_INTO_ERROR
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2020-2025)
 
-pub(crate) type Opt = u32;
+pub(crate) type Opt = u64;
 
 pub(crate) const INDENT_2: Opt = 1;
 pub(crate) const NAIVE_UTC: Opt = 1 << 1;
@@ -15,6 +15,44 @@ pub(crate) const PASSTHROUGH_SUBCLASS: Opt = 1 << 8;
 pub(crate) const PASSTHROUGH_DATETIME: Opt = 1 << 9;
 pub(crate) const APPEND_NEWLINE: Opt = 1 << 10;
 pub(crate) const PASSTHROUGH_DATACLASS: Opt = 1 << 11;
+pub(crate) const FORBID_NAIVE_DATETIME: Opt = 1 << 12;
+pub(crate) const FORBID_DUPLICATE_KEYS: Opt = 1 << 13;
+pub(crate) const DUPLICATE_KEYS_AS_LIST: Opt = 1 << 14;
+pub(crate) const TZ_OFFSET_NO_COLON: Opt = 1 << 15;
+pub(crate) const SERIALIZE_BYTES_BASE64: Opt = 1 << 16;
+pub(crate) const SERIALIZE_BYTES_HEX: Opt = 1 << 17;
+pub(crate) const PASSTHROUGH_BYTES: Opt = 1 << 18;
+pub(crate) const ENSURE_ASCII: Opt = 1 << 19;
+pub(crate) const USE_JSON_DUNDER: Opt = 1 << 20;
+pub(crate) const PASSTHROUGH_NUMPY: Opt = 1 << 21;
+pub(crate) const SERIALIZE_IPADDRESS: Opt = 1 << 22;
+pub(crate) const SERIALIZE_RANGE: Opt = 1 << 23;
+pub(crate) const RANGE_AS_LIST: Opt = 1 << 24;
+pub(crate) const SERIALIZE_BYTES_LATIN1: Opt = 1 << 25;
+pub(crate) const WEAKREF_DEAD_AS_NULL: Opt = 1 << 26;
+pub(crate) const INTERN_VALUES: Opt = 1 << 27;
+pub(crate) const DATETIME_AS_UNIX_SECONDS: Opt = 1 << 28;
+pub(crate) const DATETIME_AS_UNIX_MILLIS: Opt = 1 << 29;
+pub(crate) const SERIALIZE_FSPATH: Opt = 1 << 30;
+pub(crate) const NON_STR_KEYS_STRICT: Opt = 1 << 31;
+pub(crate) const SURROGATES_ESCAPE: Opt = 1 << 32;
+pub(crate) const SERIALIZE_ITERABLES: Opt = 1 << 33;
+pub(crate) const ESCAPE_FORWARD_SLASH: Opt = 1 << 34;
+pub(crate) const SERIALIZE_FRACTION: Opt = 1 << 35;
+pub(crate) const FRACTION_AS_FLOAT: Opt = 1 << 36;
+pub(crate) const SELF_CHECK: Opt = 1 << 37;
+pub(crate) const PARSE_RELAXED: Opt = 1 << 38;
+pub(crate) const BIG_NUMBER_AS_STRING: Opt = 1 << 39;
+pub(crate) const CACHE_TUPLES: Opt = 1 << 40;
+pub(crate) const DATE_AS_EPOCH_DAYS: Opt = 1 << 41;
+pub(crate) const SKIP_UNSERIALIZABLE: Opt = 1 << 42;
+pub(crate) const UUID_AS_BYTES: Opt = 1 << 43;
+pub(crate) const SERIALIZE_PROPERTIES: Opt = 1 << 44;
+pub(crate) const REPR_FALLBACK: Opt = 1 << 45;
+pub(crate) const RFC3339_STRICT: Opt = 1 << 46;
+pub(crate) const VALIDATE_FRAGMENTS: Opt = 1 << 47;
+pub(crate) const MEMORYVIEW_AS_NESTED: Opt = 1 << 48;
+pub(crate) const JSON5_NONFINITE: Opt = 1 << 49;
 
 // deprecated
 pub(crate) const SERIALIZE_DATACLASS: Opt = 0;
@@ -22,21 +60,102 @@ pub(crate) const SERIALIZE_UUID: Opt = 0;
 
 pub(crate) const SORT_OR_NON_STR_KEYS: Opt = SORT_KEYS | NON_STR_KEYS;
 
+pub(crate) const DATETIME_AS_UNIX: Opt = DATETIME_AS_UNIX_SECONDS | DATETIME_AS_UNIX_MILLIS;
+
+pub(crate) const BYTES_MODE: Opt =
+    PASSTHROUGH_BYTES | SERIALIZE_BYTES_BASE64 | SERIALIZE_BYTES_HEX | SERIALIZE_BYTES_LATIN1;
+
 pub(crate) const NOT_PASSTHROUGH: Opt =
     !(PASSTHROUGH_DATETIME | PASSTHROUGH_DATACLASS | PASSTHROUGH_SUBCLASS);
 
+/// The flags that apply to `loads()`. Others are rejected by it.
+pub(crate) const LOADS_OPTS: Opt =
+    DUPLICATE_KEYS_AS_LIST | FORBID_DUPLICATE_KEYS | INTERN_VALUES | PARSE_RELAXED;
+
 #[allow(clippy::cast_possible_wrap)]
-pub(crate) const MAX_OPT: i32 = (APPEND_NEWLINE
+pub(crate) const MAX_OPT: i64 = (APPEND_NEWLINE
+    | BIG_NUMBER_AS_STRING
+    | CACHE_TUPLES
+    | DATETIME_AS_UNIX_MILLIS
+    | DATETIME_AS_UNIX_SECONDS
+    | DATE_AS_EPOCH_DAYS
+    | DUPLICATE_KEYS_AS_LIST
+    | ENSURE_ASCII
+    | ESCAPE_FORWARD_SLASH
+    | FORBID_DUPLICATE_KEYS
+    | FORBID_NAIVE_DATETIME
+    | FRACTION_AS_FLOAT
     | INDENT_2
+    | INTERN_VALUES
+    | JSON5_NONFINITE
+    | MEMORYVIEW_AS_NESTED
     | NAIVE_UTC
     | NON_STR_KEYS
+    | NON_STR_KEYS_STRICT
     | OMIT_MICROSECONDS
+    | PARSE_RELAXED
+    | PASSTHROUGH_BYTES
     | PASSTHROUGH_DATETIME
     | PASSTHROUGH_DATACLASS
+    | PASSTHROUGH_NUMPY
     | PASSTHROUGH_SUBCLASS
+    | RANGE_AS_LIST
+    | REPR_FALLBACK
+    | RFC3339_STRICT
+    | SELF_CHECK
+    | SERIALIZE_BYTES_BASE64
+    | SERIALIZE_BYTES_HEX
+    | SERIALIZE_BYTES_LATIN1
     | SERIALIZE_DATACLASS
+    | SERIALIZE_FRACTION
+    | SERIALIZE_FSPATH
+    | SERIALIZE_IPADDRESS
+    | SERIALIZE_ITERABLES
     | SERIALIZE_NUMPY
+    | SERIALIZE_PROPERTIES
+    | SERIALIZE_RANGE
     | SERIALIZE_UUID
+    | SKIP_UNSERIALIZABLE
     | SORT_KEYS
     | STRICT_INTEGER
-    | UTC_Z) as i32;
+    | SURROGATES_ESCAPE
+    | TZ_OFFSET_NO_COLON
+    | USE_JSON_DUNDER
+    | UTC_Z
+    | UUID_AS_BYTES
+    | VALIDATE_FRAGMENTS
+    | WEAKREF_DEAD_AS_NULL) as i64;
+
+/// The flags of a call combined with the default of `set_default_options()`.
+/// A flag of a group of mutually exclusive flags given to the call replaces
+/// the default of the group.
+#[inline(always)]
+pub(crate) fn with_default(opts: Opt, default: Opt) -> Opt {
+    let mut default = default;
+    if default != 0 {
+        cold_path!();
+        for group in [BYTES_MODE, DATETIME_AS_UNIX] {
+            if opts & group != 0 {
+                default &= !group;
+            }
+        }
+    }
+    opts | default
+}
+
+/// The error for flags that cannot be used together, if any.
+pub(crate) fn incompatible(opts: Opt) -> Option<&'static str> {
+    if (opts & BYTES_MODE).count_ones() > 1 {
+        Some(
+            "OPT_PASSTHROUGH_BYTES, OPT_SERIALIZE_BYTES_BASE64, OPT_SERIALIZE_BYTES_HEX, and OPT_SERIALIZE_BYTES_LATIN1 are mutually exclusive",
+        )
+    } else if opts & DATETIME_AS_UNIX == DATETIME_AS_UNIX {
+        Some("OPT_DATETIME_AS_UNIX_SECONDS and OPT_DATETIME_AS_UNIX_MILLIS are mutually exclusive")
+    } else if opts & RFC3339_STRICT != 0 && opts & DATETIME_AS_UNIX != 0 {
+        Some(
+            "OPT_RFC3339_STRICT and OPT_DATETIME_AS_UNIX_SECONDS or OPT_DATETIME_AS_UNIX_MILLIS are mutually exclusive",
+        )
+    } else {
+        None
+    }
+}
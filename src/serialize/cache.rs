@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use associative_cache::{
+    AssociativeCache, Capacity64, Capacity256, HashDirectMapped, RoundRobinReplacement,
+};
+use core::ptr::NonNull;
+
+/// A `dict` key coerced to `str` by `OPT_NON_STR_KEYS`.
+///
+/// This holds a reference to the key so that it is not deallocated while
+/// cached. An address therefore identifies the same immutable object for as
+/// long as it is in the cache.
+pub(crate) struct CachedNonStrKey {
+    ptr: NonNull<crate::ffi::PyObject>,
+    key: String,
+}
+
+unsafe impl Send for CachedNonStrKey {}
+unsafe impl Sync for CachedNonStrKey {}
+
+impl CachedNonStrKey {
+    pub fn new(ptr: *mut crate::ffi::PyObject, key: String) -> CachedNonStrKey {
+        ffi!(Py_INCREF(ptr));
+        CachedNonStrKey {
+            ptr: nonnull!(ptr),
+            key: key,
+        }
+    }
+
+    pub fn get(&self) -> String {
+        self.key.clone()
+    }
+}
+
+impl Drop for CachedNonStrKey {
+    fn drop(&mut self) {
+        ffi!(Py_DECREF(self.ptr.as_ptr()));
+    }
+}
+
+pub(crate) type NonStrKeyMap =
+    AssociativeCache<usize, CachedNonStrKey, Capacity256, HashDirectMapped, RoundRobinReplacement>;
+
+/// A `tuple` serialized with `OPT_CACHE_TUPLES`, and the options it was
+/// serialized with.
+///
+/// This holds a reference to the tuple so that it is not deallocated while
+/// cached, as for `CachedNonStrKey`. Only a tuple whose elements are
+/// themselves immutable is cached.
+pub(crate) struct CachedTuple {
+    ptr: NonNull<crate::ffi::PyObject>,
+    opts: crate::opt::Opt,
+    json: Vec<u8>,
+}
+
+unsafe impl Send for CachedTuple {}
+unsafe impl Sync for CachedTuple {}
+
+impl CachedTuple {
+    pub fn new(
+        ptr: *mut crate::ffi::PyObject,
+        opts: crate::opt::Opt,
+        json: Vec<u8>,
+    ) -> CachedTuple {
+        ffi!(Py_INCREF(ptr));
+        CachedTuple {
+            ptr: nonnull!(ptr),
+            opts: opts,
+            json: json,
+        }
+    }
+
+    /// The serialized tuple, if it was serialized with `opts`.
+    pub fn get(&self, opts: crate::opt::Opt) -> Option<&[u8]> {
+        (self.opts == opts).then_some(self.json.as_slice())
+    }
+}
+
+impl Drop for CachedTuple {
+    fn drop(&mut self) {
+        ffi!(Py_DECREF(self.ptr.as_ptr()));
+    }
+}
+
+pub(crate) type TupleMap =
+    AssociativeCache<usize, CachedTuple, Capacity64, HashDirectMapped, RoundRobinReplacement>;
@@ -6,21 +6,42 @@ use core::ptr::NonNull;
 
 pub(crate) enum SerializeError {
     DatetimeLibraryUnsupported,
+    DatetimeNaive,
+    DatetimeNaiveUnix,
+    DatetimeUnavailable,
     DefaultRecursionLimit,
+    DictAttributeNotMapping,
+    FractionConversion,
+    FsPath(NonNull<crate::ffi::PyObject>),
     Integer53Bits,
     Integer64Bits,
     InvalidStr,
     InvalidFragment,
+    IpAddressConversion,
+    Iterator(NonNull<crate::ffi::PyObject>),
+    JsonDunder(NonNull<crate::ffi::PyObject>),
+    KeyFilter,
     KeyMustBeStr,
+    MappingConversion,
+    MaxOutputBytes(usize),
+    MemoryviewNotCContiguous,
+    MemoryviewUnsupportedFormat,
+    OnSkip,
+    OutputExceeded,
+    Property(NonNull<crate::ffi::PyObject>, NonNull<crate::ffi::PyObject>),
+    RangeTooLarge,
     RecursionLimit,
-    TimeHasTzinfo,
+    TimeInvalidOffset,
     DictIntegerKey64Bit,
+    DictKeyBytesInvalidUtf8,
+    DictKeyCollision,
+    DictKeyFloatInexact,
     DictKeyInvalidType,
     NumpyMalformed,
-    NumpyNotCContiguous,
     NumpyNotNativeEndian,
     NumpyUnsupportedDatatype,
     UnsupportedType(NonNull<crate::ffi::PyObject>),
+    WeakrefDead,
 }
 
 impl core::fmt::Display for SerializeError {
@@ -32,29 +53,106 @@ impl core::fmt::Display for SerializeError {
                 f,
                 "datetime's timezone library is not supported: use datetime.timezone.utc, pendulum, pytz, or dateutil"
             ),
+            SerializeError::DatetimeNaive => write!(
+                f,
+                "datetime.datetime must have tzinfo set with OPT_FORBID_NAIVE_DATETIME or OPT_RFC3339_STRICT"
+            ),
+            SerializeError::DatetimeNaiveUnix => write!(
+                f,
+                "datetime.datetime must have tzinfo set or OPT_NAIVE_UTC specified to serialize as a Unix timestamp"
+            ),
+            SerializeError::DatetimeUnavailable => write!(
+                f,
+                "datetime support unavailable: the datetime module could not be imported; call reset_state() once it can be"
+            ),
             SerializeError::DefaultRecursionLimit => {
                 write!(f, "default serializer exceeds recursion limit")
             }
+            SerializeError::DictAttributeNotMapping => {
+                write!(f, "__dict__ is not a mapping")
+            }
+            SerializeError::FractionConversion => {
+                write!(f, "fractions.Fraction could not be converted")
+            }
+            SerializeError::FsPath(ptr) => {
+                let name =
+                    unsafe { CStr::from_ptr((*ob_type!(ptr.as_ptr())).tp_name).to_string_lossy() };
+                write!(f, "__fspath__() raised an exception: {name}")
+            }
             SerializeError::Integer53Bits => write!(f, "Integer exceeds 53-bit range"),
             SerializeError::Integer64Bits => write!(f, "Integer exceeds 64-bit range"),
             SerializeError::InvalidStr => write!(f, "{}", crate::util::INVALID_STR),
             SerializeError::InvalidFragment => {
                 write!(f, "orjson.Fragment's content is not of type bytes or str")
             }
+            SerializeError::JsonDunder(ptr) => {
+                let name =
+                    unsafe { CStr::from_ptr((*ob_type!(ptr.as_ptr())).tp_name).to_string_lossy() };
+                write!(f, "__json__() raised an exception: {name}")
+            }
+            SerializeError::IpAddressConversion => {
+                write!(f, "ipaddress object could not be converted to str")
+            }
+            SerializeError::Iterator(ptr) => {
+                let name =
+                    unsafe { CStr::from_ptr((*ob_type!(ptr.as_ptr())).tp_name).to_string_lossy() };
+                write!(f, "iterator raised an exception: {name}")
+            }
+            SerializeError::KeyFilter => write!(f, "key_filter raised an exception"),
             SerializeError::KeyMustBeStr => write!(f, "Dict key must be str"),
+            SerializeError::MappingConversion => {
+                write!(f, "mapping could not be converted to dict")
+            }
+            SerializeError::MaxOutputBytes(limit) => {
+                write!(f, "Output exceeds max_output_bytes of {limit}")
+            }
+            SerializeError::MemoryviewNotCContiguous => {
+                write!(f, "memoryview is not C contiguous")
+            }
+            SerializeError::MemoryviewUnsupportedFormat => {
+                write!(
+                    f,
+                    "memoryview format is not supported by OPT_MEMORYVIEW_AS_NESTED"
+                )
+            }
+            SerializeError::OnSkip => write!(f, "on_skip raised an exception"),
+            SerializeError::OutputExceeded => write!(f, "Output exceeds its limit"),
+            SerializeError::Property(ptr, name) => {
+                let type_name =
+                    unsafe { CStr::from_ptr((*ob_type!(ptr.as_ptr())).tp_name).to_string_lossy() };
+                let name = unsafe { crate::str::PyStr::from_ptr_unchecked(name.as_ptr()) };
+                write!(
+                    f,
+                    "property raised an exception: {type_name}.{}",
+                    name.to_str().unwrap_or_default()
+                )
+            }
+            SerializeError::RangeTooLarge => {
+                write!(f, "range is too large to serialize as a list")
+            }
             SerializeError::RecursionLimit => write!(f, "Recursion limit reached"),
-            SerializeError::TimeHasTzinfo => write!(f, "datetime.time must not have tzinfo set"),
+            SerializeError::TimeInvalidOffset => {
+                write!(f, "datetime.time tzinfo utcoffset() is invalid")
+            }
             SerializeError::DictIntegerKey64Bit => {
                 write!(f, "Dict integer key must be within 64-bit range")
             }
+            SerializeError::DictKeyBytesInvalidUtf8 => write!(
+                f,
+                "Dict bytes key is not valid UTF-8: specify OPT_SERIALIZE_BYTES_BASE64 to serialize it as base64"
+            ),
+            SerializeError::DictKeyCollision => write!(
+                f,
+                "Dict keys are equal once converted to str with OPT_NON_STR_KEYS_STRICT"
+            ),
+            SerializeError::DictKeyFloatInexact => write!(
+                f,
+                "Dict float key is not exactly its decimal representation with OPT_NON_STR_KEYS_STRICT"
+            ),
             SerializeError::DictKeyInvalidType => {
                 write!(f, "Dict key must a type serializable with OPT_NON_STR_KEYS")
             }
             SerializeError::NumpyMalformed => write!(f, "numpy array is malformed"),
-            SerializeError::NumpyNotCContiguous => write!(
-                f,
-                "numpy array is not C contiguous; use ndarray.tolist() in default"
-            ),
             SerializeError::NumpyNotNativeEndian => {
                 write!(f, "numpy array is not native-endianness")
             }
@@ -66,6 +164,9 @@ impl core::fmt::Display for SerializeError {
                     unsafe { CStr::from_ptr((*ob_type!(ptr.as_ptr())).tp_name).to_string_lossy() };
                 write!(f, "Type is not JSON serializable: {name}")
             }
+            SerializeError::WeakrefDead => {
+                write!(f, "weakly-referenced object no longer exists")
+            }
         }
     }
 }
@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::ffi::{
+    Py_DECREF, PyBytes_AS_STRING, PyBytes_FromStringAndSize, PyBytes_GET_SIZE, PyErr_Occurred,
+    PyErr_SetObject, PyExc_OSError, PyLong_AsSsize_t, PyObject, PyObject_CallMethodOneArg,
+    PyUnicode_FromStringAndSize,
+};
+use crate::util::usize_to_isize;
+use core::ffi::c_char;
+
+/// The `chunk_size` of `dump()` if not given.
+pub(crate) const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Write the contents of `bytes` to `fp.write()`.
+///
+/// `write()` may accept fewer bytes than given, as a non-blocking socket
+/// does, and return the count it accepted. The remainder is then given to
+/// `write()` again until everything is accepted. A return value that is not
+/// an `int`, e.g., `None`, means everything was accepted.
+///
+/// Returns `false` with an exception set if `write()` raised or returned a
+/// count that is not between 1 and the number of bytes given.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn write_all(fp: *mut PyObject, bytes: *mut PyObject) -> bool {
+    unsafe {
+        let buf = PyBytes_AS_STRING(bytes);
+        let len = PyBytes_GET_SIZE(bytes);
+        let mut written: isize = 0;
+        while written < len {
+            let remaining = len - written;
+            let chunk = if written == 0 {
+                ffi!(Py_INCREF(bytes));
+                bytes
+            } else {
+                // a copy, as write() may keep a reference to its argument
+                PyBytes_FromStringAndSize(buf.offset(written), remaining)
+            };
+            if chunk.is_null() {
+                return false;
+            }
+            let ret = PyObject_CallMethodOneArg(fp, crate::typeref::get_write_method_str(), chunk);
+            Py_DECREF(chunk);
+            if ret.is_null() {
+                return false;
+            }
+            if !core::ptr::eq(ob_type!(ret), crate::typeref::get_int_type()) {
+                Py_DECREF(ret);
+                break;
+            }
+            let count = PyLong_AsSsize_t(ret);
+            Py_DECREF(ret);
+            if count == -1 && !PyErr_Occurred().is_null() {
+                return false;
+            }
+            if count < 1 || count > remaining {
+                let msg = format!(
+                    "write() returned invalid length {count} (should have been between 1 and {remaining})"
+                );
+                let err_msg = PyUnicode_FromStringAndSize(
+                    msg.as_ptr().cast::<c_char>(),
+                    usize_to_isize(msg.len()),
+                );
+                PyErr_SetObject(PyExc_OSError, err_msg);
+                Py_DECREF(err_msg);
+                return false;
+            }
+            written += count;
+        }
+        true
+    }
+}
+
+/// An exception taken from the error indicator to be raised later.
+pub(crate) struct PendingException {
+    #[cfg(Py_3_12)]
+    exc: *mut PyObject,
+    #[cfg(not(Py_3_12))]
+    tp: *mut PyObject,
+    #[cfg(not(Py_3_12))]
+    val: *mut PyObject,
+    #[cfg(not(Py_3_12))]
+    traceback: *mut PyObject,
+}
+
+impl PendingException {
+    #[cfg(Py_3_12)]
+    pub(crate) fn fetch() -> Self {
+        PendingException {
+            exc: ffi!(PyErr_GetRaisedException()),
+        }
+    }
+
+    #[cfg(not(Py_3_12))]
+    pub(crate) fn fetch() -> Self {
+        let mut tp: *mut PyObject = core::ptr::null_mut();
+        let mut val: *mut PyObject = core::ptr::null_mut();
+        let mut traceback: *mut PyObject = core::ptr::null_mut();
+        ffi!(PyErr_Fetch(&mut tp, &mut val, &mut traceback));
+        PendingException {
+            tp: tp,
+            val: val,
+            traceback: traceback,
+        }
+    }
+
+    #[cfg(Py_3_12)]
+    pub(crate) fn restore(self) {
+        ffi!(PyErr_SetRaisedException(self.exc));
+    }
+
+    #[cfg(not(Py_3_12))]
+    pub(crate) fn restore(self) {
+        ffi!(PyErr_Restore(self.tp, self.val, self.traceback));
+    }
+}
+
+/// The file `dump()` writes to as the buffer fills, rather than once
+/// serialization is complete.
+///
+/// If a write fails, its exception is taken from the error indicator so that
+/// serialization, which may call `default`, can continue without one set.
+/// Nothing more is written and `finish()` raises it.
+pub(crate) struct FileSink {
+    fp: *mut PyObject,
+    chunk_size: usize,
+    error: Option<PendingException>,
+}
+
+impl FileSink {
+    pub fn new(fp: *mut PyObject, chunk_size: usize) -> Self {
+        debug_assert!(chunk_size > 0);
+        FileSink {
+            fp: fp,
+            chunk_size: chunk_size,
+            error: None,
+        }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Write `data` to `fp.write()` in chunks of at most `chunk_size` bytes.
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    pub fn write(&mut self, data: &[u8]) {
+        for chunk in data.chunks(self.chunk_size) {
+            if self.error.is_some() {
+                return;
+            }
+            let bytes = unsafe {
+                PyBytes_FromStringAndSize(
+                    chunk.as_ptr().cast::<c_char>(),
+                    usize_to_isize(chunk.len()),
+                )
+            };
+            if bytes.is_null() || !write_all(self.fp, bytes) {
+                self.error = Some(PendingException::fetch());
+            }
+            if !bytes.is_null() {
+                ffi!(Py_DECREF(bytes));
+            }
+        }
+    }
+
+    /// Raise the exception of a failed write, if any. Returns `false` if one
+    /// was raised.
+    pub fn finish(&mut self) -> bool {
+        match self.error.take() {
+            Some(err) => {
+                err.restore();
+                false
+            }
+            None => true,
+        }
+    }
+}
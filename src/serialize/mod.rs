@@ -2,11 +2,18 @@
 // Copyright ijl (2021-2025)
 
 mod buffer;
+pub(crate) mod cache;
 mod error;
+mod fp;
 mod obtype;
 mod per_type;
 mod serializer;
 mod state;
 pub(crate) mod writer;
 
-pub(crate) use serializer::serialize;
+pub(crate) use fp::DEFAULT_CHUNK_SIZE;
+pub(crate) use per_type::{BytesMode, is_callable_sequence};
+pub(crate) use serializer::{
+    DumpOptions, serialize, serialize_many, serialize_str, serialize_to_fp, serialize_to_slice,
+    serialize_view,
+};
@@ -2,11 +2,56 @@
 // Copyright ijl (2020-2025), Aviram Hassan (2020)
 
 use crate::opt::{
-    Opt, PASSTHROUGH_DATACLASS, PASSTHROUGH_DATETIME, PASSTHROUGH_SUBCLASS, SERIALIZE_NUMPY,
+    CACHE_TUPLES, MEMORYVIEW_AS_NESTED, Opt, PASSTHROUGH_DATACLASS, PASSTHROUGH_DATETIME,
+    PASSTHROUGH_NUMPY, PASSTHROUGH_SUBCLASS, SERIALIZE_FRACTION, SERIALIZE_FSPATH,
+    SERIALIZE_IPADDRESS, SERIALIZE_ITERABLES, SERIALIZE_NUMPY, SERIALIZE_PROPERTIES,
+    SERIALIZE_RANGE, USE_JSON_DUNDER,
+};
+use crate::serialize::per_type::{
+    BytesMode, has_properties, is_fraction, is_ipaddress, is_iterator, is_numpy_array,
+    is_numpy_scalar, is_pandas_nat,
 };
-use crate::serialize::per_type::{is_numpy_array, is_numpy_scalar};
 // Type constants now accessed via typeref accessor functions
 
+use core::cell::Cell;
+use core::ptr::null_mut;
+
+thread_local! {
+    /// The type last classified as `ObType::Unknown`, and the options it was
+    /// classified with, by the `dumps()` call in progress on this thread. It
+    /// holds a reference, so that its address is not reused by another type
+    /// during the call.
+    static UNKNOWN_TYPE: Cell<(*mut crate::ffi::PyTypeObject, Opt)> =
+        const { Cell::new((null_mut(), 0)) };
+}
+
+/// Start a `dumps()` call with no type cached as `ObType::Unknown`, returning
+/// the cache of the call in progress, if any, for `end_unknown_type_cache()`.
+#[inline]
+pub(crate) fn begin_unknown_type_cache() -> (*mut crate::ffi::PyTypeObject, Opt) {
+    UNKNOWN_TYPE.replace((null_mut(), 0))
+}
+
+/// Release the type cached by the call that is ending and restore `previous`.
+#[inline]
+pub(crate) fn end_unknown_type_cache(previous: (*mut crate::ffi::PyTypeObject, Opt)) {
+    let (cached, _) = UNKNOWN_TYPE.replace(previous);
+    if !cached.is_null() {
+        cold_path!();
+        ffi!(Py_DECREF(cached.cast::<crate::ffi::PyObject>()));
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn cache_unknown_type(ob_type: *mut crate::ffi::PyTypeObject, opts: Opt) {
+    ffi!(Py_INCREF(ob_type.cast::<crate::ffi::PyObject>()));
+    let (previous, _) = UNKNOWN_TYPE.replace((ob_type, opts));
+    if !previous.is_null() {
+        ffi!(Py_DECREF(previous.cast::<crate::ffi::PyObject>()));
+    }
+}
+
 #[repr(u32)]
 pub(crate) enum ObType {
     Str,
@@ -27,6 +72,19 @@ pub(crate) enum ObType {
     Enum,
     StrSubclass,
     Fragment,
+    Bytes,
+    Mapping,
+    DictView,
+    CachedTuple,
+    JsonDunder,
+    IpAddress,
+    Fraction,
+    Range,
+    Weakref,
+    Array,
+    FsPath,
+    Iterable,
+    Properties,
     Unknown,
 }
 
@@ -60,14 +118,75 @@ pub(crate) fn pyobject_to_obtype_unlikely(
     ob_type: *mut crate::ffi::PyTypeObject,
     opts: Opt,
 ) -> ObType {
+    // classification depends only on the type and options, so, e.g., a list
+    // of objects passed to `default` is not checked again for each element
+    if UNKNOWN_TYPE.get() == (ob_type, opts) {
+        return ObType::Unknown;
+    }
+
     if is_class_by_type!(ob_type, crate::typeref::get_uuid_type()) {
         return ObType::Uuid;
     } else if is_class_by_type!(ob_type, crate::typeref::get_tuple_type()) {
+        if opt_enabled!(opts, CACHE_TUPLES) {
+            return ObType::CachedTuple;
+        }
         return ObType::Tuple;
     } else if is_class_by_type!(ob_type, crate::typeref::get_fragment_type()) {
         return ObType::Fragment;
     }
 
+    if is_class_by_type!(ob_type, crate::typeref::get_memoryview_type())
+        && opt_enabled!(opts, MEMORYVIEW_AS_NESTED)
+    {
+        cold_path!();
+        return ObType::Array;
+    }
+
+    if (is_class_by_type!(ob_type, crate::typeref::get_bytes_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_bytearray_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_memoryview_type()))
+        && BytesMode::from_opts(opts) != BytesMode::Passthrough
+    {
+        cold_path!();
+        return ObType::Bytes;
+    }
+
+    if is_class_by_type!(ob_type, crate::typeref::get_chainmap_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_mappingproxy_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_simple_namespace_type())
+    {
+        cold_path!();
+        return ObType::Mapping;
+    }
+
+    if is_class_by_type!(ob_type, crate::typeref::get_range_type())
+        && opt_enabled!(opts, SERIALIZE_RANGE)
+    {
+        cold_path!();
+        return ObType::Range;
+    }
+
+    if is_class_by_type!(ob_type, crate::typeref::get_weakref_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_weakproxy_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_weakcallableproxy_type())
+    {
+        cold_path!();
+        return ObType::Weakref;
+    }
+
+    if is_class_by_type!(ob_type, crate::typeref::get_array_type()) {
+        cold_path!();
+        return ObType::Array;
+    }
+
+    if is_class_by_type!(ob_type, crate::typeref::get_dict_keys_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_dict_values_type())
+        || is_class_by_type!(ob_type, crate::typeref::get_dict_items_type())
+    {
+        cold_path!();
+        return ObType::DictView;
+    }
+
     if opt_disabled!(opts, PASSTHROUGH_DATETIME) {
         if is_class_by_type!(ob_type, crate::typeref::get_date_type()) {
             return ObType::Date;
@@ -99,14 +218,83 @@ pub(crate) fn pyobject_to_obtype_unlikely(
         return ObType::Dataclass;
     }
 
+    if opt_disabled!(opts, PASSTHROUGH_DATETIME) && !crate::typeref::get_date_type().is_null() {
+        // subclasses, e.g., pendulum, irrespective of PASSTHROUGH_SUBCLASS
+        if ffi!(PyType_IsSubtype(ob_type, crate::typeref::get_date_type())) == 1 {
+            if ffi!(PyType_IsSubtype(
+                ob_type,
+                crate::typeref::get_datetime_type()
+            )) == 1
+            {
+                if is_pandas_nat(ob_type) {
+                    return ObType::None;
+                }
+                return ObType::Datetime;
+            }
+            return ObType::Date;
+        } else if ffi!(PyType_IsSubtype(ob_type, crate::typeref::get_time_type())) == 1 {
+            return ObType::Time;
+        }
+    }
+
     if opt_enabled!(opts, SERIALIZE_NUMPY) {
         cold_path!();
         if is_numpy_scalar(ob_type) {
+            if opt_enabled!(opts, PASSTHROUGH_NUMPY) {
+                return ObType::Unknown;
+            }
             return ObType::NumpyScalar;
         } else if is_numpy_array(ob_type) {
+            if opt_enabled!(opts, PASSTHROUGH_NUMPY) {
+                return ObType::Unknown;
+            }
             return ObType::NumpyArray;
         }
     }
 
+    if opt_enabled!(opts, SERIALIZE_IPADDRESS) && is_ipaddress(ob_type) {
+        cold_path!();
+        return ObType::IpAddress;
+    }
+
+    if opt_enabled!(opts, SERIALIZE_FRACTION) && is_fraction(ob_type) {
+        cold_path!();
+        return ObType::Fraction;
+    }
+
+    if opt_enabled!(opts, USE_JSON_DUNDER)
+        && ffi!(PyObject_HasAttr(
+            ob_type.cast::<crate::ffi::PyObject>(),
+            crate::typeref::get_json_dunder_str()
+        )) == 1
+    {
+        cold_path!();
+        return ObType::JsonDunder;
+    }
+
+    if opt_enabled!(opts, SERIALIZE_FSPATH)
+        && ffi!(PyObject_HasAttr(
+            ob_type.cast::<crate::ffi::PyObject>(),
+            crate::typeref::get_fspath_str()
+        )) == 1
+    {
+        cold_path!();
+        return ObType::FsPath;
+    }
+
+    // after every other type, so that one that also implements `__next__`
+    // is serialized as that type
+    if opt_enabled!(opts, SERIALIZE_ITERABLES) && is_iterator(ob_type) {
+        cold_path!();
+        return ObType::Iterable;
+    }
+
+    // last, as any class may define a property
+    if opt_enabled!(opts, SERIALIZE_PROPERTIES) && has_properties(ob_type) {
+        cold_path!();
+        return ObType::Properties;
+    }
+
+    cache_unknown_type(ob_type, opts);
     ObType::Unknown
 }
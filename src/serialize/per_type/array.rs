@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::ffi::PyMemoryView_GET_BUFFER;
+use crate::serialize::error::SerializeError;
+use crate::util::isize_to_usize;
+use core::ffi::{CStr, c_char};
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// Serializes `array.array` from its buffer. Arrays of a numeric typecode are
+/// serialized as a list of their elements and arrays of typecode `u` or `w`
+/// as a `str`. With `OPT_MEMORYVIEW_AS_NESTED`, a `memoryview` is serialized
+/// the same way by its format, as lists nested to its shape.
+#[repr(transparent)]
+pub(crate) struct ArraySerializer {
+    ptr: *mut crate::ffi::PyObject,
+}
+
+impl ArraySerializer {
+    pub fn new(ptr: *mut crate::ffi::PyObject) -> Self {
+        ArraySerializer { ptr: ptr }
+    }
+}
+
+impl Serialize for ArraySerializer {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // the memoryview holds an export, so the array cannot be resized
+        let view = ffi!(PyMemoryView_FromObject(self.ptr));
+        debug_assert!(!view.is_null());
+        let ret = unsafe {
+            let buffer = PyMemoryView_GET_BUFFER(view);
+            let ndim = isize_to_usize((*buffer).ndim as isize);
+            let itemsize = isize_to_usize((*buffer).itemsize);
+            let data = (*buffer).buf.cast::<u8>();
+            match typecode((*buffer).format) {
+                Some(typecode) if (*buffer).suboffsets.is_null() => {
+                    if ndim == 0 {
+                        serialize_scalar(serializer, typecode, itemsize, data)
+                    } else {
+                        ArrayDimension {
+                            typecode: typecode,
+                            itemsize: itemsize,
+                            data: data,
+                            shape: core::slice::from_raw_parts((*buffer).shape, ndim),
+                            strides: core::slice::from_raw_parts((*buffer).strides, ndim),
+                        }
+                        .serialize(serializer)
+                    }
+                }
+                _ => Err(serde::ser::Error::custom(
+                    SerializeError::MemoryviewUnsupportedFormat,
+                )),
+            }
+        };
+        ffi!(Py_DECREF(view));
+        ret
+    }
+}
+
+/// The typecode of a buffer format that is a single element of a type
+/// `array.array` supports, in native size and alignment.
+unsafe fn typecode(format: *const c_char) -> Option<u8> {
+    if format.is_null() {
+        // "B" if not given
+        return Some(b'B');
+    }
+    let format = unsafe { CStr::from_ptr(format).to_bytes() };
+    match format.strip_prefix(b"@").unwrap_or(format) {
+        [
+            each @ (b'b' | b'B' | b'h' | b'H' | b'i' | b'I' | b'l' | b'L' | b'q' | b'Q' | b'n'
+            | b'N' | b'f' | b'd' | b'u' | b'w'),
+        ] => Some(*each),
+        _ => None,
+    }
+}
+
+/// The elements of a buffer at `data` of the remaining dimensions `shape`,
+/// each `strides` bytes apart, as lists nested to the depth of `shape`.
+struct ArrayDimension<'a> {
+    typecode: u8,
+    itemsize: usize,
+    data: *mut u8,
+    shape: &'a [isize],
+    strides: &'a [isize],
+}
+
+impl Serialize for ArrayDimension<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = isize_to_usize(self.shape[0]);
+        let stride = self.strides[0];
+        if self.shape.len() == 1 {
+            return serialize_buffer(
+                serializer,
+                self.typecode,
+                self.itemsize,
+                self.data,
+                len,
+                stride,
+            );
+        }
+        let mut seq = serializer.serialize_seq(Some(len)).unwrap();
+        for idx in 0..len {
+            seq.serialize_element(&ArrayDimension {
+                typecode: self.typecode,
+                itemsize: self.itemsize,
+                data: self.data.wrapping_offset(offset(idx, stride)),
+                shape: &self.shape[1..],
+                strides: &self.strides[1..],
+            })?;
+        }
+        seq.end()
+    }
+}
+
+#[inline(always)]
+fn offset(idx: usize, stride: isize) -> isize {
+    crate::util::usize_to_isize(idx) * stride
+}
+
+/// `len` elements from `data`, each `stride` bytes apart.
+fn serialize_buffer<S>(
+    serializer: S,
+    typecode: u8,
+    itemsize: usize,
+    data: *mut u8,
+    len: usize,
+    stride: isize,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if typecode == b'u' || typecode == b'w' {
+        return serialize_unicode(serializer, itemsize, data, len, stride);
+    }
+    let mut seq = serializer.serialize_seq(Some(len)).unwrap();
+    for idx in 0..len {
+        match ArrayElement::read(
+            typecode,
+            itemsize,
+            data.wrapping_offset(offset(idx, stride)),
+        ) {
+            Some(element) => seq.serialize_element(&element)?,
+            None => err!(SerializeError::MemoryviewUnsupportedFormat),
+        }
+    }
+    seq.end()
+}
+
+/// The only element of a zero-dimensional buffer.
+fn serialize_scalar<S>(
+    serializer: S,
+    typecode: u8,
+    itemsize: usize,
+    data: *mut u8,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if typecode == b'u' || typecode == b'w' {
+        return serialize_unicode(serializer, itemsize, data, 1, 0);
+    }
+    match ArrayElement::read(typecode, itemsize, data) {
+        Some(element) => element.serialize(serializer),
+        None => err!(SerializeError::MemoryviewUnsupportedFormat),
+    }
+}
+
+/// `u` is `wchar_t`, i.e., UTF-16 on Windows and UTF-32 otherwise, and `w`
+/// is UTF-32.
+fn serialize_unicode<S>(
+    serializer: S,
+    itemsize: usize,
+    data: *mut u8,
+    len: usize,
+    stride: isize,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let each = |idx: usize| data.wrapping_offset(offset(idx, stride));
+    let decoded: Option<String> = if itemsize == 2 {
+        char::decode_utf16(
+            (0..len).map(|idx| unsafe { core::ptr::read_unaligned(each(idx).cast::<u16>()) }),
+        )
+        .collect::<Result<String, _>>()
+        .ok()
+    } else {
+        (0..len)
+            .map(|idx| {
+                char::from_u32(unsafe { core::ptr::read_unaligned(each(idx).cast::<u32>()) })
+            })
+            .collect()
+    };
+    match decoded {
+        Some(uni) => serializer.serialize_str(&uni),
+        None => err!(SerializeError::InvalidStr),
+    }
+}
+
+enum ArrayElement {
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+}
+
+macro_rules! read_element {
+    ($data:expr, $ty:ty) => {
+        unsafe { core::ptr::read_unaligned($data.cast::<$ty>()) }
+    };
+}
+
+impl ArrayElement {
+    /// The element at `data`. `l` and `L` are the size of a C long, so this
+    /// matches on the size of each.
+    #[inline(always)]
+    fn read(typecode: u8, itemsize: usize, data: *mut u8) -> Option<Self> {
+        let element = match (typecode, itemsize) {
+            (b'f', 4) => ArrayElement::F32(read_element!(data, f32)),
+            (b'd', 8) => ArrayElement::F64(read_element!(data, f64)),
+            (b'b' | b'h' | b'i' | b'l' | b'q' | b'n', 1) => {
+                ArrayElement::I64(i64::from(read_element!(data, i8)))
+            }
+            (b'b' | b'h' | b'i' | b'l' | b'q' | b'n', 2) => {
+                ArrayElement::I64(i64::from(read_element!(data, i16)))
+            }
+            (b'b' | b'h' | b'i' | b'l' | b'q' | b'n', 4) => {
+                ArrayElement::I64(i64::from(read_element!(data, i32)))
+            }
+            (b'b' | b'h' | b'i' | b'l' | b'q' | b'n', 8) => {
+                ArrayElement::I64(read_element!(data, i64))
+            }
+            (b'B' | b'H' | b'I' | b'L' | b'Q' | b'N', 1) => {
+                ArrayElement::U64(u64::from(read_element!(data, u8)))
+            }
+            (b'B' | b'H' | b'I' | b'L' | b'Q' | b'N', 2) => {
+                ArrayElement::U64(u64::from(read_element!(data, u16)))
+            }
+            (b'B' | b'H' | b'I' | b'L' | b'Q' | b'N', 4) => {
+                ArrayElement::U64(u64::from(read_element!(data, u32)))
+            }
+            (b'B' | b'H' | b'I' | b'L' | b'Q' | b'N', 8) => {
+                ArrayElement::U64(read_element!(data, u64))
+            }
+            _ => return None,
+        };
+        Some(element)
+    }
+}
+
+impl Serialize for ArrayElement {
+    #[inline(always)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            ArrayElement::I64(val) => serializer.serialize_i64(val),
+            ArrayElement::U64(val) => serializer.serialize_u64(val),
+            ArrayElement::F32(val) => serializer.serialize_f32(val),
+            ArrayElement::F64(val) => serializer.serialize_f64(val),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::ffi::{PyBytes_AS_STRING, PyBytes_GET_SIZE, PyMemoryView_GET_BUFFER};
+use crate::opt::{
+    BYTES_MODE, Opt, SERIALIZE_BYTES_BASE64, SERIALIZE_BYTES_HEX, SERIALIZE_BYTES_LATIN1,
+};
+use crate::serialize::error::SerializeError;
+use crate::util::isize_to_usize;
+use core::ffi::c_char;
+use core::sync::atomic::Ordering;
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const HEX_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum BytesMode {
+    Passthrough = 0,
+    Base64 = 1,
+    Hex = 2,
+    Latin1 = 3,
+}
+
+impl BytesMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "passthrough" => Some(BytesMode::Passthrough),
+            "base64" => Some(BytesMode::Base64),
+            "hex" => Some(BytesMode::Hex),
+            "latin1" => Some(BytesMode::Latin1),
+            _ => None,
+        }
+    }
+
+    fn from_u8(val: u8) -> Self {
+        match val {
+            1 => BytesMode::Base64,
+            2 => BytesMode::Hex,
+            3 => BytesMode::Latin1,
+            _ => BytesMode::Passthrough,
+        }
+    }
+
+    /// The mode selected by the per-call option, or, if none is given, the
+    /// default set for the current interpreter by `configure()`.
+    #[inline(always)]
+    pub fn from_opts(opts: Opt) -> Self {
+        if opts & BYTES_MODE == 0 {
+            Self::configured()
+        } else if opts & SERIALIZE_BYTES_BASE64 != 0 {
+            BytesMode::Base64
+        } else if opts & SERIALIZE_BYTES_HEX != 0 {
+            BytesMode::Hex
+        } else if opts & SERIALIZE_BYTES_LATIN1 != 0 {
+            BytesMode::Latin1
+        } else {
+            BytesMode::Passthrough
+        }
+    }
+
+    #[inline(always)]
+    pub fn configured() -> Self {
+        let state = unsafe {
+            crate::interpreter_state::get_current_state()
+                .as_ref()
+                .unwrap()
+        };
+        Self::from_u8(state.bytes_mode.load(Ordering::Relaxed))
+    }
+
+    pub fn set_configured(self) {
+        let state = unsafe {
+            crate::interpreter_state::get_current_state()
+                .as_ref()
+                .unwrap()
+        };
+        state.bytes_mode.store(self as u8, Ordering::Relaxed);
+    }
+}
+
+pub(crate) struct BytesSerializer {
+    ptr: *mut crate::ffi::PyObject,
+    mode: BytesMode,
+}
+
+impl BytesSerializer {
+    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+        BytesSerializer {
+            ptr: ptr,
+            mode: BytesMode::from_opts(opts),
+        }
+    }
+}
+
+impl Serialize for BytesSerializer {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let buffer: &[u8];
+        unsafe {
+            let ob_type = ob_type!(self.ptr);
+            if is_type!(ob_type, crate::typeref::get_bytes_type()) {
+                buffer = core::slice::from_raw_parts(
+                    PyBytes_AS_STRING(self.ptr).cast::<u8>(),
+                    isize_to_usize(PyBytes_GET_SIZE(self.ptr)),
+                );
+            } else if is_type!(ob_type, crate::typeref::get_bytearray_type()) {
+                buffer = core::slice::from_raw_parts(
+                    ffi!(PyByteArray_AsString(self.ptr))
+                        .cast::<u8>()
+                        .cast_const(),
+                    isize_to_usize(ffi!(PyByteArray_Size(self.ptr))),
+                );
+            } else {
+                let membuf = PyMemoryView_GET_BUFFER(self.ptr);
+                if crate::ffi::PyBuffer_IsContiguous(membuf, b'C' as c_char) == 0 {
+                    err!(SerializeError::MemoryviewNotCContiguous)
+                }
+                buffer = core::slice::from_raw_parts(
+                    (*membuf).buf.cast::<u8>().cast_const(),
+                    isize_to_usize((*membuf).len),
+                );
+            }
+        }
+        serialize_encoded(buffer, self.mode, serializer)
+    }
+}
+
+/// Serialize `buffer` as a string in `mode`, or, in `BytesMode::Passthrough`,
+/// as an array of its bytes.
+pub(crate) fn serialize_encoded<S>(
+    buffer: &[u8],
+    mode: BytesMode,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match mode {
+        BytesMode::Base64 => serializer.serialize_bytes(&encode_base64(buffer)),
+        BytesMode::Hex => serializer.serialize_bytes(&encode_hex(buffer)),
+        BytesMode::Latin1 => {
+            let decoded: String = buffer.iter().map(|&byte| char::from(byte)).collect();
+            serializer.serialize_str(&decoded)
+        }
+        BytesMode::Passthrough => {
+            let mut seq = serializer.serialize_seq(None)?;
+            for byte in buffer {
+                seq.serialize_element(&u64::from(*byte))?;
+            }
+            seq.end()
+        }
+    }
+}
+
+/// `buffer`, a `bytes` dict key with `OPT_NON_STR_KEYS`, as a `str` in
+/// `mode`, or, in `BytesMode::Passthrough`, decoded as UTF-8.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn encode_key(buffer: &[u8], mode: BytesMode) -> Result<String, SerializeError> {
+    let quoted = match mode {
+        BytesMode::Base64 => encode_base64(buffer),
+        BytesMode::Hex => encode_hex(buffer),
+        BytesMode::Latin1 => return Ok(buffer.iter().map(|&byte| char::from(byte)).collect()),
+        BytesMode::Passthrough => {
+            return core::str::from_utf8(buffer)
+                .map(String::from)
+                .map_err(|_| SerializeError::DictKeyBytesInvalidUtf8);
+        }
+    };
+    // ASCII
+    Ok(String::from(str_from_slice!(
+        quoted[1..].as_ptr(),
+        quoted.len() - 2
+    )))
+}
+
+/// Standard, padded base64, quoted.
+fn encode_base64(buffer: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(buffer.len().div_ceil(3) * 4 + 2);
+    out.push(b'"');
+    for chunk in buffer.chunks(3) {
+        let val = (u32::from(chunk[0]) << 16)
+            | (u32::from(*chunk.get(1).unwrap_or(&0)) << 8)
+            | u32::from(*chunk.get(2).unwrap_or(&0));
+        out.push(BASE64_ALPHABET[(val >> 18) as usize & 63]);
+        out.push(BASE64_ALPHABET[(val >> 12) as usize & 63]);
+        if chunk.len() > 1 {
+            out.push(BASE64_ALPHABET[(val >> 6) as usize & 63]);
+        } else {
+            out.push(b'=');
+        }
+        if chunk.len() > 2 {
+            out.push(BASE64_ALPHABET[val as usize & 63]);
+        } else {
+            out.push(b'=');
+        }
+    }
+    out.push(b'"');
+    out
+}
+
+/// Lowercase hexadecimal, quoted.
+fn encode_hex(buffer: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(buffer.len() * 2 + 2);
+    out.push(b'"');
+    for byte in buffer {
+        out.push(HEX_ALPHABET[usize::from(byte >> 4)]);
+        out.push(HEX_ALPHABET[usize::from(byte & 15)]);
+    }
+    out.push(b'"');
+    out
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::opt::CACHE_TUPLES;
+#[cfg(not(Py_GIL_DISABLED))]
+use crate::opt::INDENT_2;
+#[cfg(not(Py_GIL_DISABLED))]
+use crate::serialize::cache::CachedTuple;
+use crate::serialize::per_type::{ListTupleSerializer, ZeroListSerializer};
+use crate::serialize::serializer::PyObjectSerializer;
+#[cfg(not(Py_GIL_DISABLED))]
+use crate::serialize::serializer::{float_precision, write};
+#[cfg(not(Py_GIL_DISABLED))]
+use crate::serialize::writer::BytesWriter;
+
+use serde::ser::{Serialize, Serializer};
+
+/// Whether the elements of `ptr`, a `tuple`, are `str`, `int`, `float`,
+/// `bool`, `None`, or a `tuple` of these, so that its output depends only on
+/// the options given. Subclasses are not, as they may be mutable.
+#[cfg(not(Py_GIL_DISABLED))]
+fn is_immutable(ptr: *mut crate::ffi::PyObject, depth: u8) -> bool {
+    if depth == u8::MAX {
+        return false;
+    }
+    (0..ffi!(Py_SIZE(ptr))).all(|i| {
+        let value = ffi!(PyTuple_GET_ITEM(ptr, i));
+        let ob_type = ob_type!(value);
+        is_type!(ob_type, crate::typeref::get_str_type())
+            || is_type!(ob_type, crate::typeref::get_int_type())
+            || is_type!(ob_type, crate::typeref::get_float_type())
+            || is_type!(ob_type, crate::typeref::get_bool_type())
+            || is_type!(ob_type, crate::typeref::get_none_type())
+            || (is_type!(ob_type, crate::typeref::get_tuple_type())
+                && is_immutable(value, depth + 1))
+    })
+}
+
+/// Serializes a `tuple`, given `OPT_CACHE_TUPLES`, as the output cached for
+/// the same object by an earlier call in the interpreter, if any.
+///
+/// The output is cached only for a `tuple` of immutable elements, as by
+/// `is_immutable()`, and not with `OPT_INDENT_2`, as its indentation then
+/// depends on where it occurs, or `float_precision`, which is not one of the
+/// options it is cached with. A cached `tuple` is kept alive by the cache, so
+/// another object cannot have its address while it is cached.
+#[repr(transparent)]
+pub(crate) struct CachedTupleSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> CachedTupleSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+}
+
+impl Serialize for CachedTupleSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if ffi!(Py_SIZE(self.previous.ptr)) == 0 {
+            return ZeroListSerializer::new().serialize(serializer);
+        }
+        let opts = self.previous.state.opts();
+        // the output of a nested tuple is part of that of the cached tuple
+        let value = ListTupleSerializer::from_tuple(
+            self.previous.ptr,
+            self.previous.state.with_opts(opts & !CACHE_TUPLES),
+            self.previous.default,
+            self.previous.key_filter,
+        );
+        #[cfg(not(Py_GIL_DISABLED))]
+        if opt_disabled!(opts, INDENT_2) && float_precision().is_none() {
+            let tuple_map = unsafe {
+                let state = crate::interpreter_state::get_current_state()
+                    .as_ref()
+                    .unwrap();
+                &mut *state.tuple_map.get()
+            };
+            let key = self.previous.ptr as usize;
+            if let Some(json) = tuple_map.get(&key).and_then(|cached| cached.get(opts)) {
+                return serializer.serialize_bytes(json);
+            }
+            if is_immutable(self.previous.ptr, 0) {
+                let mut buf = BytesWriter::default();
+                let res = write(&mut buf, &value, opts, None);
+                let json = buf.as_slice().to_vec();
+                buf.abort();
+                if let Err(err) = res {
+                    err!(err)
+                }
+                let ret = serializer.serialize_bytes(&json);
+                tuple_map.insert(key, CachedTuple::new(self.previous.ptr, opts, json));
+                return ret;
+            }
+        }
+        value.serialize(serializer)
+    }
+}
@@ -3,6 +3,7 @@
 
 use crate::serialize::error::SerializeError;
 use crate::serialize::per_type::dict::ZeroDictSerializer;
+use crate::serialize::per_type::serialize_entry;
 use crate::serialize::serializer::PyObjectSerializer;
 use crate::serialize::state::SerializerState;
 use crate::str::PyStr;
@@ -13,6 +14,44 @@ use serde::ser::{Serialize, SerializeMap, Serializer};
 
 use core::ptr::NonNull;
 
+/// Whether the type or a base class of it defines `__slots__`, so that a
+/// field may be stored in a slot rather than in the instance `__dict__`,
+/// e.g., the fields of a base class of a dataclass that does not itself
+/// define `__slots__`. The empty `__slots__` of, e.g., `abc.ABC` and
+/// `typing.Generic` are ignored.
+#[inline(always)]
+fn has_slots(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    if pydict_contains!(ob_type, crate::typeref::get_slots_str()) {
+        return true;
+    }
+    let mro = unsafe { (*ob_type).tp_mro };
+    if mro.is_null() {
+        cold_path!();
+        return false;
+    }
+    // the type itself is first and `object` is last
+    for idx in 1..ffi!(Py_SIZE(mro)) - 1 {
+        let base = ffi!(PyTuple_GET_ITEM(mro, idx)).cast::<crate::ffi::PyTypeObject>();
+        let base_dict = unsafe { (*base).tp_dict };
+        if base_dict.is_null() {
+            // a static type, which has no `__slots__`
+            continue;
+        }
+        let slots = ffi!(PyDict_GetItem(base_dict, crate::typeref::get_slots_str()));
+        if !slots.is_null() {
+            cold_path!();
+            let len = ffi!(PyObject_Size(slots));
+            if len == -1 {
+                ffi!(PyErr_Clear());
+            }
+            if len != 0 {
+                return true;
+            }
+        }
+    }
+    false
+}
+
 #[repr(transparent)]
 pub(crate) struct DataclassGenericSerializer<'a> {
     previous: &'a PyObjectSerializer,
@@ -42,31 +81,63 @@ impl Serialize for DataclassGenericSerializer<'_> {
                 self.previous.ptr,
                 self.previous.state,
                 self.previous.default,
+                self.previous.key_filter,
             )
             .serialize(serializer)
-        } else if pydict_contains!(ob_type, crate::typeref::get_slots_str()) {
+        } else if has_slots(ob_type) {
             let ret = DataclassFallbackSerializer::new(
                 self.previous.ptr,
                 self.previous.state,
                 self.previous.default,
+                self.previous.key_filter,
             )
             .serialize(serializer);
             ffi!(Py_DECREF(dict));
             ret
         } else {
-            let ret =
-                DataclassFastSerializer::new(dict, self.previous.state, self.previous.default)
-                    .serialize(serializer);
+            let dict = instance_dict(dict);
+            if dict.is_null() {
+                cold_path!();
+                err!(SerializeError::DictAttributeNotMapping)
+            }
+            let ret = DataclassFastSerializer::new(
+                dict,
+                self.previous.state,
+                self.previous.default,
+                self.previous.key_filter,
+            )
+            .serialize(serializer);
             ffi!(Py_DECREF(dict));
             ret
         }
     }
 }
 
+/// The `dict` of `attr`, a new reference to the value of `__dict__`, which
+/// is a `dict` unless the class overrides `__dict__`, e.g., with a property.
+/// A mapping is copied to a `dict`. Returns null with an exception set if
+/// it is not a mapping. The reference to `attr` is released.
+#[inline(always)]
+fn instance_dict(attr: *mut crate::ffi::PyObject) -> *mut crate::ffi::PyObject {
+    if is_subclass_by_flag!(tp_flags!(ob_type!(attr)), Py_TPFLAGS_DICT_SUBCLASS) {
+        return attr;
+    }
+    cold_path!();
+    let dict = ffi!(PyDict_New());
+    if !dict.is_null() && ffi!(PyDict_Merge(dict, attr, 1)) == -1 {
+        ffi!(Py_DECREF(dict));
+        ffi!(Py_DECREF(attr));
+        return core::ptr::null_mut();
+    }
+    ffi!(Py_DECREF(attr));
+    dict
+}
+
 pub(crate) struct DataclassFastSerializer {
     ptr: *mut crate::ffi::PyObject,
     state: SerializerState,
     default: Option<NonNull<crate::ffi::PyObject>>,
+    key_filter: Option<NonNull<crate::ffi::PyObject>>,
 }
 
 impl DataclassFastSerializer {
@@ -74,11 +145,13 @@ impl DataclassFastSerializer {
         ptr: *mut crate::ffi::PyObject,
         state: SerializerState,
         default: Option<NonNull<crate::ffi::PyObject>>,
+        key_filter: Option<NonNull<crate::ffi::PyObject>>,
     ) -> Self {
         DataclassFastSerializer {
             ptr: ptr,
             state: state.copy_for_recursive_call(),
             default: default,
+            key_filter: key_filter,
         }
     }
 }
@@ -119,13 +192,12 @@ impl Serialize for DataclassFastSerializer {
                     None => err!(SerializeError::InvalidStr),
                 }
             };
-            if key_as_str.as_bytes()[0] == b'_' {
+            if key_as_str.starts_with('_') {
                 cold_path!();
                 continue;
             }
-            let pyvalue = PyObjectSerializer::new(value, self.state, self.default);
-            map.serialize_key(key_as_str).unwrap();
-            map.serialize_value(&pyvalue)?;
+            let pyvalue = PyObjectSerializer::new(value, self.state, self.default, self.key_filter);
+            serialize_entry(&mut map, key_as_str, &pyvalue)?;
         }
         map.end()
     }
@@ -135,6 +207,7 @@ pub(crate) struct DataclassFallbackSerializer {
     ptr: *mut crate::ffi::PyObject,
     state: SerializerState,
     default: Option<NonNull<crate::ffi::PyObject>>,
+    key_filter: Option<NonNull<crate::ffi::PyObject>>,
 }
 
 impl DataclassFallbackSerializer {
@@ -142,11 +215,13 @@ impl DataclassFallbackSerializer {
         ptr: *mut crate::ffi::PyObject,
         state: SerializerState,
         default: Option<NonNull<crate::ffi::PyObject>>,
+        key_filter: Option<NonNull<crate::ffi::PyObject>>,
     ) -> Self {
         DataclassFallbackSerializer {
             ptr: ptr,
             state: state.copy_for_recursive_call(),
             default: default,
+            key_filter: key_filter,
         }
     }
 }
@@ -192,7 +267,7 @@ impl Serialize for DataclassFallbackSerializer {
                 Some(uni) => uni,
                 None => err!(SerializeError::InvalidStr),
             };
-            if key_as_str.as_bytes()[0] == b'_' {
+            if key_as_str.starts_with('_') {
                 cold_path!();
                 continue;
             }
@@ -200,10 +275,8 @@ impl Serialize for DataclassFallbackSerializer {
             let value = ffi!(PyObject_GetAttr(self.ptr, attr));
             debug_assert!(ffi!(Py_REFCNT(value)) >= 2);
             ffi!(Py_DECREF(value));
-            let pyvalue = PyObjectSerializer::new(value, self.state, self.default);
-
-            map.serialize_key(key_as_str).unwrap();
-            map.serialize_value(&pyvalue)?;
+            let pyvalue = PyObjectSerializer::new(value, self.state, self.default, self.key_filter);
+            serialize_entry(&mut map, key_as_str, &pyvalue)?;
         }
         map.end()
     }
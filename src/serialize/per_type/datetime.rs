@@ -1,11 +1,21 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025), Ben Sully (2021)
 
+use crate::ffi::{
+    Py_DECREF, Py_INCREF, PyDict_GetItemString, PyErr_Clear, PyImport_GetModuleDict,
+    PyObject_GetAttrString,
+};
 use crate::opt::{OMIT_MICROSECONDS, Opt};
 use crate::serialize::buffer::SmallFixedBuffer;
 use crate::serialize::error::SerializeError;
-use crate::serialize::per_type::datetimelike::{DateTimeError, DateTimeLike, Offset};
+use crate::serialize::per_type::datetimelike::{
+    DateTimeError, DateTimeFormat, DateTimeLike, Offset, write_offset,
+};
+use crate::serialize::serializer::datetime_format;
 // CONVERT_METHOD_STR, DST_STR, NORMALIZE_METHOD_STR, UTCOFFSET_METHOD_STR, ZONEINFO_TYPE now accessed via typeref accessor functions
+use core::ffi::CStr;
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering;
 use serde::ser::{Serialize, Serializer};
 
 macro_rules! write_double_digit {
@@ -71,12 +81,39 @@ impl Date {
             write_double_digit!(buf, val);
         }
     }
+
+    /// The number of days since 1970-01-01, for `OPT_DATE_AS_EPOCH_DAYS`, as
+    /// the difference of the proleptic Gregorian ordinals, i.e.,
+    /// `date.toordinal()`, of the date and of the epoch.
+    #[cold]
+    #[inline(never)]
+    pub fn epoch_days(&self) -> i64 {
+        const DAYS_BEFORE_MONTH: [i64; 12] =
+            [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+        const EPOCH_ORDINAL: i64 = 719_163;
+        let year = i64::from(ffi!(PyDateTime_GET_YEAR(self.ptr)));
+        let month = ffi!(PyDateTime_GET_MONTH(self.ptr));
+        let day = i64::from(ffi!(PyDateTime_GET_DAY(self.ptr)));
+        let is_leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let before_year = year - 1;
+        let mut ordinal = before_year * 365 + before_year / 4 - before_year / 100
+            + before_year / 400
+            + DAYS_BEFORE_MONTH[(month - 1) as usize]
+            + day;
+        if month > 2 && is_leap {
+            ordinal += 1;
+        }
+        ordinal - EPOCH_ORDINAL
+    }
 }
 impl Serialize for Date {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        if datetime_format().date_as_epoch_days {
+            return serializer.serialize_i64(self.epoch_days());
+        }
         let mut buf = SmallFixedBuffer::new();
         self.write_buf(&mut buf);
         serializer.serialize_unit_struct(str_from_slice!(buf.as_ptr(), buf.len()))
@@ -84,7 +121,8 @@ impl Serialize for Date {
 }
 
 pub(crate) enum TimeError {
-    HasTimezone,
+    /// `utcoffset()` raised, and the exception is set.
+    Offset,
 }
 
 pub(crate) struct Time {
@@ -100,14 +138,44 @@ impl Time {
         }
     }
 
+    /// The offset of the tzinfo, or `None` if naive or if `utcoffset()`
+    /// returns `None`, as `datetime.time.isoformat()` does. A time has no
+    /// date to resolve a timezone with DST, so e.g. `zoneinfo` has no
+    /// offset for it.
+    #[cold]
+    #[inline(never)]
+    fn offset(&self) -> Result<Option<Offset>, TimeError> {
+        let tzinfo = ffi!(PyDateTime_TIME_GET_TZINFO(self.ptr));
+        #[cfg(CPython)]
+        if unsafe { core::ptr::eq(ob_type!(tzinfo), crate::typeref::get_timezone_type()) } {
+            let py_offset = unsafe { crate::ffi::PyTimeZone_GET_OFFSET(tzinfo) };
+            return Ok(Some(Offset {
+                second: ffi!(PyDateTime_DELTA_GET_SECONDS(py_offset)),
+                day: ffi!(PyDateTime_DELTA_GET_DAYS(py_offset)),
+            }));
+        }
+        let py_offset = call_method!(self.ptr, crate::typeref::get_utcoffset_method_str());
+        if py_offset.is_null() {
+            cold_path!();
+            return Err(TimeError::Offset);
+        }
+        if unsafe { core::ptr::eq(py_offset, crate::typeref::get_none()) } {
+            ffi!(Py_DECREF(py_offset));
+            return Ok(None);
+        }
+        let offset = Offset {
+            second: ffi!(PyDateTime_DELTA_GET_SECONDS(py_offset)),
+            day: ffi!(PyDateTime_DELTA_GET_DAYS(py_offset)),
+        };
+        ffi!(Py_DECREF(py_offset));
+        Ok(Some(offset))
+    }
+
     #[inline(never)]
     pub fn write_buf<B>(&self, buf: &mut B) -> Result<(), TimeError>
     where
         B: bytes::BufMut,
     {
-        if unsafe { (*self.ptr.cast::<crate::ffi::PyDateTime_Time>()).hastzinfo == 1 } {
-            return Err(TimeError::HasTimezone);
-        }
         let hour = ffi!(PyDateTime_TIME_GET_HOUR(self.ptr)) as u8;
         write_double_digit!(buf, hour);
         buf.put_u8(b':');
@@ -120,6 +188,11 @@ impl Time {
             let microsecond = ffi!(PyDateTime_TIME_GET_MICROSECOND(self.ptr)) as u32;
             write_microsecond!(buf, microsecond);
         }
+        if unsafe { (*self.ptr.cast::<crate::ffi::PyDateTime_Time>()).hastzinfo == 1 } {
+            if let Some(offset) = self.offset()? {
+                write_offset(buf, &offset, datetime_format());
+            }
+        }
         Ok(())
     }
 }
@@ -131,7 +204,7 @@ impl Serialize for Time {
     {
         let mut buf = SmallFixedBuffer::new();
         if self.write_buf(&mut buf).is_err() {
-            err!(SerializeError::DatetimeLibraryUnsupported)
+            err!(SerializeError::TimeInvalidOffset)
         }
         serializer.serialize_unit_struct(str_from_slice!(buf.as_ptr(), buf.len()))
     }
@@ -139,14 +212,45 @@ impl Serialize for Time {
 
 pub(crate) struct DateTime {
     ptr: *mut crate::ffi::PyObject,
-    opts: Opt,
+    format: DateTimeFormat,
 }
 
 impl DateTime {
-    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+    pub fn new(ptr: *mut crate::ffi::PyObject, format: DateTimeFormat) -> Self {
         DateTime {
             ptr: ptr,
-            opts: opts,
+            format: format,
+        }
+    }
+
+    /// OPT_FORBID_NAIVE_DATETIME rejects a datetime without tzinfo unless
+    /// OPT_NAIVE_UTC says how to interpret it.
+    #[inline(always)]
+    pub fn is_forbidden_naive(&self) -> bool {
+        self.format.forbid_naive && !self.has_tz()
+    }
+
+    /// OPT_DATETIME_AS_UNIX_SECONDS and OPT_DATETIME_AS_UNIX_MILLIS: a float
+    /// with the fraction of the unit or, with OPT_OMIT_MICROSECONDS, an
+    /// integer rounded down.
+    #[cold]
+    #[inline(never)]
+    fn serialize_unix<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !self.has_tz() && !self.format.naive_utc {
+            err!(SerializeError::DatetimeNaiveUnix)
+        }
+        let Ok(microseconds) = self.unix_microseconds() else {
+            err!(SerializeError::DatetimeLibraryUnsupported)
+        };
+        let per_unit = self.format.unix_per_unit;
+        if !self.format.microseconds {
+            serializer.serialize_i64(microseconds.div_euclid(per_unit))
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            serializer.serialize_f64(microseconds as f64 / per_unit as f64)
         }
     }
 }
@@ -163,6 +267,99 @@ macro_rules! pydatetime_get {
     };
 }
 
+/// `zoneinfo.ZoneInfo` is resolved from `sys.modules` when first seen instead
+/// of being imported with the state. Before Python 3.12, `_zoneinfo` keeps
+/// process-global state that importing it in a subinterpreter clobbers.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn resolve_zoneinfo_type(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    unsafe {
+        if !CStr::from_ptr((*ob_type).tp_name)
+            .to_bytes()
+            .ends_with(b"ZoneInfo")
+        {
+            return false;
+        }
+        let module = PyDict_GetItemString(PyImport_GetModuleDict(), c"zoneinfo".as_ptr());
+        if module.is_null() {
+            return false;
+        }
+        let zoneinfo_type = PyObject_GetAttrString(module, c"ZoneInfo".as_ptr());
+        if zoneinfo_type.is_null() {
+            PyErr_Clear();
+            return false;
+        }
+        if !core::ptr::eq(zoneinfo_type.cast::<crate::ffi::PyTypeObject>(), ob_type) {
+            Py_DECREF(zoneinfo_type);
+            return false;
+        }
+        let state = crate::interpreter_state::get_current_state()
+            .as_ref()
+            .unwrap();
+        if state
+            .zoneinfo_type
+            .compare_exchange(null_mut(), ob_type, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            Py_DECREF(zoneinfo_type);
+        }
+        true
+    }
+}
+
+/// Whether `ob_type`, a `datetime.datetime` subclass, is the type of
+/// `pandas.NaT`. Like `zoneinfo.ZoneInfo`, it is resolved from `sys.modules`
+/// when first seen, so pandas is never imported.
+#[inline(always)]
+pub(crate) fn is_pandas_nat(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    let nat_type = crate::typeref::get_pandas_nat_type();
+    if nat_type.is_null() {
+        resolve_pandas_nat_type(ob_type)
+    } else {
+        core::ptr::eq(ob_type, nat_type)
+    }
+}
+
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn resolve_pandas_nat_type(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    unsafe {
+        if !CStr::from_ptr((*ob_type).tp_name)
+            .to_bytes()
+            .ends_with(b"NaTType")
+        {
+            return false;
+        }
+        let module = PyDict_GetItemString(PyImport_GetModuleDict(), c"pandas".as_ptr());
+        if module.is_null() {
+            return false;
+        }
+        let nat = PyObject_GetAttrString(module, c"NaT".as_ptr());
+        if nat.is_null() {
+            PyErr_Clear();
+            return false;
+        }
+        let is_nat = core::ptr::eq(ob_type!(nat), ob_type);
+        Py_DECREF(nat);
+        if !is_nat {
+            return false;
+        }
+        let state = crate::interpreter_state::get_current_state()
+            .as_ref()
+            .unwrap();
+        if state
+            .pandas_nat_type
+            .compare_exchange(null_mut(), ob_type, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            Py_INCREF(ob_type.cast::<crate::ffi::PyObject>());
+        }
+        true
+    }
+}
+
 impl DateTimeLike for DateTime {
     pydatetime_get!(year, PyDateTime_GET_YEAR, i32);
     pydatetime_get!(month, PyDateTime_GET_MONTH, u8);
@@ -193,8 +390,15 @@ impl DateTimeLike for DateTime {
             ffi!(Py_DECREF(py_offset));
             Ok(offset)
         } else if ffi!(PyObject_HasAttr(tzinfo, crate::typeref::get_normalize_method_str())) == 1 {
-            // pytz
+            // pytz: the tzinfo of a datetime holds the offset in effect when
+            // it was localized, which is stale after arithmetic across a DST
+            // transition, so read the offset of the normalized datetime
             let method_ptr = call_method!(tzinfo, crate::typeref::get_normalize_method_str(), self.ptr);
+            if method_ptr.is_null() {
+                cold_path!();
+                ffi!(PyErr_Clear());
+                return Err(DateTimeError::LibraryUnsupported);
+            }
             let py_offset = call_method!(method_ptr, crate::typeref::get_utcoffset_method_str());
             ffi!(Py_DECREF(method_ptr));
             let offset = Offset {
@@ -204,7 +408,7 @@ impl DateTimeLike for DateTime {
             ffi!(Py_DECREF(py_offset));
             Ok(offset)
         } else if ffi!(PyObject_HasAttr(tzinfo, crate::typeref::get_dst_str())) == 1 {
-            // dateutil/arrow, datetime.timezone.utc
+            // dateutil/arrow, and datetime.timezone if not CPython
             let py_offset = call_method!(tzinfo, crate::typeref::get_utcoffset_method_str(), self.ptr);
             let offset = Offset {
                 second: ffi!(PyDateTime_DELTA_GET_SECONDS(py_offset)),
@@ -223,8 +427,23 @@ impl DateTimeLike for DateTime {
             Ok(Offset::default())
         } else {
             let tzinfo = ffi!(PyDateTime_DATE_GET_TZINFO(self.ptr));
-            if unsafe { core::ptr::eq(ob_type!(tzinfo), crate::typeref::get_zoneinfo_type()) } {
-                // zoneinfo
+            #[cfg(CPython)]
+            if unsafe { core::ptr::eq(ob_type!(tzinfo), crate::typeref::get_timezone_type()) } {
+                // datetime.timezone, e.g., timezone.utc: a fixed offset held
+                // by the object, so there is nothing to call
+                let py_offset = unsafe { crate::ffi::PyTimeZone_GET_OFFSET(tzinfo) };
+                return Ok(Offset {
+                    second: ffi!(PyDateTime_DELTA_GET_SECONDS(py_offset)),
+                    day: ffi!(PyDateTime_DELTA_GET_DAYS(py_offset)),
+                });
+            }
+            let zoneinfo_type = crate::typeref::get_zoneinfo_type();
+            if unsafe { core::ptr::eq(ob_type!(tzinfo), zoneinfo_type) }
+                || (zoneinfo_type.is_null() && resolve_zoneinfo_type(ob_type!(tzinfo)))
+            {
+                // zoneinfo: given the datetime, so that `fold` selects the
+                // offset of a local time that is ambiguous or skipped at a
+                // DST transition
                 let py_offset = call_method!(tzinfo, crate::typeref::get_utcoffset_method_str(), self.ptr);
                 let offset = Offset {
                     second: ffi!(PyDateTime_DELTA_GET_SECONDS(py_offset)),
@@ -244,8 +463,14 @@ impl Serialize for DateTime {
     where
         S: Serializer,
     {
+        if self.is_forbidden_naive() {
+            err!(SerializeError::DatetimeNaive)
+        }
+        if self.format.unix_per_unit != 0 {
+            return self.serialize_unix(serializer);
+        }
         let mut buf = SmallFixedBuffer::new();
-        if self.write_buf(&mut buf, self.opts).is_err() {
+        if self.write_buf(&mut buf, self.format).is_err() {
             err!(SerializeError::DatetimeLibraryUnsupported)
         }
         serializer.serialize_unit_struct(str_from_slice!(buf.as_ptr(), buf.len()))
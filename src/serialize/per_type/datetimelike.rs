@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright Ben Sully (2021), ijl (2020-2025)
 
-use crate::opt::{NAIVE_UTC, OMIT_MICROSECONDS, Opt, UTC_Z};
+use crate::opt::{
+    DATE_AS_EPOCH_DAYS, DATETIME_AS_UNIX, DATETIME_AS_UNIX_SECONDS, FORBID_NAIVE_DATETIME,
+    NAIVE_UTC, OMIT_MICROSECONDS, Opt, RFC3339_STRICT, TZ_OFFSET_NO_COLON, UTC_Z,
+};
 
 pub(crate) enum DateTimeError {
     LibraryUnsupported,
@@ -28,6 +31,59 @@ macro_rules! write_triple_digit {
     };
 }
 
+/// How datetimes are serialized, decided once from the options of a
+/// `dumps()` call rather than for each datetime.
+#[derive(Clone, Copy)]
+pub(crate) struct DateTimeFormat {
+    /// `OPT_FORBID_NAIVE_DATETIME` or `OPT_RFC3339_STRICT` without
+    /// `OPT_NAIVE_UTC`.
+    pub forbid_naive: bool,
+    /// `OPT_NAIVE_UTC`.
+    pub naive_utc: bool,
+    /// Not `OPT_OMIT_MICROSECONDS`, or `OPT_RFC3339_STRICT`.
+    pub microseconds: bool,
+    /// Not `OPT_TZ_OFFSET_NO_COLON`, or `OPT_RFC3339_STRICT`.
+    pub offset_colon: bool,
+    /// The offset of UTC, as given by `OPT_UTC_Z` and `OPT_TZ_OFFSET_NO_COLON`.
+    /// `OPT_RFC3339_STRICT` ignores the latter.
+    pub utc_offset: &'static [u8],
+    /// The microseconds per unit of `OPT_DATETIME_AS_UNIX_SECONDS` or
+    /// `OPT_DATETIME_AS_UNIX_MILLIS`, or 0 to serialize as RFC 3339.
+    pub unix_per_unit: i64,
+    /// `OPT_DATE_AS_EPOCH_DAYS`.
+    pub date_as_epoch_days: bool,
+}
+
+impl DateTimeFormat {
+    pub const fn new(opts: Opt) -> Self {
+        // RFC 3339 requires an offset, `time-numoffset` has a colon, and
+        // `time-secfrac` is not to be dropped
+        let strict = opt_enabled!(opts, RFC3339_STRICT);
+        DateTimeFormat {
+            forbid_naive: (opt_enabled!(opts, FORBID_NAIVE_DATETIME) || strict)
+                && opt_disabled!(opts, NAIVE_UTC),
+            naive_utc: opt_enabled!(opts, NAIVE_UTC),
+            microseconds: opt_disabled!(opts, OMIT_MICROSECONDS) || strict,
+            offset_colon: opt_disabled!(opts, TZ_OFFSET_NO_COLON) || strict,
+            utc_offset: if opt_enabled!(opts, UTC_Z) {
+                b"Z"
+            } else if opt_enabled!(opts, TZ_OFFSET_NO_COLON) && !strict {
+                b"+0000"
+            } else {
+                b"+00:00"
+            },
+            unix_per_unit: if opt_disabled!(opts, DATETIME_AS_UNIX) {
+                0
+            } else if opt_enabled!(opts, DATETIME_AS_UNIX_SECONDS) {
+                1_000_000
+            } else {
+                1_000
+            },
+            date_as_epoch_days: opt_enabled!(opts, DATE_AS_EPOCH_DAYS),
+        }
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct Offset {
     pub day: i32,
@@ -65,10 +121,31 @@ pub(crate) trait DateTimeLike {
     /// The offset of the timezone.
     fn offset(&self) -> Result<Offset, DateTimeError>;
 
-    /// Write `self` to a buffer in RFC3339 format, using `opts` to
-    /// customise if desired.
+    /// The number of microseconds since the Unix epoch, using the offset of
+    /// the timezone, or UTC if naive.
+    fn unix_microseconds(&self) -> Result<i64, DateTimeError> {
+        // days from the civil date, per Howard Hinnant's algorithm
+        let year = i64::from(self.year()) - i64::from(self.month() <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = i64::from(self.month());
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day()) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        let offset = self.offset()?;
+        let seconds = days * 86_400
+            + i64::from(self.hour()) * 3_600
+            + i64::from(self.minute()) * 60
+            + i64::from(self.second())
+            - (i64::from(offset.day) * 86_400 + i64::from(offset.second));
+        Ok(seconds * 1_000_000 + i64::from(self.microsecond()))
+    }
+
+    /// Write `self` to a buffer in RFC3339 format, as given by `format`.
     #[inline(never)]
-    fn write_buf<B>(&self, buf: &mut B, opts: Opt) -> Result<(), DateTimeError>
+    fn write_buf<B>(&self, buf: &mut B, format: DateTimeFormat) -> Result<(), DateTimeError>
     where
         B: bytes::BufMut,
     {
@@ -93,7 +170,7 @@ pub(crate) trait DateTimeLike {
         write_double_digit!(buf, self.minute());
         buf.put_u8(b':');
         write_double_digit!(buf, self.second());
-        if opt_disabled!(opts, OMIT_MICROSECONDS) {
+        if format.microseconds {
             let microsecond = self.microsecond();
             if microsecond != 0 {
                 buf.put_u8(b'.');
@@ -108,43 +185,48 @@ pub(crate) trait DateTimeLike {
                 // }
             }
         }
-        if self.has_tz() || opt_enabled!(opts, NAIVE_UTC) {
-            let offset = self.offset()?;
-            let mut offset_second = offset.second;
-            if offset_second == 0 {
-                if opt_enabled!(opts, UTC_Z) {
-                    buf.put_u8(b'Z');
-                } else {
-                    buf.put_slice(b"+00:00");
-                }
-            } else {
-                // This branch is only really hit by the Python datetime implementation,
-                // since numpy datetimes are all converted to UTC.
-                if offset.day == -1 {
-                    // datetime.timedelta(days=-1, seconds=68400) -> -05:00
-                    buf.put_u8(b'-');
-                    offset_second = 86400 - offset_second;
-                } else {
-                    // datetime.timedelta(seconds=37800) -> +10:30
-                    buf.put_u8(b'+');
-                }
-                let offset_minute = offset_second / 60;
-                let offset_hour = offset_minute / 60;
-                write_double_digit!(buf, offset_hour);
-                buf.put_u8(b':');
-                let mut offset_minute_print = offset_minute % 60;
-                // https://tools.ietf.org/html/rfc3339#section-5.8
-                // "exactly 19 minutes and 32.13 seconds ahead of UTC"
-                // "closest representable UTC offset"
-                //  "+20:00"
-                let offset_excess_second =
-                    offset_second - (offset_minute_print * 60 + offset_hour * 3600);
-                if offset_excess_second >= 30 {
-                    offset_minute_print += 1;
-                }
-                write_double_digit!(buf, offset_minute_print);
-            }
+        if self.has_tz() || format.naive_utc {
+            write_offset(buf, &self.offset()?, format);
         }
         Ok(())
     }
 }
+
+/// Write the `+HH:MM` offset of a datetime-like object to a buffer, or that
+/// of UTC as given by `format`.
+pub(crate) fn write_offset<B>(buf: &mut B, offset: &Offset, format: DateTimeFormat)
+where
+    B: bytes::BufMut,
+{
+    let mut offset_second = offset.second;
+    if offset_second == 0 {
+        buf.put_slice(format.utc_offset);
+    } else {
+        // This branch is only really hit by the Python datetime implementation,
+        // since numpy datetimes are all converted to UTC.
+        if offset.day == -1 {
+            // datetime.timedelta(days=-1, seconds=68400) -> -05:00
+            buf.put_u8(b'-');
+            offset_second = 86400 - offset_second;
+        } else {
+            // datetime.timedelta(seconds=37800) -> +10:30
+            buf.put_u8(b'+');
+        }
+        let offset_minute = offset_second / 60;
+        let offset_hour = offset_minute / 60;
+        write_double_digit!(buf, offset_hour);
+        if format.offset_colon {
+            buf.put_u8(b':');
+        }
+        let mut offset_minute_print = offset_minute % 60;
+        // https://tools.ietf.org/html/rfc3339#section-5.8
+        // "exactly 19 minutes and 32.13 seconds ahead of UTC"
+        // "closest representable UTC offset"
+        //  "+20:00"
+        let offset_excess_second = offset_second - (offset_minute_print * 60 + offset_hour * 3600);
+        if offset_excess_second >= 30 {
+            offset_minute_print += 1;
+        }
+        write_double_digit!(buf, offset_minute_print);
+    }
+}
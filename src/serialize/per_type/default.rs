@@ -1,10 +1,135 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025)
 
+use crate::ffi::PyObject;
+use crate::opt::{REPR_FALLBACK, SKIP_UNSERIALIZABLE};
 use crate::serialize::error::SerializeError;
-use crate::serialize::serializer::PyObjectSerializer;
+use crate::serialize::obtype::{ObType, pyobject_to_obtype};
+use crate::serialize::serializer::{PyObjectSerializer, on_skip, output_exceeded};
 
-use serde::ser::{Serialize, Serializer};
+use core::ptr::NonNull;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// Whether `ob_type` is, or is a subclass of, a type of the datetime module
+/// that is not recognized because the datetime CAPI could not be imported
+/// when the interpreter state was created.
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn is_unrecognized_datetime(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    if !crate::typeref::get_date_type().is_null() {
+        return false;
+    }
+    let mut tp = ob_type;
+    while !tp.is_null() {
+        let name = unsafe { core::ffi::CStr::from_ptr((*tp).tp_name) };
+        if name == c"datetime.date" || name == c"datetime.datetime" || name == c"datetime.time" {
+            return true;
+        }
+        tp = unsafe { (*tp).tp_base };
+    }
+    false
+}
+
+/// The handler given to `register_type()` for `ob_type` or else for the first
+/// type in its MRO that has one, with a new reference.
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn registered_handler(ob_type: *mut crate::ffi::PyTypeObject) -> Option<NonNull<PyObject>> {
+    let handlers = crate::typeref::get_type_handlers();
+    if ffi!(PyDict_Size(handlers)) == 0 {
+        return None;
+    }
+    let mut handler = ffi!(PyDict_GetItem(handlers, ob_type.cast::<PyObject>()));
+    if handler.is_null() {
+        let mro = unsafe { (*ob_type).tp_mro };
+        if !mro.is_null() {
+            for i in 1..ffi!(Py_SIZE(mro)) {
+                handler = ffi!(PyDict_GetItem(handlers, ffi!(PyTuple_GET_ITEM(mro, i))));
+                if !handler.is_null() {
+                    break;
+                }
+            }
+        }
+    }
+    // the handler may unregister itself while it is called
+    NonNull::new(handler).inspect(|handler| ffi!(Py_INCREF(handler.as_ptr())))
+}
+
+/// Whether `callables`, a `list` or `tuple` given as `default`, contains only
+/// callables.
+#[cold]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn is_callable_sequence(callables: *mut PyObject) -> bool {
+    let len = ffi!(Py_SIZE(callables));
+    let is_list = is_default_list(callables);
+    (0..len).all(|i| {
+        let callable = if is_list {
+            ffi!(PyList_GET_ITEM(callables, i))
+        } else {
+            ffi!(PyTuple_GET_ITEM(callables, i))
+        };
+        ffi!(PyCallable_Check(callable)) != 0
+    })
+}
+
+#[inline(always)]
+fn is_default_list(callables: *mut PyObject) -> bool {
+    core::ptr::eq(ob_type!(callables), crate::typeref::get_list_type())
+}
+
+#[inline(always)]
+fn is_default_sequence(callables: *mut PyObject) -> bool {
+    is_default_list(callables)
+        || core::ptr::eq(ob_type!(callables), crate::typeref::get_tuple_type())
+}
+
+/// Serialize the entry of `key` and `value` to `map`, or omit it if `value`
+/// is skipped with `OPT_SKIP_UNSERIALIZABLE`.
+#[inline(always)]
+pub(crate) fn serialize_entry<M>(
+    map: &mut M,
+    key: &str,
+    value: &PyObjectSerializer,
+) -> Result<(), M::Error>
+where
+    M: SerializeMap,
+{
+    if opt_enabled!(value.state.opts(), SKIP_UNSERIALIZABLE)
+        && matches!(
+            pyobject_to_obtype(value.ptr, value.state.opts()),
+            ObType::Unknown
+        )
+    {
+        cold_path!();
+        return DefaultSerializer::new(value).serialize_entry(map, key);
+    }
+    map.serialize_key(key).unwrap();
+    map.serialize_value(value)
+}
+
+/// Call `on_skip`, if given, with `obj` and the `JSONEncodeError` that would
+/// otherwise have been raised. The exception already set, if any, e.g., raised
+/// by `default`, is the cause of the error.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn skip(obj: *mut PyObject, err: &SerializeError) -> Result<(), SerializeError> {
+    let Some(callable) = on_skip() else {
+        ffi!(PyErr_Clear());
+        return Ok(());
+    };
+    let exc = crate::exception::dumps_exception(&err.to_string());
+    let args = [obj, exc];
+    let ret = unsafe {
+        crate::ffi::PyObject_Vectorcall(callable.as_ptr(), args.as_ptr(), 2, core::ptr::null_mut())
+    };
+    ffi!(Py_DECREF(exc));
+    if ret.is_null() {
+        return Err(SerializeError::OnSkip);
+    }
+    ffi!(Py_DECREF(ret));
+    Ok(())
+}
 
 #[repr(transparent)]
 pub(crate) struct DefaultSerializer<'a> {
@@ -24,44 +149,214 @@ impl Serialize for DefaultSerializer<'_> {
     where
         S: Serializer,
     {
-        match self.previous.default {
-            Some(callable) => {
-                if self.previous.state.default_calls_limit() {
-                    cold_path!();
-                    err!(SerializeError::DefaultRecursionLimit)
+        match self.default_object() {
+            Ok(default_obj) => self.serialize_default_obj(default_obj.as_ptr(), serializer),
+            Err(err) if self.is_skipped(&err) => {
+                if let Err(err) = self.skip(&err) {
+                    err!(err)
                 }
-                #[cfg(not(Py_3_10))]
-                let default_obj = ffi!(PyObject_CallFunctionObjArgs(
-                    callable.as_ptr(),
-                    self.previous.ptr,
-                    core::ptr::null_mut::<crate::ffi::PyObject>()
-                ));
-                #[cfg(Py_3_10)]
-                #[allow(clippy::cast_sign_loss)]
-                let nargs = ffi!(PyVectorcall_NARGS(1)) as usize;
-                #[cfg(Py_3_10)]
-                let default_obj = unsafe {
-                    crate::ffi::PyObject_Vectorcall(
-                        callable.as_ptr(),
-                        &raw const self.previous.ptr,
-                        nargs,
-                        core::ptr::null_mut(),
-                    )
-                };
-                if default_obj.is_null() {
-                    err!(SerializeError::UnsupportedType(nonnull!(self.previous.ptr)))
-                } else {
-                    let res = PyObjectSerializer::new(
-                        default_obj,
-                        self.previous.state.copy_for_default_call(),
-                        self.previous.default,
-                    )
-                    .serialize(serializer);
-                    ffi!(Py_DECREF(default_obj));
-                    res
+                serializer.serialize_unit()
+            }
+            Err(err) => err!(err),
+        }
+    }
+}
+
+impl DefaultSerializer<'_> {
+    /// Serialize the entry of `key` and the object to `map`, or, if the object
+    /// is skipped with `OPT_SKIP_UNSERIALIZABLE`, omit the entry.
+    #[cold]
+    #[inline(never)]
+    pub fn serialize_entry<M>(&self, map: &mut M, key: &str) -> Result<(), M::Error>
+    where
+        M: SerializeMap,
+    {
+        if opt_disabled!(self.previous.state.opts(), SKIP_UNSERIALIZABLE) {
+            map.serialize_key(key).unwrap();
+            return map.serialize_value(self);
+        }
+        match self.default_object() {
+            Ok(default_obj) => {
+                map.serialize_key(key).unwrap();
+                let res = map.serialize_value(&self.default_obj_serializer(default_obj.as_ptr()));
+                ffi!(Py_DECREF(default_obj.as_ptr()));
+                res
+            }
+            Err(err) if self.is_skipped(&err) => {
+                if let Err(err) = self.skip(&err) {
+                    err!(err)
                 }
+                Ok(())
             }
-            None => err!(SerializeError::UnsupportedType(nonnull!(self.previous.ptr))),
+            Err(err) => err!(err),
+        }
+    }
+
+    /// The object to serialize in place of the object, as a new reference,
+    /// returned by the handler registered for its type or by `default`, or,
+    /// if neither gives one, its `repr()` with `OPT_REPR_FALLBACK`.
+    fn default_object(&self) -> Result<NonNull<PyObject>, SerializeError> {
+        match self.handled_object() {
+            Err(err) if self.is_repr_fallback(&err) => self.repr_object(),
+            res => res,
         }
     }
+
+    /// The object returned by the handler registered for the type of the
+    /// object or by `default`, as a new reference.
+    fn handled_object(&self) -> Result<NonNull<PyObject>, SerializeError> {
+        if output_exceeded() {
+            cold_path!();
+            return Err(SerializeError::OutputExceeded);
+        }
+        if let Some(handler) = registered_handler(ob_type!(self.previous.ptr)) {
+            let res = self.call(handler);
+            ffi!(Py_DECREF(handler.as_ptr()));
+            return res;
+        }
+        match self.previous.default {
+            Some(callables) if is_default_sequence(callables.as_ptr()) => {
+                self.call_each(callables.as_ptr())
+            }
+            Some(callable) => self.call(callable),
+            None => {
+                if is_unrecognized_datetime(ob_type!(self.previous.ptr)) {
+                    return Err(SerializeError::DatetimeUnavailable);
+                }
+                Err(SerializeError::UnsupportedType(nonnull!(self.previous.ptr)))
+            }
+        }
+    }
+
+    /// Whether `err`, from `handled_object()`, is replaced by the `repr()` of
+    /// the object.
+    fn is_repr_fallback(&self, err: &SerializeError) -> bool {
+        opt_enabled!(self.previous.state.opts(), REPR_FALLBACK)
+            && matches!(
+                err,
+                SerializeError::UnsupportedType(_) | SerializeError::DatetimeUnavailable
+            )
+    }
+
+    /// The `repr()` of the object, as a new reference. If it raises, the
+    /// exception is the cause of the error.
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn repr_object(&self) -> Result<NonNull<PyObject>, SerializeError> {
+        // the exception raised by `default`, if any, is discarded
+        ffi!(PyErr_Clear());
+        NonNull::new(ffi!(PyObject_Repr(self.previous.ptr)))
+            .ok_or(SerializeError::UnsupportedType(nonnull!(self.previous.ptr)))
+    }
+
+    /// Whether `err`, from `default_object()`, skips the object rather than
+    /// fails serialization.
+    fn is_skipped(&self, err: &SerializeError) -> bool {
+        opt_enabled!(self.previous.state.opts(), SKIP_UNSERIALIZABLE)
+            && matches!(
+                err,
+                SerializeError::UnsupportedType(_) | SerializeError::DatetimeUnavailable
+            )
+    }
+
+    fn skip(&self, err: &SerializeError) -> Result<(), SerializeError> {
+        skip(self.previous.ptr, err)
+    }
+
+    /// Call `callable` with the object.
+    fn call(&self, callable: NonNull<PyObject>) -> Result<NonNull<PyObject>, SerializeError> {
+        if self.previous.state.default_calls_limit() {
+            cold_path!();
+            return Err(SerializeError::DefaultRecursionLimit);
+        }
+        NonNull::new(self.call_default(callable.as_ptr()))
+            .ok_or(SerializeError::UnsupportedType(nonnull!(self.previous.ptr)))
+    }
+
+    /// Call each of `callables` with the object until one neither raises nor
+    /// returns `NotImplemented`. If none does, the exception raised by the
+    /// last, if any, is the cause of the error.
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn call_each(&self, callables: *mut PyObject) -> Result<NonNull<PyObject>, SerializeError> {
+        if self.previous.state.default_calls_limit() {
+            return Err(SerializeError::DefaultRecursionLimit);
+        }
+        let is_list = is_default_list(callables);
+        let mut idx = 0;
+        // the length is read on each iteration, as a callable may modify the
+        // list
+        while idx < ffi!(Py_SIZE(callables)) {
+            let callable = if is_list {
+                ffi!(PyList_GET_ITEM(callables, idx))
+            } else {
+                ffi!(PyTuple_GET_ITEM(callables, idx))
+            };
+            idx += 1;
+            ffi!(Py_INCREF(callable));
+            ffi!(PyErr_Clear());
+            let default_obj = self.call_default(callable);
+            ffi!(Py_DECREF(callable));
+            if default_obj.is_null() {
+                continue;
+            }
+            if core::ptr::eq(default_obj, ffi!(Py_NotImplemented())) {
+                ffi!(Py_DECREF(default_obj));
+                continue;
+            }
+            return Ok(nonnull!(default_obj));
+        }
+        Err(SerializeError::UnsupportedType(nonnull!(self.previous.ptr)))
+    }
+
+    /// Call `callable` with the object, returning a new reference or null
+    /// with an exception set.
+    fn call_default(&self, callable: *mut PyObject) -> *mut PyObject {
+        #[cfg(not(Py_3_10))]
+        let default_obj = ffi!(PyObject_CallFunctionObjArgs(
+            callable,
+            self.previous.ptr,
+            core::ptr::null_mut::<crate::ffi::PyObject>()
+        ));
+        #[cfg(Py_3_10)]
+        #[allow(clippy::cast_sign_loss)]
+        let nargs = ffi!(PyVectorcall_NARGS(1)) as usize;
+        #[cfg(Py_3_10)]
+        let default_obj = unsafe {
+            crate::ffi::PyObject_Vectorcall(
+                callable,
+                &raw const self.previous.ptr,
+                nargs,
+                core::ptr::null_mut(),
+            )
+        };
+        default_obj
+    }
+
+    /// Serialize `default_obj`, a new reference returned by `default`.
+    fn serialize_default_obj<S>(
+        &self,
+        default_obj: *mut PyObject,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let res = self
+            .default_obj_serializer(default_obj)
+            .serialize(serializer);
+        ffi!(Py_DECREF(default_obj));
+        res
+    }
+
+    fn default_obj_serializer(&self, default_obj: *mut PyObject) -> PyObjectSerializer {
+        PyObjectSerializer::new(
+            default_obj,
+            self.previous.state.copy_for_default_call(),
+            self.previous.default,
+            self.previous.key_filter,
+        )
+    }
 }
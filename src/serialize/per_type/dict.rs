@@ -1,18 +1,27 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025), Aviram Hassan (2020)
 
-use crate::opt::{NON_STR_KEYS, NOT_PASSTHROUGH, SORT_KEYS, SORT_OR_NON_STR_KEYS};
+use crate::opt::{
+    NON_STR_KEYS, NON_STR_KEYS_STRICT, NOT_PASSTHROUGH, SORT_KEYS, SORT_OR_NON_STR_KEYS,
+};
 use crate::serialize::buffer::SmallFixedBuffer;
+#[cfg(not(Py_GIL_DISABLED))]
+use crate::serialize::cache::CachedNonStrKey;
 use crate::serialize::error::SerializeError;
 use crate::serialize::obtype::{ObType, pyobject_to_obtype};
 use crate::serialize::per_type::datetimelike::DateTimeLike;
 use crate::serialize::per_type::{
-    BoolSerializer, DataclassGenericSerializer, Date, DateTime, DefaultSerializer, EnumSerializer,
-    FloatSerializer, FragmentSerializer, IntSerializer, ListTupleSerializer, NoneSerializer,
-    NumpyScalar, NumpySerializer, StrSerializer, StrSubclassSerializer, Time, UUID,
-    ZeroListSerializer,
+    ArraySerializer, BoolSerializer, BytesMode, BytesSerializer, CachedTupleSerializer,
+    DataclassGenericSerializer, Date, DateTime, DefaultSerializer, DictViewSerializer,
+    EnumSerializer, FloatSerializer, FractionSerializer, FragmentSerializer, FsPathSerializer,
+    IntSerializer, IpAddressSerializer, IterableSerializer, JsonDunderSerializer,
+    ListTupleSerializer, MappingSerializer, NoneSerializer, NumpyScalar, NumpySerializer,
+    PropertiesSerializer, RangeSerializer, StrSerializer, StrSubclassSerializer, Time, UUID,
+    WeakrefSerializer, ZeroListSerializer, encode_key, serialize_entry,
+};
+use crate::serialize::serializer::{
+    PyObjectSerializer, datetime_format, output_exceeded, skip_key_prefix,
 };
-use crate::serialize::serializer::PyObjectSerializer;
 use crate::serialize::state::SerializerState;
 use crate::str::{PyStr, PyStrSubclass};
 // STR_TYPE, TRUE, VALUE_STR now accessed via typeref accessor functions
@@ -42,8 +51,8 @@ impl Serialize for ZeroDictSerializer {
 pub(crate) struct DictGenericSerializer {
     ptr: *mut crate::ffi::PyObject,
     state: SerializerState,
-    #[allow(dead_code)]
     default: Option<NonNull<crate::ffi::PyObject>>,
+    key_filter: Option<NonNull<crate::ffi::PyObject>>,
 }
 
 impl DictGenericSerializer {
@@ -51,11 +60,13 @@ impl DictGenericSerializer {
         ptr: *mut crate::ffi::PyObject,
         state: SerializerState,
         default: Option<NonNull<crate::ffi::PyObject>>,
+        key_filter: Option<NonNull<crate::ffi::PyObject>>,
     ) -> Self {
         DictGenericSerializer {
             ptr: ptr,
             state: state.copy_for_recursive_call(),
             default: default,
+            key_filter: key_filter,
         }
     }
 }
@@ -74,7 +85,22 @@ impl Serialize for DictGenericSerializer {
         if ffi!(Py_SIZE(self.ptr)) == 0 {
             cold_path!();
             ZeroDictSerializer::new().serialize(serializer)
-        } else if opt_disabled!(self.state.opts(), SORT_OR_NON_STR_KEYS) {
+        } else if let Some(key_filter) = self.key_filter {
+            cold_path!();
+            self.serialize_filtered(key_filter, serializer)
+        } else {
+            self.serialize_entries(serializer)
+        }
+    }
+}
+
+impl DictGenericSerializer {
+    #[inline(always)]
+    fn serialize_entries<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if opt_disabled!(self.state.opts(), SORT_OR_NON_STR_KEYS) {
             unsafe {
                 (*(core::ptr::from_ref::<DictGenericSerializer>(self)).cast::<Dict>())
                     .serialize(serializer)
@@ -91,6 +117,66 @@ impl Serialize for DictGenericSerializer {
             }
         }
     }
+
+    /// Serializes the dict of the entries whose keys `key_filter` maps to a
+    /// key other than `None`, keyed by what it returns. Nested dicts are
+    /// filtered in turn.
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize_filtered<S>(
+        &self,
+        key_filter: NonNull<crate::ffi::PyObject>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let filtered = ffi!(PyDict_New());
+        if filtered.is_null() {
+            err!(SerializeError::KeyFilter)
+        }
+        let mut pos = 0;
+        let mut key: *mut crate::ffi::PyObject = core::ptr::null_mut();
+        let mut value: *mut crate::ffi::PyObject = core::ptr::null_mut();
+        while ffi!(PyDict_Next(self.ptr, &mut pos, &mut key, &mut value)) == 1 {
+            let new_key = unsafe {
+                crate::ffi::PyObject_Vectorcall(
+                    key_filter.as_ptr(),
+                    &raw const key,
+                    1,
+                    core::ptr::null_mut(),
+                )
+            };
+            if new_key.is_null() {
+                ffi!(Py_DECREF(filtered));
+                err!(SerializeError::KeyFilter)
+            }
+            let ret = if core::ptr::eq(new_key, crate::typeref::get_none()) {
+                0
+            } else {
+                ffi!(PyDict_SetItem(filtered, new_key, value))
+            };
+            ffi!(Py_DECREF(new_key));
+            if ret == -1 {
+                ffi!(Py_DECREF(filtered));
+                err!(SerializeError::KeyFilter)
+            }
+        }
+        let ret = if ffi!(Py_SIZE(filtered)) == 0 {
+            ZeroDictSerializer::new().serialize(serializer)
+        } else {
+            DictGenericSerializer {
+                ptr: filtered,
+                state: self.state,
+                default: self.default,
+                key_filter: self.key_filter,
+            }
+            .serialize_entries(serializer)
+        };
+        ffi!(Py_DECREF(filtered));
+        ret
+    }
 }
 
 macro_rules! impl_serialize_entry {
@@ -98,11 +184,11 @@ macro_rules! impl_serialize_entry {
         match pyobject_to_obtype($value, $self.state.opts()) {
             ObType::Str => {
                 $map.serialize_key($key).unwrap();
-                $map.serialize_value(&StrSerializer::new($value))?;
+                $map.serialize_value(&StrSerializer::new($value, $self.state.opts()))?;
             }
             ObType::StrSubclass => {
                 $map.serialize_key($key).unwrap();
-                $map.serialize_value(&StrSubclassSerializer::new($value))?;
+                $map.serialize_value(&StrSubclassSerializer::new($value, $self.state.opts()))?;
             }
             ObType::Int => {
                 $map.serialize_key($key).unwrap();
@@ -122,7 +208,7 @@ macro_rules! impl_serialize_entry {
             }
             ObType::Datetime => {
                 $map.serialize_key($key).unwrap();
-                $map.serialize_value(&DateTime::new($value, $self.state.opts()))?;
+                $map.serialize_value(&DateTime::new($value, datetime_format()))?;
             }
             ObType::Date => {
                 $map.serialize_key($key).unwrap();
@@ -134,10 +220,15 @@ macro_rules! impl_serialize_entry {
             }
             ObType::Uuid => {
                 $map.serialize_key($key).unwrap();
-                $map.serialize_value(&UUID::new($value)).unwrap();
+                $map.serialize_value(&UUID::new($value, $self.state.opts()))?;
             }
             ObType::Dict => {
-                let pyvalue = DictGenericSerializer::new($value, $self.state, $self.default);
+                let pyvalue = DictGenericSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                );
                 $map.serialize_key($key).unwrap();
                 $map.serialize_value(&pyvalue)?;
             }
@@ -146,8 +237,12 @@ macro_rules! impl_serialize_entry {
                     $map.serialize_key($key).unwrap();
                     $map.serialize_value(&ZeroListSerializer::new()).unwrap();
                 } else {
-                    let pyvalue =
-                        ListTupleSerializer::from_list($value, $self.state, $self.default);
+                    let pyvalue = ListTupleSerializer::from_list(
+                        $value,
+                        $self.state,
+                        $self.default,
+                        $self.key_filter,
+                    );
                     $map.serialize_key($key).unwrap();
                     $map.serialize_value(&pyvalue)?;
                 }
@@ -157,8 +252,12 @@ macro_rules! impl_serialize_entry {
                     $map.serialize_key($key).unwrap();
                     $map.serialize_value(&ZeroListSerializer::new()).unwrap();
                 } else {
-                    let pyvalue =
-                        ListTupleSerializer::from_tuple($value, $self.state, $self.default);
+                    let pyvalue = ListTupleSerializer::from_tuple(
+                        $value,
+                        $self.state,
+                        $self.default,
+                        $self.key_filter,
+                    );
                     $map.serialize_key($key).unwrap();
                     $map.serialize_value(&pyvalue)?;
                 }
@@ -169,6 +268,7 @@ macro_rules! impl_serialize_entry {
                     $value,
                     $self.state,
                     $self.default,
+                    $self.key_filter,
                 )))?;
             }
             ObType::Enum => {
@@ -177,6 +277,7 @@ macro_rules! impl_serialize_entry {
                     $value,
                     $self.state,
                     $self.default,
+                    $self.key_filter,
                 )))?;
             }
             ObType::NumpyArray => {
@@ -185,6 +286,7 @@ macro_rules! impl_serialize_entry {
                     $value,
                     $self.state,
                     $self.default,
+                    $self.key_filter,
                 )))?;
             }
             ObType::NumpyScalar => {
@@ -193,24 +295,145 @@ macro_rules! impl_serialize_entry {
             }
             ObType::Fragment => {
                 $map.serialize_key($key).unwrap();
-                $map.serialize_value(&FragmentSerializer::new($value))?;
+                $map.serialize_value(&FragmentSerializer::new($value, $self.state.opts()))?;
             }
-            ObType::Unknown => {
+            ObType::Bytes => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&BytesSerializer::new($value, $self.state.opts()))?;
+            }
+            ObType::Mapping => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&MappingSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                )))?;
+            }
+            ObType::DictView => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&DictViewSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                )))?;
+            }
+            ObType::CachedTuple => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&CachedTupleSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                )))?;
+            }
+            ObType::JsonDunder => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&JsonDunderSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                )))?;
+            }
+            ObType::IpAddress => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&IpAddressSerializer::new($value))?;
+            }
+            ObType::Fraction => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&FractionSerializer::new($value, $self.state.opts()))?;
+            }
+            ObType::Range => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&RangeSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                )))?;
+            }
+            ObType::Weakref => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&WeakrefSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                )))?;
+            }
+            ObType::Array => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&ArraySerializer::new($value))?;
+            }
+            ObType::FsPath => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&FsPathSerializer::new($value, $self.state.opts()))?;
+            }
+            ObType::Iterable => {
+                $map.serialize_key($key).unwrap();
+                $map.serialize_value(&IterableSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                )))?;
+            }
+            ObType::Properties => {
                 $map.serialize_key($key).unwrap();
-                $map.serialize_value(&DefaultSerializer::new(&PyObjectSerializer::new(
+                $map.serialize_value(&PropertiesSerializer::new(&PyObjectSerializer::new(
                     $value,
                     $self.state,
                     $self.default,
+                    $self.key_filter,
                 )))?;
             }
+            ObType::Unknown => {
+                DefaultSerializer::new(&PyObjectSerializer::new(
+                    $value,
+                    $self.state,
+                    $self.default,
+                    $self.key_filter,
+                ))
+                .serialize_entry(&mut $map, $key)?;
+            }
         }
     };
 }
 
+/// The key of an entry without `OPT_NON_STR_KEYS`, which must be a `str`.
+#[inline(always)]
+fn str_key<'a>(key: *mut crate::ffi::PyObject) -> Result<&'a str, SerializeError> {
+    if !is_class_by_type!(ob_type!(key), crate::typeref::get_str_type()) {
+        return Err(SerializeError::KeyMustBeStr);
+    }
+    match unsafe { PyStr::from_ptr_unchecked(key).to_str() } {
+        Some(uni) => Ok(uni),
+        None => Err(SerializeError::InvalidStr),
+    }
+}
+
+/// The prefix of the `str` keys of the entries omitted given
+/// `skip_key_prefix`, if any.
+#[inline(always)]
+fn skipped_prefix<'a>() -> Option<&'a [u8]> {
+    skip_key_prefix().map(|prefix| {
+        // validated by `dumps()`
+        unsafe {
+            PyStr::from_ptr_unchecked(prefix.as_ptr())
+                .to_str()
+                .unwrap_unchecked()
+        }
+        .as_bytes()
+    })
+}
+
 pub(crate) struct Dict {
     ptr: *mut crate::ffi::PyObject,
     state: SerializerState,
     default: Option<NonNull<crate::ffi::PyObject>>,
+    key_filter: Option<NonNull<crate::ffi::PyObject>>,
 }
 
 impl Serialize for Dict {
@@ -230,6 +453,8 @@ impl Serialize for Dict {
         let len = isize_to_usize(ffi!(Py_SIZE(self.ptr)));
         assume!(len > 0);
 
+        let prefix = skipped_prefix();
+
         for _ in 0..len {
             let key = next_key;
             let value = next_value;
@@ -237,16 +462,20 @@ impl Serialize for Dict {
             pydict_next!(self.ptr, &mut pos, &mut next_key, &mut next_value);
 
             // key
-            let key_ob_type = ob_type!(key);
-            if !is_class_by_type!(key_ob_type, crate::typeref::get_str_type()) {
-                err!(SerializeError::KeyMustBeStr)
+            let key_as_str = match str_key(key) {
+                Ok(key_as_str) => key_as_str,
+                Err(err) => err!(err),
+            };
+            if let Some(prefix) = prefix {
+                if key_as_str.as_bytes().starts_with(prefix) {
+                    continue;
+                }
             }
-            let pystr = unsafe { PyStr::from_ptr_unchecked(key) };
-            let uni = pystr.to_str();
-            if uni.is_none() {
-                err!(SerializeError::InvalidStr)
+
+            if output_exceeded() {
+                cold_path!();
+                err!(SerializeError::OutputExceeded)
             }
-            let key_as_str = uni.unwrap();
 
             // value
             impl_serialize_entry!(map, self, key_as_str, value);
@@ -260,6 +489,7 @@ pub(crate) struct DictSortedKey {
     ptr: *mut crate::ffi::PyObject,
     state: SerializerState,
     default: Option<NonNull<crate::ffi::PyObject>>,
+    key_filter: Option<NonNull<crate::ffi::PyObject>>,
 }
 
 impl Serialize for DictSortedKey {
@@ -280,32 +510,32 @@ impl Serialize for DictSortedKey {
         let mut items: SmallVec<[(&str, *mut crate::ffi::PyObject); 8]> =
             SmallVec::with_capacity(len);
 
+        let prefix = skipped_prefix();
+
         for _ in 0..len as usize {
             let key = next_key;
             let value = next_value;
 
             pydict_next!(self.ptr, &mut pos, &mut next_key, &mut next_value);
 
-            if unsafe { !core::ptr::eq(ob_type!(key), crate::typeref::get_str_type()) } {
-                err!(SerializeError::KeyMustBeStr)
-            }
-            let pystr = unsafe { PyStr::from_ptr_unchecked(key) };
-            let uni = pystr.to_str();
-            if uni.is_none() {
-                err!(SerializeError::InvalidStr)
+            match str_key(key) {
+                Ok(key_as_str)
+                    if prefix.is_some_and(|prefix| key_as_str.as_bytes().starts_with(prefix)) => {}
+                Ok(key_as_str) => items.push((key_as_str, value)),
+                Err(err) => err!(err),
             }
-            let key_as_str = uni.unwrap();
-
-            items.push((key_as_str, value));
         }
 
         sort_dict_items(&mut items);
 
         let mut map = serializer.serialize_map(None).unwrap();
         for (key, val) in items.iter() {
-            let pyvalue = PyObjectSerializer::new(*val, self.state, self.default);
-            map.serialize_key(key).unwrap();
-            map.serialize_value(&pyvalue)?;
+            if output_exceeded() {
+                cold_path!();
+                err!(SerializeError::OutputExceeded)
+            }
+            let pyvalue = PyObjectSerializer::new(*val, self.state, self.default, self.key_filter);
+            serialize_entry(&mut map, key, &pyvalue)?;
         }
         map.end()
     }
@@ -315,21 +545,44 @@ impl Serialize for DictSortedKey {
 fn non_str_str(key: *mut crate::ffi::PyObject) -> Result<String, SerializeError> {
     // because of ObType::Enum
     let uni = unsafe { PyStr::from_ptr_unchecked(key).to_str() };
-    if uni.is_none() {
-        Err(SerializeError::InvalidStr)
-    } else {
-        Ok(String::from(uni.unwrap()))
+    match uni {
+        Some(uni) => Ok(String::from(uni)),
+        None => Err(SerializeError::InvalidStr),
+    }
+}
+
+fn non_str_bytes(
+    key: *mut crate::ffi::PyObject,
+    opts: crate::opt::Opt,
+) -> Result<String, SerializeError> {
+    let buffer = unsafe {
+        core::slice::from_raw_parts(
+            crate::ffi::PyBytes_AS_STRING(key).cast::<u8>(),
+            isize_to_usize(crate::ffi::PyBytes_GET_SIZE(key)),
+        )
+    };
+    encode_key(buffer, BytesMode::from_opts(opts))
+}
+
+#[cold]
+#[inline(never)]
+fn non_str_ipaddress(key: *mut crate::ffi::PyObject) -> Result<String, SerializeError> {
+    let uni = ffi!(PyObject_Str(key));
+    if uni.is_null() {
+        return Err(SerializeError::IpAddressConversion);
     }
+    let ret = non_str_str(uni);
+    ffi!(Py_DECREF(uni));
+    ret
 }
 
 #[cold]
 #[inline(never)]
 fn non_str_str_subclass(key: *mut crate::ffi::PyObject) -> Result<String, SerializeError> {
     let uni = unsafe { PyStrSubclass::from_ptr_unchecked(key).to_str() };
-    if uni.is_none() {
-        Err(SerializeError::InvalidStr)
-    } else {
-        Ok(String::from(uni.unwrap()))
+    match uni {
+        Some(uni) => Ok(String::from(uni)),
+        None => Err(SerializeError::InvalidStr),
     }
 }
 
@@ -343,13 +596,14 @@ fn non_str_date(key: *mut crate::ffi::PyObject) -> Result<String, SerializeError
 }
 
 #[inline(never)]
-fn non_str_datetime(
-    key: *mut crate::ffi::PyObject,
-    opts: crate::opt::Opt,
-) -> Result<String, SerializeError> {
+fn non_str_datetime(key: *mut crate::ffi::PyObject) -> Result<String, SerializeError> {
     let mut buf = SmallFixedBuffer::new();
-    let dt = DateTime::new(key, opts);
-    if dt.write_buf(&mut buf, opts).is_err() {
+    let format = datetime_format();
+    let dt = DateTime::new(key, format);
+    if dt.is_forbidden_naive() {
+        return Err(SerializeError::DatetimeNaive);
+    }
+    if dt.write_buf(&mut buf, format).is_err() {
         return Err(SerializeError::DatetimeLibraryUnsupported);
     }
     let key_as_str = str_from_slice!(buf.as_ptr(), buf.len());
@@ -365,7 +619,7 @@ fn non_str_time(
     let mut buf = SmallFixedBuffer::new();
     let time = Time::new(key, opts);
     if time.write_buf(&mut buf).is_err() {
-        return Err(SerializeError::TimeHasTzinfo);
+        return Err(SerializeError::TimeInvalidOffset);
     }
     let key_as_str = str_from_slice!(buf.as_ptr(), buf.len());
     Ok(String::from(key_as_str))
@@ -375,7 +629,8 @@ fn non_str_time(
 #[inline(never)]
 fn non_str_uuid(key: *mut crate::ffi::PyObject) -> Result<String, SerializeError> {
     let mut buf = SmallFixedBuffer::new();
-    UUID::new(key).write_buf(&mut buf);
+    // a dict key is a str regardless of OPT_UUID_AS_BYTES
+    UUID::new(key, 0).write_buf(&mut buf);
     let key_as_str = str_from_slice!(buf.as_ptr(), buf.len());
     Ok(String::from(key_as_str))
 }
@@ -392,6 +647,77 @@ fn non_str_float(key: *mut crate::ffi::PyObject) -> Result<String, SerializeErro
     }
 }
 
+/// Whether `formatted`, the shortest representation of the finite `val`
+/// given by `non_str_float()`, is exactly `val`, e.g., `0.5` but not `0.1`.
+/// Both are compared as a product of powers of 2 and 5 and a remainder.
+#[cold]
+#[inline(never)]
+fn float_key_is_exact(val: f64, formatted: &str) -> bool {
+    if val == 0.0 {
+        return true;
+    }
+    // `val` is `mantissa * 2^exp2` with `mantissa` odd
+    let bits = val.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (mut mantissa, mut exp2) = if biased == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), biased - 1075)
+    };
+    let zeros = mantissa.trailing_zeros();
+    mantissa >>= zeros;
+    exp2 += zeros as i32;
+
+    // `formatted` is `digits * 10^exp10`
+    let (significand, exp10) = match formatted.split_once('e') {
+        Some((significand, exp)) => (significand, exp.parse::<i32>().unwrap()),
+        None => (formatted, 0),
+    };
+    let significand = significand.trim_start_matches('-');
+    let (int_part, frac_part) = significand.split_once('.').unwrap_or((significand, ""));
+    let mut digits: u64 = 0;
+    for each in int_part.bytes().chain(frac_part.bytes()) {
+        digits = digits * 10 + u64::from(each - b'0');
+    }
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    let mut exp10 = exp10 - frac_part.len() as i32;
+    while digits % 10 == 0 {
+        digits /= 10;
+        exp10 += 1;
+    }
+
+    let twos = digits.trailing_zeros();
+    digits >>= twos;
+    let mut fives = 0;
+    while digits % 5 == 0 {
+        digits /= 5;
+        fives += 1;
+    }
+    let mut mantissa_fives = 0;
+    while mantissa % 5 == 0 {
+        mantissa /= 5;
+        mantissa_fives += 1;
+    }
+    twos as i32 + exp10 == exp2 && fives + exp10 == mantissa_fives && digits == mantissa
+}
+
+/// Whether two of the keys, sorted if `sorted`, are equal once coerced.
+#[cold]
+#[inline(never)]
+fn has_colliding_keys(
+    items: &SmallVec<[(&str, *mut crate::ffi::PyObject); 8]>,
+    sorted: bool,
+) -> bool {
+    if sorted {
+        items.windows(2).any(|pair| pair[0].0 == pair[1].0)
+    } else {
+        let mut keys: SmallVec<[&str; 8]> = items.iter().map(|(key, _)| *key).collect();
+        keys.sort_unstable();
+        keys.windows(2).any(|pair| pair[0] == pair[1])
+    }
+}
+
 #[allow(clippy::unnecessary_wraps)]
 #[inline(never)]
 fn non_str_int(key: *mut crate::ffi::PyObject) -> Result<String, SerializeError> {
@@ -414,17 +740,138 @@ fn sort_dict_items(items: &mut SmallVec<[(&str, *mut crate::ffi::PyObject); 8]>)
     items.sort_unstable_by(|a, b| a.0.cmp(b.0));
 }
 
+/// A key coerced with `OPT_NON_STR_KEYS`. `int` keys, e.g., of sparse
+/// arrays or JSON Pointer-style data, are formatted inline rather than
+/// allocated or cached.
+enum NonStrKey {
+    Int { buf: [u8; 20], len: u8 },
+    String(String),
+}
+
+impl NonStrKey {
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        match self {
+            NonStrKey::Int { buf, len } => str_from_slice!(buf.as_ptr(), usize::from(*len)),
+            NonStrKey::String(key) => key.as_str(),
+        }
+    }
+
+    #[inline(always)]
+    fn from_int(formatted: &str) -> Self {
+        debug_assert!(formatted.len() <= 20);
+        let mut buf = [0u8; 20];
+        buf[..formatted.len()].copy_from_slice(formatted.as_bytes());
+        #[allow(clippy::cast_possible_truncation)]
+        NonStrKey::Int {
+            buf: buf,
+            len: formatted.len() as u8,
+        }
+    }
+}
+
 pub(crate) struct DictNonStrKey {
     ptr: *mut crate::ffi::PyObject,
     state: SerializerState,
     default: Option<NonNull<crate::ffi::PyObject>>,
+    key_filter: Option<NonNull<crate::ffi::PyObject>>,
 }
 
 impl DictNonStrKey {
+    /// The key of an entry with `OPT_NON_STR_KEYS`: a `str` as is and any
+    /// other supported type coerced.
+    #[inline(always)]
+    fn key_to_string(
+        key: *mut crate::ffi::PyObject,
+        opts: crate::opt::Opt,
+    ) -> Result<NonStrKey, SerializeError> {
+        let ob_type = ob_type!(key);
+        if is_type!(ob_type, crate::typeref::get_str_type()) {
+            match unsafe { PyStr::from_ptr_unchecked(key).to_str() } {
+                Some(uni) => Ok(NonStrKey::String(String::from(uni))),
+                None => Err(SerializeError::InvalidStr),
+            }
+        } else if is_type!(ob_type, crate::typeref::get_int_type()) {
+            Self::int_key(key)
+        } else {
+            let key_as_str = Self::pyobject_to_string_cached(key, opts)?;
+            if opt_enabled!(opts, NON_STR_KEYS_STRICT)
+                && is_type!(ob_type, crate::typeref::get_float_type())
+            {
+                cold_path!();
+                let val = ffi!(PyFloat_AS_DOUBLE(key));
+                if !val.is_finite() || !float_key_is_exact(val, &key_as_str) {
+                    return Err(SerializeError::DictKeyFloatInexact);
+                }
+            }
+            Ok(NonStrKey::String(key_as_str))
+        }
+    }
+
+    /// Format an `int` key in the `i64` or `u64` range directly.
+    #[inline(always)]
+    fn int_key(key: *mut crate::ffi::PyObject) -> Result<NonStrKey, SerializeError> {
+        let ival = ffi!(PyLong_AsLongLong(key));
+        if ival == -1 && !ffi!(PyErr_Occurred()).is_null() {
+            cold_path!();
+            ffi!(PyErr_Clear());
+            let uval = ffi!(PyLong_AsUnsignedLongLong(key));
+            if uval == u64::MAX && !ffi!(PyErr_Occurred()).is_null() {
+                return Err(SerializeError::DictIntegerKey64Bit);
+            }
+            Ok(NonStrKey::from_int(itoa::Buffer::new().format(uval)))
+        } else {
+            Ok(NonStrKey::from_int(itoa::Buffer::new().format(ival)))
+        }
+    }
+
+    /// Coerce `float` and `uuid.UUID` keys using the per-interpreter cache,
+    /// as dicts sharing the same key objects are common.
+    #[cfg(not(Py_GIL_DISABLED))]
+    #[inline(never)]
+    fn pyobject_to_string_cached(
+        key: *mut crate::ffi::PyObject,
+        opts: crate::opt::Opt,
+    ) -> Result<String, SerializeError> {
+        let ob_type = ob_type!(key);
+        if is_type!(ob_type, crate::typeref::get_float_type())
+            || is_type!(ob_type, crate::typeref::get_uuid_type())
+        {
+            let key_map = unsafe {
+                let state = crate::interpreter_state::get_current_state()
+                    .as_ref()
+                    .unwrap();
+                &mut *state.non_str_key_map.get()
+            };
+            if let Some(cached) = key_map.get(&(key as usize)) {
+                return Ok(cached.get());
+            }
+            let key_as_str = Self::pyobject_to_string(key, opts)?;
+            key_map.insert(key as usize, CachedNonStrKey::new(key, key_as_str.clone()));
+            Ok(key_as_str)
+        } else {
+            Self::pyobject_to_string(key, opts)
+        }
+    }
+
+    #[cfg(Py_GIL_DISABLED)]
+    #[inline(always)]
+    fn pyobject_to_string_cached(
+        key: *mut crate::ffi::PyObject,
+        opts: crate::opt::Opt,
+    ) -> Result<String, SerializeError> {
+        Self::pyobject_to_string(key, opts)
+    }
+
     fn pyobject_to_string(
         key: *mut crate::ffi::PyObject,
         opts: crate::opt::Opt,
     ) -> Result<String, SerializeError> {
+        // irrespective of whether `bytes` values are serialized
+        if is_type!(ob_type!(key), crate::typeref::get_bytes_type()) {
+            cold_path!();
+            return non_str_bytes(key, opts);
+        }
         match pyobject_to_obtype(key, opts) {
             ObType::None => Ok(String::from("null")),
             ObType::Bool => {
@@ -436,7 +883,7 @@ impl DictNonStrKey {
             }
             ObType::Int => non_str_int(key),
             ObType::Float => non_str_float(key),
-            ObType::Datetime => non_str_datetime(key, opts),
+            ObType::Datetime => non_str_datetime(key),
             ObType::Date => non_str_date(key),
             ObType::Time => non_str_time(key, opts),
             ObType::Uuid => non_str_uuid(key),
@@ -448,6 +895,7 @@ impl DictNonStrKey {
                 ret
             }
             ObType::Str => non_str_str(key),
+            ObType::IpAddress => non_str_ipaddress(key),
             ObType::StrSubclass => non_str_str_subclass(key),
             ObType::Tuple
             | ObType::NumpyScalar
@@ -456,6 +904,18 @@ impl DictNonStrKey {
             | ObType::List
             | ObType::Dataclass
             | ObType::Fragment
+            | ObType::Bytes
+            | ObType::Mapping
+            | ObType::DictView
+            | ObType::CachedTuple
+            | ObType::JsonDunder
+            | ObType::Fraction
+            | ObType::Range
+            | ObType::Weakref
+            | ObType::Array
+            | ObType::FsPath
+            | ObType::Iterable
+            | ObType::Properties
             | ObType::Unknown => Err(SerializeError::DictKeyInvalidType),
         }
     }
@@ -478,45 +938,57 @@ impl Serialize for DictNonStrKey {
         let len = isize_to_usize(ffi!(Py_SIZE(self.ptr)));
         assume!(len > 0);
 
-        let mut items: SmallVec<[(String, *mut crate::ffi::PyObject); 8]> =
+        let mut items: SmallVec<[(NonStrKey, *mut crate::ffi::PyObject); 8]> =
             SmallVec::with_capacity(len);
 
+        let prefix = skipped_prefix();
+
         for _ in 0..len {
             let key = next_key;
             let value = next_value;
 
             pydict_next!(self.ptr, &mut pos, &mut next_key, &mut next_value);
 
-            if is_type!(ob_type!(key), crate::typeref::get_str_type()) {
-                match unsafe { PyStr::from_ptr_unchecked(key).to_str() } {
-                    Some(uni) => {
-                        items.push((String::from(uni), value));
-                    }
-                    None => err!(SerializeError::InvalidStr),
-                }
-            } else {
-                match Self::pyobject_to_string(key, opts) {
-                    Ok(key_as_str) => items.push((key_as_str, value)),
-                    Err(err) => err!(err),
+            // only `str` keys are compared, not the coerced keys of other types
+            if let Some(prefix) = prefix {
+                if is_type!(ob_type!(key), crate::typeref::get_str_type())
+                    && unsafe { PyStr::from_ptr_unchecked(key).to_str() }
+                        .is_some_and(|key_as_str| key_as_str.as_bytes().starts_with(prefix))
+                {
+                    continue;
                 }
             }
+
+            match Self::key_to_string(key, opts) {
+                Ok(key_as_str) => items.push((key_as_str, value)),
+                Err(err) => err!(err),
+            }
         }
 
         let mut items_as_str: SmallVec<[(&str, *mut crate::ffi::PyObject); 8]> =
             SmallVec::with_capacity(len);
         items
             .iter()
-            .for_each(|(key, val)| items_as_str.push(((*key).as_str(), *val)));
+            .for_each(|(key, val)| items_as_str.push((key.as_str(), *val)));
 
         if opt_enabled!(opts, SORT_KEYS) {
             sort_dict_items(&mut items_as_str);
         }
 
+        if opt_enabled!(opts, NON_STR_KEYS_STRICT)
+            && has_colliding_keys(&items_as_str, opt_enabled!(opts, SORT_KEYS))
+        {
+            err!(SerializeError::DictKeyCollision)
+        }
+
         let mut map = serializer.serialize_map(None).unwrap();
         for (key, val) in items_as_str.iter() {
-            let pyvalue = PyObjectSerializer::new(*val, self.state, self.default);
-            map.serialize_key(key).unwrap();
-            map.serialize_value(&pyvalue)?;
+            if output_exceeded() {
+                cold_path!();
+                err!(SerializeError::OutputExceeded)
+            }
+            let pyvalue = PyObjectSerializer::new(*val, self.state, self.default, self.key_filter);
+            serialize_entry(&mut map, key, &pyvalue)?;
         }
         map.end()
     }
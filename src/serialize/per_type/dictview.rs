@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::serialize::error::SerializeError;
+use crate::serialize::serializer::PyObjectSerializer;
+use crate::util::isize_to_usize;
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// Serializes the views of `dict.keys()` and `dict.values()` as an array of
+/// their elements and that of `dict.items()` as an array of `[key,value]`
+/// arrays, in the order of the `dict`.
+#[repr(transparent)]
+pub(crate) struct DictViewSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> DictViewSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+}
+
+impl Serialize for DictViewSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.previous.state.recursion_limit() {
+            err!(SerializeError::RecursionLimit)
+        }
+        let len = isize_to_usize(ffi!(PyObject_Size(self.previous.ptr)));
+        let iter = ffi!(PyObject_GetIter(self.previous.ptr));
+        debug_assert!(!iter.is_null());
+        let mut seq = serializer.serialize_seq(Some(len)).unwrap();
+        loop {
+            let value = ffi!(PyIter_Next(iter));
+            if value.is_null() {
+                ffi!(Py_DECREF(iter));
+                // e.g., the `dict` changed size, as by `default`
+                if !ffi!(PyErr_Occurred()).is_null() {
+                    err!(SerializeError::Iterator(nonnull!(self.previous.ptr)))
+                }
+                break;
+            }
+            // an item is a `(key, value)` tuple, serialized as an array
+            let ret = seq.serialize_element(&PyObjectSerializer::new(
+                value,
+                self.previous.state.copy_for_recursive_call(),
+                self.previous.default,
+                self.previous.key_filter,
+            ));
+            ffi!(Py_DECREF(value));
+            if let Err(err) = ret {
+                ffi!(Py_DECREF(iter));
+                return Err(err);
+            }
+        }
+        seq.end()
+    }
+}
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::ffi::{
+    Py_DECREF, PyDict_GetItemString, PyErr_Clear, PyImport_GetModuleDict, PyObject_GetAttrString,
+};
+use crate::opt::{FRACTION_AS_FLOAT, Opt};
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::IntSerializer;
+
+use core::ffi::CStr;
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering;
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// Whether `ob_type` is exactly `fractions.Fraction`. It is resolved from
+/// `sys.modules` when first seen, like `zoneinfo.ZoneInfo`, so `fractions`
+/// is never imported.
+#[inline(always)]
+pub(crate) fn is_fraction(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    let fraction_type = crate::typeref::get_fraction_type();
+    if fraction_type.is_null() {
+        resolve_fraction_type(ob_type)
+    } else {
+        core::ptr::eq(fraction_type, ob_type)
+    }
+}
+
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn resolve_fraction_type(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    unsafe {
+        if CStr::from_ptr((*ob_type).tp_name) != c"Fraction" {
+            return false;
+        }
+        let module = PyDict_GetItemString(PyImport_GetModuleDict(), c"fractions".as_ptr());
+        if module.is_null() {
+            return false;
+        }
+        let fraction_type = PyObject_GetAttrString(module, c"Fraction".as_ptr());
+        if fraction_type.is_null() {
+            PyErr_Clear();
+            return false;
+        }
+        if !core::ptr::eq(fraction_type.cast::<crate::ffi::PyTypeObject>(), ob_type) {
+            Py_DECREF(fraction_type);
+            return false;
+        }
+        let state = crate::interpreter_state::get_current_state()
+            .as_ref()
+            .unwrap();
+        if state
+            .fraction_type
+            .compare_exchange(null_mut(), ob_type, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            Py_DECREF(fraction_type);
+        }
+        true
+    }
+}
+
+/// Serializes `fractions.Fraction`, given `OPT_SERIALIZE_FRACTION`, as
+/// `[numerator,denominator]` in lowest terms or, with
+/// `OPT_FRACTION_AS_FLOAT`, as the nearest `float`.
+pub(crate) struct FractionSerializer {
+    ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
+}
+
+impl FractionSerializer {
+    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+        FractionSerializer {
+            ptr: ptr,
+            opts: opts,
+        }
+    }
+}
+
+impl Serialize for FractionSerializer {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if opt_enabled!(self.opts, FRACTION_AS_FLOAT) {
+            self.serialize_float(serializer)
+        } else {
+            self.serialize_pair(serializer)
+        }
+    }
+}
+
+impl FractionSerializer {
+    fn serialize_pair<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(2)).unwrap();
+        // normalized on construction, with the sign on the numerator
+        for name in [c"numerator", c"denominator"] {
+            let value = ffi!(PyObject_GetAttrString(self.ptr, name.as_ptr()));
+            if value.is_null() {
+                err!(SerializeError::FractionConversion)
+            }
+            let ret = seq.serialize_element(&IntSerializer::new(value, self.opts));
+            ffi!(Py_DECREF(value));
+            ret?;
+        }
+        seq.end()
+    }
+
+    fn serialize_float<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // `Fraction.__float__()` rounds correctly, and raises `OverflowError`
+        // if out of range
+        let value = ffi!(PyNumber_Float(self.ptr));
+        if value.is_null() {
+            err!(SerializeError::FractionConversion)
+        }
+        let ret = ffi!(PyFloat_AS_DOUBLE(value));
+        ffi!(Py_DECREF(value));
+        serializer.serialize_f64(ret)
+    }
+}
@@ -2,21 +2,25 @@
 // Copyright ijl (2018-2025)
 
 use crate::ffi::{Fragment, PyBytes_AS_STRING, PyBytes_GET_SIZE};
+use crate::opt::{Opt, VALIDATE_FRAGMENTS};
 use crate::serialize::error::SerializeError;
 use crate::str::PyStr;
 // BYTES_TYPE, STR_TYPE now accessed via typeref accessor functions
 use crate::util::isize_to_usize;
 
-use serde::ser::{Serialize, Serializer};
+use serde::ser::{Error, Serialize, Serializer};
 
-#[repr(transparent)]
 pub(crate) struct FragmentSerializer {
     ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
 }
 
 impl FragmentSerializer {
-    pub fn new(ptr: *mut crate::ffi::PyObject) -> Self {
-        FragmentSerializer { ptr: ptr }
+    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+        FragmentSerializer {
+            ptr: ptr,
+            opts: opts,
+        }
     }
 }
 
@@ -28,24 +32,65 @@ impl Serialize for FragmentSerializer {
     where
         S: Serializer,
     {
-        let buffer: &[u8];
-        unsafe {
-            let fragment: *mut Fragment = self.ptr.cast::<Fragment>();
-            let ob_type = ob_type!((*fragment).contents);
-            if core::ptr::eq(ob_type, crate::typeref::get_bytes_type()) {
-                buffer = core::slice::from_raw_parts(
-                    PyBytes_AS_STRING((*fragment).contents).cast::<u8>(),
-                    isize_to_usize(PyBytes_GET_SIZE((*fragment).contents)),
-                );
-            } else if core::ptr::eq(ob_type, crate::typeref::get_str_type()) {
-                match unsafe { PyStr::from_ptr_unchecked((*fragment).contents).to_str() } {
-                    Some(uni) => buffer = uni.as_bytes(),
-                    None => err!(SerializeError::InvalidStr),
-                }
-            } else {
-                err!(SerializeError::InvalidFragment)
+        let fragment: *mut Fragment = self.ptr.cast::<Fragment>();
+        serialize_raw(
+            unsafe { (*fragment).contents },
+            opt_enabled!(self.opts, VALIDATE_FRAGMENTS),
+            serializer,
+        )
+    }
+}
+
+/// Write `contents`, a `bytes` or `str` of already serialized JSON, to the
+/// output as is. If `validate`, it is first parsed, and raises if it is not
+/// a single valid JSON document.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn serialize_raw<S>(
+    contents: *mut crate::ffi::PyObject,
+    validate: bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let buffer: &[u8];
+    unsafe {
+        let ob_type = ob_type!(contents);
+        if core::ptr::eq(ob_type, crate::typeref::get_bytes_type()) {
+            buffer = core::slice::from_raw_parts(
+                PyBytes_AS_STRING(contents).cast::<u8>(),
+                isize_to_usize(PyBytes_GET_SIZE(contents)),
+            );
+        } else if core::ptr::eq(ob_type, crate::typeref::get_str_type()) {
+            match unsafe { PyStr::from_ptr_unchecked(contents).to_str() } {
+                Some(uni) => buffer = uni.as_bytes(),
+                None => err!(SerializeError::InvalidStr),
             }
+        } else {
+            err!(SerializeError::InvalidFragment)
+        }
+    }
+    if validate {
+        validate_raw(buffer).map_err(S::Error::custom)?;
+    }
+    serializer.serialize_bytes(buffer)
+}
+
+/// Parse `buffer`, for `OPT_VALIDATE_FRAGMENTS`, so that invalid content
+/// raises rather than corrupt the document it would be spliced into.
+#[cold]
+#[inline(never)]
+fn validate_raw(buffer: &[u8]) -> Result<(), String> {
+    match crate::deserialize::deserialize_buf(buffer, 0, None) {
+        Ok(parsed) => {
+            ffi!(Py_DECREF(parsed.as_ptr()));
+            Ok(())
         }
-        serializer.serialize_bytes(buffer)
+        Err(err) => Err(format!(
+            "orjson.Fragment's content is not valid JSON: {} at position {}",
+            err.message, err.pos
+        )),
     }
 }
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::opt::Opt;
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::{StrSerializer, StrSubclassSerializer};
+
+use serde::ser::{Serialize, Serializer};
+
+/// Serializes an `os.PathLike`, i.e., an object with an `__fspath__()`
+/// method, given `OPT_SERIALIZE_FSPATH`, as the `str` it returns. A `bytes`
+/// path is decoded with the filesystem encoding.
+pub(crate) struct FsPathSerializer {
+    ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
+}
+
+impl FsPathSerializer {
+    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+        FsPathSerializer {
+            ptr: ptr,
+            opts: opts,
+        }
+    }
+}
+
+impl Serialize for FsPathSerializer {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // a str or bytes, including a subclass of either, or TypeError
+        let mut path = ffi!(PyOS_FSPath(self.ptr));
+        if path.is_null() {
+            err!(SerializeError::FsPath(nonnull!(self.ptr)))
+        }
+        if is_subclass_by_flag!(tp_flags!(ob_type!(path)), Py_TPFLAGS_BYTES_SUBCLASS) {
+            let uni = unsafe {
+                crate::ffi::PyUnicode_DecodeFSDefaultAndSize(
+                    crate::ffi::PyBytes_AS_STRING(path),
+                    crate::ffi::PyBytes_GET_SIZE(path),
+                )
+            };
+            ffi!(Py_DECREF(path));
+            if uni.is_null() {
+                err!(SerializeError::FsPath(nonnull!(self.ptr)))
+            }
+            path = uni;
+        }
+        let ret = if is_class_by_type!(ob_type!(path), crate::typeref::get_str_type()) {
+            StrSerializer::new(path, self.opts).serialize(serializer)
+        } else {
+            StrSubclassSerializer::new(path, self.opts).serialize(serializer)
+        };
+        ffi!(Py_DECREF(path));
+        ret
+    }
+}
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025)
 
-use crate::opt::{Opt, STRICT_INTEGER};
+use crate::opt::{BIG_NUMBER_AS_STRING, Opt, STRICT_INTEGER};
 use crate::serialize::error::SerializeError;
 use serde::ser::{Serialize, Serializer};
 
@@ -10,6 +10,58 @@ use serde::ser::{Serialize, Serializer};
 const STRICT_INT_MIN: i64 = -9007199254740991;
 const STRICT_INT_MAX: i64 = 9007199254740991;
 
+/// Whether `val` is outside `[-(2**53)+1, (2**53)-1]`, so that it is not
+/// exact if parsed as a double, e.g., by JavaScript.
+#[inline(always)]
+pub(crate) const fn is_big_i64(val: i64) -> bool {
+    val < STRICT_INT_MIN || val > STRICT_INT_MAX
+}
+
+/// Whether `val` is greater than `(2**53)-1`, as `is_big_i64()`.
+#[inline(always)]
+pub(crate) const fn is_big_u64(val: u64) -> bool {
+    val > STRICT_INT_MAX as u64
+}
+
+/// Serialize an integer as a string of its digits, for
+/// `OPT_BIG_NUMBER_AS_STRING`.
+#[cold]
+#[inline(never)]
+pub(crate) fn serialize_big_int<S, I>(serializer: S, val: I) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    I: itoa::Integer,
+{
+    serializer.serialize_str(itoa::Buffer::new().format(val))
+}
+
+/// Serialize an integer that does not fit in 64 bits as a string of its
+/// digits, for `OPT_BIG_NUMBER_AS_STRING`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn serialize_bigger_int<S>(serializer: S, ptr: *mut crate::ffi::PyObject) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    // not `str()`, which a subclass may override
+    let digits = ffi!(PyNumber_ToBase(ptr, 10));
+    if digits.is_null() {
+        // e.g., more digits than `sys.get_int_max_str_digits()`
+        ffi!(PyErr_Clear());
+        err!(SerializeError::Integer64Bits)
+    }
+    // the digits are ASCII
+    let uni = unsafe {
+        crate::str::PyStr::from_ptr_unchecked(digits)
+            .to_str()
+            .unwrap()
+    };
+    let ret = serializer.serialize_str(uni);
+    ffi!(Py_DECREF(digits));
+    ret
+}
+
 pub(crate) struct IntSerializer {
     ptr: *mut crate::ffi::PyObject,
     opts: Opt,
@@ -56,20 +108,29 @@ impl Serialize for IntSerializer {
                     cold_path!();
                     #[cfg(not(Py_3_13))]
                     ffi!(PyErr_Clear());
+                    if opt_enabled!(self.opts, BIG_NUMBER_AS_STRING) {
+                        return serialize_bigger_int(serializer, self.ptr);
+                    }
                     err!(SerializeError::Integer64Bits)
                 }
                 if is_signed == 0 {
                     let val = u64::from_ne_bytes(buffer);
-                    if opt_enabled!(self.opts, STRICT_INTEGER) && val > STRICT_INT_MAX as u64 {
-                        err!(SerializeError::Integer53Bits)
+                    if is_big_u64(val) {
+                        if opt_enabled!(self.opts, BIG_NUMBER_AS_STRING) {
+                            return serialize_big_int(serializer, val);
+                        } else if opt_enabled!(self.opts, STRICT_INTEGER) {
+                            err!(SerializeError::Integer53Bits)
+                        }
                     }
                     serializer.serialize_u64(val)
                 } else {
                     let val = i64::from_ne_bytes(buffer);
-                    if opt_enabled!(self.opts, STRICT_INTEGER)
-                        && !(STRICT_INT_MIN..=STRICT_INT_MAX).contains(&val)
-                    {
-                        err!(SerializeError::Integer53Bits)
+                    if is_big_i64(val) {
+                        if opt_enabled!(self.opts, BIG_NUMBER_AS_STRING) {
+                            return serialize_big_int(serializer, val);
+                        } else if opt_enabled!(self.opts, STRICT_INTEGER) {
+                            err!(SerializeError::Integer53Bits)
+                        }
                     }
                     serializer.serialize_i64(val)
                 }
@@ -88,8 +149,13 @@ impl Serialize for IntSerializer {
                 let val = ffi!(PyLong_AsUnsignedLongLong(self.ptr));
                 if val == u64::MAX && !ffi!(PyErr_Occurred()).is_null() {
                     ffi!(PyErr_Clear());
+                    if opt_enabled!(self.opts, BIG_NUMBER_AS_STRING) {
+                        return serialize_bigger_int(serializer, self.ptr);
+                    }
                     err!(SerializeError::Integer64Bits)
-                } else if opt_enabled!(self.opts, STRICT_INTEGER) && val > STRICT_INT_MAX as u64 {
+                } else if is_big_u64(val) && opt_enabled!(self.opts, BIG_NUMBER_AS_STRING) {
+                    serialize_big_int(serializer, val)
+                } else if is_big_u64(val) && opt_enabled!(self.opts, STRICT_INTEGER) {
                     err!(SerializeError::Integer53Bits)
                 } else {
                     serializer.serialize_u64(val)
@@ -98,10 +164,13 @@ impl Serialize for IntSerializer {
                 let val = ffi!(PyLong_AsLongLong(self.ptr));
                 if val == -1 && !ffi!(PyErr_Occurred()).is_null() {
                     ffi!(PyErr_Clear());
+                    if opt_enabled!(self.opts, BIG_NUMBER_AS_STRING) {
+                        return serialize_bigger_int(serializer, self.ptr);
+                    }
                     err!(SerializeError::Integer64Bits)
-                } else if opt_enabled!(self.opts, STRICT_INTEGER)
-                    && !(STRICT_INT_MIN..=STRICT_INT_MAX).contains(&val)
-                {
+                } else if is_big_i64(val) && opt_enabled!(self.opts, BIG_NUMBER_AS_STRING) {
+                    serialize_big_int(serializer, val)
+                } else if is_big_i64(val) && opt_enabled!(self.opts, STRICT_INTEGER) {
                     err!(SerializeError::Integer53Bits)
                 } else {
                     serializer.serialize_i64(val)
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::ffi::{
+    Py_DECREF, PyDict_GetItemString, PyErr_Clear, PyImport_GetModuleDict, PyObject_GetAttrString,
+};
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::StrSerializer;
+
+use core::ffi::CStr;
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering;
+use serde::ser::{Serialize, Serializer};
+
+const IPADDRESS_TYPE_NAMES: [&CStr; 4] = [
+    c"IPv4Address",
+    c"IPv6Address",
+    c"IPv4Network",
+    c"IPv6Network",
+];
+
+/// Whether `ob_type` is exactly one of the `ipaddress` address or network
+/// types. They are resolved from `sys.modules` when first seen, like
+/// `zoneinfo.ZoneInfo`, so `ipaddress` is never imported.
+#[inline(always)]
+pub(crate) fn is_ipaddress(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    let types = crate::typeref::get_ipaddress_types();
+    if types[0].load(Ordering::Relaxed).is_null() {
+        resolve_ipaddress_types(ob_type)
+    } else {
+        types
+            .iter()
+            .any(|each| core::ptr::eq(each.load(Ordering::Relaxed), ob_type))
+    }
+}
+
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn resolve_ipaddress_types(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    unsafe {
+        let name = CStr::from_ptr((*ob_type).tp_name);
+        if !IPADDRESS_TYPE_NAMES.contains(&name) {
+            return false;
+        }
+        let module = PyDict_GetItemString(PyImport_GetModuleDict(), c"ipaddress".as_ptr());
+        if module.is_null() {
+            return false;
+        }
+        let mut resolved = [null_mut(); 4];
+        for (idx, name) in IPADDRESS_TYPE_NAMES.iter().enumerate() {
+            let typ = PyObject_GetAttrString(module, name.as_ptr());
+            if typ.is_null() {
+                PyErr_Clear();
+                for each in resolved.iter().take(idx) {
+                    Py_DECREF(*each);
+                }
+                return false;
+            }
+            resolved[idx] = typ;
+        }
+        let is_ipaddress = resolved
+            .iter()
+            .any(|each| core::ptr::eq(each.cast::<crate::ffi::PyTypeObject>(), ob_type));
+        let types = &crate::interpreter_state::get_current_state()
+            .as_ref()
+            .unwrap()
+            .ipaddress_types;
+        for (slot, typ) in types.iter().zip(resolved) {
+            if slot
+                .compare_exchange(
+                    null_mut(),
+                    typ.cast::<crate::ffi::PyTypeObject>(),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+            {
+                Py_DECREF(typ);
+            }
+        }
+        is_ipaddress
+    }
+}
+
+/// Serializes `ipaddress.IPv4Address`, `IPv6Address`, `IPv4Network`, and
+/// `IPv6Network`, given `OPT_SERIALIZE_IPADDRESS`, as their `str()`.
+#[repr(transparent)]
+pub(crate) struct IpAddressSerializer {
+    ptr: *mut crate::ffi::PyObject,
+}
+
+impl IpAddressSerializer {
+    pub fn new(ptr: *mut crate::ffi::PyObject) -> Self {
+        IpAddressSerializer { ptr: ptr }
+    }
+}
+
+impl Serialize for IpAddressSerializer {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let uni = ffi!(PyObject_Str(self.ptr));
+        if uni.is_null() {
+            err!(SerializeError::IpAddressConversion)
+        }
+        // always ASCII
+        let ret = StrSerializer::new(uni, 0).serialize(serializer);
+        ffi!(Py_DECREF(uni));
+        ret
+    }
+}
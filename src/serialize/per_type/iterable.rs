@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::serialize::error::SerializeError;
+use crate::serialize::serializer::{PyObjectSerializer, max_output_bytes, output_exceeded};
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// Whether instances of `ob_type` implement the iterator protocol, e.g.,
+/// generators and `map` objects.
+#[inline]
+pub(crate) fn is_iterator(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    unsafe { (*ob_type).tp_iternext.is_some() }
+}
+
+/// Serializes an iterator, given `OPT_SERIALIZE_ITERABLES`, as an array of
+/// its elements, consuming it. Each element is written as it is produced.
+#[repr(transparent)]
+pub(crate) struct IterableSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> IterableSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+}
+
+impl Serialize for IterableSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.previous.state.recursion_limit() {
+            err!(SerializeError::RecursionLimit)
+        }
+        let limit = max_output_bytes();
+        let mut seq = serializer.serialize_seq(None).unwrap();
+        let mut len: usize = 0;
+        loop {
+            if output_exceeded() {
+                cold_path!();
+                err!(SerializeError::OutputExceeded)
+            }
+            let value = ffi!(PyIter_Next(self.previous.ptr));
+            if value.is_null() {
+                if !ffi!(PyErr_Occurred()).is_null() {
+                    err!(SerializeError::Iterator(nonnull!(self.previous.ptr)))
+                }
+                break;
+            }
+            len += 1;
+            // the brackets and, for each element, at least one byte and a
+            // comma, so that an infinite iterator is stopped
+            if let Some(limit) = limit {
+                if len.saturating_mul(2).saturating_add(1) > limit {
+                    ffi!(Py_DECREF(value));
+                    err!(SerializeError::MaxOutputBytes(limit))
+                }
+            }
+            let ret = seq.serialize_element(&PyObjectSerializer::new(
+                value,
+                self.previous.state.copy_for_recursive_call(),
+                self.previous.default,
+                self.previous.key_filter,
+            ));
+            ffi!(Py_DECREF(value));
+            ret?;
+        }
+        seq.end()
+    }
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::serialize_raw;
+use crate::serialize::serializer::PyObjectSerializer;
+
+use serde::ser::{Serialize, Serializer};
+
+/// Serializes an object with a `__json__()` method, given
+/// `OPT_USE_JSON_DUNDER`, by calling it. A `bytes` or `str` it returns is
+/// written as is, like `orjson.Fragment`, and anything else is serialized as
+/// if returned by `default`.
+#[repr(transparent)]
+pub(crate) struct JsonDunderSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> JsonDunderSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+}
+
+impl Serialize for JsonDunderSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.previous.state.default_calls_limit() {
+            cold_path!();
+            err!(SerializeError::DefaultRecursionLimit)
+        }
+        let obj = ffi!(PyObject_CallMethodNoArgs(
+            self.previous.ptr,
+            crate::typeref::get_json_dunder_str()
+        ));
+        if obj.is_null() {
+            err!(SerializeError::JsonDunder(nonnull!(self.previous.ptr)))
+        }
+        let ob_type = ob_type!(obj);
+        let res = if is_class_by_type!(ob_type, crate::typeref::get_bytes_type())
+            || is_class_by_type!(ob_type, crate::typeref::get_str_type())
+        {
+            serialize_raw(obj, false, serializer)
+        } else {
+            PyObjectSerializer::new(
+                obj,
+                self.previous.state.copy_for_default_call(),
+                self.previous.default,
+                self.previous.key_filter,
+            )
+            .serialize(serializer)
+        };
+        ffi!(Py_DECREF(obj));
+        res
+    }
+}
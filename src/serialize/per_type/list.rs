@@ -4,11 +4,14 @@
 use crate::serialize::error::SerializeError;
 use crate::serialize::obtype::{ObType, pyobject_to_obtype};
 use crate::serialize::per_type::{
-    BoolSerializer, DataclassGenericSerializer, Date, DateTime, DefaultSerializer,
-    DictGenericSerializer, EnumSerializer, FloatSerializer, FragmentSerializer, IntSerializer,
-    NoneSerializer, NumpyScalar, NumpySerializer, StrSerializer, StrSubclassSerializer, Time, UUID,
+    ArraySerializer, BoolSerializer, BytesSerializer, CachedTupleSerializer,
+    DataclassGenericSerializer, Date, DateTime, DefaultSerializer, DictGenericSerializer,
+    DictViewSerializer, EnumSerializer, FloatSerializer, FractionSerializer, FragmentSerializer,
+    FsPathSerializer, IntSerializer, IpAddressSerializer, IterableSerializer, JsonDunderSerializer,
+    MappingSerializer, NoneSerializer, NumpyScalar, NumpySerializer, PropertiesSerializer,
+    RangeSerializer, StrSerializer, StrSubclassSerializer, Time, UUID, WeakrefSerializer,
 };
-use crate::serialize::serializer::PyObjectSerializer;
+use crate::serialize::serializer::{PyObjectSerializer, datetime_format, output_exceeded};
 use crate::serialize::state::SerializerState;
 // LIST_TYPE, TUPLE_TYPE now accessed via typeref accessor functions
 use crate::util::isize_to_usize;
@@ -38,6 +41,7 @@ pub(crate) struct ListTupleSerializer {
     data_ptr: *const *mut crate::ffi::PyObject,
     state: SerializerState,
     default: Option<NonNull<crate::ffi::PyObject>>,
+    key_filter: Option<NonNull<crate::ffi::PyObject>>,
     len: usize,
 }
 
@@ -46,6 +50,7 @@ impl ListTupleSerializer {
         ptr: *mut crate::ffi::PyObject,
         state: SerializerState,
         default: Option<NonNull<crate::ffi::PyObject>>,
+        key_filter: Option<NonNull<crate::ffi::PyObject>>,
     ) -> Self {
         debug_assert!(
             is_type!(ob_type!(ptr), crate::typeref::get_list_type())
@@ -58,6 +63,7 @@ impl ListTupleSerializer {
             len: len,
             state: state.copy_for_recursive_call(),
             default: default,
+            key_filter: key_filter,
         }
     }
 
@@ -65,6 +71,7 @@ impl ListTupleSerializer {
         ptr: *mut crate::ffi::PyObject,
         state: SerializerState,
         default: Option<NonNull<crate::ffi::PyObject>>,
+        key_filter: Option<NonNull<crate::ffi::PyObject>>,
     ) -> Self {
         debug_assert!(
             is_type!(ob_type!(ptr), crate::typeref::get_tuple_type())
@@ -77,6 +84,7 @@ impl ListTupleSerializer {
             len: len,
             state: state.copy_for_recursive_call(),
             default: default,
+            key_filter: key_filter,
         }
     }
 }
@@ -94,13 +102,17 @@ impl Serialize for ListTupleSerializer {
         debug_assert!(self.len >= 1);
         let mut seq = serializer.serialize_seq(None).unwrap();
         for idx in 0..self.len {
+            if output_exceeded() {
+                cold_path!();
+                err!(SerializeError::OutputExceeded)
+            }
             let value = unsafe { *((self.data_ptr).add(idx)) };
             match pyobject_to_obtype(value, self.state.opts()) {
                 ObType::Str => {
-                    seq.serialize_element(&StrSerializer::new(value))?;
+                    seq.serialize_element(&StrSerializer::new(value, self.state.opts()))?;
                 }
                 ObType::StrSubclass => {
-                    seq.serialize_element(&StrSubclassSerializer::new(value))?;
+                    seq.serialize_element(&StrSubclassSerializer::new(value, self.state.opts()))?;
                 }
                 ObType::Int => {
                     seq.serialize_element(&IntSerializer::new(value, self.state.opts()))?;
@@ -115,7 +127,7 @@ impl Serialize for ListTupleSerializer {
                     seq.serialize_element(&BoolSerializer::new(value)).unwrap();
                 }
                 ObType::Datetime => {
-                    seq.serialize_element(&DateTime::new(value, self.state.opts()))?;
+                    seq.serialize_element(&DateTime::new(value, datetime_format()))?;
                 }
                 ObType::Date => {
                     seq.serialize_element(&Date::new(value))?;
@@ -124,18 +136,27 @@ impl Serialize for ListTupleSerializer {
                     seq.serialize_element(&Time::new(value, self.state.opts()))?;
                 }
                 ObType::Uuid => {
-                    seq.serialize_element(&UUID::new(value)).unwrap();
+                    seq.serialize_element(&UUID::new(value, self.state.opts()))?;
                 }
                 ObType::Dict => {
-                    let pyvalue = DictGenericSerializer::new(value, self.state, self.default);
+                    let pyvalue = DictGenericSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    );
                     seq.serialize_element(&pyvalue)?;
                 }
                 ObType::List => {
                     if ffi!(Py_SIZE(value)) == 0 {
                         seq.serialize_element(&ZeroListSerializer::new()).unwrap();
                     } else {
-                        let pyvalue =
-                            ListTupleSerializer::from_list(value, self.state, self.default);
+                        let pyvalue = ListTupleSerializer::from_list(
+                            value,
+                            self.state,
+                            self.default,
+                            self.key_filter,
+                        );
                         seq.serialize_element(&pyvalue)?;
                     }
                 }
@@ -143,14 +164,18 @@ impl Serialize for ListTupleSerializer {
                     if ffi!(Py_SIZE(value)) == 0 {
                         seq.serialize_element(&ZeroListSerializer::new()).unwrap();
                     } else {
-                        let pyvalue =
-                            ListTupleSerializer::from_tuple(value, self.state, self.default);
+                        let pyvalue = ListTupleSerializer::from_tuple(
+                            value,
+                            self.state,
+                            self.default,
+                            self.key_filter,
+                        );
                         seq.serialize_element(&pyvalue)?;
                     }
                 }
                 ObType::Dataclass => {
                     seq.serialize_element(&DataclassGenericSerializer::new(
-                        &PyObjectSerializer::new(value, self.state, self.default),
+                        &PyObjectSerializer::new(value, self.state, self.default, self.key_filter),
                     ))?;
                 }
                 ObType::Enum => {
@@ -158,6 +183,7 @@ impl Serialize for ListTupleSerializer {
                         value,
                         self.state,
                         self.default,
+                        self.key_filter,
                     )))?;
                 }
                 ObType::NumpyArray => {
@@ -165,19 +191,100 @@ impl Serialize for ListTupleSerializer {
                         value,
                         self.state,
                         self.default,
+                        self.key_filter,
                     )))?;
                 }
                 ObType::NumpyScalar => {
                     seq.serialize_element(&NumpyScalar::new(value, self.state.opts()))?;
                 }
                 ObType::Fragment => {
-                    seq.serialize_element(&FragmentSerializer::new(value))?;
+                    seq.serialize_element(&FragmentSerializer::new(value, self.state.opts()))?;
+                }
+                ObType::Bytes => {
+                    seq.serialize_element(&BytesSerializer::new(value, self.state.opts()))?;
+                }
+                ObType::Mapping => {
+                    seq.serialize_element(&MappingSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
+                }
+                ObType::DictView => {
+                    seq.serialize_element(&DictViewSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
+                }
+                ObType::CachedTuple => {
+                    seq.serialize_element(&CachedTupleSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
+                }
+                ObType::JsonDunder => {
+                    seq.serialize_element(&JsonDunderSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
+                }
+                ObType::IpAddress => {
+                    seq.serialize_element(&IpAddressSerializer::new(value))?;
+                }
+                ObType::Fraction => {
+                    seq.serialize_element(&FractionSerializer::new(value, self.state.opts()))?;
+                }
+                ObType::Range => {
+                    seq.serialize_element(&RangeSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
+                }
+                ObType::Weakref => {
+                    seq.serialize_element(&WeakrefSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
+                }
+                ObType::Array => {
+                    seq.serialize_element(&ArraySerializer::new(value))?;
+                }
+                ObType::FsPath => {
+                    seq.serialize_element(&FsPathSerializer::new(value, self.state.opts()))?;
+                }
+                ObType::Iterable => {
+                    seq.serialize_element(&IterableSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
+                }
+                ObType::Properties => {
+                    seq.serialize_element(&PropertiesSerializer::new(&PyObjectSerializer::new(
+                        value,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )))?;
                 }
                 ObType::Unknown => {
                     seq.serialize_element(&DefaultSerializer::new(&PyObjectSerializer::new(
                         value,
                         self.state,
                         self.default,
+                        self.key_filter,
                     )))?;
                 }
             }
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::dict::DictGenericSerializer;
+use crate::serialize::serializer::PyObjectSerializer;
+
+use serde::ser::{Serialize, Serializer};
+
+/// Serializes `collections.ChainMap`, `types.MappingProxyType`, and
+/// `types.SimpleNamespace` by way of a `dict` of the same entries, so that
+/// keys are handled, e.g., with `OPT_NON_STR_KEYS` and `OPT_SORT_KEYS`,
+/// exactly as for `dict`.
+#[repr(transparent)]
+pub(crate) struct MappingSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> MappingSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+}
+
+impl Serialize for MappingSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ptr = self.previous.ptr;
+        let dict = if is_type!(ob_type!(ptr), crate::typeref::get_simple_namespace_type()) {
+            ffi!(PyObject_GetAttr(ptr, crate::typeref::get_dict_str()))
+        } else {
+            // a ChainMap's first mapping takes precedence, as on lookup
+            let dict = ffi!(PyDict_New());
+            if !dict.is_null() && ffi!(PyDict_Merge(dict, ptr, 1)) == -1 {
+                ffi!(Py_DECREF(dict));
+                core::ptr::null_mut()
+            } else {
+                dict
+            }
+        };
+        if dict.is_null() {
+            err!(SerializeError::MappingConversion)
+        }
+        let ret = DictGenericSerializer::new(
+            dict,
+            self.previous.state,
+            self.previous.default,
+            self.previous.key_filter,
+        )
+        .serialize(serializer);
+        ffi!(Py_DECREF(dict));
+        ret
+    }
+}
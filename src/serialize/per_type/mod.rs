@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2020-2025), Ben Sully (2021)
 
+mod array;
+mod bytes;
+mod cached_tuple;
 mod dataclass;
 mod datetime;
 mod pybool;
@@ -8,28 +11,51 @@ mod pybool;
 mod datetimelike;
 mod default;
 mod dict;
+mod dictview;
 mod float;
+mod fraction;
 mod fragment;
+mod fspath;
 mod int;
+mod ipaddress;
+mod iterable;
+mod json_dunder;
 mod list;
+mod mapping;
 mod none;
 mod numpy;
+mod properties;
 mod pyenum;
+mod range;
 mod unicode;
 mod uuid;
+mod weakref;
 
+pub(crate) use array::ArraySerializer;
+pub(crate) use bytes::{BytesMode, BytesSerializer, encode_key, serialize_encoded};
+pub(crate) use cached_tuple::CachedTupleSerializer;
 pub(crate) use dataclass::DataclassGenericSerializer;
-pub(crate) use datetime::{Date, DateTime, Time};
-pub(crate) use datetimelike::{DateTimeError, DateTimeLike, Offset};
-pub(crate) use default::DefaultSerializer;
-pub(crate) use dict::DictGenericSerializer;
+pub(crate) use datetime::{Date, DateTime, Time, is_pandas_nat};
+pub(crate) use datetimelike::{DateTimeError, DateTimeFormat, DateTimeLike, Offset};
+pub(crate) use default::{DefaultSerializer, is_callable_sequence, serialize_entry};
+pub(crate) use dict::{DictGenericSerializer, ZeroDictSerializer};
+pub(crate) use dictview::DictViewSerializer;
 pub(crate) use float::FloatSerializer;
-pub(crate) use fragment::FragmentSerializer;
-pub(crate) use int::IntSerializer;
+pub(crate) use fraction::{FractionSerializer, is_fraction};
+pub(crate) use fragment::{FragmentSerializer, serialize_raw};
+pub(crate) use fspath::FsPathSerializer;
+pub(crate) use int::{IntSerializer, is_big_i64, is_big_u64, serialize_big_int};
+pub(crate) use ipaddress::{IpAddressSerializer, is_ipaddress};
+pub(crate) use iterable::{IterableSerializer, is_iterator};
+pub(crate) use json_dunder::JsonDunderSerializer;
 pub(crate) use list::{ListTupleSerializer, ZeroListSerializer};
+pub(crate) use mapping::MappingSerializer;
 pub(crate) use none::NoneSerializer;
 pub(crate) use numpy::{NumpyScalar, NumpySerializer, is_numpy_array, is_numpy_scalar};
+pub(crate) use properties::{PropertiesSerializer, has_properties};
 pub(crate) use pybool::BoolSerializer;
 pub(crate) use pyenum::EnumSerializer;
+pub(crate) use range::RangeSerializer;
 pub(crate) use unicode::{StrSerializer, StrSubclassSerializer};
 pub(crate) use uuid::UUID;
+pub(crate) use weakref::WeakrefSerializer;
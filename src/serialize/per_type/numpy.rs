@@ -2,15 +2,16 @@
 // Copyright ijl (2018-2025), Ben Sully (2021), Nazar Kostetskyi (2022), Aviram Hassan (2020-2021)
 
 use crate::ffi::{Py_intptr_t, Py_ssize_t, PyObject, PyTypeObject};
-use crate::opt::Opt;
+use crate::opt::{BIG_NUMBER_AS_STRING, Opt};
 use crate::serialize::buffer::SmallFixedBuffer;
 use crate::serialize::error::SerializeError;
 use crate::serialize::per_type::{
-    DateTimeError, DateTimeLike, DefaultSerializer, Offset, ZeroListSerializer,
+    DateTimeError, DateTimeFormat, DateTimeLike, DefaultSerializer, NoneSerializer, Offset,
+    ZeroListSerializer, is_big_i64, is_big_u64, serialize_big_int,
 };
-use crate::serialize::serializer::PyObjectSerializer;
+use crate::serialize::serializer::{PyObjectSerializer, datetime_format};
 use crate::str::PyStr;
-use crate::typeref::{ARRAY_STRUCT_STR, DESCR_STR, DTYPE_STR, NUMPY_TYPES, load_numpy_types};
+use crate::typeref::{NUMPY_TYPES, get_numpy_types, load_numpy_types};
 use crate::util::isize_to_usize;
 use core::ffi::{c_char, c_int, c_void};
 use jiff::Timestamp;
@@ -37,17 +38,22 @@ impl Serialize for NumpySerializer<'_> {
     where
         S: Serializer,
     {
-        match NumpyArray::new(self.previous.ptr, self.previous.state.opts()) {
+        let array = if is_masked_array(ob_type!(self.previous.ptr)) {
+            NumpyArray::new_masked(self.previous.ptr, self.previous.state.opts())
+        } else {
+            NumpyArray::new(self.previous.ptr, self.previous.state.opts())
+        };
+        match array {
+            Ok(val) if matches!(val.kind, ItemType::OBJECT) => NumpyObjectArray {
+                array: &val,
+                previous: self.previous,
+            }
+            .serialize(serializer),
             Ok(val) => val.serialize(serializer),
             Err(PyArrayError::Malformed) => err!(SerializeError::NumpyMalformed),
-            Err(PyArrayError::NotContiguous | PyArrayError::UnsupportedDataType)
-                if self.previous.default.is_some() =>
-            {
+            Err(PyArrayError::UnsupportedDataType) if self.previous.default.is_some() => {
                 DefaultSerializer::new(self.previous).serialize(serializer)
             }
-            Err(PyArrayError::NotContiguous) => {
-                err!(SerializeError::NumpyNotCContiguous)
-            }
             Err(PyArrayError::NotNativeEndian) => {
                 err!(SerializeError::NumpyNotNativeEndian)
             }
@@ -66,7 +72,7 @@ macro_rules! slice {
 
 #[cold]
 pub(crate) fn is_numpy_scalar(ob_type: *mut PyTypeObject) -> bool {
-    let numpy_types = unsafe { NUMPY_TYPES.get_or_init(load_numpy_types) };
+    let numpy_types = get_numpy_types();
     if numpy_types.is_none() {
         false
     } else {
@@ -89,15 +95,24 @@ pub(crate) fn is_numpy_scalar(ob_type: *mut PyTypeObject) -> bool {
 
 #[cold]
 pub(crate) fn is_numpy_array(ob_type: *mut PyTypeObject) -> bool {
-    let numpy_types = unsafe { NUMPY_TYPES.get_or_init(load_numpy_types) };
+    let numpy_types = get_numpy_types();
     if numpy_types.is_none() {
         false
     } else {
         let scalar_types = unsafe { numpy_types.unwrap().as_ref() };
-        unsafe { core::ptr::eq(ob_type, scalar_types.array) }
+        core::ptr::eq(ob_type, scalar_types.array)
+            || core::ptr::eq(ob_type, scalar_types.masked_array)
     }
 }
 
+#[cold]
+fn is_masked_array(ob_type: *mut PyTypeObject) -> bool {
+    let numpy_types = get_numpy_types();
+    numpy_types.is_some_and(|numpy_types| unsafe {
+        core::ptr::eq(ob_type, numpy_types.as_ref().masked_array)
+    })
+}
+
 #[repr(C)]
 pub(crate) struct PyCapsule {
     pub ob_refcnt: Py_ssize_t,
@@ -110,7 +125,7 @@ pub(crate) struct PyCapsule {
 
 // https://docs.scipy.org/doc/numpy/reference/arrays.interface.html#c.__array_struct__
 
-const NPY_ARRAY_C_CONTIGUOUS: c_int = 0x1;
+const NPY_ARRAY_ALIGNED: c_int = 0x100;
 const NPY_ARRAY_NOTSWAPPED: c_int = 0x200;
 
 #[repr(C)]
@@ -141,6 +156,7 @@ pub(crate) enum ItemType {
     U16,
     U32,
     U64,
+    OBJECT,
 }
 
 impl ItemType {
@@ -162,6 +178,9 @@ impl ItemType {
             (117, 2) => Some(ItemType::U16),
             (117, 4) => Some(ItemType::U32),
             (117, 8) => Some(ItemType::U64),
+            (079, size) if size as usize == core::mem::size_of::<*mut PyObject>() => {
+                Some(ItemType::OBJECT)
+            }
             _ => None,
         }
     }
@@ -169,7 +188,6 @@ impl ItemType {
 
 pub(crate) enum PyArrayError {
     Malformed,
-    NotContiguous,
     NotNativeEndian,
     UnsupportedDataType,
 }
@@ -189,6 +207,8 @@ pub(crate) struct NumpyArray {
     capsule: *mut PyCapsule,
     kind: ItemType,
     opts: Opt,
+    /// For a `numpy.ma.MaskedArray`, its mask as an array of the same shape.
+    mask: Option<Box<NumpyArray>>,
 }
 
 impl NumpyArray {
@@ -196,7 +216,10 @@ impl NumpyArray {
     #[inline(never)]
     #[cfg_attr(feature = "optimize", optimize(size))]
     pub fn new(ptr: *mut PyObject, opts: Opt) -> Result<Self, PyArrayError> {
-        let capsule = ffi!(PyObject_GetAttr(ptr, ARRAY_STRUCT_STR));
+        let capsule = ffi!(PyObject_GetAttr(
+            ptr,
+            crate::typeref::get_array_struct_str()
+        ));
         debug_assert!(!capsule.is_null());
         let array = unsafe {
             (*capsule.cast::<PyCapsule>())
@@ -207,9 +230,6 @@ impl NumpyArray {
         if unsafe { (*array).two != 2 } {
             ffi!(Py_DECREF(capsule));
             Err(PyArrayError::Malformed)
-        } else if unsafe { (*array).flags } & NPY_ARRAY_C_CONTIGUOUS != NPY_ARRAY_C_CONTIGUOUS {
-            ffi!(Py_DECREF(capsule));
-            Err(PyArrayError::NotContiguous)
         } else if unsafe { (*array).flags } & NPY_ARRAY_NOTSWAPPED != NPY_ARRAY_NOTSWAPPED {
             ffi!(Py_DECREF(capsule));
             Err(PyArrayError::NotNativeEndian)
@@ -235,6 +255,7 @@ impl NumpyArray {
                         capsule: capsule.cast::<PyCapsule>(),
                         kind: kind,
                         opts,
+                        mask: None,
                     };
                     if pyarray.dimensions() > 1 {
                         pyarray.build();
@@ -255,11 +276,54 @@ impl NumpyArray {
             capsule: self.capsule,
             kind: self.kind,
             opts: self.opts,
+            mask: None,
         };
         arr.build();
         arr
     }
 
+    /// A `numpy.ma.MaskedArray`, whose `__array_struct__` is that of its data.
+    /// Its `mask` is either `numpy.ma.nomask` or a `bool` array of the same
+    /// shape, which is held alongside so masked elements serialize as `null`.
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    pub fn new_masked(ptr: *mut PyObject, opts: Opt) -> Result<Self, PyArrayError> {
+        let mut array = NumpyArray::new(ptr, opts)?;
+        let mask_obj = ffi!(PyObject_GetAttrString(ptr, c"mask".as_ptr()));
+        if mask_obj.is_null() {
+            ffi!(PyErr_Clear());
+            return Err(PyArrayError::Malformed);
+        }
+        let mask = if is_numpy_array(ob_type!(mask_obj)) {
+            NumpyArray::new(mask_obj, opts).map(Some)
+        } else {
+            // numpy.ma.nomask
+            Ok(None)
+        };
+        ffi!(Py_DECREF(mask_obj));
+        match mask? {
+            None => Ok(array),
+            Some(mask) => {
+                if !matches!(mask.kind, ItemType::BOOL) || mask.shape() != array.shape() {
+                    return Err(PyArrayError::Malformed);
+                }
+                array.set_mask(mask);
+                Ok(array)
+            }
+        }
+    }
+
+    /// Pair each child with the child of `mask` at the same position.
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn set_mask(&mut self, mut mask: NumpyArray) {
+        let mask_children = core::mem::take(&mut mask.children);
+        for (child, mask_child) in self.children.iter_mut().zip(mask_children) {
+            child.set_mask(mask_child);
+        }
+        self.mask = Some(Box::new(mask));
+    }
+
     #[cfg_attr(feature = "optimize", optimize(size))]
     fn build(&mut self) {
         if self.depth < self.dimensions() - 1 {
@@ -289,6 +353,16 @@ impl NumpyArray {
         unsafe { (*self.array).data.offset(offset) }
     }
 
+    /// Whether the innermost dimension can be read as an aligned slice. It
+    /// is not, e.g., for a transposed array or a slice with a step.
+    #[inline(always)]
+    fn is_contiguous_items(&self) -> bool {
+        unsafe {
+            (*self.array).flags & NPY_ARRAY_ALIGNED == NPY_ARRAY_ALIGNED
+                && self.strides()[self.dimensions() - 1] == (*self.array).itemsize as isize
+        }
+    }
+
     fn num_items(&self) -> usize {
         isize_to_usize(self.shape()[self.shape().len() - 1])
     }
@@ -336,6 +410,13 @@ impl Serialize for NumpyArray {
                 seq.serialize_element(child).unwrap();
             }
             seq.end()
+        } else if self.mask.is_some() || !self.is_contiguous_items() {
+            cold_path!();
+            NumpyStridedItems {
+                array: self,
+                mask: self.mask.as_deref(),
+            }
+            .serialize(serializer)
         } else {
             match self.kind {
                 ItemType::F64 => {
@@ -350,10 +431,11 @@ impl Serialize for NumpyArray {
                     NumpyF16Array::new(slice!(self.data().cast::<u16>(), self.num_items()))
                         .serialize(serializer)
                 }
-                ItemType::U64 => {
-                    NumpyU64Array::new(slice!(self.data().cast::<u64>(), self.num_items()))
-                        .serialize(serializer)
-                }
+                ItemType::U64 => NumpyU64Array::new(
+                    slice!(self.data().cast::<u64>(), self.num_items()),
+                    self.opts,
+                )
+                .serialize(serializer),
                 ItemType::U32 => {
                     NumpyU32Array::new(slice!(self.data().cast::<u32>(), self.num_items()))
                         .serialize(serializer)
@@ -366,10 +448,11 @@ impl Serialize for NumpyArray {
                     NumpyU8Array::new(slice!(self.data().cast::<u8>(), self.num_items()))
                         .serialize(serializer)
                 }
-                ItemType::I64 => {
-                    NumpyI64Array::new(slice!(self.data().cast::<i64>(), self.num_items()))
-                        .serialize(serializer)
-                }
+                ItemType::I64 => NumpyI64Array::new(
+                    slice!(self.data().cast::<i64>(), self.num_items()),
+                    self.opts,
+                )
+                .serialize(serializer),
                 ItemType::I32 => {
                     NumpyI32Array::new(slice!(self.data().cast::<i32>(), self.num_items()))
                         .serialize(serializer)
@@ -389,14 +472,204 @@ impl Serialize for NumpyArray {
                 ItemType::DATETIME64(unit) => NumpyDatetime64Array::new(
                     slice!(self.data().cast::<i64>(), self.num_items()),
                     unit,
-                    self.opts,
+                    datetime_format(),
                 )
                 .serialize(serializer),
+                // serialized by `NumpyObjectArray`, which has `default`
+                ItemType::OBJECT => err!(SerializeError::NumpyUnsupportedDatatype),
             }
         }
     }
 }
 
+/// The element at `idx` of the innermost dimension of `$array`, whose first
+/// element is at `$data`, by its stride.
+macro_rules! strided_item {
+    ($array:expr, $data:expr, $ty:ty, $idx:expr) => {
+        unsafe {
+            core::ptr::read_unaligned(
+                $data
+                    .cast::<u8>()
+                    .offset($array.strides()[$array.dimensions() - 1] * $idx as isize)
+                    .cast::<$ty>(),
+            )
+        }
+    };
+}
+
+macro_rules! serialize_strided {
+    ($seq:expr, $array:expr, $mask:expr, $ty:ty, $item:expr) => {
+        let data = $array.data();
+        let mask_data = $mask.map(NumpyArray::data);
+        for idx in 0..$array.num_items() {
+            let masked = match ($mask, mask_data) {
+                (Some(mask), Some(mask_data)) => strided_item!(mask, mask_data, u8, idx) != 0,
+                _ => false,
+            };
+            if masked {
+                $seq.serialize_element(&NoneSerializer::new())?;
+            } else {
+                $seq.serialize_element(&$item(strided_item!($array, data, $ty, idx))?)?;
+            }
+        }
+    };
+}
+
+/// The innermost dimension of an array that is not contiguous or aligned,
+/// read element by element, or of a masked array, with `null` for masked
+/// elements.
+struct NumpyStridedItems<'a> {
+    array: &'a NumpyArray,
+    mask: Option<&'a NumpyArray>,
+}
+
+impl Serialize for NumpyStridedItems<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(None)?;
+        match self.array.kind {
+            ItemType::F64 => {
+                serialize_strided!(seq, self.array, self.mask, f64, |obj| {
+                    Ok::<_, S::Error>(DataTypeF64 { obj })
+                });
+            }
+            ItemType::F32 => {
+                serialize_strided!(seq, self.array, self.mask, f32, |obj| {
+                    Ok::<_, S::Error>(DataTypeF32 { obj })
+                });
+            }
+            ItemType::F16 => {
+                serialize_strided!(seq, self.array, self.mask, u16, |obj| {
+                    Ok::<_, S::Error>(DataTypeF16 { obj })
+                });
+            }
+            ItemType::U64 => {
+                let big_as_str = opt_enabled!(self.array.opts, BIG_NUMBER_AS_STRING);
+                serialize_strided!(seq, self.array, self.mask, u64, |obj| {
+                    Ok::<_, S::Error>(DataTypeU64 { obj, big_as_str })
+                });
+            }
+            ItemType::U32 => {
+                serialize_strided!(seq, self.array, self.mask, u32, |obj| {
+                    Ok::<_, S::Error>(DataTypeU32 { obj })
+                });
+            }
+            ItemType::U16 => {
+                serialize_strided!(seq, self.array, self.mask, u16, |obj| {
+                    Ok::<_, S::Error>(DataTypeU16 { obj })
+                });
+            }
+            ItemType::U8 => {
+                serialize_strided!(seq, self.array, self.mask, u8, |obj| {
+                    Ok::<_, S::Error>(DataTypeU8 { obj })
+                });
+            }
+            ItemType::I64 => {
+                let big_as_str = opt_enabled!(self.array.opts, BIG_NUMBER_AS_STRING);
+                serialize_strided!(seq, self.array, self.mask, i64, |obj| {
+                    Ok::<_, S::Error>(DataTypeI64 { obj, big_as_str })
+                });
+            }
+            ItemType::I32 => {
+                serialize_strided!(seq, self.array, self.mask, i32, |obj| {
+                    Ok::<_, S::Error>(DataTypeI32 { obj })
+                });
+            }
+            ItemType::I16 => {
+                serialize_strided!(seq, self.array, self.mask, i16, |obj| {
+                    Ok::<_, S::Error>(DataTypeI16 { obj })
+                });
+            }
+            ItemType::I8 => {
+                serialize_strided!(seq, self.array, self.mask, i8, |obj| {
+                    Ok::<_, S::Error>(DataTypeI8 { obj })
+                });
+            }
+            ItemType::BOOL => {
+                serialize_strided!(seq, self.array, self.mask, u8, |obj| {
+                    Ok::<_, S::Error>(DataTypeBool { obj })
+                });
+            }
+            ItemType::DATETIME64(unit) => {
+                let format = datetime_format();
+                serialize_strided!(seq, self.array, self.mask, i64, |obj| {
+                    unit.datetime(obj, format)
+                        .map_err(NumpyDateTimeError::into_serde_err::<S::Error>)
+                });
+            }
+            ItemType::OBJECT => err!(SerializeError::NumpyUnsupportedDatatype),
+        }
+        seq.end()
+    }
+}
+
+/// An array of `dtype=object`, whose items are references to Python objects
+/// serialized as any other object, with the array's shape and mask.
+struct NumpyObjectArray<'a> {
+    array: &'a NumpyArray,
+    previous: &'a PyObjectSerializer,
+}
+
+impl Serialize for NumpyObjectArray<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let array = self.array;
+        if !(array.depth >= array.dimensions() || array.shape()[array.depth] != 0) {
+            return ZeroListSerializer::new().serialize(serializer);
+        }
+        let mut seq = serializer.serialize_seq(None)?;
+        if !array.children.is_empty() {
+            for child in &array.children {
+                seq.serialize_element(&NumpyObjectArray {
+                    array: child,
+                    previous: self.previous,
+                })?;
+            }
+            return seq.end();
+        }
+        let state = self.previous.state.copy_for_recursive_call();
+        let data = array.data();
+        let mask = array.mask.as_deref();
+        let mask_data = mask.map(NumpyArray::data);
+        for idx in 0..array.num_items() {
+            let masked = match (mask, mask_data) {
+                (Some(mask), Some(mask_data)) => strided_item!(mask, mask_data, u8, idx) != 0,
+                _ => false,
+            };
+            if masked {
+                seq.serialize_element(&NoneSerializer::new())?;
+                continue;
+            }
+            let value = strided_item!(array, data, *mut PyObject, idx);
+            if value.is_null() {
+                seq.serialize_element(&NoneSerializer::new())?;
+                continue;
+            }
+            // the array holds a reference, but `default` may replace the item
+            ffi!(Py_INCREF(value));
+            let ret = seq.serialize_element(&PyObjectSerializer::new(
+                value,
+                state,
+                self.previous.default,
+                self.previous.key_filter,
+            ));
+            ffi!(Py_DECREF(value));
+            ret?;
+        }
+        seq.end()
+    }
+}
+
 #[repr(transparent)]
 struct NumpyF64Array<'a> {
     data: &'a [f64],
@@ -522,14 +795,17 @@ impl Serialize for DataTypeF16 {
     }
 }
 
-#[repr(transparent)]
 struct NumpyU64Array<'a> {
     data: &'a [u64],
+    big_as_str: bool,
 }
 
 impl<'a> NumpyU64Array<'a> {
-    fn new(data: &'a [u64]) -> Self {
-        Self { data }
+    fn new(data: &'a [u64], opts: Opt) -> Self {
+        Self {
+            data,
+            big_as_str: opt_enabled!(opts, BIG_NUMBER_AS_STRING),
+        }
     }
 }
 
@@ -542,15 +818,20 @@ impl Serialize for NumpyU64Array<'_> {
     {
         let mut seq = serializer.serialize_seq(None).unwrap();
         for &each in self.data.iter() {
-            seq.serialize_element(&DataTypeU64 { obj: each }).unwrap();
+            seq.serialize_element(&DataTypeU64 {
+                obj: each,
+                big_as_str: self.big_as_str,
+            })?;
         }
         seq.end()
     }
 }
 
-#[repr(transparent)]
+/// An element of an array, as a string if `big_as_str`, given
+/// `OPT_BIG_NUMBER_AS_STRING`, and outside `[-(2**53)+1, (2**53)-1]`.
 pub(crate) struct DataTypeU64 {
     obj: u64,
+    big_as_str: bool,
 }
 
 impl Serialize for DataTypeU64 {
@@ -559,6 +840,9 @@ impl Serialize for DataTypeU64 {
     where
         S: Serializer,
     {
+        if self.big_as_str && is_big_u64(self.obj) {
+            return serialize_big_int(serializer, self.obj);
+        }
         serializer.serialize_u64(self.obj)
     }
 }
@@ -645,14 +929,17 @@ impl Serialize for DataTypeU16 {
     }
 }
 
-#[repr(transparent)]
 struct NumpyI64Array<'a> {
     data: &'a [i64],
+    big_as_str: bool,
 }
 
 impl<'a> NumpyI64Array<'a> {
-    fn new(data: &'a [i64]) -> Self {
-        Self { data }
+    fn new(data: &'a [i64], opts: Opt) -> Self {
+        Self {
+            data,
+            big_as_str: opt_enabled!(opts, BIG_NUMBER_AS_STRING),
+        }
     }
 }
 
@@ -665,15 +952,20 @@ impl Serialize for NumpyI64Array<'_> {
     {
         let mut seq = serializer.serialize_seq(None).unwrap();
         for &each in self.data.iter() {
-            seq.serialize_element(&DataTypeI64 { obj: each }).unwrap();
+            seq.serialize_element(&DataTypeI64 {
+                obj: each,
+                big_as_str: self.big_as_str,
+            })?;
         }
         seq.end()
     }
 }
 
-#[repr(transparent)]
+/// An element of an array, as a string if `big_as_str`, given
+/// `OPT_BIG_NUMBER_AS_STRING`, and outside `[-(2**53)+1, (2**53)-1]`.
 pub(crate) struct DataTypeI64 {
     obj: i64,
+    big_as_str: bool,
 }
 
 impl Serialize for DataTypeI64 {
@@ -682,6 +974,9 @@ impl Serialize for DataTypeI64 {
     where
         S: Serializer,
     {
+        if self.big_as_str && is_big_i64(self.obj) {
+            return serialize_big_int(serializer, self.obj);
+        }
         serializer.serialize_i64(self.obj)
     }
 }
@@ -920,7 +1215,11 @@ impl Serialize for NumpyScalar {
             } else if core::ptr::eq(ob_type, scalar_types.float16) {
                 (*(self.ptr.cast::<NumpyFloat16>())).serialize(serializer)
             } else if core::ptr::eq(ob_type, scalar_types.int64) {
-                (*(self.ptr.cast::<NumpyInt64>())).serialize(serializer)
+                DataTypeI64 {
+                    obj: (*(self.ptr.cast::<NumpyInt64>())).value,
+                    big_as_str: opt_enabled!(self.opts, BIG_NUMBER_AS_STRING),
+                }
+                .serialize(serializer)
             } else if core::ptr::eq(ob_type, scalar_types.int32) {
                 (*(self.ptr.cast::<NumpyInt32>())).serialize(serializer)
             } else if core::ptr::eq(ob_type, scalar_types.int16) {
@@ -928,7 +1227,11 @@ impl Serialize for NumpyScalar {
             } else if core::ptr::eq(ob_type, scalar_types.int8) {
                 (*(self.ptr.cast::<NumpyInt8>())).serialize(serializer)
             } else if core::ptr::eq(ob_type, scalar_types.uint64) {
-                (*(self.ptr.cast::<NumpyUint64>())).serialize(serializer)
+                DataTypeU64 {
+                    obj: (*(self.ptr.cast::<NumpyUint64>())).value,
+                    big_as_str: opt_enabled!(self.opts, BIG_NUMBER_AS_STRING),
+                }
+                .serialize(serializer)
             } else if core::ptr::eq(ob_type, scalar_types.uint32) {
                 (*(self.ptr.cast::<NumpyUint32>())).serialize(serializer)
             } else if core::ptr::eq(ob_type, scalar_types.uint16) {
@@ -941,7 +1244,7 @@ impl Serialize for NumpyScalar {
                 let unit = NumpyDatetimeUnit::from_pyobject(self.ptr);
                 let obj = &*self.ptr.cast::<NumpyDatetime64>();
                 let dt = unit
-                    .datetime(obj.value, self.opts)
+                    .datetime(obj.value, datetime_format())
                     .map_err(NumpyDateTimeError::into_serde_err)?;
                 dt.serialize(serializer)
             } else {
@@ -1009,16 +1312,6 @@ pub(crate) struct NumpyInt64 {
     value: i64,
 }
 
-impl Serialize for NumpyInt64 {
-    #[cold]
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_i64(self.value)
-    }
-}
-
 #[repr(C)]
 pub(crate) struct NumpyUint8 {
     ob_refcnt: Py_ssize_t,
@@ -1077,16 +1370,6 @@ pub(crate) struct NumpyUint64 {
     value: u64,
 }
 
-impl Serialize for NumpyUint64 {
-    #[cold]
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_u64(self.value)
-    }
-}
-
 #[repr(C)]
 pub(crate) struct NumpyFloat16 {
     ob_refcnt: Py_ssize_t,
@@ -1139,11 +1422,13 @@ impl Serialize for NumpyFloat64 {
     }
 }
 
+/// `numpy.bool_`, whose value is an `npy_bool`, a byte, rather than a Rust
+/// `bool`, which must be 0 or 1 to be read.
 #[repr(C)]
 pub(crate) struct NumpyBool {
     ob_refcnt: Py_ssize_t,
     ob_type: *mut PyTypeObject,
-    value: bool,
+    value: u8,
 }
 
 impl Serialize for NumpyBool {
@@ -1152,10 +1437,14 @@ impl Serialize for NumpyBool {
     where
         S: Serializer,
     {
-        serializer.serialize_bool(self.value)
+        // written as `BoolSerializer` writes `True` and `False`
+        serializer.serialize_bool(self.value != 0)
     }
 }
 
+/// The value of `numpy.datetime64("NaT")`, `NPY_DATETIME_NAT`.
+const NUMPY_DATETIME64_NAT: i64 = i64::MIN;
+
 /// This mimicks the units supported by numpy's datetime64 type.
 ///
 /// See
@@ -1248,8 +1537,8 @@ impl NumpyDatetimeUnit {
     #[cold]
     #[cfg_attr(feature = "optimize", optimize(size))]
     fn from_pyobject(ptr: *mut PyObject) -> Self {
-        let dtype = ffi!(PyObject_GetAttr(ptr, DTYPE_STR));
-        let descr = ffi!(PyObject_GetAttr(dtype, DESCR_STR));
+        let dtype = ffi!(PyObject_GetAttr(ptr, crate::typeref::get_dtype_str()));
+        let descr = ffi!(PyObject_GetAttr(dtype, crate::typeref::get_descr_str()));
         let el0 = ffi!(PyList_GET_ITEM(descr, 0));
         let descr_str = ffi!(PyTuple_GET_ITEM(el0, 1));
         let uni = unsafe { PyStr::from_ptr_unchecked(descr_str).to_str().unwrap() };
@@ -1280,12 +1569,22 @@ impl NumpyDatetimeUnit {
         ret
     }
 
-    /// Return a `NumpyDatetime64Repr` for a value in array with this unit.
+    /// Return a `NumpyDatetime64Repr` for a value in array with this unit, or
+    /// `None`, serialized as `null`, for `NaT`.
     ///
     /// Returns an `Err(NumpyDateTimeError)` if the value is invalid for this unit.
     #[cold]
     #[cfg_attr(feature = "optimize", optimize(size))]
-    fn datetime(self, val: i64, opts: Opt) -> Result<NumpyDatetime64Repr, NumpyDateTimeError> {
+    fn datetime(
+        self,
+        val: i64,
+        format: DateTimeFormat,
+    ) -> Result<Option<NumpyDatetime64Repr>, NumpyDateTimeError> {
+        // `NaT` is the same value in every unit, including generic, and must
+        // be checked before it is converted as a number of the unit
+        if val == NUMPY_DATETIME64_NAT {
+            return Ok(None);
+        }
         match self {
             Self::Years => Ok(DateTime::new(
                 (val + 1970)
@@ -1327,19 +1626,19 @@ impl NumpyDatetimeUnit {
             }
             _ => Err(NumpyDateTimeError::UnsupportedUnit(self)),
         }
-        .map(|dt| NumpyDatetime64Repr { dt, opts })
+        .map(|dt| Some(NumpyDatetime64Repr { dt, format }))
     }
 }
 
 struct NumpyDatetime64Array<'a> {
     data: &'a [i64],
     unit: NumpyDatetimeUnit,
-    opts: Opt,
+    format: DateTimeFormat,
 }
 
 impl<'a> NumpyDatetime64Array<'a> {
-    fn new(data: &'a [i64], unit: NumpyDatetimeUnit, opts: Opt) -> Self {
-        Self { data, unit, opts }
+    fn new(data: &'a [i64], unit: NumpyDatetimeUnit, format: DateTimeFormat) -> Self {
+        Self { data, unit, format }
     }
 }
 
@@ -1353,7 +1652,7 @@ impl Serialize for NumpyDatetime64Array<'_> {
         for &each in self.data.iter() {
             let dt = self
                 .unit
-                .datetime(each, self.opts)
+                .datetime(each, self.format)
                 .map_err(NumpyDateTimeError::into_serde_err)?;
             seq.serialize_element(&dt).unwrap();
         }
@@ -1381,7 +1680,7 @@ macro_rules! forward_inner {
 
 struct NumpyDatetime64Repr {
     dt: DateTime,
-    opts: Opt,
+    format: DateTimeFormat,
 }
 
 impl DateTimeLike for NumpyDatetime64Repr {
@@ -1423,7 +1722,7 @@ impl Serialize for NumpyDatetime64Repr {
         S: Serializer,
     {
         let mut buf = SmallFixedBuffer::new();
-        let _ = self.write_buf(&mut buf, self.opts);
+        let _ = self.write_buf(&mut buf, self.format);
         serializer.collect_str(str_from_slice!(buf.as_ptr(), buf.len()))
     }
 }
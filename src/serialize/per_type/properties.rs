@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::opt::SKIP_UNSERIALIZABLE;
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::default::skip;
+use crate::serialize::per_type::dict::DictGenericSerializer;
+use crate::serialize::serializer::PyObjectSerializer;
+use crate::str::PyStr;
+
+use serde::ser::{Serialize, Serializer};
+
+/// The types of the MRO of `ob_type` other than `object`, from the type
+/// itself to its furthest base.
+#[inline(always)]
+fn mro_types(ob_type: *mut crate::ffi::PyTypeObject) -> Vec<*mut crate::ffi::PyTypeObject> {
+    let mro = unsafe { (*ob_type).tp_mro };
+    if mro.is_null() {
+        cold_path!();
+        return Vec::new();
+    }
+    (0..ffi!(Py_SIZE(mro)) - 1)
+        .map(|idx| ffi!(PyTuple_GET_ITEM(mro, idx)).cast::<crate::ffi::PyTypeObject>())
+        .collect()
+}
+
+/// Whether `value` is a `property`, including a subclass of it.
+#[inline(always)]
+fn is_property(value: *mut crate::ffi::PyObject) -> bool {
+    let ob_type = ob_type!(value);
+    is_class_by_type!(ob_type, crate::typeref::get_property_type())
+        || ffi!(PyType_IsSubtype(
+            ob_type,
+            crate::typeref::get_property_type()
+        )) == 1
+}
+
+/// Whether `ob_type` or a base class of it other than `object` defines a
+/// `property`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn has_properties(ob_type: *mut crate::ffi::PyTypeObject) -> bool {
+    mro_types(ob_type).iter().any(|base| {
+        let base_dict = unsafe { (**base).tp_dict };
+        if base_dict.is_null() {
+            return false;
+        }
+        let mut pos = 0;
+        let mut key: *mut crate::ffi::PyObject = core::ptr::null_mut();
+        let mut value: *mut crate::ffi::PyObject = core::ptr::null_mut();
+        while ffi!(PyDict_Next(base_dict, &mut pos, &mut key, &mut value)) != 0 {
+            if is_property(value) {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// The names of the properties of `ob_type` that are serialized, as new
+/// references, from the furthest base class to the type itself. A property
+/// overridden by a subclass is named where the subclass defines it, and one
+/// whose name starts with an underscore is omitted.
+fn property_names(ob_type: *mut crate::ffi::PyTypeObject) -> Vec<*mut crate::ffi::PyObject> {
+    let types = mro_types(ob_type);
+    let mut names = Vec::new();
+    for (idx, base) in types.iter().enumerate().rev() {
+        let base_dict = unsafe { (**base).tp_dict };
+        if base_dict.is_null() {
+            continue;
+        }
+        let mut pos = 0;
+        let mut key: *mut crate::ffi::PyObject = core::ptr::null_mut();
+        let mut value: *mut crate::ffi::PyObject = core::ptr::null_mut();
+        while ffi!(PyDict_Next(base_dict, &mut pos, &mut key, &mut value)) != 0 {
+            if !is_property(value)
+                || !is_class_by_type!(ob_type!(key), crate::typeref::get_str_type())
+            {
+                continue;
+            }
+            let name = unsafe { PyStr::from_ptr_unchecked(key).to_str() };
+            if name.is_none_or(|name| name.starts_with('_')) {
+                continue;
+            }
+            let shadowed = types[..idx].iter().any(|derived| {
+                let derived_dict = unsafe { (**derived).tp_dict };
+                !derived_dict.is_null() && ffi!(PyDict_Contains(derived_dict, key)) == 1
+            });
+            if !shadowed {
+                ffi!(Py_INCREF(key));
+                names.push(key);
+            }
+        }
+    }
+    names
+}
+
+/// Serializes an object whose type defines a `property`, given
+/// `OPT_SERIALIZE_PROPERTIES`, as an object of the name of each property to
+/// its value. A property that raises fails serialization or, with
+/// `OPT_SKIP_UNSERIALIZABLE`, is omitted. The entries are serialized by way of
+/// a `dict`, so that, e.g., `OPT_SORT_KEYS` applies as for `dict`.
+#[repr(transparent)]
+pub(crate) struct PropertiesSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> PropertiesSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+
+    /// A `dict` of each of `names` to the value of the property, as a new
+    /// reference.
+    fn to_dict(
+        &self,
+        names: &[*mut crate::ffi::PyObject],
+    ) -> Result<*mut crate::ffi::PyObject, SerializeError> {
+        let ptr = self.previous.ptr;
+        let dict = ffi!(PyDict_New());
+        for &name in names {
+            let value = ffi!(PyObject_GetAttr(ptr, name));
+            if value.is_null() {
+                let err = SerializeError::Property(nonnull!(ptr), nonnull!(name));
+                let res = if opt_disabled!(self.previous.state.opts(), SKIP_UNSERIALIZABLE) {
+                    Err(err)
+                } else {
+                    skip(ptr, &err)
+                };
+                if let Err(err) = res {
+                    ffi!(Py_DECREF(dict));
+                    return Err(err);
+                }
+                continue;
+            }
+            ffi!(PyDict_SetItem(dict, name, value));
+            ffi!(Py_DECREF(value));
+        }
+        Ok(dict)
+    }
+}
+
+impl Serialize for PropertiesSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let names = property_names(ob_type!(self.previous.ptr));
+        // the error is formatted while the names are referenced
+        let ret = match self.to_dict(&names) {
+            Ok(dict) => {
+                let ret = DictGenericSerializer::new(
+                    dict,
+                    self.previous.state,
+                    self.previous.default,
+                    self.previous.key_filter,
+                )
+                .serialize(serializer);
+                ffi!(Py_DECREF(dict));
+                ret
+            }
+            Err(err) => Err(serde::ser::Error::custom(err)),
+        };
+        for name in names {
+            ffi!(Py_DECREF(name));
+        }
+        ret
+    }
+}
@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025)
 
+use crate::serialize::fp::PendingException;
 use crate::serialize::serializer::PyObjectSerializer;
+use crate::str::PyStr;
+use core::ffi::CStr;
 // VALUE_STR now accessed via typeref::get_value_str()
 use serde::ser::{Serialize, Serializer};
 
@@ -24,9 +27,40 @@ impl Serialize for EnumSerializer<'_> {
     {
         let value = ffi!(PyObject_GetAttr(self.previous.ptr, crate::typeref::get_value_str()));
         debug_assert!(ffi!(Py_REFCNT(value)) >= 2);
-        let ret = PyObjectSerializer::new(value, self.previous.state, self.previous.default)
-            .serialize(serializer);
+        let ret = PyObjectSerializer::new(
+            value,
+            self.previous.state,
+            self.previous.default,
+            self.previous.key_filter,
+        )
+        .serialize(serializer)
+        .map_err(|err| member_error(self.previous.ptr, &err));
         ffi!(Py_DECREF(value));
         ret
     }
 }
+
+/// The error of serializing the value of an enum member, naming the member,
+/// e.g., `Color.RED`, as the value may be nested in it.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn member_error<E: serde::ser::Error>(member: *mut crate::ffi::PyObject, err: &E) -> E {
+    let type_name = unsafe { CStr::from_ptr((*ob_type!(member)).tp_name).to_string_lossy() };
+    let pending = (!ffi!(PyErr_Occurred()).is_null()).then(PendingException::fetch);
+    let name = ffi!(PyObject_GetAttrString(member, c"_name_".as_ptr()));
+    let mut member_name = None;
+    if !name.is_null() && is_type!(ob_type!(name), crate::typeref::get_str_type()) {
+        member_name = unsafe { PyStr::from_ptr_unchecked(name).to_str() };
+    }
+    ffi!(PyErr_Clear());
+    if let Some(pending) = pending {
+        pending.restore();
+    }
+    let ret = E::custom(format_args!(
+        "{err} (in value of enum member {type_name}.{})",
+        member_name.unwrap_or("?")
+    ));
+    ffi!(Py_XDECREF(name));
+    ret
+}
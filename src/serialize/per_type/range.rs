@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::opt::RANGE_AS_LIST;
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::IntSerializer;
+use crate::serialize::serializer::{PyObjectSerializer, max_output_bytes};
+use crate::util::isize_to_usize;
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// Serializes `range`, given `OPT_SERIALIZE_RANGE`, as `[start,stop,step]`
+/// or, with `OPT_RANGE_AS_LIST`, as a list of its elements.
+#[repr(transparent)]
+pub(crate) struct RangeSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> RangeSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+}
+
+impl Serialize for RangeSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if opt_enabled!(self.previous.state.opts(), RANGE_AS_LIST) {
+            self.serialize_elements(serializer)
+        } else {
+            self.serialize_triple(serializer)
+        }
+    }
+}
+
+impl RangeSerializer<'_> {
+    fn serialize_triple<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let opts = self.previous.state.opts();
+        let mut seq = serializer.serialize_seq(Some(3)).unwrap();
+        for name in [c"start", c"stop", c"step"] {
+            let value = ffi!(PyObject_GetAttrString(self.previous.ptr, name.as_ptr()));
+            debug_assert!(!value.is_null());
+            let ret = seq.serialize_element(&IntSerializer::new(value, opts));
+            ffi!(Py_DECREF(value));
+            ret?;
+        }
+        seq.end()
+    }
+
+    fn serialize_elements<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = ffi!(PyObject_Size(self.previous.ptr));
+        if len < 0 {
+            // longer than `sys.maxsize`
+            ffi!(PyErr_Clear());
+            err!(SerializeError::RangeTooLarge)
+        }
+        let len = isize_to_usize(len);
+        // the brackets and, for each element, at least a digit and a comma
+        if let Some(limit) = max_output_bytes() {
+            if len.saturating_mul(2).saturating_add(1) > limit {
+                err!(SerializeError::MaxOutputBytes(limit))
+            }
+        }
+        let opts = self.previous.state.opts();
+        let iter = ffi!(PyObject_GetIter(self.previous.ptr));
+        debug_assert!(!iter.is_null());
+        let mut seq = serializer.serialize_seq(Some(len)).unwrap();
+        loop {
+            let value = ffi!(PyIter_Next(iter));
+            if value.is_null() {
+                break;
+            }
+            let ret = seq.serialize_element(&IntSerializer::new(value, opts));
+            ffi!(Py_DECREF(value));
+            if let Err(err) = ret {
+                ffi!(Py_DECREF(iter));
+                return Err(err);
+            }
+        }
+        ffi!(Py_DECREF(iter));
+        seq.end()
+    }
+}
@@ -1,19 +1,25 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025)
 
+use crate::opt::{Opt, SURROGATES_ESCAPE};
 use crate::serialize::error::SerializeError;
+use crate::serialize::writer::{StrEscape, format_escaped_code_points};
 use crate::str::{PyStr, PyStrSubclass};
+use crate::util::{isize_to_usize, usize_to_isize};
 
 use serde::ser::{Serialize, Serializer};
 
-#[repr(transparent)]
 pub(crate) struct StrSerializer {
     ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
 }
 
 impl StrSerializer {
-    pub fn new(ptr: *mut crate::ffi::PyObject) -> Self {
-        StrSerializer { ptr: ptr }
+    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+        StrSerializer {
+            ptr: ptr,
+            opts: opts,
+        }
     }
 }
 
@@ -25,19 +31,22 @@ impl Serialize for StrSerializer {
     {
         match unsafe { PyStr::from_ptr_unchecked(self.ptr).to_str() } {
             Some(uni) => serializer.serialize_str(uni),
-            None => err!(SerializeError::InvalidStr),
+            None => serialize_surrogates(self.ptr, self.opts, serializer),
         }
     }
 }
 
-#[repr(transparent)]
 pub(crate) struct StrSubclassSerializer {
     ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
 }
 
 impl StrSubclassSerializer {
-    pub fn new(ptr: *mut crate::ffi::PyObject) -> Self {
-        StrSubclassSerializer { ptr: ptr }
+    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+        StrSubclassSerializer {
+            ptr: ptr,
+            opts: opts,
+        }
     }
 }
 
@@ -49,7 +58,38 @@ impl Serialize for StrSubclassSerializer {
     {
         match unsafe { PyStrSubclass::from_ptr_unchecked(self.ptr).to_str() } {
             Some(uni) => serializer.serialize_str(uni),
-            None => err!(SerializeError::InvalidStr),
+            None => serialize_surrogates(self.ptr, self.opts, serializer),
         }
     }
 }
+
+/// A `str` that is not valid UTF-8 because it contains surrogates raises
+/// unless `OPT_SURROGATES_ESCAPE`, in which case each is escaped as `\uXXXX`.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn serialize_surrogates<S>(
+    ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if opt_disabled!(opts, SURROGATES_ESCAPE) {
+        err!(SerializeError::InvalidStr)
+    }
+    // the UnicodeEncodeError of encoding to UTF-8
+    ffi!(PyErr_Clear());
+    let len = isize_to_usize(ffi!(PyUnicode_GetLength(ptr)));
+    let mut buf: Vec<u8> = Vec::with_capacity(len * 12 + 2);
+    unsafe {
+        let written = format_escaped_code_points(
+            buf.as_mut_ptr(),
+            (0..len).map(|idx| ffi!(PyUnicode_ReadChar(ptr, usize_to_isize(idx)))),
+            StrEscape::new(opts),
+        );
+        buf.set_len(written);
+    }
+    serializer.serialize_bytes(&buf)
+}
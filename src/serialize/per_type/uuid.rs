@@ -1,19 +1,46 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025)
 
+use crate::opt::{Opt, UUID_AS_BYTES};
 use crate::serialize::buffer::SmallFixedBuffer;
+use crate::serialize::per_type::{BytesMode, serialize_encoded};
 // INT_ATTR_STR now accessed via typeref::get_int_attr_str()
 use core::ffi::c_uchar;
 use serde::ser::{Serialize, Serializer};
 
-#[repr(transparent)]
 pub(crate) struct UUID {
     ptr: *mut crate::ffi::PyObject,
+    opts: Opt,
 }
 
 impl UUID {
-    pub fn new(ptr: *mut crate::ffi::PyObject) -> Self {
-        UUID { ptr: ptr }
+    pub fn new(ptr: *mut crate::ffi::PyObject, opts: Opt) -> Self {
+        UUID {
+            ptr: ptr,
+            opts: opts,
+        }
+    }
+
+    /// The value of `uuid.UUID.int`.
+    fn value(&self) -> u128 {
+        // test_uuid_immutable, test_uuid_int
+        let py_int = ffi!(PyObject_GetAttr(
+            self.ptr,
+            crate::typeref::get_int_attr_str()
+        ));
+        ffi!(Py_DECREF(py_int));
+        let mut buffer: [c_uchar; 16] = [0; 16];
+        unsafe {
+            // test_uuid_overflow
+            crate::ffi::PyLong_AsByteArray(
+                py_int.cast::<crate::ffi::PyLongObject>(),
+                buffer.as_mut_ptr(),
+                16,
+                1, // little_endian
+                0, // is_signed
+            );
+        };
+        u128::from_le_bytes(buffer)
     }
 
     #[inline(never)]
@@ -21,24 +48,7 @@ impl UUID {
     where
         B: bytes::BufMut,
     {
-        let value: u128;
-        {
-            // test_uuid_immutable, test_uuid_int
-            let py_int = ffi!(PyObject_GetAttr(self.ptr, crate::typeref::get_int_attr_str()));
-            ffi!(Py_DECREF(py_int));
-            let mut buffer: [c_uchar; 16] = [0; 16];
-            unsafe {
-                // test_uuid_overflow
-                crate::ffi::PyLong_AsByteArray(
-                    py_int.cast::<crate::ffi::PyLongObject>(),
-                    buffer.as_mut_ptr(),
-                    16,
-                    1, // little_endian
-                    0, // is_signed
-                );
-            };
-            value = u128::from_le_bytes(buffer);
-        }
+        let value = self.value();
         unsafe {
             let buffer_length: usize = 40;
             debug_assert!(buf.remaining_mut() >= buffer_length);
@@ -59,6 +69,12 @@ impl Serialize for UUID {
     where
         S: Serializer,
     {
+        if opt_enabled!(self.opts, UUID_AS_BYTES) {
+            cold_path!();
+            // as uuid.UUID.bytes, big-endian
+            let bytes = self.value().to_be_bytes();
+            return serialize_encoded(&bytes, BytesMode::from_opts(self.opts), serializer);
+        }
         let mut buf = SmallFixedBuffer::new();
         self.write_buf(&mut buf);
         serializer.serialize_unit_struct(str_from_slice!(buf.as_ptr(), buf.len()))
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::opt::WEAKREF_DEAD_AS_NULL;
+use crate::serialize::error::SerializeError;
+use crate::serialize::per_type::NoneSerializer;
+use crate::serialize::serializer::PyObjectSerializer;
+
+use serde::ser::{Serialize, Serializer};
+
+/// Serializes `weakref.ref`, `weakref.proxy`, and callable proxies as their
+/// referent. A dead reference raises or, with `OPT_WEAKREF_DEAD_AS_NULL`, is
+/// `null`.
+#[repr(transparent)]
+pub(crate) struct WeakrefSerializer<'a> {
+    previous: &'a PyObjectSerializer,
+}
+
+impl<'a> WeakrefSerializer<'a> {
+    pub fn new(previous: &'a PyObjectSerializer) -> Self {
+        Self { previous: previous }
+    }
+}
+
+impl Serialize for WeakrefSerializer<'_> {
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(feature = "optimize", optimize(size))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut referent: *mut crate::ffi::PyObject = core::ptr::null_mut();
+        let ret = unsafe { pyo3_ffi::compat::PyWeakref_GetRef(self.previous.ptr, &mut referent) };
+        debug_assert!(ret != -1);
+        if referent.is_null() {
+            if opt_enabled!(self.previous.state.opts(), WEAKREF_DEAD_AS_NULL) {
+                return NoneSerializer::new().serialize(serializer);
+            }
+            err!(SerializeError::WeakrefDead)
+        }
+        // a strong reference, as serializing may drop the last other one
+        let ret = PyObjectSerializer::new(
+            referent,
+            self.previous.state.copy_for_recursive_call(),
+            self.previous.default,
+            self.previous.key_filter,
+        )
+        .serialize(serializer);
+        ffi!(Py_DECREF(referent));
+        ret
+    }
+}
@@ -1,37 +1,536 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2018-2025)
 
-use crate::opt::{APPEND_NEWLINE, INDENT_2, Opt};
-use crate::serialize::obtype::{ObType, pyobject_to_obtype};
+use crate::opt::{
+    APPEND_NEWLINE, ENSURE_ASCII, ESCAPE_FORWARD_SLASH, INDENT_2, JSON5_NONFINITE, Opt,
+};
+use crate::serialize::fp::FileSink;
+use crate::serialize::obtype::{
+    ObType, begin_unknown_type_cache, end_unknown_type_cache, pyobject_to_obtype,
+};
 use crate::serialize::per_type::{
-    BoolSerializer, DataclassGenericSerializer, Date, DateTime, DefaultSerializer,
-    DictGenericSerializer, EnumSerializer, FloatSerializer, FragmentSerializer, IntSerializer,
-    ListTupleSerializer, NoneSerializer, NumpyScalar, NumpySerializer, StrSerializer,
-    StrSubclassSerializer, Time, UUID, ZeroListSerializer,
+    ArraySerializer, BoolSerializer, BytesSerializer, CachedTupleSerializer,
+    DataclassGenericSerializer, Date, DateTime, DateTimeFormat, DefaultSerializer,
+    DictGenericSerializer, DictViewSerializer, EnumSerializer, FloatSerializer, FractionSerializer,
+    FragmentSerializer, FsPathSerializer, IntSerializer, IpAddressSerializer, IterableSerializer,
+    JsonDunderSerializer, ListTupleSerializer, MappingSerializer, NoneSerializer, NumpyScalar,
+    NumpySerializer, PropertiesSerializer, RangeSerializer, StrSerializer, StrSubclassSerializer,
+    Time, UUID, WeakrefSerializer, ZeroDictSerializer, ZeroListSerializer, serialize_entry,
 };
 use crate::serialize::state::SerializerState;
-use crate::serialize::writer::{BytesWriter, to_writer, to_writer_pretty};
+use crate::serialize::writer::{BytesWriter, FloatFormat, StrEscape, to_writer, to_writer_pretty};
+use crate::util::{isize_to_usize, usize_to_isize};
+use core::cell::Cell;
 use core::ptr::NonNull;
-use serde::ser::{Serialize, Serializer};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// The arguments of a `dumps()` call other than the object it serializes.
+#[derive(Clone, Copy)]
+pub(crate) struct DumpOptions<'a> {
+    pub opts: Opt,
+    pub default: Option<NonNull<crate::ffi::PyObject>>,
+    pub key_filter: Option<NonNull<crate::ffi::PyObject>>,
+    pub envelope: Option<&'a str>,
+    pub tuple_fields: Option<&'a [&'a str]>,
+    pub max_output_bytes: Option<usize>,
+    pub float_precision: Option<u8>,
+    pub on_skip: Option<NonNull<crate::ffi::PyObject>>,
+    pub skip_key_prefix: Option<NonNull<crate::ffi::PyObject>>,
+    /// The expected length of the output, or 0, for `size_hint`.
+    pub size_hint: usize,
+}
+
+impl DumpOptions<'_> {
+    /// The arguments of a call given only `opts`.
+    pub const fn new(opts: Opt) -> Self {
+        DumpOptions {
+            opts: opts,
+            default: None,
+            key_filter: None,
+            envelope: None,
+            tuple_fields: None,
+            max_output_bytes: None,
+            float_precision: None,
+            on_skip: None,
+            skip_key_prefix: None,
+            size_hint: 0,
+        }
+    }
+}
+
+/// What serializers read of the `DumpOptions` of the `dumps()` call in
+/// progress, as they are not given them.
+#[derive(Clone, Copy)]
+struct Call {
+    max_output_bytes: Option<usize>,
+    float_precision: Option<u8>,
+    on_skip: Option<NonNull<crate::ffi::PyObject>>,
+    skip_key_prefix: Option<NonNull<crate::ffi::PyObject>>,
+    /// Decided once from the options rather than for each datetime.
+    datetime_format: DateTimeFormat,
+    /// Whether the output exceeds its limit, so that serializers stop rather
+    /// than serialize what is discarded.
+    output_exceeded: bool,
+}
+
+impl Call {
+    const fn new(options: &DumpOptions) -> Self {
+        Call {
+            max_output_bytes: options.max_output_bytes,
+            float_precision: options.float_precision,
+            on_skip: options.on_skip,
+            skip_key_prefix: options.skip_key_prefix,
+            datetime_format: DateTimeFormat::new(options.opts),
+            output_exceeded: false,
+        }
+    }
+}
+
+thread_local! {
+    /// The `dumps()` call in progress on this thread. It is restored after
+    /// each call, as `default` may call `dumps()`.
+    static CALL: Cell<Call> = const { Cell::new(Call::new(&DumpOptions::new(0))) };
+}
+
+/// The `max_output_bytes` of the `dumps()` call in progress, if any.
+#[inline]
+pub(crate) fn max_output_bytes() -> Option<usize> {
+    CALL.get().max_output_bytes
+}
+
+/// The number of fractional digits floats are written with by the `dumps()`
+/// call in progress, if not the shortest that round-trips.
+#[inline(always)]
+pub(crate) fn float_precision() -> Option<u8> {
+    CALL.get().float_precision
+}
+
+/// The callable the `dumps()` call in progress reports each object skipped
+/// with `OPT_SKIP_UNSERIALIZABLE` to, if any.
+#[inline]
+pub(crate) fn on_skip() -> Option<NonNull<crate::ffi::PyObject>> {
+    CALL.get().on_skip
+}
+
+/// The `str` the `dumps()` call in progress omits the dict entries whose keys
+/// start with, if any.
+#[inline(always)]
+pub(crate) fn skip_key_prefix() -> Option<NonNull<crate::ffi::PyObject>> {
+    CALL.get().skip_key_prefix
+}
+
+/// Whether the output of the `dumps()` call in progress exceeds its limit.
+/// Serializers of containers and `default` check it so as to stop, returning
+/// `SerializeError::OutputExceeded`, which is replaced by the error of the
+/// limit.
+#[inline(always)]
+pub(crate) fn output_exceeded() -> bool {
+    CALL.get().output_exceeded
+}
+
+/// Mark the output of the `dumps()` call in progress as exceeding its limit.
+#[cold]
+pub(crate) fn set_output_exceeded() {
+    let mut call = CALL.get();
+    call.output_exceeded = true;
+    CALL.set(call);
+}
+
+/// How the `dumps()` call in progress serializes datetimes.
+#[inline(always)]
+pub(crate) fn datetime_format() -> DateTimeFormat {
+    CALL.get().datetime_format
+}
 
 pub(crate) fn serialize(
     ptr: *mut crate::ffi::PyObject,
-    default: Option<NonNull<crate::ffi::PyObject>>,
+    options: &DumpOptions,
+) -> Result<NonNull<crate::ffi::PyObject>, String> {
+    serialize_to_writer(ptr, options, None)
+        .map(|mut buf| buf.finish(opt_enabled!(options.opts, APPEND_NEWLINE)))
+}
+
+/// Like `serialize()`, but returns a `str` created from the output.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn serialize_str(
+    ptr: *mut crate::ffi::PyObject,
+    options: &DumpOptions,
+) -> Result<NonNull<crate::ffi::PyObject>, String> {
+    serialize_to_writer(ptr, options, None)
+        .map(|mut buf| buf.finish_str(opt_enabled!(options.opts, APPEND_NEWLINE)))
+}
+
+/// Like `serialize()`, but returns a `memoryview` of the output.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn serialize_view(
+    ptr: *mut crate::ffi::PyObject,
+    options: &DumpOptions,
+) -> Result<NonNull<crate::ffi::PyObject>, String> {
+    serialize_to_writer(ptr, options, None)
+        .map(|mut buf| buf.finish_view(opt_enabled!(options.opts, APPEND_NEWLINE)))
+}
+
+/// Like `serialize()`, but writes the output to `fp.write()` in chunks of
+/// `chunk_size` bytes as it is serialized. Returns `Ok(false)` with an
+/// exception set if `write()` failed. Output may have been written before
+/// an error.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn serialize_to_fp(
+    ptr: *mut crate::ffi::PyObject,
+    options: &DumpOptions,
+    fp: *mut crate::ffi::PyObject,
+    chunk_size: usize,
+) -> Result<bool, String> {
+    let mut sink = FileSink::new(fp, chunk_size);
+    serialize_to_writer(ptr, options, Some(&mut sink))
+        .map(|mut buf| buf.finish_sink(opt_enabled!(options.opts, APPEND_NEWLINE)))
+}
+
+/// Like `serialize()`, but copies the output to `out`, for
+/// `orjson_dumps_into()`. Returns the length of the output, or `Ok(None)` if
+/// it does not fit in `out`, in which case `out` may have been written to.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn serialize_to_slice(
+    ptr: *mut crate::ffi::PyObject,
     opts: Opt,
+    out: &mut [u8],
+) -> Result<Option<usize>, String> {
+    let mut buf = BytesWriter::default();
+    // the limit is given to the buffer alone so that exceeding it is not an
+    // error of serialization
+    buf.set_limit(out.len());
+    let res = serialize_into(&mut buf, ptr, &DumpOptions::new(opts));
+    let append = opt_enabled!(opts, APPEND_NEWLINE);
+    let ret = match res {
+        Ok(()) if buf.exceeds_limit(append) => Ok(None),
+        Ok(()) => {
+            let contents = buf.as_slice();
+            out[..contents.len()].copy_from_slice(contents);
+            if append {
+                out[contents.len()] = b'\n';
+            }
+            Ok(Some(contents.len() + usize::from(append)))
+        }
+        Err(err) => Err(err),
+    };
+    buf.abort();
+    ret
+}
+
+/// Serialize each element of the list `ptr` to its own `bytes`, returning a
+/// `list` of them. The buffer is reused for every element. Stops at the first
+/// element that fails and names its index in the error. `tuple_fields` and
+/// `size_hint` are not used.
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+pub(crate) fn serialize_many(
+    ptr: *mut crate::ffi::PyObject,
+    options: &DumpOptions,
 ) -> Result<NonNull<crate::ffi::PyObject>, String> {
+    debug_assert!(options.tuple_fields.is_none());
     let mut buf = BytesWriter::default();
-    let obj = PyObjectSerializer::new(ptr, SerializerState::new(opts), default);
-    let res = if opt_disabled!(opts, INDENT_2) {
-        to_writer(&mut buf, &obj)
-    } else {
-        to_writer_pretty(&mut buf, &obj)
+    let mut items: Vec<*mut crate::ffi::PyObject> =
+        Vec::with_capacity(isize_to_usize(ffi!(Py_SIZE(ptr))));
+    // the length is read on each iteration, as `default` may modify the list
+    let mut idx = 0;
+    while idx < isize_to_usize(ffi!(Py_SIZE(ptr))) {
+        let item = ffi!(PyList_GET_ITEM(ptr, usize_to_isize(idx)));
+        ffi!(Py_INCREF(item));
+        let res = serialize_into(&mut buf, item, options);
+        ffi!(Py_DECREF(item));
+        if let Err(err) = res {
+            cold_path!();
+            buf.abort();
+            for each in items {
+                ffi!(Py_DECREF(each));
+            }
+            return Err(format!("{err} (at index {idx})"));
+        }
+        items.push(
+            buf.take_bytes(opt_enabled!(options.opts, APPEND_NEWLINE))
+                .as_ptr(),
+        );
+        idx += 1;
+    }
+    buf.abort();
+    let list = ffi!(PyList_New(usize_to_isize(items.len())));
+    for (idx, each) in items.into_iter().enumerate() {
+        ffi!(PyList_SET_ITEM(list, usize_to_isize(idx), each));
+    }
+    Ok(nonnull!(list))
+}
+
+#[inline(always)]
+fn serialize_to_writer(
+    ptr: *mut crate::ffi::PyObject,
+    options: &DumpOptions,
+    sink: Option<&mut FileSink>,
+) -> Result<BytesWriter, String> {
+    let mut buf = BytesWriter::default();
+    if options.size_hint != 0 {
+        cold_path!();
+        buf.presize(options.size_hint);
+    }
+    if let Some(sink) = sink {
+        cold_path!();
+        buf.set_sink(sink);
+    }
+    match serialize_into(&mut buf, ptr, options) {
+        Ok(()) => Ok(buf),
+        Err(err) => {
+            buf.abort();
+            Err(err)
+        }
+    }
+}
+
+/// Serialize `ptr` to the end of `buf`. The caller releases `buf` on error.
+#[inline(always)]
+fn serialize_into(
+    buf: &mut BytesWriter,
+    ptr: *mut crate::ffi::PyObject,
+    options: &DumpOptions,
+) -> Result<(), String> {
+    let opts = options.opts;
+    if let Some(limit) = options.max_output_bytes {
+        cold_path!();
+        buf.set_limit(limit);
+    }
+    let obj = PyObjectSerializer::new(
+        ptr,
+        SerializerState::new(opts),
+        options.default,
+        options.key_filter,
+    );
+    let previous_call = CALL.replace(Call::new(options));
+    let previous_unknown_type = begin_unknown_type_cache();
+    let res = match (options.envelope, options.tuple_fields) {
+        (None, None) => write(buf, &obj, opts, options.float_precision),
+        (None, Some(fields)) => {
+            cold_path!();
+            write(
+                buf,
+                &TupleFieldsSerializer::new(fields, &obj),
+                opts,
+                options.float_precision,
+            )
+        }
+        (Some(key), None) => {
+            cold_path!();
+            write(
+                buf,
+                &EnvelopeSerializer::new(key, &obj),
+                opts,
+                options.float_precision,
+            )
+        }
+        (Some(key), Some(fields)) => {
+            cold_path!();
+            let obj = TupleFieldsSerializer::new(fields, &obj);
+            write(
+                buf,
+                &EnvelopeSerializer::new(key, &obj),
+                opts,
+                options.float_precision,
+            )
+        }
     };
+    end_unknown_type_cache(previous_unknown_type);
+    CALL.set(previous_call);
     match res {
-        Ok(()) => Ok(buf.finish(opt_enabled!(opts, APPEND_NEWLINE))),
+        // the exception of `write()` is raised rather than any error after
+        _ if buf.sink_failed() => Ok(()),
+        // serializers stop with an error once the limit is exceeded, and
+        // `serialize_to_slice()` checks the limit itself
+        _ if buf.exceeds_limit(opt_enabled!(opts, APPEND_NEWLINE)) => {
+            cold_path!();
+            match options.max_output_bytes {
+                Some(limit) => Err(format!("Output exceeds max_output_bytes of {limit}")),
+                None => Ok(()),
+            }
+        }
+        #[cfg(any(debug_assertions, feature = "self_check"))]
+        Ok(()) if opt_enabled!(opts, crate::opt::SELF_CHECK) && !buf.has_sink() => {
+            cold_path!();
+            self_check(buf.as_slice(), opts, options.float_precision)
+        }
+        Ok(()) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Parse `output` and serialize the parsed value again, for `OPT_SELF_CHECK`.
+/// A JSON document serializes as it was written, given the same options, so
+/// the two differ only if `output` is invalid or misrepresents a value.
+#[cfg(any(debug_assertions, feature = "self_check"))]
+#[cold]
+#[inline(never)]
+fn self_check(output: &[u8], opts: Opt, float_precision: Option<u8>) -> Result<(), String> {
+    // the tokens of OPT_JSON5_NONFINITE are accepted by OPT_PARSE_RELAXED
+    let parse_opts = if opt_enabled!(opts, JSON5_NONFINITE) {
+        crate::opt::PARSE_RELAXED
+    } else {
+        0
+    };
+    let parsed = match crate::deserialize::deserialize_buf(output, parse_opts, None) {
+        Ok(parsed) => parsed,
         Err(err) => {
-            buf.abort();
-            Err(err.to_string())
+            return Err(format!(
+                "OPT_SELF_CHECK: output is not valid JSON: {} at position {}",
+                err.message, err.pos
+            ));
         }
+    };
+    let mut buf = BytesWriter::default();
+    let options = DumpOptions {
+        float_precision: float_precision,
+        ..DumpOptions::new(opts & !crate::opt::SELF_CHECK)
+    };
+    let res = serialize_into(&mut buf, parsed.as_ptr(), &options);
+    ffi!(Py_DECREF(parsed.as_ptr()));
+    let ret = match res {
+        Ok(()) => {
+            let reserialized = buf.as_slice();
+            let diverges = output
+                .iter()
+                .zip(reserialized)
+                .position(|(lhs, rhs)| lhs != rhs)
+                .or_else(|| {
+                    (output.len() != reserialized.len())
+                        .then(|| output.len().min(reserialized.len()))
+                });
+            match diverges {
+                None => Ok(()),
+                Some(pos) => Err(format!(
+                    "OPT_SELF_CHECK: output differs from its parsed value serialized again at position {pos}"
+                )),
+            }
+        }
+        Err(err) => Err(format!("OPT_SELF_CHECK: {err}")),
+    };
+    buf.abort();
+    ret
+}
+
+#[inline(always)]
+pub(crate) fn write<T: Serialize>(
+    buf: &mut BytesWriter,
+    value: &T,
+    opts: Opt,
+    float_precision: Option<u8>,
+) -> serde_json::Result<()> {
+    let floats = FloatFormat {
+        precision: float_precision,
+        json5_nonfinite: opt_enabled!(opts, JSON5_NONFINITE),
+    };
+    if opt_enabled!(opts, ENSURE_ASCII | ESCAPE_FORWARD_SLASH) {
+        cold_path!();
+        write_escaped(buf, value, opts, floats)
+    } else if opt_disabled!(opts, INDENT_2) {
+        to_writer::<_, _, false>(buf, value, StrEscape::default(), floats)
+    } else {
+        to_writer_pretty::<_, _, false>(buf, value, StrEscape::default(), floats)
+    }
+}
+
+#[cold]
+#[inline(never)]
+#[cfg_attr(feature = "optimize", optimize(size))]
+fn write_escaped<T: Serialize>(
+    buf: &mut BytesWriter,
+    value: &T,
+    opts: Opt,
+    floats: FloatFormat,
+) -> serde_json::Result<()> {
+    let escape = StrEscape::new(opts);
+    if opt_disabled!(opts, INDENT_2) {
+        to_writer::<_, _, true>(buf, value, escape, floats)
+    } else {
+        to_writer_pretty::<_, _, true>(buf, value, escape, floats)
+    }
+}
+
+/// Serializes the top-level object as the only value of an object, i.e.,
+/// `{"<key>": <obj>}`, for the `envelope` argument of `dumps()`.
+struct EnvelopeSerializer<'a, T: Serialize> {
+    key: &'a str,
+    value: &'a T,
+}
+
+impl<'a, T: Serialize> EnvelopeSerializer<'a, T> {
+    fn new(key: &'a str, value: &'a T) -> Self {
+        EnvelopeSerializer {
+            key: key,
+            value: value,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for EnvelopeSerializer<'_, T> {
+    #[cold]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None).unwrap();
+        map.serialize_key(self.key).unwrap();
+        map.serialize_value(self.value)?;
+        map.end()
+    }
+}
+
+/// Serializes the top-level tuple as an object mapping each of the names
+/// given as the `tuple_fields` argument of `dumps()` to the element at the
+/// same index. The caller checks that the tuple and names have equal length.
+struct TupleFieldsSerializer<'a> {
+    fields: &'a [&'a str],
+    value: &'a PyObjectSerializer,
+}
+
+impl<'a> TupleFieldsSerializer<'a> {
+    fn new(fields: &'a [&'a str], value: &'a PyObjectSerializer) -> Self {
+        debug_assert!(fields.len() == isize_to_usize(ffi!(Py_SIZE(value.ptr))));
+        TupleFieldsSerializer {
+            fields: fields,
+            value: value,
+        }
+    }
+}
+
+impl Serialize for TupleFieldsSerializer<'_> {
+    #[cold]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.fields.is_empty() {
+            return ZeroDictSerializer::new().serialize(serializer);
+        }
+        let state = self.value.state.copy_for_recursive_call();
+        let data_ptr = unsafe {
+            (*self.value.ptr.cast::<crate::ffi::PyTupleObject>())
+                .ob_item
+                .as_ptr()
+        };
+        let mut map = serializer.serialize_map(None).unwrap();
+        for (idx, key) in self.fields.iter().enumerate() {
+            let value = unsafe { *data_ptr.add(idx) };
+            serialize_entry(
+                &mut map,
+                key,
+                &PyObjectSerializer::new(value, state, self.value.default, self.value.key_filter),
+            )?;
+        }
+        map.end()
     }
 }
 
@@ -39,6 +538,7 @@ pub(crate) struct PyObjectSerializer {
     pub ptr: *mut crate::ffi::PyObject,
     pub state: SerializerState,
     pub default: Option<NonNull<crate::ffi::PyObject>>,
+    pub key_filter: Option<NonNull<crate::ffi::PyObject>>,
 }
 
 impl PyObjectSerializer {
@@ -46,11 +546,13 @@ impl PyObjectSerializer {
         ptr: *mut crate::ffi::PyObject,
         state: SerializerState,
         default: Option<NonNull<crate::ffi::PyObject>>,
+        key_filter: Option<NonNull<crate::ffi::PyObject>>,
     ) -> Self {
         PyObjectSerializer {
             ptr: ptr,
             state: state,
             default: default,
+            key_filter: key_filter,
         }
     }
 }
@@ -61,33 +563,46 @@ impl Serialize for PyObjectSerializer {
         S: Serializer,
     {
         match pyobject_to_obtype(self.ptr, self.state.opts()) {
-            ObType::Str => StrSerializer::new(self.ptr).serialize(serializer),
-            ObType::StrSubclass => StrSubclassSerializer::new(self.ptr).serialize(serializer),
+            ObType::Str => StrSerializer::new(self.ptr, self.state.opts()).serialize(serializer),
+            ObType::StrSubclass => {
+                StrSubclassSerializer::new(self.ptr, self.state.opts()).serialize(serializer)
+            }
             ObType::Int => IntSerializer::new(self.ptr, self.state.opts()).serialize(serializer),
             ObType::None => NoneSerializer::new().serialize(serializer),
             ObType::Float => FloatSerializer::new(self.ptr).serialize(serializer),
             ObType::Bool => BoolSerializer::new(self.ptr).serialize(serializer),
-            ObType::Datetime => DateTime::new(self.ptr, self.state.opts()).serialize(serializer),
+            ObType::Datetime => DateTime::new(self.ptr, datetime_format()).serialize(serializer),
             ObType::Date => Date::new(self.ptr).serialize(serializer),
             ObType::Time => Time::new(self.ptr, self.state.opts()).serialize(serializer),
-            ObType::Uuid => UUID::new(self.ptr).serialize(serializer),
+            ObType::Uuid => UUID::new(self.ptr, self.state.opts()).serialize(serializer),
             ObType::Dict => {
-                DictGenericSerializer::new(self.ptr, self.state, self.default).serialize(serializer)
+                DictGenericSerializer::new(self.ptr, self.state, self.default, self.key_filter)
+                    .serialize(serializer)
             }
             ObType::List => {
                 if ffi!(Py_SIZE(self.ptr)) == 0 {
                     ZeroListSerializer::new().serialize(serializer)
                 } else {
-                    ListTupleSerializer::from_list(self.ptr, self.state, self.default)
-                        .serialize(serializer)
+                    ListTupleSerializer::from_list(
+                        self.ptr,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )
+                    .serialize(serializer)
                 }
             }
             ObType::Tuple => {
                 if ffi!(Py_SIZE(self.ptr)) == 0 {
                     ZeroListSerializer::new().serialize(serializer)
                 } else {
-                    ListTupleSerializer::from_tuple(self.ptr, self.state, self.default)
-                        .serialize(serializer)
+                    ListTupleSerializer::from_tuple(
+                        self.ptr,
+                        self.state,
+                        self.default,
+                        self.key_filter,
+                    )
+                    .serialize(serializer)
                 }
             }
             ObType::Dataclass => DataclassGenericSerializer::new(self).serialize(serializer),
@@ -96,7 +611,28 @@ impl Serialize for PyObjectSerializer {
             ObType::NumpyScalar => {
                 NumpyScalar::new(self.ptr, self.state.opts()).serialize(serializer)
             }
-            ObType::Fragment => FragmentSerializer::new(self.ptr).serialize(serializer),
+            ObType::Fragment => {
+                FragmentSerializer::new(self.ptr, self.state.opts()).serialize(serializer)
+            }
+            ObType::Bytes => {
+                BytesSerializer::new(self.ptr, self.state.opts()).serialize(serializer)
+            }
+            ObType::Mapping => MappingSerializer::new(self).serialize(serializer),
+            ObType::DictView => DictViewSerializer::new(self).serialize(serializer),
+            ObType::CachedTuple => CachedTupleSerializer::new(self).serialize(serializer),
+            ObType::JsonDunder => JsonDunderSerializer::new(self).serialize(serializer),
+            ObType::IpAddress => IpAddressSerializer::new(self.ptr).serialize(serializer),
+            ObType::Fraction => {
+                FractionSerializer::new(self.ptr, self.state.opts()).serialize(serializer)
+            }
+            ObType::Range => RangeSerializer::new(self).serialize(serializer),
+            ObType::Weakref => WeakrefSerializer::new(self).serialize(serializer),
+            ObType::Array => ArraySerializer::new(self.ptr).serialize(serializer),
+            ObType::FsPath => {
+                FsPathSerializer::new(self.ptr, self.state.opts()).serialize(serializer)
+            }
+            ObType::Iterable => IterableSerializer::new(self).serialize(serializer),
+            ObType::Properties => PropertiesSerializer::new(self).serialize(serializer),
             ObType::Unknown => DefaultSerializer::new(self).serialize(serializer),
         }
     }
@@ -3,58 +3,60 @@
 
 use crate::opt::Opt;
 
-const RECURSION_SHIFT: usize = 24;
-const RECURSION_MASK: u32 = 255 << RECURSION_SHIFT;
+const RECURSION_LIMIT: u8 = 255;
+const DEFAULT_CALLS_LIMIT: u8 = 255;
 
-const DEFAULT_SHIFT: usize = 16;
-const DEFAULT_MASK: u32 = 255 << DEFAULT_SHIFT;
-
-#[repr(transparent)]
 #[derive(Copy, Clone)]
 pub(crate) struct SerializerState {
-    // recursion: u8,
-    // default_calls: u8,
-    // opts: u16,
-    state: u32,
+    opts: Opt,
+    recursion: u8,
+    default_calls: u8,
 }
 
 impl SerializerState {
     #[inline(always)]
     pub fn new(opts: Opt) -> Self {
-        debug_assert!(opts < u32::from(u16::MAX));
-        Self { state: opts }
+        Self {
+            opts: opts,
+            recursion: 0,
+            default_calls: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn opts(self) -> Opt {
+        self.opts
     }
 
+    /// The state with `opts` in place of its options.
     #[inline(always)]
-    pub fn opts(self) -> u32 {
-        self.state
+    pub fn with_opts(self, opts: Opt) -> Self {
+        Self { opts: opts, ..self }
     }
 
     #[inline(always)]
     pub fn recursion_limit(self) -> bool {
-        self.state & RECURSION_MASK == RECURSION_MASK
+        self.recursion == RECURSION_LIMIT
     }
 
     #[inline(always)]
     pub fn default_calls_limit(self) -> bool {
-        self.state & DEFAULT_MASK == DEFAULT_MASK
+        self.default_calls == DEFAULT_CALLS_LIMIT
     }
 
     #[inline(always)]
     pub fn copy_for_recursive_call(self) -> Self {
-        let opt = self.state & !RECURSION_MASK;
-        let recursion = (((self.state & RECURSION_MASK) >> RECURSION_SHIFT) + 1) << RECURSION_SHIFT;
         Self {
-            state: opt | recursion,
+            recursion: self.recursion.wrapping_add(1),
+            ..self
         }
     }
 
     #[inline(always)]
     pub fn copy_for_default_call(self) -> Self {
-        let opt = self.state & !DEFAULT_MASK;
-        let default_calls = (((self.state & DEFAULT_MASK) >> DEFAULT_SHIFT) + 1) << DEFAULT_SHIFT;
         Self {
-            state: opt | default_calls,
+            default_calls: self.default_calls.wrapping_add(1),
+            ..self
         }
     }
 }
@@ -1,7 +1,13 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 // Copyright ijl (2020-2025)
 
-use crate::ffi::{PyBytes_FromStringAndSize, PyObject};
+#[cfg(not(Py_GIL_DISABLED))]
+use super::pool::BufferPool;
+#[cfg(Py_GIL_DISABLED)]
+use super::pool::release_buffer;
+use crate::ffi::{Py_DECREF, PyBytes_FromStringAndSize, PyObject};
+use crate::serialize::fp::FileSink;
+use crate::str::PyStr;
 use crate::util::usize_to_isize;
 use bytes::{BufMut, buf::UninitSlice};
 use core::mem::MaybeUninit;
@@ -13,9 +19,33 @@ const BUFFER_LENGTH: usize = 1024;
 #[cfg(not(CPython))]
 const BUFFER_LENGTH: usize = 4096;
 
+/// The most a `size_hint` preallocates, so that an inaccurate hint cannot
+/// allocate more than this up front. Output past it grows the buffer as usual.
+const MAX_SIZE_HINT: usize = 128 * 1024 * 1024;
+
+#[cfg(not(Py_GIL_DISABLED))]
+#[inline(always)]
+fn buffer_pool() -> &'static mut BufferPool {
+    unsafe {
+        &mut *crate::interpreter_state::get_current_state()
+            .as_ref()
+            .unwrap()
+            .buffer_pool
+            .get()
+    }
+}
+
 pub(crate) struct BytesWriter {
     cap: usize,
     len: usize,
+    /// `reserve()` calls `grow()` for a write to this length, the lesser of
+    /// `cap` and the length past which the output exceeds `limit`.
+    threshold: usize,
+    limit: usize,
+    limit_exceeded: bool,
+    /// Bytes given to `sink` so far.
+    flushed: usize,
+    sink: *mut FileSink,
     #[cfg(CPython)]
     bytes: *mut crate::ffi::PyBytesObject,
     #[cfg(not(CPython))]
@@ -23,11 +53,31 @@ pub(crate) struct BytesWriter {
 }
 
 impl BytesWriter {
+    /// A buffer from the pool of the interpreter if one is available, or a new
+    /// one.
     #[inline]
     pub fn default() -> Self {
+        #[cfg(not(Py_GIL_DISABLED))]
+        if let Some((bytes, cap)) = buffer_pool().take() {
+            return BytesWriter {
+                cap: cap,
+                len: 0,
+                threshold: cap,
+                limit: usize::MAX,
+                limit_exceeded: false,
+                flushed: 0,
+                sink: core::ptr::null_mut(),
+                bytes: bytes,
+            };
+        }
         BytesWriter {
             cap: BUFFER_LENGTH,
             len: 0,
+            threshold: BUFFER_LENGTH,
+            limit: usize::MAX,
+            limit_exceeded: false,
+            flushed: 0,
+            sink: core::ptr::null_mut(),
             #[cfg(CPython)]
             bytes: unsafe {
                 PyBytes_FromStringAndSize(core::ptr::null_mut(), usize_to_isize(BUFFER_LENGTH))
@@ -38,16 +88,116 @@ impl BytesWriter {
         }
     }
 
-    #[cfg(CPython)]
-    pub fn abort(&mut self) {
-        ffi!(Py_DECREF(self.bytes.cast::<PyObject>()));
+    /// Stop growing the buffer once more than `limit` bytes are written. What
+    /// is written from then on is discarded, `exceeds_limit()` is true, and
+    /// the `dumps()` call in progress is told to stop serializing.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.set_threshold();
     }
 
-    #[cfg(not(CPython))]
-    pub fn abort(&mut self) {
+    fn set_threshold(&mut self) {
+        let remaining = self.limit.saturating_sub(self.flushed).saturating_add(1);
+        self.threshold = self.cap.min(remaining);
+    }
+
+    /// Grow the buffer to hold `size_hint` bytes, up to `MAX_SIZE_HINT`,
+    /// before anything is written, so that output of about that length does
+    /// not reallocate it as it grows.
+    #[cold]
+    #[inline(never)]
+    pub fn presize(&mut self, size_hint: usize) {
+        debug_assert!(self.len == 0);
+        // one more for the terminating null byte
+        let cap = size_hint.min(MAX_SIZE_HINT) + 1;
+        if cap > self.cap {
+            self.resize(cap);
+        }
+    }
+
+    /// Whether the output, including a newline if `append`, is longer than
+    /// the limit given to `set_limit()`.
+    pub fn exceeds_limit(&self, append: bool) -> bool {
+        self.limit_exceeded || self.flushed + self.len + usize::from(append) > self.limit
+    }
+
+    /// Give each complete chunk of the contents to `sink` once the buffer is
+    /// full rather than growing it past what is needed for a chunk.
+    #[cold]
+    #[inline(never)]
+    pub fn set_sink(&mut self, sink: &mut FileSink) {
+        // a buffer from the pool may be much larger than a chunk
+        self.cap = self
+            .cap
+            .min(sink.chunk_size().saturating_mul(2).max(BUFFER_LENGTH));
+        self.set_threshold();
+        self.sink = sink;
+    }
+
+    /// Give the contents to the sink, including a newline if `append`, and
+    /// release the buffer. Returns `false` with an exception set if a write
+    /// failed.
+    #[cold]
+    #[inline(never)]
+    pub fn finish_sink(&mut self, append: bool) -> bool {
+        debug_assert!(!self.sink.is_null());
+        self.append_and_terminate(append);
+        self.flush(self.len);
+        self.abort();
+        unsafe { (*self.sink).finish() }
+    }
+
+    /// Whether the output is given to a sink rather than kept in the buffer.
+    pub fn has_sink(&self) -> bool {
+        !self.sink.is_null()
+    }
+
+    /// Whether a write to the sink given to `set_sink()` failed.
+    pub fn sink_failed(&self) -> bool {
+        !self.sink.is_null() && unsafe { (*self.sink).failed() }
+    }
+
+    /// Give the first `len` bytes to the sink and move the rest to the start
+    /// of the buffer.
+    fn flush(&mut self, len: usize) {
         unsafe {
-            crate::ffi::PyMem_Free(self.bytes.cast::<core::ffi::c_void>());
+            let start = self.buffer_ptr().sub(self.len);
+            (*self.sink).write(core::slice::from_raw_parts(start, len));
+            core::ptr::copy(start.add(len), start, self.len - len);
         }
+        self.flushed += len;
+        self.len -= len;
+        self.set_threshold();
+    }
+
+    /// Create a `bytes` from the contents, including a newline if `append`,
+    /// and clear the buffer for the next output.
+    #[cold]
+    #[inline(never)]
+    pub fn take_bytes(&mut self, append: bool) -> NonNull<PyObject> {
+        self.append_and_terminate(append);
+        let bytes = unsafe {
+            PyBytes_FromStringAndSize(
+                self.buffer_ptr().sub(self.len).cast::<core::ffi::c_char>(),
+                usize_to_isize(self.len),
+            )
+        };
+        self.len = 0;
+        self.limit_exceeded = false;
+        nonnull!(bytes)
+    }
+
+    /// The contents written so far.
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.buffer_ptr().sub(self.len), self.len) }
+    }
+
+    /// Release the buffer, returning it to the pool for reuse.
+    pub fn abort(&mut self) {
+        #[cfg(not(Py_GIL_DISABLED))]
+        buffer_pool().give(self.bytes, self.cap);
+        #[cfg(Py_GIL_DISABLED)]
+        release_buffer(self.bytes);
     }
 
     fn append_and_terminate(&mut self, append: bool) {
@@ -85,11 +235,57 @@ impl BytesWriter {
                 usize_to_isize(self.len),
             );
             debug_assert!(!bytes.is_null());
-            crate::ffi::PyMem_Free(self.bytes.cast::<core::ffi::c_void>());
+            self.abort();
             nonnull!(bytes)
         }
     }
 
+    /// Like `finish()`, but returns a `memoryview` of the contents. The
+    /// `bytes` object is not shrunk to its length, as that may copy, and is
+    /// kept alive by the `memoryview`.
+    #[cfg(CPython)]
+    #[cold]
+    #[inline(never)]
+    pub fn finish_view(&mut self, append: bool) -> NonNull<PyObject> {
+        unsafe {
+            self.append_and_terminate(append);
+            crate::ffi::Py_SET_SIZE(
+                self.bytes.cast::<crate::ffi::PyVarObject>(),
+                usize_to_isize(self.len),
+            );
+            let bytes = self.bytes.cast::<PyObject>();
+            let view = crate::ffi::PyMemoryView_FromObject(bytes);
+            Py_DECREF(bytes);
+            nonnull!(view)
+        }
+    }
+
+    #[cfg(not(CPython))]
+    #[cold]
+    #[inline(never)]
+    pub fn finish_view(&mut self, append: bool) -> NonNull<PyObject> {
+        let bytes = self.finish(append).as_ptr();
+        unsafe {
+            let view = crate::ffi::PyMemoryView_FromObject(bytes);
+            Py_DECREF(bytes);
+            nonnull!(view)
+        }
+    }
+
+    /// Create a `str` from the contents, which are UTF-8 by construction,
+    /// and release the buffer.
+    #[cold]
+    #[inline(never)]
+    pub fn finish_str(&mut self, append: bool) -> NonNull<PyObject> {
+        self.append_and_terminate(append);
+        let contents =
+            unsafe { core::slice::from_raw_parts(self.buffer_ptr().sub(self.len), self.len) };
+        debug_assert!(core::str::from_utf8(contents).is_ok());
+        let obj = PyStr::from_str(str_from_slice!(contents.as_ptr(), contents.len()));
+        self.abort();
+        obj.as_non_null_ptr()
+    }
+
     #[cfg(CPython)]
     #[inline]
     fn buffer_ptr(&self) -> *mut u8 {
@@ -107,6 +303,7 @@ impl BytesWriter {
     #[inline]
     pub fn resize(&mut self, len: usize) {
         self.cap = len;
+        self.set_threshold();
         unsafe {
             crate::ffi::_PyBytes_Resize(
                 (&raw mut self.bytes).cast::<*mut PyObject>(),
@@ -119,6 +316,7 @@ impl BytesWriter {
     #[inline]
     pub fn resize(&mut self, len: usize) {
         self.cap = len;
+        self.set_threshold();
         unsafe {
             self.bytes =
                 crate::ffi::PyMem_Realloc(self.bytes.cast::<core::ffi::c_void>(), len).cast::<u8>();
@@ -129,6 +327,33 @@ impl BytesWriter {
     #[cold]
     #[inline(never)]
     fn grow(&mut self, len: usize) {
+        let mut len = len;
+        if self.flushed + self.len > self.limit {
+            // rewind rather than allocate for output that is discarded
+            if !self.limit_exceeded {
+                self.limit_exceeded = true;
+                crate::serialize::serializer::set_output_exceeded();
+            }
+            len -= self.len;
+            self.len = 0;
+            if len < self.cap {
+                return;
+            }
+        } else if !self.sink.is_null() {
+            let chunk_size = unsafe { (*self.sink).chunk_size() };
+            if self.len >= chunk_size {
+                let complete = self.len - self.len % chunk_size;
+                len -= complete;
+                self.flush(complete);
+                if len < self.cap {
+                    return;
+                }
+            }
+        }
+        if len < self.cap {
+            // called for the limit rather than the capacity
+            return;
+        }
         let mut cap = self.cap;
         while len >= cap {
             cap *= 2;
@@ -208,7 +433,7 @@ impl WriteExt for &mut BytesWriter {
     #[inline(always)]
     fn reserve(&mut self, len: usize) {
         let end_length = self.len + len;
-        if end_length >= self.cap {
+        if end_length >= self.threshold {
             cold_path!();
             self.grow(end_length);
         }
@@ -88,6 +88,8 @@ pub(crate) trait Formatter {
         Ok(())
     }
 
+    /// The shortest representation that round-trips as an `f32`, which is not
+    /// widened to `f64`, e.g., `0.1` rather than `0.10000000149011612`.
     #[inline]
     fn write_f32<W>(&mut self, writer: &mut W, value: f32) -> io::Result<()>
     where
@@ -101,6 +103,9 @@ pub(crate) trait Formatter {
         Ok(())
     }
 
+    /// The shortest representation that round-trips, by Ryū, e.g., `0.3`
+    /// rather than `0.29999999999999999`. `float` and `numpy.float64` are
+    /// written by this.
     #[inline]
     fn write_f64<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
     where
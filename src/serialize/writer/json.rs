@@ -4,46 +4,67 @@
 
 use crate::serialize::writer::WriteExt;
 use crate::serialize::writer::formatter::{CompactFormatter, Formatter, PrettyFormatter};
+use crate::serialize::writer::str::StrEscape;
 use serde::ser::{self, Impossible, Serialize};
 use serde_json::error::{Error, Result};
 
-pub(crate) struct Serializer<W, F = CompactFormatter> {
+/// How floats are written, as given by the arguments of the `dumps()` call.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct FloatFormat {
+    /// The number of fractional digits, if not the shortest that round-trips,
+    /// for `float_precision`.
+    pub precision: Option<u8>,
+    /// Write non-finite floats as the `NaN`, `Infinity`, and `-Infinity`
+    /// tokens of JSON5 rather than `null`, for `OPT_JSON5_NONFINITE`.
+    pub json5_nonfinite: bool,
+}
+
+/// If `ESCAPE`, strings are written by the scalar `format_escaped_str_opts()`
+/// as given by `escape`, and otherwise by the SIMD `format_escaped_str()`.
+pub(crate) struct Serializer<W, F = CompactFormatter, const ESCAPE: bool = false> {
     writer: W,
     formatter: F,
+    escape: StrEscape,
+    floats: FloatFormat,
 }
 
-impl<W> Serializer<W>
+impl<W, const ESCAPE: bool> Serializer<W, CompactFormatter, ESCAPE>
 where
     W: WriteExt + bytes::BufMut,
 {
     #[inline]
-    pub fn new(writer: W) -> Self {
-        Serializer::with_formatter(writer, CompactFormatter)
+    pub fn new(writer: W, escape: StrEscape, floats: FloatFormat) -> Self {
+        Serializer::with_formatter(writer, CompactFormatter, escape, floats)
     }
 }
 
-impl<W> Serializer<W, PrettyFormatter>
+impl<W, const ESCAPE: bool> Serializer<W, PrettyFormatter, ESCAPE>
 where
     W: WriteExt + bytes::BufMut,
 {
     #[inline]
-    pub fn pretty(writer: W) -> Self {
-        Serializer::with_formatter(writer, PrettyFormatter::new())
+    pub fn pretty(writer: W, escape: StrEscape, floats: FloatFormat) -> Self {
+        Serializer::with_formatter(writer, PrettyFormatter::new(), escape, floats)
     }
 }
 
-impl<W, F> Serializer<W, F>
+impl<W, F, const ESCAPE: bool> Serializer<W, F, ESCAPE>
 where
     W: WriteExt + bytes::BufMut,
     F: Formatter,
 {
     #[inline]
-    pub fn with_formatter(writer: W, formatter: F) -> Self {
-        Serializer { writer, formatter }
+    pub fn with_formatter(writer: W, formatter: F, escape: StrEscape, floats: FloatFormat) -> Self {
+        Serializer {
+            writer,
+            formatter,
+            escape,
+            floats,
+        }
     }
 }
 
-impl<'a, W, F> ser::Serializer for &'a mut Serializer<W, F>
+impl<'a, W, F, const ESCAPE: bool> ser::Serializer for &'a mut Serializer<W, F, ESCAPE>
 where
     W: WriteExt + bytes::BufMut,
     F: Formatter,
@@ -51,11 +72,11 @@ where
     type Ok = ();
     type Error = Error;
 
-    type SerializeSeq = Compound<'a, W, F>;
+    type SerializeSeq = Compound<'a, W, F, ESCAPE>;
     type SerializeTuple = Impossible<(), Error>;
     type SerializeTupleStruct = Impossible<(), Error>;
     type SerializeTupleVariant = Impossible<(), Error>;
-    type SerializeMap = Compound<'a, W, F>;
+    type SerializeMap = Compound<'a, W, F, ESCAPE>;
     type SerializeStruct = Impossible<(), Error>;
     type SerializeStructVariant = Impossible<(), Error>;
 
@@ -122,7 +143,16 @@ where
     fn serialize_f32(self, value: f32) -> Result<()> {
         if value.is_infinite() || value.is_nan() {
             cold_path!();
-            self.serialize_unit()
+            if self.floats.json5_nonfinite {
+                format_nonfinite(&mut self.writer, f64::from(value));
+                Ok(())
+            } else {
+                self.serialize_unit()
+            }
+        } else if let Some(precision) = self.floats.precision {
+            cold_path!();
+            format_fixed(&mut self.writer, f64::from(value), precision);
+            Ok(())
         } else {
             self.formatter
                 .write_f32(&mut self.writer, value)
@@ -133,7 +163,16 @@ where
     fn serialize_f64(self, value: f64) -> Result<()> {
         if value.is_infinite() || value.is_nan() {
             cold_path!();
-            self.serialize_unit()
+            if self.floats.json5_nonfinite {
+                format_nonfinite(&mut self.writer, value);
+                Ok(())
+            } else {
+                self.serialize_unit()
+            }
+        } else if let Some(precision) = self.floats.precision {
+            cold_path!();
+            format_fixed(&mut self.writer, value, precision);
+            Ok(())
         } else {
             self.formatter
                 .write_f64(&mut self.writer, value)
@@ -147,7 +186,11 @@ where
 
     #[inline(always)]
     fn serialize_str(self, value: &str) -> Result<()> {
-        format_escaped_str(&mut self.writer, value);
+        if ESCAPE {
+            format_escaped_str_opts(&mut self.writer, value, self.escape);
+        } else {
+            format_escaped_str(&mut self.writer, value);
+        }
         Ok(())
     }
 
@@ -286,12 +329,12 @@ pub(crate) enum State {
     Rest,
 }
 
-pub(crate) struct Compound<'a, W: 'a, F: 'a> {
-    ser: &'a mut Serializer<W, F>,
+pub(crate) struct Compound<'a, W: 'a, F: 'a, const ESCAPE: bool> {
+    ser: &'a mut Serializer<W, F, ESCAPE>,
     state: State,
 }
 
-impl<W, F> ser::SerializeSeq for Compound<'_, W, F>
+impl<W, F, const ESCAPE: bool> ser::SerializeSeq for Compound<'_, W, F, ESCAPE>
 where
     W: WriteExt + bytes::BufMut,
     F: Formatter,
@@ -325,7 +368,7 @@ where
     }
 }
 
-impl<W, F> ser::SerializeMap for Compound<'_, W, F>
+impl<W, F, const ESCAPE: bool> ser::SerializeMap for Compound<'_, W, F, ESCAPE>
 where
     W: WriteExt + bytes::BufMut,
     F: Formatter,
@@ -386,11 +429,11 @@ where
 }
 
 #[repr(transparent)]
-struct MapKeySerializer<'a, W: 'a, F: 'a> {
-    ser: &'a mut Serializer<W, F>,
+struct MapKeySerializer<'a, W: 'a, F: 'a, const ESCAPE: bool> {
+    ser: &'a mut Serializer<W, F, ESCAPE>,
 }
 
-impl<W, F> ser::Serializer for MapKeySerializer<'_, W, F>
+impl<W, F, const ESCAPE: bool> ser::Serializer for MapKeySerializer<'_, W, F, ESCAPE>
 where
     W: WriteExt + bytes::BufMut,
     F: Formatter,
@@ -664,22 +707,86 @@ where
     }
 }
 
+#[cold]
+#[inline(never)]
+fn format_escaped_str_opts<W>(writer: &mut W, value: &str, escape: StrEscape)
+where
+    W: ?Sized + WriteExt + bytes::BufMut,
+{
+    unsafe {
+        reserve_str!(writer, value);
+
+        let written = crate::serialize::writer::str::format_escaped_str_opts(
+            writer.as_mut_buffer_ptr(),
+            value,
+            escape,
+        );
+
+        writer.advance_mut(written);
+    }
+}
+
+/// Write `value`, which is finite, with `precision` fractional digits, for
+/// `float_precision`.
+#[cold]
+#[inline(never)]
+fn format_fixed<W>(writer: &mut W, value: f64, precision: u8)
+where
+    W: ?Sized + WriteExt + bytes::BufMut,
+{
+    let formatted = format!("{value:.*}", usize::from(precision));
+    writer.reserve(formatted.len());
+    unsafe {
+        writer.put_slice(formatted.as_bytes());
+    }
+}
+
+/// Write the non-finite `value` as a token of JSON5, for `OPT_JSON5_NONFINITE`.
+#[cold]
+#[inline(never)]
+fn format_nonfinite<W>(writer: &mut W, value: f64)
+where
+    W: ?Sized + WriteExt + bytes::BufMut,
+{
+    let token: &[u8] = if value.is_nan() {
+        b"NaN"
+    } else if value.is_sign_positive() {
+        b"Infinity"
+    } else {
+        b"-Infinity"
+    };
+    writer.reserve(token.len());
+    unsafe {
+        writer.put_slice(token);
+    }
+}
+
 #[inline]
-pub(crate) fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
+pub(crate) fn to_writer<W, T, const ESCAPE: bool>(
+    writer: W,
+    value: &T,
+    escape: StrEscape,
+    floats: FloatFormat,
+) -> Result<()>
 where
     W: WriteExt + bytes::BufMut,
     T: ?Sized + Serialize,
 {
-    let mut ser = Serializer::new(writer);
+    let mut ser = Serializer::<W, CompactFormatter, ESCAPE>::new(writer, escape, floats);
     value.serialize(&mut ser)
 }
 
 #[inline]
-pub(crate) fn to_writer_pretty<W, T>(writer: W, value: &T) -> Result<()>
+pub(crate) fn to_writer_pretty<W, T, const ESCAPE: bool>(
+    writer: W,
+    value: &T,
+    escape: StrEscape,
+    floats: FloatFormat,
+) -> Result<()>
 where
     W: WriteExt + bytes::BufMut,
     T: ?Sized + Serialize,
 {
-    let mut ser = Serializer::pretty(writer);
+    let mut ser = Serializer::<W, PrettyFormatter, ESCAPE>::pretty(writer, escape, floats);
     value.serialize(&mut ser)
 }
@@ -4,7 +4,10 @@
 mod byteswriter;
 mod formatter;
 mod json;
+mod pool;
 mod str;
 
 pub(crate) use byteswriter::{BytesWriter, WriteExt};
-pub(crate) use json::{set_str_formatter_fn, to_writer, to_writer_pretty};
+pub(crate) use json::{FloatFormat, set_str_formatter_fn, to_writer, to_writer_pretty};
+pub(crate) use pool::{BUFFER_POOL_MAX_SIZE, BufferPool};
+pub(crate) use str::{StrEscape, format_escaped_code_points};
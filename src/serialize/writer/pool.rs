@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+/// The number of buffers a `BufferPool` retains unless set by `configure()`.
+pub(crate) const BUFFER_POOL_SIZE: usize = 4;
+
+/// The greatest number of buffers `configure()` allows a pool to retain.
+pub(crate) const BUFFER_POOL_MAX_SIZE: usize = 1024;
+
+/// Buffers of a greater capacity are released rather than retained, so that
+/// one large document does not keep its memory allocated.
+const BUFFER_POOL_MAX_CAPACITY: usize = 64 * 1024;
+
+#[cfg(CPython)]
+pub(crate) type PooledBuffer = *mut crate::ffi::PyBytesObject;
+
+#[cfg(not(CPython))]
+pub(crate) type PooledBuffer = *mut u8;
+
+#[cfg(CPython)]
+pub(crate) fn release_buffer(buffer: PooledBuffer) {
+    ffi!(Py_DECREF(buffer.cast::<crate::ffi::PyObject>()));
+}
+
+#[cfg(not(CPython))]
+pub(crate) fn release_buffer(buffer: PooledBuffer) {
+    unsafe {
+        crate::ffi::PyMem_Free(buffer.cast::<core::ffi::c_void>());
+    }
+}
+
+/// Output buffers of `BytesWriter` kept for reuse by the next call. A buffer
+/// is returned to the pool only if its contents were copied out, e.g., by
+/// `dumps_str()` or on error, as the `bytes` returned by `dumps()` is the
+/// buffer itself. Each interpreter has its own, guarded by the GIL.
+pub(crate) struct BufferPool {
+    buffers: Vec<(PooledBuffer, usize)>,
+    size: usize,
+}
+
+impl BufferPool {
+    pub fn new() -> Self {
+        BufferPool {
+            buffers: Vec::with_capacity(BUFFER_POOL_SIZE),
+            size: BUFFER_POOL_SIZE,
+        }
+    }
+
+    /// Set the number of buffers retained, releasing any in excess.
+    pub fn set_size(&mut self, size: usize) {
+        self.size = size;
+        while self.buffers.len() > size {
+            release_buffer(self.buffers.pop().unwrap().0);
+        }
+    }
+
+    /// A buffer and its capacity, if one is available.
+    #[inline(always)]
+    pub fn take(&mut self) -> Option<(PooledBuffer, usize)> {
+        self.buffers.pop()
+    }
+
+    /// Retain `buffer` of capacity `cap`, or release it if the pool is full
+    /// or the buffer is too large.
+    #[inline]
+    pub fn give(&mut self, buffer: PooledBuffer, cap: usize) {
+        if self.buffers.len() < self.size && cap <= BUFFER_POOL_MAX_CAPACITY {
+            self.buffers.push((buffer, cap));
+        } else {
+            release_buffer(buffer);
+        }
+    }
+}
+
+impl Drop for BufferPool {
+    fn drop(&mut self) {
+        for (buffer, _) in self.buffers.drain(..) {
+            release_buffer(buffer);
+        }
+    }
+}
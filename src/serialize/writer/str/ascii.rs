@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+// Copyright ijl (2025)
+
+use crate::opt::{ENSURE_ASCII, ESCAPE_FORWARD_SLASH, Opt};
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// What `format_escaped_str_opts()` escapes beyond what JSON requires.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct StrEscape {
+    /// Escape every non-ASCII code point, for `OPT_ENSURE_ASCII`.
+    pub ascii: bool,
+    /// Escape `/` as `\/`, for `OPT_ESCAPE_FORWARD_SLASH`.
+    pub forward_slash: bool,
+}
+
+impl StrEscape {
+    pub const fn new(opts: Opt) -> Self {
+        Self {
+            ascii: opts & ENSURE_ASCII != 0,
+            forward_slash: opts & ESCAPE_FORWARD_SLASH != 0,
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn write_ascii_escaped(dst: *mut u8, byte: u8, escape: StrEscape) -> *mut u8 {
+    unsafe {
+        let mut dst = dst;
+        if *super::escape::NEED_ESCAPED.get_unchecked(usize::from(byte)) != 0 {
+            write_escape!(byte, dst);
+        } else if byte == b'/' && escape.forward_slash {
+            core::ptr::copy_nonoverlapping(b"\\/".as_ptr(), dst, 2);
+            dst = dst.add(2);
+        } else {
+            core::ptr::write(dst, byte);
+            dst = dst.add(1);
+        }
+        dst
+    }
+}
+
+#[inline(always)]
+unsafe fn write_u_escape(dst: *mut u8, val: u16) -> *mut u8 {
+    unsafe {
+        core::ptr::copy_nonoverlapping(b"\\u".as_ptr(), dst, 2);
+        core::ptr::write(dst.add(2), HEX[usize::from(val >> 12)]);
+        core::ptr::write(dst.add(3), HEX[usize::from((val >> 8) & 15)]);
+        core::ptr::write(dst.add(4), HEX[usize::from((val >> 4) & 15)]);
+        core::ptr::write(dst.add(5), HEX[usize::from(val & 15)]);
+        dst.add(6)
+    }
+}
+
+/// Like the other `format_escaped_str` implementations, but also escapes as
+/// given by `escape`: every non-ASCII code point as `\uXXXX`, using a
+/// surrogate pair for code points outside the BMP, and `/` as `\/`. The
+/// output is at most six times the length of the input.
+#[inline(never)]
+pub(crate) unsafe fn format_escaped_str_opts(
+    odst: *mut u8,
+    value: &str,
+    escape: StrEscape,
+) -> usize {
+    unsafe {
+        let mut dst = odst;
+
+        core::ptr::write(dst, b'"');
+        dst = dst.add(1);
+
+        for ch in value.chars() {
+            if ch.is_ascii() {
+                #[allow(clippy::cast_possible_truncation)]
+                let byte = ch as u8;
+                dst = write_ascii_escaped(dst, byte, escape);
+            } else if escape.ascii {
+                let mut units = [0u16; 2];
+                for unit in ch.encode_utf16(&mut units) {
+                    dst = write_u_escape(dst, *unit);
+                }
+            } else {
+                let len = ch
+                    .encode_utf8(core::slice::from_raw_parts_mut(dst, 4))
+                    .len();
+                dst = dst.add(len);
+            }
+        }
+
+        core::ptr::write(dst, b'"');
+        dst = dst.add(1);
+
+        dst as usize - odst as usize
+    }
+}
+
+/// Format the code points of a `str` that contains surrogates, and so is not
+/// valid UTF-8, escaping each surrogate as `\uXXXX`. Other code points are
+/// escaped as by `format_escaped_str_opts()`. The output is at most twelve
+/// bytes per code point and two more.
+#[cold]
+#[inline(never)]
+pub(crate) unsafe fn format_escaped_code_points(
+    odst: *mut u8,
+    code_points: impl Iterator<Item = u32>,
+    escape: StrEscape,
+) -> usize {
+    unsafe {
+        let mut dst = odst;
+
+        core::ptr::write(dst, b'"');
+        dst = dst.add(1);
+
+        for val in code_points {
+            match char::from_u32(val) {
+                Some(ch) if ch.is_ascii() => {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let byte = ch as u8;
+                    dst = write_ascii_escaped(dst, byte, escape);
+                }
+                Some(ch) if escape.ascii => {
+                    let mut units = [0u16; 2];
+                    for unit in ch.encode_utf16(&mut units) {
+                        dst = write_u_escape(dst, *unit);
+                    }
+                }
+                Some(ch) => {
+                    let len = ch
+                        .encode_utf8(core::slice::from_raw_parts_mut(dst, 4))
+                        .len();
+                    dst = dst.add(len);
+                }
+                None => {
+                    // a surrogate, i.e., in 0xD800..=0xDFFF
+                    #[allow(clippy::cast_possible_truncation)]
+                    let unit = val as u16;
+                    dst = write_u_escape(dst, unit);
+                }
+            }
+        }
+
+        core::ptr::write(dst, b'"');
+        dst = dst.add(1);
+
+        dst as usize - odst as usize
+    }
+}
@@ -6,6 +6,8 @@ mod escape;
 #[macro_use]
 mod scalar;
 
+mod ascii;
+
 #[cfg(all(feature = "generic_simd", not(target_arch = "x86_64")))]
 mod generic;
 
@@ -15,6 +17,8 @@ mod sse2;
 #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
 mod avx512;
 
+pub(crate) use ascii::{StrEscape, format_escaped_code_points, format_escaped_str_opts};
+
 #[cfg(all(not(target_arch = "x86_64"), not(feature = "generic_simd")))]
 pub(crate) use scalar::format_escaped_str_scalar;
 
@@ -3,115 +3,40 @@
 
 use core::ffi::CStr;
 use core::ptr::{NonNull, null_mut};
+use core::sync::atomic::{AtomicPtr, Ordering};
 use once_cell::race::OnceBox;
-use std::sync::OnceLock;
+use std::sync::Once;
 
 use crate::ffi::{
-    Py_DECREF, Py_False, Py_INCREF, Py_None, Py_True, Py_XDECREF, PyBool_Type, PyByteArray_Type,
-    PyBytes_Type, PyDict_Type, PyErr_Clear, PyErr_NewException, PyExc_TypeError, PyFloat_Type,
-    PyImport_ImportModule, PyList_Type, PyLong_Type, PyMapping_GetItemString, PyMemoryView_Type,
-    PyObject, PyObject_GenericGetDict, PyTuple_Type, PyTypeObject, PyUnicode_InternFromString,
-    PyUnicode_New, PyUnicode_Type, orjson_fragmenttype_new,
+    Py_XDECREF, PyErr_Clear, PyImport_ImportModule, PyMapping_GetItemString, PyObject,
+    PyObject_GenericGetDict, PyTypeObject,
 };
 
-pub(crate) static mut DEFAULT: *mut PyObject = null_mut();
-pub(crate) static mut OPTION: *mut PyObject = null_mut();
-
-pub(crate) static mut NONE: *mut PyObject = null_mut();
-pub(crate) static mut TRUE: *mut PyObject = null_mut();
-pub(crate) static mut FALSE: *mut PyObject = null_mut();
-pub(crate) static mut EMPTY_UNICODE: *mut PyObject = null_mut();
-
-pub(crate) static mut BYTES_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut BYTEARRAY_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut MEMORYVIEW_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut STR_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut INT_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut BOOL_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut NONE_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut FLOAT_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut LIST_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut DICT_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut DATETIME_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut DATE_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut TIME_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut TUPLE_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut UUID_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut ENUM_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut FIELD_TYPE: *mut PyTypeObject = null_mut();
-pub(crate) static mut FRAGMENT_TYPE: *mut PyTypeObject = null_mut();
-
-pub(crate) static mut ZONEINFO_TYPE: *mut PyTypeObject = null_mut();
-
-pub(crate) static mut UTCOFFSET_METHOD_STR: *mut PyObject = null_mut();
-pub(crate) static mut NORMALIZE_METHOD_STR: *mut PyObject = null_mut();
-pub(crate) static mut CONVERT_METHOD_STR: *mut PyObject = null_mut();
-pub(crate) static mut DST_STR: *mut PyObject = null_mut();
-
-pub(crate) static mut DICT_STR: *mut PyObject = null_mut();
-pub(crate) static mut DATACLASS_FIELDS_STR: *mut PyObject = null_mut();
-pub(crate) static mut SLOTS_STR: *mut PyObject = null_mut();
-pub(crate) static mut FIELD_TYPE_STR: *mut PyObject = null_mut();
-pub(crate) static mut ARRAY_STRUCT_STR: *mut PyObject = null_mut();
-pub(crate) static mut DTYPE_STR: *mut PyObject = null_mut();
-pub(crate) static mut DESCR_STR: *mut PyObject = null_mut();
-pub(crate) static mut VALUE_STR: *mut PyObject = null_mut();
-pub(crate) static mut INT_ATTR_STR: *mut PyObject = null_mut();
-
-#[allow(non_upper_case_globals)]
-pub(crate) static mut JsonEncodeError: *mut PyObject = null_mut();
-#[allow(non_upper_case_globals)]
-pub(crate) static mut JsonDecodeError: *mut PyObject = null_mut();
-
-unsafe fn look_up_type_object(module_name: &CStr, member_name: &CStr) -> *mut PyTypeObject {
-    unsafe {
-        let module = PyImport_ImportModule(module_name.as_ptr());
-        let module_dict = PyObject_GenericGetDict(module, null_mut());
-        let ptr = PyMapping_GetItemString(module_dict, member_name.as_ptr()).cast::<PyTypeObject>();
-        Py_DECREF(module_dict);
-        Py_DECREF(module);
-        ptr
-    }
-}
-
-#[cfg(not(PyPy))]
-unsafe fn look_up_datetime() {
-    unsafe {
-        crate::ffi::PyDateTime_IMPORT();
-        let datetime_capsule = crate::ffi::PyCapsule_Import(c"datetime.datetime_CAPI".as_ptr(), 1)
-            .cast::<crate::ffi::PyDateTime_CAPI>();
-        debug_assert!(!datetime_capsule.is_null());
-
-        DATETIME_TYPE = (*datetime_capsule).DateTimeType;
-        DATE_TYPE = (*datetime_capsule).DateType;
-        TIME_TYPE = (*datetime_capsule).TimeType;
-        ZONEINFO_TYPE = (*datetime_capsule).TZInfoType;
-    }
-}
-
-#[cfg(PyPy)]
-unsafe fn look_up_datetime() {
-    unsafe {
-        DATETIME_TYPE = look_up_type_object(c"datetime", c"datetime");
-        DATE_TYPE = look_up_type_object(c"datetime", c"date");
-        TIME_TYPE = look_up_type_object(c"datetime", c"time");
-        ZONEINFO_TYPE = look_up_type_object(c"zoneinfo", c"ZoneInfo");
-    }
-}
-
-// Legacy initialization - now handled by interpreter_state
-// Kept for compatibility during transition
+/// Select the string functions for the CPU. This is process-wide and done
+/// once, by the first interpreter to import the module.
 pub(crate) fn init_typerefs() {
-    // No-op - initialization now happens per-interpreter in interpreter_state
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        crate::serialize::writer::set_str_formatter_fn();
+        crate::str::set_str_create_fn();
+    });
 }
 
 // Accessor macros that use interpreter state instead of static variables
 // These provide a drop-in replacement for the old static variables
+//
+// Entry points (dumps, loads, Fragment) check that the state resolves and
+// raise RuntimeError otherwise, so by the time an accessor runs the state is
+// cached for the thread. The unwrap panics rather than dereferencing null.
 
 #[macro_export]
 macro_rules! get_state {
     () => {
-        unsafe { crate::interpreter_state::get_current_state().as_ref().unwrap() }
+        unsafe {
+            $crate::interpreter_state::get_current_state()
+                .as_ref()
+                .unwrap()
+        }
     };
 }
 
@@ -126,6 +51,86 @@ pub(crate) fn get_option() -> *mut PyObject {
     unsafe { get_state!().option }
 }
 
+#[inline(always)]
+pub(crate) fn get_envelope() -> *mut PyObject {
+    unsafe { get_state!().envelope }
+}
+
+#[inline(always)]
+pub(crate) fn get_tuple_fields() -> *mut PyObject {
+    unsafe { get_state!().tuple_fields }
+}
+
+#[inline(always)]
+pub(crate) fn get_max_output_bytes() -> *mut PyObject {
+    unsafe { get_state!().max_output_bytes }
+}
+
+#[inline(always)]
+pub(crate) fn get_float_precision() -> *mut PyObject {
+    unsafe { get_state!().float_precision }
+}
+
+#[inline(always)]
+pub(crate) fn get_on_skip() -> *mut PyObject {
+    unsafe { get_state!().on_skip }
+}
+
+#[inline(always)]
+pub(crate) fn get_skip_key_prefix() -> *mut PyObject {
+    unsafe { get_state!().skip_key_prefix }
+}
+
+#[inline(always)]
+pub(crate) fn get_chunk_size() -> *mut PyObject {
+    unsafe { get_state!().chunk_size }
+}
+
+#[inline(always)]
+pub(crate) fn get_size_hint() -> *mut PyObject {
+    unsafe { get_state!().size_hint }
+}
+
+#[inline(always)]
+pub(crate) fn get_key_filter() -> *mut PyObject {
+    unsafe { get_state!().key_filter }
+}
+
+#[inline(always)]
+pub(crate) fn get_object_hook() -> *mut PyObject {
+    unsafe { get_state!().object_hook }
+}
+
+#[inline(always)]
+pub(crate) fn get_object_pairs_hook() -> *mut PyObject {
+    unsafe { get_state!().object_pairs_hook }
+}
+
+#[inline(always)]
+pub(crate) fn get_bytes_mode_str() -> *mut PyObject {
+    unsafe { get_state!().bytes_mode_str }
+}
+
+#[inline(always)]
+pub(crate) fn get_buffer_pool_size_str() -> *mut PyObject {
+    unsafe { get_state!().buffer_pool_size_str }
+}
+
+#[inline(always)]
+pub(crate) fn get_reset_str() -> *mut PyObject {
+    unsafe { get_state!().reset_str }
+}
+
+#[inline(always)]
+pub(crate) fn get_default_opts() -> crate::opt::Opt {
+    unsafe { get_state!().default_opts.load(Ordering::Relaxed) }
+}
+
+#[inline(always)]
+pub(crate) fn get_type_handlers() -> *mut PyObject {
+    unsafe { get_state!().type_handlers }
+}
+
 #[inline(always)]
 pub(crate) fn get_none() -> *mut PyObject {
     unsafe { get_state!().none }
@@ -161,14 +166,25 @@ pub(crate) fn get_fragment_type() -> *mut PyTypeObject {
     unsafe { get_state!().fragment_type }
 }
 
+#[inline(always)]
+pub(crate) fn get_options_type() -> *mut PyTypeObject {
+    unsafe { get_state!().options_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_array_stream_type() -> *mut PyTypeObject {
+    unsafe { get_state!().array_stream_type }
+}
+
 #[inline(always)]
 pub(crate) fn get_json_encode_error() -> *mut PyObject {
     unsafe { get_state!().json_encode_error }
 }
 
+/// Returns null with an exception set if it cannot be created.
 #[inline(always)]
 pub(crate) fn get_json_decode_error() -> *mut PyObject {
-    unsafe { get_state!().json_decode_error }
+    unsafe { get_state!().json_decode_error() }
 }
 
 // Additional accessors for string constants
@@ -228,6 +244,11 @@ pub(crate) fn get_time_type() -> *mut PyTypeObject {
     unsafe { get_state!().time_type }
 }
 
+#[inline(always)]
+pub(crate) fn get_timezone_type() -> *mut PyTypeObject {
+    unsafe { get_state!().timezone_type }
+}
+
 #[inline(always)]
 pub(crate) fn get_uuid_type() -> *mut PyTypeObject {
     unsafe { get_state!().uuid_type }
@@ -245,7 +266,22 @@ pub(crate) fn get_field_type() -> *mut PyTypeObject {
 
 #[inline(always)]
 pub(crate) fn get_zoneinfo_type() -> *mut PyTypeObject {
-    unsafe { get_state!().zoneinfo_type }
+    unsafe { get_state!().zoneinfo_type.load(Ordering::Relaxed) }
+}
+
+#[inline(always)]
+pub(crate) fn get_pandas_nat_type() -> *mut PyTypeObject {
+    unsafe { get_state!().pandas_nat_type.load(Ordering::Relaxed) }
+}
+
+#[inline(always)]
+pub(crate) fn get_ipaddress_types() -> &'static [AtomicPtr<PyTypeObject>; 4] {
+    unsafe { &get_state!().ipaddress_types }
+}
+
+#[inline(always)]
+pub(crate) fn get_fraction_type() -> *mut PyTypeObject {
+    unsafe { get_state!().fraction_type.load(Ordering::Relaxed) }
 }
 
 #[inline(always)]
@@ -263,6 +299,66 @@ pub(crate) fn get_memoryview_type() -> *mut PyTypeObject {
     unsafe { get_state!().memoryview_type }
 }
 
+#[inline(always)]
+pub(crate) fn get_chainmap_type() -> *mut PyTypeObject {
+    unsafe { get_state!().chainmap_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_mappingproxy_type() -> *mut PyTypeObject {
+    unsafe { get_state!().mappingproxy_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_property_type() -> *mut PyTypeObject {
+    unsafe { get_state!().property_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_range_type() -> *mut PyTypeObject {
+    unsafe { get_state!().range_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_simple_namespace_type() -> *mut PyTypeObject {
+    unsafe { get_state!().simple_namespace_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_weakref_type() -> *mut PyTypeObject {
+    unsafe { get_state!().weakref_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_weakproxy_type() -> *mut PyTypeObject {
+    unsafe { get_state!().weakproxy_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_weakcallableproxy_type() -> *mut PyTypeObject {
+    unsafe { get_state!().weakcallableproxy_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_array_type() -> *mut PyTypeObject {
+    unsafe { get_state!().array_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_dict_keys_type() -> *mut PyTypeObject {
+    unsafe { get_state!().dict_keys_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_dict_values_type() -> *mut PyTypeObject {
+    unsafe { get_state!().dict_values_type }
+}
+
+#[inline(always)]
+pub(crate) fn get_dict_items_type() -> *mut PyTypeObject {
+    unsafe { get_state!().dict_items_type }
+}
+
 // String constant accessors
 #[inline(always)]
 pub(crate) fn get_utcoffset_method_str() -> *mut PyObject {
@@ -279,6 +375,11 @@ pub(crate) fn get_convert_method_str() -> *mut PyObject {
     unsafe { get_state!().convert_method_str }
 }
 
+#[inline(always)]
+pub(crate) fn get_write_method_str() -> *mut PyObject {
+    unsafe { get_state!().write_method_str }
+}
+
 #[inline(always)]
 pub(crate) fn get_dst_str() -> *mut PyObject {
     unsafe { get_state!().dst_str }
@@ -289,6 +390,16 @@ pub(crate) fn get_dict_str() -> *mut PyObject {
     unsafe { get_state!().dict_str }
 }
 
+#[inline(always)]
+pub(crate) fn get_json_dunder_str() -> *mut PyObject {
+    unsafe { get_state!().json_dunder_str }
+}
+
+#[inline(always)]
+pub(crate) fn get_fspath_str() -> *mut PyObject {
+    unsafe { get_state!().fspath_str }
+}
+
 #[inline(always)]
 pub(crate) fn get_dataclass_fields_str() -> *mut PyObject {
     unsafe { get_state!().dataclass_fields_str }
@@ -319,74 +430,6 @@ pub(crate) fn get_descr_str() -> *mut PyObject {
     unsafe { get_state!().descr_str }
 }
 
-#[cold]
-#[cfg_attr(feature = "optimize", optimize(size))]
-fn _init_typerefs_impl() -> bool {
-    unsafe {
-        debug_assert!(crate::opt::MAX_OPT < i32::from(u16::MAX));
-
-        // KEY_MAP is now per-interpreter, initialized in InterpreterState::new()
-
-        crate::serialize::writer::set_str_formatter_fn();
-        crate::str::set_str_create_fn();
-
-        NONE = Py_None();
-        TRUE = Py_True();
-        FALSE = Py_False();
-        EMPTY_UNICODE = PyUnicode_New(0, 255);
-
-        STR_TYPE = &raw mut PyUnicode_Type;
-        BYTES_TYPE = &raw mut PyBytes_Type;
-        DICT_TYPE = &raw mut PyDict_Type;
-        LIST_TYPE = &raw mut PyList_Type;
-        TUPLE_TYPE = &raw mut PyTuple_Type;
-        NONE_TYPE = ob_type!(NONE);
-        BOOL_TYPE = &raw mut PyBool_Type;
-        INT_TYPE = &raw mut PyLong_Type;
-        FLOAT_TYPE = &raw mut PyFloat_Type;
-        BYTEARRAY_TYPE = &raw mut PyByteArray_Type;
-        MEMORYVIEW_TYPE = &raw mut PyMemoryView_Type;
-
-        look_up_datetime();
-
-        UUID_TYPE = look_up_type_object(c"uuid", c"UUID");
-        ENUM_TYPE = look_up_type_object(c"enum", c"EnumMeta");
-        FIELD_TYPE = look_up_type_object(c"dataclasses", c"_FIELD");
-
-        FRAGMENT_TYPE = orjson_fragmenttype_new();
-
-        INT_ATTR_STR = PyUnicode_InternFromString(c"int".as_ptr());
-        UTCOFFSET_METHOD_STR = PyUnicode_InternFromString(c"utcoffset".as_ptr());
-        NORMALIZE_METHOD_STR = PyUnicode_InternFromString(c"normalize".as_ptr());
-        CONVERT_METHOD_STR = PyUnicode_InternFromString(c"convert".as_ptr());
-        DST_STR = PyUnicode_InternFromString(c"dst".as_ptr());
-        DICT_STR = PyUnicode_InternFromString(c"__dict__".as_ptr());
-        DATACLASS_FIELDS_STR = PyUnicode_InternFromString(c"__dataclass_fields__".as_ptr());
-        SLOTS_STR = PyUnicode_InternFromString(c"__slots__".as_ptr());
-        FIELD_TYPE_STR = PyUnicode_InternFromString(c"_field_type".as_ptr());
-        ARRAY_STRUCT_STR = PyUnicode_InternFromString(c"__array_struct__".as_ptr());
-        DTYPE_STR = PyUnicode_InternFromString(c"dtype".as_ptr());
-        DESCR_STR = PyUnicode_InternFromString(c"descr".as_ptr());
-        VALUE_STR = PyUnicode_InternFromString(c"value".as_ptr());
-        DEFAULT = PyUnicode_InternFromString(c"default".as_ptr());
-        OPTION = PyUnicode_InternFromString(c"option".as_ptr());
-
-        JsonEncodeError = PyExc_TypeError;
-        Py_INCREF(JsonEncodeError);
-        let json_jsondecodeerror =
-            look_up_type_object(c"json", c"JSONDecodeError").cast::<PyObject>();
-        debug_assert!(!json_jsondecodeerror.is_null());
-        JsonDecodeError = PyErr_NewException(
-            c"hyperjson.JSONDecodeError".as_ptr(),
-            json_jsondecodeerror,
-            null_mut(),
-        );
-        debug_assert!(!JsonDecodeError.is_null());
-        Py_XDECREF(json_jsondecodeerror);
-    };
-    true
-}
-
 pub(crate) struct NumpyTypes {
     pub array: *mut PyTypeObject,
     pub float64: *mut PyTypeObject,
@@ -402,6 +445,7 @@ pub(crate) struct NumpyTypes {
     pub uint8: *mut PyTypeObject,
     pub bool_: *mut PyTypeObject,
     pub datetime64: *mut PyTypeObject,
+    pub masked_array: *mut PyTypeObject,
 }
 
 pub(crate) static mut NUMPY_TYPES: OnceBox<Option<NonNull<NumpyTypes>>> = OnceBox::new();
@@ -417,6 +461,42 @@ unsafe fn look_up_numpy_type(
     }
 }
 
+/// `numpy.ma.MaskedArray`, or null if `numpy.ma` cannot be imported.
+unsafe fn look_up_masked_array() -> *mut PyTypeObject {
+    unsafe {
+        let numpy_ma = PyImport_ImportModule(c"numpy.ma".as_ptr());
+        if numpy_ma.is_null() {
+            PyErr_Clear();
+            return null_mut();
+        }
+        let numpy_ma_dict = PyObject_GenericGetDict(numpy_ma, null_mut());
+        let ptr = look_up_numpy_type(numpy_ma_dict, c"MaskedArray");
+        Py_XDECREF(numpy_ma_dict);
+        Py_XDECREF(numpy_ma);
+        ptr
+    }
+}
+
+/// The numpy types, importing numpy on the first call in the process, or
+/// `None` if it cannot be imported or `disable_numpy()` was called in the
+/// current interpreter, in which case it is not imported.
+pub(crate) fn get_numpy_types() -> &'static Option<NonNull<NumpyTypes>> {
+    if unsafe { get_state!().numpy_disabled.load(Ordering::Relaxed) } {
+        return &None;
+    }
+    unsafe { NUMPY_TYPES.get_or_init(load_numpy_types) }
+}
+
+/// Forget the numpy types so that numpy is imported again on the next call,
+/// after Python is finalized. The types of the previous initialization are
+/// leaked.
+#[cold]
+pub(crate) unsafe fn reset_numpy_types() {
+    unsafe {
+        NUMPY_TYPES = OnceBox::new();
+    }
+}
+
 #[cold]
 #[cfg_attr(feature = "optimize", optimize(size))]
 pub(crate) fn load_numpy_types() -> Box<Option<NonNull<NumpyTypes>>> {
@@ -442,6 +522,7 @@ pub(crate) fn load_numpy_types() -> Box<Option<NonNull<NumpyTypes>>> {
             uint8: look_up_numpy_type(numpy_module_dict, c"uint8"),
             bool_: look_up_numpy_type(numpy_module_dict, c"bool_"),
             datetime64: look_up_numpy_type(numpy_module_dict, c"datetime64"),
+            masked_array: look_up_masked_array(),
         });
         Py_XDECREF(numpy_module_dict);
         Py_XDECREF(numpy);
@@ -90,6 +90,19 @@ macro_rules! cold_path {
     };
 }
 
+// Panics in a debug build if the calling thread does not hold the GIL, e.g.,
+// a call through `ctypes.CDLL`, which releases it. `PyGILState_Check()` is
+// not used as it always succeeds once subinterpreters have been created.
+macro_rules! debug_assert_gil_held {
+    ($name:expr) => {
+        debug_assert!(
+            unsafe { crate::ffi::thread_state_is_attached() },
+            "{}() called without holding the GIL",
+            $name
+        );
+    };
+}
+
 macro_rules! nonnull {
     ($exp:expr) => {
         unsafe { core::ptr::NonNull::new_unchecked($exp) }
@@ -1,15 +1,28 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2018-2025), hauntsaninja (2020)
 
+import ctypes
+import dataclasses
 import datetime
+import enum
+import gc
 import inspect
+import io
 import json
+import operator
+import os
 import re
+import subprocess
+import sys
+import textwrap
+import uuid
 
 import pytest
 
 import orjson
 
+from .util import IS_FREETHREADING, numpy, run_in_subinterpreter
+
 SIMPLE_TYPES = (1, 1.0, -1, None, "str", True, False)
 
 LOADS_RECURSION_LIMIT = 1024
@@ -19,6 +32,130 @@ def default(obj):
     return str(obj)
 
 
+OBJ = {
+    "b": [1, 2.5, None],
+    "a": datetime.datetime(2020, 1, 2, 3, 4, 5, 678),
+    "c": b"\x00\xff",
+}
+
+
+DT = datetime.datetime(1970, 1, 1, 0, 0, 0, 1)
+
+
+DUMPS_INTO_ERROR = -1
+DUMPS_INTO_BUFFER_TOO_SMALL = -2
+
+
+def dumps_into_fn():
+    # the extension module, which may be a submodule of the package
+    lib = ctypes.PyDLL(sys.modules[orjson.dumps.__module__].__file__)
+    fn = lib.orjson_dumps_into
+    fn.argtypes = (ctypes.py_object, ctypes.c_void_p, ctypes.c_size_t, ctypes.c_uint64)
+    fn.restype = ctypes.c_ssize_t
+    return fn
+
+
+def dumps_into(obj, capacity, option=0):
+    buf = ctypes.create_string_buffer(capacity)
+    ret = dumps_into_fn()(obj, ctypes.cast(buf, ctypes.c_void_p), capacity, option)
+    return ret, buf.raw[: max(ret, 0)]
+
+
+class ShortWriter:
+    """
+    Accepts at most `limit` bytes per call and returns the count accepted,
+    as a non-blocking socket does.
+    """
+
+    def __init__(self, limit):
+        self.limit = limit
+        self.calls = []
+
+    def write(self, data):
+        accepted = bytes(data[: self.limit])
+        self.calls.append(accepted)
+        return len(accepted)
+
+    def getvalue(self):
+        return b"".join(self.calls)
+
+
+class ChunkWriter:
+    """
+    Records each chunk given to write().
+    """
+
+    def __init__(self):
+        self.calls = []
+
+    def write(self, data):
+        self.calls.append(bytes(data))
+
+    def getvalue(self):
+        return b"".join(self.calls)
+
+
+DATA = {"items": [{"id": i, "name": f"item {i}", "é": "\\"} for i in range(10_000)]}
+
+
+CHECK = orjson.OPT_SELF_CHECK
+
+SELF_CHECK = orjson.build_info()["self_check"]
+
+needs_self_check = pytest.mark.skipif(
+    not SELF_CHECK, reason="OPT_SELF_CHECK has no effect in this build"
+)
+
+
+class Color(enum.Enum):
+    RED = "red"
+    GREEN = 2
+
+
+class Level(enum.IntEnum):
+    LOW = 1
+
+
+@dataclasses.dataclass
+class Point:
+    x: float
+    y: float
+
+
+DEBUG = orjson.build_info()["debug"]
+
+SCRIPT = textwrap.dedent(
+    """
+    import ctypes
+    import sys
+
+    import orjson
+
+    path = sys.modules[orjson.dumps.__module__].__file__
+    # ctypes.CDLL releases the GIL for the duration of the call
+    lib = ctypes.CDLL(path) if sys.argv[1] == "released" else ctypes.PyDLL(path)
+    fn = lib.orjson_dumps_into
+    fn.argtypes = (ctypes.py_object, ctypes.c_char_p, ctypes.c_size_t, ctypes.c_uint64)
+    fn.restype = ctypes.c_ssize_t
+    buf = ctypes.create_string_buffer(16)
+    print(fn({"a": 1}, buf, 16, 0), flush=True)
+    """
+)
+
+
+def _run(arg):
+    env = dict(os.environ)
+    env["PYTHONPATH"] = os.pathsep.join(path for path in sys.path if path)
+    return subprocess.run(
+        [sys.executable, "-c", SCRIPT, arg],
+        capture_output=True,
+        check=False,
+        env=env,
+        text=True,
+        timeout=60,
+    )
+
+
 class TestApi:
     def test_loads_trailing(self):
         """
@@ -129,6 +266,32 @@ class TestApi:
         """
         assert re.match(r"^\d+\.\d+(\.\d+)?$", orjson.__version__)
 
+    def test_build_info(self):
+        """
+        build_info() keys and types
+        """
+        info = orjson.build_info()
+        assert set(info) == {
+            "version",
+            "debug",
+            "gil_disabled",
+            "key_cache",
+            "numpy_available",
+            "self_check",
+            "subinterpreter_support",
+        }
+        assert info["version"] == orjson.__version__
+        for key in set(info) - {"version"}:
+            assert isinstance(info[key], bool)
+        assert info["gil_disabled"] == bool(IS_FREETHREADING)
+        assert info["key_cache"] is not info["gil_disabled"]
+        if numpy is not None:
+            assert info["numpy_available"] is True
+        if sys.version_info < (3, 12):
+            assert info["subinterpreter_support"] is False
+        info["version"] = None
+        assert orjson.build_info()["version"] == orjson.__version__
+
     def test_valueerror(self):
         """
         orjson.JSONDecodeError is a subclass of ValueError
@@ -171,7 +334,7 @@ class TestApi:
         dumps() option out of range high
         """
         with pytest.raises(orjson.JSONEncodeError):
-            orjson.dumps(True, option=1 << 12)
+            orjson.dumps(True, option=1 << 50)
 
     def test_opts_multiple(self):
         """
@@ -245,19 +408,84 @@ class TestApi:
         """
         assert (
             str(inspect.signature(orjson.dumps))
-            == "(obj, /, default=None, option=None)"
+            == "(obj, /, default=None, option=None, *, envelope=None, "
+            "tuple_fields=None, max_output_bytes=None, key_filter=None, "
+            "float_precision=None, on_skip=None, "
+            "skip_key_prefix=None, size_hint=None)"
         )
         inspect.signature(orjson.dumps).bind("str")
         inspect.signature(orjson.dumps).bind("str", default=default, option=1)
         inspect.signature(orjson.dumps).bind("str", default=None, option=None)
+        inspect.signature(orjson.dumps).bind("str", envelope="data")
+        inspect.signature(orjson.dumps).bind((1,), tuple_fields=["a"])
 
     def test_loads_signature(self):
         """
         loads() valid __text_signature__
         """
-        assert str(inspect.signature(orjson.loads)), "(obj == /)"
+        assert str(inspect.signature(orjson.loads)) == (
+            "(obj, /, option=None, *, object_hook=None, object_pairs_hook=None)"
+        )
         inspect.signature(orjson.loads).bind("[]")
 
+    def test_loads_option(self):
+        """
+        loads() option positional, keyword, or None
+        """
+        assert orjson.loads("{}", orjson.OPT_FORBID_DUPLICATE_KEYS) == {}
+        assert orjson.loads("{}", option=orjson.OPT_FORBID_DUPLICATE_KEYS) == {}
+        assert orjson.loads("{}", option=None) == {}
+
+    def test_loads_option_invalid(self):
+        """
+        loads() invalid option raises TypeError
+        """
+        for val in (1 << 50, -1, 1.0, "1"):
+            with pytest.raises(TypeError):
+                orjson.loads("{}", option=val)  # type: ignore
+
+    def test_loads_option_serialize_only(self):
+        """
+        loads() option for serializing raises TypeError
+        """
+        for val in (
+            orjson.OPT_INDENT_2,
+            orjson.OPT_SORT_KEYS,
+            orjson.OPT_BIG_NUMBER_AS_STRING,
+            orjson.OPT_PARSE_RELAXED | orjson.OPT_NON_STR_KEYS,
+            orjson.Options(orjson.OPT_INDENT_2),
+        ):
+            with pytest.raises(TypeError):
+                orjson.loads("{}", option=val)
+            with pytest.raises(TypeError):
+                orjson.loads_many(["{}"], option=val)
+            with pytest.raises(TypeError):
+                orjson.loads_array_stream("[]", option=val)
+
+    def test_loads_option_default_serialize_only(self):
+        """
+        loads() ignores flags for serializing from set_default_options()
+        """
+        orjson.set_default_options(orjson.OPT_SORT_KEYS | orjson.OPT_INDENT_2)
+        try:
+            assert orjson.loads('{"b":1,"a":2}') == {"b": 1, "a": 2}
+            assert orjson.loads("[1,]", option=orjson.OPT_PARSE_RELAXED) == [1]
+        finally:
+            orjson.set_default_options(None)
+
+    def test_loads_args_invalid(self):
+        """
+        loads() invalid arguments raise TypeError
+        """
+        with pytest.raises(TypeError):
+            orjson.loads()  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.loads("{}", 0, 0)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.loads("{}", 0, option=0)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.loads("{}", opt=0)  # type: ignore
+
     def test_dumps_module_str(self):
         """
         orjson.dumps.__module__ is a str
@@ -285,3 +513,1365 @@ class TestApi:
         """
         # would raise ValueError: invalid literal for int() with base 10: b'1596728892'
         int(orjson.dumps(1596728892))
+
+
+class TestOptions:
+    def test_options_empty(self):
+        """
+        Options() is no options
+        """
+        assert int(orjson.Options()) == 0
+        assert orjson.dumps(OBJ, option=orjson.Options(), default=list) == orjson.dumps(
+            OBJ, default=list
+        )
+
+    def test_options_int(self):
+        """
+        int() and operator.index() give the equivalent bitmask
+        """
+        opts = orjson.Options(orjson.OPT_SORT_KEYS, orjson.OPT_INDENT_2)
+        assert int(opts) == orjson.OPT_SORT_KEYS | orjson.OPT_INDENT_2
+        assert operator.index(opts) == orjson.OPT_SORT_KEYS | orjson.OPT_INDENT_2
+        assert repr(opts) == f"orjson.Options({orjson.OPT_SORT_KEYS | orjson.OPT_INDENT_2})"
+
+    def test_options_dumps(self):
+        """
+        dumps() with Options matches the equivalent bitmask
+        """
+        presets = (
+            (orjson.OPT_SORT_KEYS,),
+            (orjson.OPT_SORT_KEYS, orjson.OPT_INDENT_2),
+            (orjson.OPT_OMIT_MICROSECONDS, orjson.OPT_NAIVE_UTC, orjson.OPT_UTC_Z),
+            (orjson.OPT_SERIALIZE_BYTES_HEX, orjson.OPT_APPEND_NEWLINE),
+            (orjson.OPT_SERIALIZE_BYTES_BASE64 | orjson.OPT_SORT_KEYS,),
+        )
+        for flags in presets:
+            bitmask = 0
+            for flag in flags:
+                bitmask |= flag
+            opts = orjson.Options(*flags)
+            for dumps in (orjson.dumps, orjson.dumps_str):
+                assert dumps(OBJ, option=opts, default=list) == dumps(
+                    OBJ, option=bitmask, default=list
+                )
+            assert orjson.dumps(OBJ, list, opts) == orjson.dumps(OBJ, list, bitmask)
+
+    def test_options_fluent(self):
+        """
+        with_option() and without_option() return a copy
+        """
+        base = orjson.Options(orjson.OPT_SORT_KEYS)
+        pretty = base.with_option(orjson.OPT_INDENT_2)
+        assert int(base) == orjson.OPT_SORT_KEYS
+        assert int(pretty) == orjson.OPT_SORT_KEYS | orjson.OPT_INDENT_2
+        assert int(pretty.without_option(orjson.OPT_SORT_KEYS)) == orjson.OPT_INDENT_2
+        assert int(pretty.without_option(pretty)) == 0
+        assert int(base.without_option(orjson.OPT_INDENT_2)) == orjson.OPT_SORT_KEYS
+        assert int(base.with_option(orjson.Options(orjson.OPT_UTC_Z))) == (
+            orjson.OPT_SORT_KEYS | orjson.OPT_UTC_Z
+        )
+        assert orjson.dumps({"b": 1, "a": 2}, option=pretty) == b'{\n  "a": 2,\n  "b": 1\n}'
+
+    def test_options_or(self):
+        """
+        | with an int or Options returns Options
+        """
+        opts = orjson.Options(orjson.OPT_SORT_KEYS)
+        for val in (
+            opts | orjson.OPT_INDENT_2,
+            orjson.OPT_INDENT_2 | opts,
+            opts | orjson.Options(orjson.OPT_INDENT_2),
+        ):
+            assert isinstance(val, orjson.Options)
+            assert int(val) == orjson.OPT_SORT_KEYS | orjson.OPT_INDENT_2
+        with pytest.raises(TypeError):
+            opts | 1.0  # type: ignore
+        with pytest.raises(TypeError):
+            opts | "a"  # type: ignore
+
+    def test_options_loads(self):
+        """
+        loads() accepts Options
+        """
+        opts = orjson.Options(orjson.OPT_FORBID_DUPLICATE_KEYS)
+        assert orjson.loads('{"a":1}', option=opts) == {"a": 1}
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads('{"a":1,"a":2}', option=opts)
+        assert orjson.loads('{"a":1,"a":2}', option=orjson.Options()) == {"a": 2}
+
+    def test_options_incompatible(self):
+        """
+        mutually exclusive options raise ValueError when built
+        """
+        exclusive = (
+            orjson.OPT_PASSTHROUGH_BYTES,
+            orjson.OPT_SERIALIZE_BYTES_BASE64,
+            orjson.OPT_SERIALIZE_BYTES_HEX,
+            orjson.OPT_SERIALIZE_BYTES_LATIN1,
+        )
+        for first in exclusive:
+            for second in exclusive:
+                if first == second:
+                    continue
+                with pytest.raises(ValueError):
+                    orjson.Options(first, second)
+                with pytest.raises(ValueError):
+                    orjson.Options(first | second)
+                with pytest.raises(ValueError):
+                    orjson.Options(first).with_option(second)
+                with pytest.raises(ValueError):
+                    orjson.Options(first) | second
+                with pytest.raises(orjson.JSONEncodeError):
+                    orjson.dumps(b"", option=first | second)
+        opts = orjson.Options(orjson.OPT_SERIALIZE_BYTES_HEX)
+        assert orjson.dumps(b"\x00", option=opts) == orjson.dumps(
+            b"\x00", option=orjson.OPT_SERIALIZE_BYTES_HEX
+        )
+        swapped = opts.without_option(orjson.OPT_SERIALIZE_BYTES_HEX).with_option(
+            orjson.OPT_SERIALIZE_BYTES_BASE64
+        )
+        assert int(swapped) == orjson.OPT_SERIALIZE_BYTES_BASE64
+
+    def test_options_invalid(self):
+        """
+        flags that are not a valid option raise
+        """
+        for val in (-1, 1 << 50, 1 << 64, True, 1.0, "a", None):
+            with pytest.raises(ValueError):
+                orjson.Options(val)  # type: ignore
+            with pytest.raises(ValueError):
+                orjson.Options().with_option(val)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.Options(flags=1)  # type: ignore
+
+    def test_options_immutable(self):
+        """
+        Options cannot be subclassed or have attributes set
+        """
+        with pytest.raises(TypeError):
+
+            class Subclass(orjson.Options):  # type: ignore
+                pass
+
+        with pytest.raises(AttributeError):
+            orjson.Options().opts = 1  # type: ignore
+
+
+class TestDefaultOptions:
+    def test_default_options(self):
+        """
+        calls without an option use the default
+        """
+        try:
+            orjson.set_default_options(orjson.OPT_NAIVE_UTC | orjson.OPT_OMIT_MICROSECONDS)
+            expected = b'"1970-01-01T00:00:00+00:00"'
+            assert orjson.dumps(DT) == expected
+            assert orjson.dumps(DT, option=None) == expected
+            assert orjson.dumps_str(DT) == expected.decode()
+            assert bytes(orjson.dumps_view(DT)) == expected
+            assert orjson.dumps_many([DT]) == [expected]
+            fp = io.BytesIO()
+            orjson.dump(DT, fp)
+            assert fp.getvalue() == expected
+        finally:
+            orjson.set_default_options(None)
+        assert orjson.dumps(DT) == b'"1970-01-01T00:00:00.000001"'
+
+    def test_default_options_combined(self):
+        """
+        the option of a call is combined with the default
+        """
+        try:
+            orjson.set_default_options(orjson.OPT_SORT_KEYS)
+            assert orjson.dumps({"b": 1, "a": 2}) == b'{"a":2,"b":1}'
+            assert orjson.dumps({"b": 1, "a": 2}, option=orjson.OPT_INDENT_2) == (
+                b'{\n  "a": 2,\n  "b": 1\n}'
+            )
+            assert orjson.dumps(
+                {"b": 1, "a": 2}, option=orjson.Options(orjson.OPT_APPEND_NEWLINE)
+            ) == (b'{"a":2,"b":1}\n')
+        finally:
+            orjson.set_default_options(0)
+        assert orjson.dumps({"b": 1, "a": 2}) == b'{"b":1,"a":2}'
+
+    def test_default_options_exclusive_override(self):
+        """
+        the call's flag of a mutually exclusive group replaces the default's
+        """
+        try:
+            orjson.set_default_options(
+                orjson.OPT_SERIALIZE_BYTES_HEX | orjson.OPT_DATETIME_AS_UNIX_SECONDS
+            )
+            assert orjson.dumps(b"ab") == b'"6162"'
+            assert orjson.dumps(b"ab", option=orjson.OPT_SERIALIZE_BYTES_BASE64) == (
+                b'"YWI="'
+            )
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(b"ab", option=orjson.OPT_PASSTHROUGH_BYTES)
+            dt = datetime.datetime(1970, 1, 1, 0, 0, 1, tzinfo=datetime.timezone.utc)
+            assert orjson.dumps(dt) == b"1.0"
+            assert orjson.dumps(dt, option=orjson.OPT_DATETIME_AS_UNIX_MILLIS) == (
+                b"1000.0"
+            )
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(
+                    b"ab",
+                    option=orjson.OPT_SERIALIZE_BYTES_BASE64
+                    | orjson.OPT_SERIALIZE_BYTES_LATIN1,
+                )
+        finally:
+            orjson.set_default_options(None)
+
+    def test_default_options_loads(self):
+        """
+        loads() uses the default
+        """
+        try:
+            orjson.set_default_options(orjson.OPT_FORBID_DUPLICATE_KEYS)
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads('{"a":1,"a":2}')
+            assert orjson.loads('{"a":1}') == {"a": 1}
+        finally:
+            orjson.set_default_options(None)
+        assert orjson.loads('{"a":1,"a":2}') == {"a": 2}
+
+    def test_default_options_replaced(self):
+        """
+        set_default_options() replaces the previous default
+        """
+        try:
+            orjson.set_default_options(orjson.OPT_SORT_KEYS)
+            orjson.set_default_options(orjson.Options(orjson.OPT_APPEND_NEWLINE))
+            assert orjson.dumps({"b": 1, "a": 2}) == b'{"b":1,"a":2}\n'
+        finally:
+            orjson.set_default_options(None)
+
+    def test_default_options_reset_state(self):
+        """
+        reset_state() clears the default
+        """
+        try:
+            orjson.set_default_options(orjson.OPT_APPEND_NEWLINE)
+            orjson.reset_state()
+            assert orjson.dumps([]) == b"[]"
+        finally:
+            orjson.set_default_options(None)
+
+    def test_default_options_invalid(self):
+        """
+        set_default_options() raises on an invalid option
+        """
+        exclusive = orjson.OPT_SERIALIZE_BYTES_HEX | orjson.OPT_PASSTHROUGH_BYTES
+        for val in (-1, 1 << 50, exclusive):
+            with pytest.raises(ValueError):
+                orjson.set_default_options(val)
+        for val in (1.0, "a", True):
+            with pytest.raises(TypeError):
+                orjson.set_default_options(val)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.set_default_options()  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.set_default_options(0, 0)  # type: ignore
+        assert orjson.dumps([]) == b"[]"
+
+    def test_default_options_subinterpreter_isolated(self):
+        """
+        the default is per-interpreter
+        """
+        try:
+            orjson.set_default_options(orjson.OPT_APPEND_NEWLINE)
+            run_in_subinterpreter(
+                """
+                import orjson
+                assert orjson.dumps([]) == b"[]"
+                orjson.set_default_options(orjson.OPT_INDENT_2)
+                assert orjson.dumps([1]) == b"[\\n  1\\n]"
+                """,
+            )
+            assert orjson.dumps([1]) == b"[1]\n"
+        finally:
+            orjson.set_default_options(None)
+
+
+class TestDumpsStr:
+    def test_dumps_str(self):
+        """
+        dumps_str() returns the same document as dumps() as str
+        """
+        for obj in (
+            None,
+            True,
+            1,
+            -1.5,
+            "",
+            "a",
+            [],
+            {},
+            [1, "a", None, {"b": [1.0, False]}],
+            {"a": "é中\U0001f600", "é": "\x00\n\""},
+        ):
+            res = orjson.dumps_str(obj)
+            assert type(res) is str
+            assert res == orjson.dumps(obj).decode("utf-8")
+
+    def test_dumps_str_buffer(self):
+        """
+        dumps_str() where output is greater than the initial buffer
+        """
+        val = ["a" * 900, "é" * 4096, "\U0001f600" * 4096]
+        assert orjson.dumps_str(val) == orjson.dumps(val).decode("utf-8")
+
+    def test_dumps_str_arguments(self):
+        """
+        dumps_str() default, option, envelope, and tuple_fields
+        """
+        assert (
+            orjson.dumps_str(
+                {"b": 1, "a": object()},
+                default=lambda _: "x",
+                option=orjson.OPT_SORT_KEYS,
+            )
+            == '{"a":"x","b":1}'
+        )
+        assert orjson.dumps_str([1], option=orjson.OPT_APPEND_NEWLINE) == "[1]\n"
+        assert orjson.dumps_str([1], option=orjson.OPT_INDENT_2) == "[\n  1\n]"
+        assert orjson.dumps_str([1], envelope="data") == '{"data":[1]}'
+        assert orjson.dumps_str((1, "a"), tuple_fields=["id", "name"]) == '{"id":1,"name":"a"}'
+
+    def test_dumps_str_fragment(self):
+        """
+        dumps_str() Fragment
+        """
+        assert orjson.dumps_str([orjson.Fragment('{"é":1}')]) == '[{"é":1}]'
+
+    def test_dumps_str_error(self):
+        """
+        dumps_str() raises JSONEncodeError
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_str(object())
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_str("\ud800")
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_str([1], envelope=1)  # type: ignore
+
+    def test_dumps_str_args_invalid(self):
+        """
+        dumps_str() invalid arguments raise TypeError naming dumps_str()
+        """
+        with pytest.raises(TypeError):
+            orjson.dumps_str()  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.dumps_str(1, opt=0)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.dumps_str(1, option=1 << 50)
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps_str(1, None, default=None)  # type: ignore
+        assert "dumps_str()" in str(exc_info.value)
+
+    def test_dumps_str_signature(self):
+        """
+        dumps_str() valid __text_signature__ and __module__
+        """
+        assert (
+            str(inspect.signature(orjson.dumps_str))
+            == "(obj, /, default=None, option=None, *, envelope=None, "
+            "tuple_fields=None, max_output_bytes=None, key_filter=None, "
+            "float_precision=None, on_skip=None, "
+            "skip_key_prefix=None, size_hint=None)"
+        )
+        assert orjson.dumps_str.__module__ == "orjson"
+
+
+class TestDumpsView:
+    def test_dumps_view(self):
+        """
+        dumps_view() returns a memoryview of the same document as dumps()
+        """
+        for obj in (
+            None,
+            1,
+            "a",
+            [],
+            {},
+            [1, "a", None, {"b": [1.0, False]}],
+            {"a": "é中\U0001f600"},
+        ):
+            res = orjson.dumps_view(obj)
+            assert type(res) is memoryview
+            assert res.readonly
+            assert res.format == "B"
+            assert res.nbytes == len(orjson.dumps(obj))
+            assert res.tobytes() == orjson.dumps(obj)
+
+    def test_dumps_view_bytesio(self):
+        """
+        dumps_view() written to a BytesIO
+        """
+        obj = {"a": ["b" * 4096, "é" * 4096], "c": list(range(1000))}
+        fp = io.BytesIO()
+        assert fp.write(orjson.dumps_view(obj)) == len(orjson.dumps(obj))
+        assert fp.getvalue() == orjson.dumps(obj)
+        assert orjson.loads(fp.getvalue()) == obj
+
+    def test_dumps_view_lifetime(self):
+        """
+        dumps_view() is valid until released and not after
+        """
+        view = orjson.dumps_view(["a" * 2048])
+        gc.collect()
+        orjson.dumps(["b" * 2048])
+        assert bytes(view) == orjson.dumps(["a" * 2048])
+        sliced = view[1:4]
+        view.release()
+        with pytest.raises(ValueError):
+            bytes(view)
+        assert bytes(sliced) == b'"aa'
+        sliced.release()
+
+    def test_dumps_view_loads(self):
+        """
+        loads() accepts the result of dumps_view()
+        """
+        obj = {"a": [1, 2.5, None]}
+        assert orjson.loads(orjson.dumps_view(obj)) == obj
+
+    def test_dumps_view_arguments(self):
+        """
+        dumps_view() default, option, envelope, and tuple_fields
+        """
+        assert bytes(orjson.dumps_view([1], option=orjson.OPT_APPEND_NEWLINE)) == b"[1]\n"
+        assert (
+            bytes(
+                orjson.dumps_view(
+                    {"b": 1, "a": object()},
+                    default=lambda _: "x",
+                    option=orjson.OPT_SORT_KEYS,
+                )
+            )
+            == b'{"a":"x","b":1}'
+        )
+        assert bytes(orjson.dumps_view([1], envelope="data")) == b'{"data":[1]}'
+        assert bytes(orjson.dumps_view((1, "a"), tuple_fields=["id", "name"])) == (
+            b'{"id":1,"name":"a"}'
+        )
+
+    def test_dumps_view_error(self):
+        """
+        dumps_view() raises JSONEncodeError
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_view(object())
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_view(["a" * 100], max_output_bytes=10)
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps_view(1, None, default=None)  # type: ignore
+        assert "dumps_view()" in str(exc_info.value)
+
+    def test_dumps_view_signature(self):
+        """
+        dumps_view() valid __text_signature__ and __module__
+        """
+        assert (
+            str(inspect.signature(orjson.dumps_view))
+            == "(obj, /, default=None, option=None, *, envelope=None, "
+            "tuple_fields=None, max_output_bytes=None, key_filter=None, "
+            "float_precision=None, on_skip=None, "
+            "skip_key_prefix=None, size_hint=None)"
+        )
+        assert orjson.dumps_view.__module__ == "orjson"
+
+
+class TestDumpsInto:
+    def test_dumps_into(self):
+        obj = {"a": [1, 2.5, "b", None, True], "c": {"d": "é"}}
+        ret, output = dumps_into(obj, 1024)
+        assert ret == len(orjson.dumps(obj))
+        assert output == orjson.dumps(obj)
+
+    def test_dumps_into_exact(self):
+        obj = [1, 2, 3]
+        assert dumps_into(obj, len(orjson.dumps(obj))) == (7, b"[1,2,3]")
+
+    def test_dumps_into_too_small(self):
+        obj = [1, 2, 3]
+        assert dumps_into(obj, 6)[0] == DUMPS_INTO_BUFFER_TOO_SMALL
+        assert dumps_into("a" * 10_000, 1024)[0] == DUMPS_INTO_BUFFER_TOO_SMALL
+
+    def test_dumps_into_empty_buffer(self):
+        assert dumps_into_fn()([], None, 0, 0) == DUMPS_INTO_BUFFER_TOO_SMALL
+
+    def test_dumps_into_option(self):
+        obj = {"b": 1, "a": 2}
+        assert dumps_into(obj, 64, orjson.OPT_SORT_KEYS) == (13, b'{"a":2,"b":1}')
+        assert dumps_into(obj, 64, orjson.OPT_INDENT_2)[1] == orjson.dumps(
+            obj, option=orjson.OPT_INDENT_2
+        )
+
+    def test_dumps_into_append_newline(self):
+        assert dumps_into([], 3, orjson.OPT_APPEND_NEWLINE) == (3, b"[]\n")
+        assert (
+            dumps_into([], 2, orjson.OPT_APPEND_NEWLINE)[0]
+            == DUMPS_INTO_BUFFER_TOO_SMALL
+        )
+
+    def test_dumps_into_invalid_option(self):
+        """
+        the error is raised as the exception set by the function
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            dumps_into([], 64, 1 << 50)
+
+    def test_dumps_into_incompatible_option(self):
+        """
+        options that dumps() rejects together are rejected
+        """
+        for option in (
+            orjson.OPT_SERIALIZE_BYTES_BASE64 | orjson.OPT_SERIALIZE_BYTES_HEX,
+            orjson.OPT_DATETIME_AS_UNIX_SECONDS | orjson.OPT_DATETIME_AS_UNIX_MILLIS,
+        ):
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                orjson.dumps([], option=option)
+            expected = str(exc_info.value)
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                dumps_into([], 64, option)
+            assert str(exc_info.value) == expected
+
+    def test_dumps_into_unsupported_type(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            dumps_into(object(), 64)
+        with pytest.raises(orjson.JSONEncodeError):
+            dumps_into({1: 2}, 64)
+
+    def test_dumps_into_repeated(self):
+        """
+        the buffer of the serializer is released after each call
+        """
+        for idx in range(1000):
+            assert dumps_into({"a": idx}, 64)[1] == orjson.dumps({"a": idx})
+
+
+class TestDumpsMany:
+    def test_dumps_many(self):
+        """
+        dumps_many() returns the dumps() of each element
+        """
+        objs = [
+            {"a": [1, 2.5, None, "é"]},
+            [],
+            "a" * 5000,
+            1,
+            None,
+            {"b": {"c": list(range(1000))}},
+            datetime.date(1970, 1, 1),
+        ]
+        res = orjson.dumps_many(objs)
+        assert isinstance(res, list)
+        assert len(res) == len(objs)
+        for obj, val in zip(objs, res):
+            assert type(val) is bytes
+            assert val == orjson.dumps(obj)
+
+    def test_dumps_many_empty(self):
+        """
+        dumps_many() of an empty list
+        """
+        assert orjson.dumps_many([]) == []
+
+    def test_dumps_many_large_then_small(self):
+        """
+        the buffer grown for an element does not affect the next
+        """
+        objs = ["a" * 100_000, 1, ["b" * 10_000], {}]
+        assert orjson.dumps_many(objs) == [orjson.dumps(obj) for obj in objs]
+
+    def test_dumps_many_options(self):
+        """
+        dumps_many() applies default, option, envelope, and key_filter to each
+        """
+        objs = [{"b": 1, "a": object()}, {"c": 2, "_d": 3}]
+        option = orjson.OPT_SORT_KEYS | orjson.OPT_APPEND_NEWLINE
+
+        def key_filter(key):
+            return None if key.startswith("_") else key
+
+        kwargs = {
+            "default": lambda _: "x",
+            "option": option,
+            "envelope": "data",
+            "key_filter": key_filter,
+        }
+        assert orjson.dumps_many(objs, **kwargs) == [
+            orjson.dumps(obj, **kwargs) for obj in objs
+        ]
+        assert orjson.dumps_many([[1]], None, orjson.OPT_INDENT_2) == [b"[\n  1\n]"]
+        assert orjson.dumps_many([[1]], option=orjson.Options(orjson.OPT_INDENT_2)) == [
+            b"[\n  1\n]"
+        ]
+
+    def test_dumps_many_error_index(self):
+        """
+        dumps_many() raises on the first element that fails with its index
+        """
+        objs = [1, {"a": 2}, [object()], {"b": object()}]
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps_many(objs)
+        assert str(exc_info.value).endswith("(at index 2)")
+        assert "Type is not JSON serializable: object" in str(exc_info.value)
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps_many([object()])
+        assert str(exc_info.value).endswith("(at index 0)")
+
+    def test_dumps_many_error_default(self):
+        """
+        an exception raised by default is the cause of the error
+        """
+
+        def default(obj):
+            raise ValueError("default")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps_many([1, 2, object()], default=default)
+        assert str(exc_info.value).endswith("(at index 2)")
+        assert isinstance(exc_info.value.__cause__, ValueError)
+
+    def test_dumps_many_max_output_bytes(self):
+        """
+        max_output_bytes is the limit of each element
+        """
+        objs = ["a" * 10, "b" * 10, "c" * 100]
+        assert orjson.dumps_many(objs[:2], max_output_bytes=12) == [
+            b'"aaaaaaaaaa"',
+            b'"bbbbbbbbbb"',
+        ]
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps_many(objs, max_output_bytes=12)
+        assert str(exc_info.value).endswith("(at index 2)")
+
+    def test_dumps_many_not_list(self):
+        """
+        dumps_many() of other than a list raises JSONEncodeError
+        """
+        for val in ((1, 2), {"a": 1}, "ab", None, iter([1])):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps_many(val)  # type: ignore
+
+    def test_dumps_many_list_subclass(self):
+        """
+        dumps_many() of a list subclass
+        """
+
+        class Subclass(list):
+            pass
+
+        assert orjson.dumps_many(Subclass([1, "a"])) == [b"1", b'"a"']
+
+    def test_dumps_many_args_invalid(self):
+        """
+        dumps_many() invalid arguments raise naming dumps_many()
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps_many()  # type: ignore
+        assert "dumps_many()" in str(exc_info.value)
+        for kwargs in ({"tuple_fields": ["a"]}, {"chunk_size": 1}, {"opt": 0}):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps_many([(1,)], **kwargs)  # type: ignore
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_many([1], option=1 << 50)
+
+    def test_dumps_many_modified_by_default(self):
+        """
+        default that shrinks the list does not read past its end
+        """
+        objs: list = [object(), 1, 2, 3]
+
+        def default(obj):
+            del objs[1:]
+            return "x"
+
+        assert orjson.dumps_many(objs, default=default) == [b'"x"']
+
+    def test_dumps_many_refcount(self):
+        """
+        dumps_many() does not leak the elements or the output
+        """
+        obj = {"a": [1, 2]}
+        objs = [obj] * 10
+        before = sys.getrefcount(obj)
+        for _ in range(100):
+            res = orjson.dumps_many(objs)
+            assert sys.getrefcount(res[0]) == 2
+            del res
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps_many([*objs, object()])
+        assert sys.getrefcount(obj) == before
+
+    def test_dumps_many_signature(self):
+        """
+        dumps_many() valid __text_signature__ and __module__
+        """
+        assert (
+            str(inspect.signature(orjson.dumps_many))
+            == "(objs, /, default=None, option=None, *, envelope=None, "
+            "max_output_bytes=None, key_filter=None, "
+            "float_precision=None, on_skip=None, "
+            "skip_key_prefix=None)"
+        )
+        assert orjson.dumps_many.__module__ == "orjson"
+
+
+class TestDump:
+    def test_dump_bytesio(self):
+        """
+        dump() writes the same document as dumps()
+        """
+        obj = {"a": [1, 2.5, None, "é"], "b": True}
+        fp = io.BytesIO()
+        assert orjson.dump(obj, fp) is None
+        assert fp.getvalue() == orjson.dumps(obj)
+
+    def test_dump_arguments(self):
+        """
+        dump() default, option, envelope, and tuple_fields
+        """
+        fp = io.BytesIO()
+        orjson.dump(
+            {"b": 1, "a": object()},
+            fp,
+            default=lambda _: "x",
+            option=orjson.OPT_SORT_KEYS | orjson.OPT_APPEND_NEWLINE,
+        )
+        assert fp.getvalue() == b'{"a":"x","b":1}\n'
+        fp = io.BytesIO()
+        orjson.dump([1], fp, None, orjson.OPT_INDENT_2)
+        assert fp.getvalue() == b"[\n  1\n]"
+        fp = io.BytesIO()
+        orjson.dump((1, "a"), fp, tuple_fields=["id", "name"], envelope="data")
+        assert fp.getvalue() == b'{"data":{"id":1,"name":"a"}}'
+
+    def test_dump_short_write(self):
+        """
+        dump() retries the remainder when write() accepts part of the data
+        """
+        obj = ["a" * 10, "b" * 10]
+        expected = orjson.dumps(obj)
+        for limit in (1, 3, 7, len(expected) - 1):
+            fp = ShortWriter(limit)
+            orjson.dump(obj, fp)
+            assert fp.getvalue() == expected
+            assert len(fp.calls) == -(-len(expected) // limit)
+            assert all(len(call) <= limit for call in fp.calls)
+
+    def test_dump_short_write_large(self):
+        """
+        dump() short writes of output greater than the initial buffer
+        """
+        obj = ["a" * 4096, "é" * 4096]
+        fp = ShortWriter(1000)
+        orjson.dump(obj, fp)
+        assert fp.getvalue() == orjson.dumps(obj)
+
+    def test_dump_write_returns_none(self):
+        """
+        dump() treats a write() that does not return a count as accepting
+        everything
+        """
+        calls = []
+
+        class Writer:
+            def write(self, data):
+                calls.append(bytes(data))
+
+        orjson.dump([1, 2], Writer())
+        assert calls == [b"[1,2]"]
+
+    def test_dump_write_invalid_count(self):
+        """
+        dump() raises OSError if write() accepts no bytes or more than given
+        """
+        for count in (0, -1, 100):
+
+            class Writer:
+                def write(self, data):
+                    return count
+
+            with pytest.raises(OSError):
+                orjson.dump([1, 2], Writer())
+
+    def test_dump_write_exception(self):
+        """
+        dump() propagates an exception raised by write()
+        """
+
+        class Writer:
+            def write(self, data):
+                raise BlockingIOError
+
+        with pytest.raises(BlockingIOError):
+            orjson.dump([1], Writer())
+        with pytest.raises(AttributeError):
+            orjson.dump([1], object())
+        with pytest.raises(TypeError):
+            orjson.dump([1], io.StringIO())
+
+    def test_dump_serialize_error(self):
+        """
+        dump() raises JSONEncodeError and does not write on failure before
+        the first chunk
+        """
+        fp = ShortWriter(1)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dump([1, object()], fp)
+        assert fp.calls == []
+
+    def test_dump_args_invalid(self):
+        """
+        dump() invalid arguments raise TypeError naming dump()
+        """
+        with pytest.raises(TypeError):
+            orjson.dump()  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.dump([1])  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.dump([1], io.BytesIO(), opt=0)  # type: ignore
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dump([1], io.BytesIO(), None, default=None)  # type: ignore
+        assert "dump()" in str(exc_info.value)
+
+    def test_dump_signature(self):
+        """
+        dump() valid __text_signature__ and __module__
+        """
+        assert (
+            str(inspect.signature(orjson.dump))
+            == "(obj, fp, /, default=None, option=None, *, envelope=None, "
+            "tuple_fields=None, max_output_bytes=None, key_filter=None, "
+            "chunk_size=None, float_precision=None, on_skip=None, "
+            "skip_key_prefix=None)"
+        )
+        assert orjson.dump.__module__ == "orjson"
+
+    def test_dump_chunk_size(self):
+        """
+        dump() writes chunks of at most chunk_size as it serializes
+        """
+        obj = [{"a": i, "b": "x" * (i % 50)} for i in range(1000)]
+        expected = orjson.dumps(obj)
+        for chunk_size in (1, 100, 4096, len(expected) - 1):
+            fp = ChunkWriter()
+            orjson.dump(obj, fp, chunk_size=chunk_size)
+            assert fp.getvalue() == expected
+            assert len(fp.calls) == -(-len(expected) // chunk_size)
+            assert all(len(call) == chunk_size for call in fp.calls[:-1])
+
+    def test_dump_chunk_size_single(self):
+        """
+        dump() output smaller than chunk_size is written in one call
+        """
+        fp = ChunkWriter()
+        orjson.dump({"a": [1, 2]}, fp, chunk_size=1000)
+        assert fp.calls == [b'{"a":[1,2]}']
+        fp = ChunkWriter()
+        orjson.dump("a" * 100, fp, chunk_size=None)
+        assert fp.calls == [orjson.dumps("a" * 100)]
+
+    def test_dump_chunk_size_default(self):
+        """
+        dump() writes output larger than 64KiB in more than one chunk by default
+        """
+        obj = ["a" * 1000] * 1000
+        fp = ChunkWriter()
+        orjson.dump(obj, fp)
+        assert fp.getvalue() == orjson.dumps(obj)
+        assert len(fp.calls) > 1
+        assert all(len(call) <= 64 * 1024 for call in fp.calls)
+
+    def test_dump_chunk_size_bytesio(self):
+        """
+        dump() chunks to BytesIO with options
+        """
+        obj = {"b": ["é" * 100] * 100, "a": list(range(1000))}
+        option = orjson.OPT_SORT_KEYS | orjson.OPT_INDENT_2 | orjson.OPT_APPEND_NEWLINE
+        fp = io.BytesIO()
+        orjson.dump(obj, fp, option=option, chunk_size=128)
+        assert fp.getvalue() == orjson.dumps(obj, option=option)
+
+    def test_dump_chunk_size_short_write(self):
+        """
+        dump() retries the remainder of a chunk on a short write
+        """
+        obj = ["a" * 100] * 100
+        fp = ShortWriter(7)
+        orjson.dump(obj, fp, chunk_size=50)
+        assert fp.getvalue() == orjson.dumps(obj)
+        assert all(len(call) <= 7 for call in fp.calls)
+
+    def test_dump_chunk_write_exception(self):
+        """
+        dump() propagates an exception raised by write() after the first chunk
+        and writes nothing more
+        """
+        calls = []
+
+        class Writer:
+            def write(self, data):
+                if len(calls) == 2:
+                    raise BlockingIOError
+                calls.append(bytes(data))
+
+        obj = ["a" * 100] * 1000
+        with pytest.raises(BlockingIOError):
+            orjson.dump(obj, Writer(), chunk_size=1024)
+        assert len(calls) == 2
+        assert b"".join(calls) == orjson.dumps(obj)[:2048]
+
+    def test_dump_chunk_write_exception_default(self):
+        """
+        dump() raises the exception of write() and not a later error, and
+        calls default without an exception set
+        """
+        calls = []
+
+        class Writer:
+            def write(self, data):
+                calls.append(len(data))
+                raise BlockingIOError
+
+        obj = ["a" * 1000] * 10 + [object(), object()]
+        with pytest.raises(BlockingIOError):
+            orjson.dump(obj, Writer(), default=str, chunk_size=1024)
+        with pytest.raises(BlockingIOError):
+            orjson.dump(obj, Writer(), chunk_size=1024)
+        assert calls == [1024, 1024]
+
+    def test_dump_chunk_serialize_error(self):
+        """
+        dump() raises JSONEncodeError after writing the chunks before the error
+        """
+        obj = ["a" * 100] * 100 + [object()]
+        fp = ChunkWriter()
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dump(obj, fp, chunk_size=1024)
+        assert fp.calls
+        assert orjson.dumps(obj[:-1]).startswith(fp.getvalue())
+
+    def test_dump_chunk_max_output_bytes(self):
+        """
+        dump() writes no more than max_output_bytes
+        """
+        obj = ["a" * 100] * 100
+        fp = ChunkWriter()
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dump(obj, fp, max_output_bytes=5000, chunk_size=1024)
+        assert len(fp.getvalue()) <= 5000
+        fp = ChunkWriter()
+        limit = len(orjson.dumps(obj))
+        orjson.dump(obj, fp, max_output_bytes=limit, chunk_size=1024)
+        assert fp.getvalue() == orjson.dumps(obj)
+
+    def test_dump_chunk_size_invalid(self):
+        """
+        dump() chunk_size that is not a positive int raises JSONEncodeError
+        """
+        for val in (0, -1, 1 << 64, 1.0, "a", True):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dump([1], io.BytesIO(), chunk_size=val)  # type: ignore
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1], chunk_size=1)  # type: ignore
+
+
+class TestMaxOutputBytes:
+    def test_max_output_bytes_exceeded(self):
+        """
+        max_output_bytes raises JSONEncodeError on a large list
+        """
+        obj = ["a" * 100] * 100_000
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(obj, max_output_bytes=4096)
+        assert "max_output_bytes" in str(exc_info.value)
+
+    def test_max_output_bytes_within(self):
+        """
+        max_output_bytes allows output up to and including the limit
+        """
+        obj = {"a": [1, 2, 3]}
+        assert orjson.dumps(obj, max_output_bytes=1 << 20) == b'{"a":[1,2,3]}'
+        assert orjson.dumps([1, 2, 3], max_output_bytes=7) == b"[1,2,3]"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1, 2, 3], max_output_bytes=6)
+        assert orjson.dumps([1, 2, 3], max_output_bytes=None) == b"[1,2,3]"
+
+    def test_max_output_bytes_exact_large(self):
+        """
+        max_output_bytes at the length of output greater than the initial buffer
+        """
+        obj = ["é" * 4096, "\\" * 4096, "a" * 100_000]
+        expected = orjson.dumps(obj)
+        assert orjson.dumps(obj, max_output_bytes=len(expected)) == expected
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(obj, max_output_bytes=len(expected) - 1)
+
+    def test_max_output_bytes_append_newline(self):
+        """
+        max_output_bytes includes the newline of OPT_APPEND_NEWLINE
+        """
+        opt = orjson.OPT_APPEND_NEWLINE
+        assert orjson.dumps([1], option=opt, max_output_bytes=4) == b"[1]\n"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1], option=opt, max_output_bytes=3)
+
+    def test_max_output_bytes_zero(self):
+        """
+        max_output_bytes of 0
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(None, max_output_bytes=0)
+
+    def test_max_output_bytes_dumps_str_dump(self):
+        """
+        max_output_bytes with dumps_str() and dump()
+        """
+        assert orjson.dumps_str([1], max_output_bytes=3) == "[1]"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_str([1], max_output_bytes=2)
+        fp = io.BytesIO()
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dump(list(range(10_000)), fp, max_output_bytes=100)
+        assert fp.getvalue() == b""
+
+    def test_max_output_bytes_invalid(self):
+        """
+        max_output_bytes must be a non-negative int
+        """
+        for val in (-1, 1.0, "1", 1 << 70):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps([], max_output_bytes=val)  # type: ignore
+
+    def test_max_output_bytes_stops_default(self):
+        """
+        max_output_bytes stops serialization, and so calls to default, once
+        exceeded
+        """
+        calls = []
+
+        def default(obj):
+            calls.append(obj)
+            return "x" * 10
+
+        obj = [object() for _ in range(1000)]
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(obj, default=default, max_output_bytes=100)
+        assert len(calls) < 20
+
+    def test_max_output_bytes_stops_dict(self):
+        """
+        max_output_bytes stops serialization of a dict once exceeded
+        """
+        calls = []
+
+        def default(obj):
+            calls.append(obj)
+            return "x" * 10
+
+        obj = {str(idx): object() for idx in range(1000)}
+        for opt in (0, orjson.OPT_SORT_KEYS, orjson.OPT_NON_STR_KEYS):
+            calls.clear()
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(obj, default=default, option=opt, max_output_bytes=100)
+            assert len(calls) < 20
+
+
+class TestSizeHint:
+    def test_size_hint(self):
+        """
+        size_hint does not change the output of dumps()
+        """
+        expected = orjson.dumps(DATA)
+        for hint in (None, 0, 1, 1024, len(expected) - 1, len(expected)):
+            assert orjson.dumps(DATA, size_hint=hint) == expected
+        assert orjson.dumps(DATA, size_hint=len(expected) * 4) == expected
+
+    def test_size_hint_small(self):
+        """
+        size_hint greater than small output
+        """
+        assert orjson.dumps([1, 2], size_hint=1 << 20) == b"[1,2]"
+        assert orjson.dumps(None, size_hint=1) == b"null"
+        assert orjson.dumps("", size_hint=0) == b'""'
+
+    def test_size_hint_absurd(self):
+        """
+        size_hint larger than can be allocated is capped
+        """
+        expected = orjson.dumps(DATA)
+        for hint in (1 << 40, (1 << 63) - 1, 1 << 64, 10**100):
+            assert orjson.dumps(DATA, size_hint=hint) == expected
+            assert orjson.dumps([1], size_hint=hint) == b"[1]"
+
+    def test_size_hint_options(self):
+        """
+        size_hint with options and other arguments
+        """
+        opt = orjson.OPT_APPEND_NEWLINE | orjson.OPT_INDENT_2
+        expected = orjson.dumps(DATA, option=opt)
+        assert orjson.dumps(DATA, option=opt, size_hint=len(expected)) == expected
+        assert orjson.dumps([1, 2, 3], max_output_bytes=7, size_hint=1 << 20) == (
+            b"[1,2,3]"
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1, 2, 3], max_output_bytes=6, size_hint=1 << 20)
+
+    def test_size_hint_dumps_str_view(self):
+        """
+        size_hint with dumps_str() and dumps_view()
+        """
+        expected = orjson.dumps(DATA)
+        assert orjson.dumps_str(DATA, size_hint=len(expected)) == expected.decode()
+        view = orjson.dumps_view(DATA, size_hint=len(expected) * 2)
+        assert bytes(view) == expected
+        view.release()
+
+    def test_size_hint_dumps_many_dump(self):
+        """
+        dumps_many() and dump() do not take size_hint
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps_many([1], size_hint=1024)  # type: ignore
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dump([1], io.BytesIO(), size_hint=1024)  # type: ignore
+
+    @pytest.mark.parametrize("hint", [-1, -(1 << 64), 1.5, "1024", True, b"1"])
+    def test_size_hint_invalid(self, hint):
+        """
+        size_hint of other than a non-negative int raises JSONEncodeError
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps([1], size_hint=hint)
+        assert "size_hint" in str(exc_info.value)
+
+    def test_size_hint_error(self):
+        """
+        size_hint with an error during serialization
+        """
+        for _ in range(10):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps([1, object()], size_hint=1 << 20)
+        assert orjson.dumps([1], size_hint=1 << 20) == b"[1]"
+
+
+class TestSelfCheck:
+    def test_self_check_types(self):
+        obj = {
+            "str": "a\n\"é\U0001f600",
+            "int": [0, -1, 2**63, 2**64 - 1, -(2**63)],
+            "float": [0.1, -0.0, 1e300, 5e-324, 1 / 3, float("nan")],
+            "datetime": datetime.datetime(
+                2000, 1, 2, 3, 4, 5, 678, tzinfo=datetime.timezone.utc
+            ),
+            "date": datetime.date(2000, 1, 2),
+            "time": datetime.time(3, 4, 5),
+            "enum": [Color.RED, Color.GREEN, Level.LOW],
+            "uuid": uuid.UUID("7202d115-7ff3-4c81-a7c1-2a1f067b1ece"),
+            "dataclass": Point(1.5, -2.0),
+            "nested": [[[]], {}, {"a": {"b": [None, True, False]}}],
+        }
+        assert orjson.dumps(obj, option=CHECK) == orjson.dumps(obj)
+
+    def test_self_check_options(self):
+        obj = {"b": [1, {"d": "é/", "c": None}], "a": "</script>"}
+        for option in (
+            orjson.OPT_INDENT_2,
+            orjson.OPT_SORT_KEYS,
+            orjson.OPT_APPEND_NEWLINE,
+            orjson.OPT_ENSURE_ASCII,
+            orjson.OPT_ESCAPE_FORWARD_SLASH,
+            orjson.OPT_INDENT_2 | orjson.OPT_SORT_KEYS | orjson.OPT_ENSURE_ASCII,
+        ):
+            assert orjson.dumps(obj, option=CHECK | option) == orjson.dumps(
+                obj, option=option
+            )
+
+    def test_self_check_datetime_options(self):
+        obj = [
+            datetime.datetime(2000, 1, 2, 3, 4, 5, 678),
+            datetime.datetime(
+                2000, 1, 2, tzinfo=datetime.timezone(datetime.timedelta(hours=-5))
+            ),
+        ]
+        for option in (
+            orjson.OPT_NAIVE_UTC | orjson.OPT_UTC_Z,
+            orjson.OPT_OMIT_MICROSECONDS,
+            orjson.OPT_NAIVE_UTC | orjson.OPT_DATETIME_AS_UNIX_MILLIS,
+        ):
+            assert orjson.dumps(obj, option=CHECK | option) == orjson.dumps(
+                obj, option=option
+            )
+
+    def test_self_check_non_str_keys(self):
+        obj = {1: "a", datetime.date(2000, 1, 1): "b", None: "c"}
+        option = orjson.OPT_NON_STR_KEYS
+        assert orjson.dumps(obj, option=CHECK | option) == orjson.dumps(
+            obj, option=option
+        )
+
+    def test_self_check_default(self):
+        obj = {"a": {1, 2}}
+        assert orjson.dumps(obj, default=sorted, option=CHECK) == b'{"a":[1,2]}'
+
+    @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
+    def test_self_check_numpy(self):
+        obj = {
+            "int": numpy.array([[1, 2], [3, 4]], numpy.int64),
+            "uint": numpy.uint64(2**64 - 1),
+            "float32": numpy.array([0.1, 1 / 3], numpy.float32),
+            "float16": numpy.float16(0.5),
+            "bool": numpy.bool_(True),
+            "datetime64": numpy.datetime64("2000-01-02T03:04:05"),
+        }
+        option = orjson.OPT_SERIALIZE_NUMPY
+        assert orjson.dumps(obj, option=CHECK | option) == orjson.dumps(
+            obj, option=option
+        )
+
+    def test_self_check_outputs(self):
+        obj = [datetime.date(2000, 1, 1), Color.RED]
+        assert orjson.dumps_str(obj, option=CHECK) == '["2000-01-01","red"]'
+        assert bytes(orjson.dumps_view(obj, option=CHECK)) == b'["2000-01-01","red"]'
+        assert orjson.dumps_many([obj, Color.GREEN], option=CHECK) == [
+            b'["2000-01-01","red"]',
+            b"2",
+        ]
+
+    def test_self_check_fragment_compact(self):
+        obj = {"a": orjson.Fragment(b'{"b":[1,2]}')}
+        assert orjson.dumps(obj, option=CHECK) == b'{"a":{"b":[1,2]}}'
+
+    @needs_self_check
+    def test_self_check_fragment_whitespace(self):
+        """
+        the contents of a Fragment that are not as they would be serialized
+        fail
+        """
+        obj = {"a": orjson.Fragment(b'{ "b": [1, 2] }')}
+        assert orjson.dumps(obj) == b'{"a":{ "b": [1, 2] }}'
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(obj, option=CHECK)
+        assert str(exc_info.value).startswith("OPT_SELF_CHECK: output differs")
+
+    @needs_self_check
+    def test_self_check_fragment_invalid(self):
+        obj = [orjson.Fragment(b"{")]
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(obj, option=CHECK)
+        assert str(exc_info.value).startswith(
+            "OPT_SELF_CHECK: output is not valid JSON"
+        )
+
+    @needs_self_check
+    def test_self_check_duplicate_keys(self):
+        obj = {1: "a", "1": "b"}
+        assert orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS) == b'{"1":"a","1":"b"}'
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(obj, option=CHECK | orjson.OPT_NON_STR_KEYS)
+
+    def test_self_check_dump(self):
+        """
+        dump() is not checked
+        """
+        fp = io.BytesIO()
+        orjson.dump([orjson.Fragment(b"[ ]")], fp, option=CHECK)
+        assert fp.getvalue() == b"[[ ]]"
+
+    @pytest.mark.skipif(SELF_CHECK, reason="OPT_SELF_CHECK has an effect")
+    def test_self_check_ignored(self):
+        obj = [orjson.Fragment(b"{")]
+        assert orjson.dumps(obj, option=CHECK) == b"[{]"
+
+
+class TestGILCheck:
+    def test_gil_held(self):
+        proc = _run("held")
+        assert proc.returncode == 0
+        assert proc.stdout.split() == ["7"]
+
+    @pytest.mark.skipif(not DEBUG, reason="GIL is only checked in a debug build")
+    def test_gil_not_held(self):
+        """
+        a debug build aborts with a message if called without the GIL
+        """
+        proc = _run("released")
+        assert proc.returncode != 0
+        assert proc.stdout == ""
+        assert "orjson_dumps_into() called without holding the GIL" in proc.stderr
+
+
+class TestEnvelope:
+    def test_envelope_list(self):
+        """
+        dumps() envelope list
+        """
+        assert orjson.dumps([1, "a", None], envelope="data") == b'{"data":[1,"a",null]}'
+
+    def test_envelope_scalar(self):
+        """
+        dumps() envelope scalar
+        """
+        assert orjson.dumps(1, envelope="data") == b'{"data":1}'
+        assert orjson.dumps("a", envelope="result") == b'{"result":"a"}'
+        assert orjson.dumps(None, envelope="") == b'{"":null}'
+
+    def test_envelope_dict(self):
+        """
+        dumps() envelope dict
+        """
+        assert (
+            orjson.dumps({"b": 2, "a": 1}, option=orjson.OPT_SORT_KEYS, envelope="data")
+            == b'{"data":{"a":1,"b":2}}'
+        )
+
+    def test_envelope_escape(self):
+        """
+        dumps() envelope key is escaped
+        """
+        assert orjson.dumps([], envelope='"\n\u00e5') == b'{"\\"\\n\xc3\xa5":[]}'
+
+    def test_envelope_indent(self):
+        """
+        dumps() envelope OPT_INDENT_2 and OPT_APPEND_NEWLINE
+        """
+        assert (
+            orjson.dumps(
+                [1],
+                option=orjson.OPT_INDENT_2 | orjson.OPT_APPEND_NEWLINE,
+                envelope="data",
+            )
+            == b'{\n  "data": [\n    1\n  ]\n}\n'
+        )
+
+    def test_envelope_none(self):
+        """
+        dumps() envelope=None does not wrap
+        """
+        assert orjson.dumps([], envelope=None) == b"[]"
+
+    def test_envelope_default(self):
+        """
+        dumps() envelope with default
+        """
+        assert orjson.dumps({1}, default=list, envelope="data") == b'{"data":[1]}'
+
+    def test_envelope_invalid(self):
+        """
+        dumps() envelope not str
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([], envelope=b"data")  # type: ignore
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([], envelope="\ud800")
+
+    def test_envelope_error(self):
+        """
+        dumps() envelope propagates errors from serializing the object
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([object()], envelope="data")
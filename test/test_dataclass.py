@@ -2,6 +2,8 @@
 # Copyright ijl (2019-2025)
 
 import abc
+import dataclasses
+import types
 import uuid
 from dataclasses import InitVar, asdict, dataclass, field
 from enum import Enum
@@ -114,6 +116,162 @@ class ConcreteAbc(AbstractBase):
         return "dkjf"
 
 
+@dataclass
+class SlotsBase:
+    __slots__ = ("a",)
+    a: int
+
+
+@dataclass
+class SlotsMiddle(SlotsBase):
+    __slots__ = ("b",)
+    b: int
+
+
+@dataclass
+class SlotsLeaf(SlotsMiddle):
+    __slots__ = ("c",)
+    c: int
+
+
+@dataclass
+class SlotsLeafDict(SlotsMiddle):
+    c: int
+
+
+@dataclass
+class SlotsRedeclared(SlotsMiddle):
+    __slots__ = ("c",)
+    c: int
+    a: int
+
+
+@dataclass
+class AbstractDataclass(AbstractBase):
+    a: int
+
+    def __post_init__(self):
+        self.extra = 1
+
+    def key(self):
+        return "a"
+
+
+@dataclass
+class DictPropertyDataclass:
+    name: str
+
+    @property
+    def __dict__(self):
+        return {"name": self.name.upper(), "_private": 1}
+
+
+@dataclass
+class DictPropertyMappingDataclass:
+    name: str
+
+    @property
+    def __dict__(self):
+        return types.MappingProxyType({"name": self.name})
+
+
+@dataclass
+class DictPropertyInvalidDataclass:
+    name: str
+
+    @property
+    def __dict__(self):
+        return [("name", self.name)]
+
+
+PROPERTIES = orjson.OPT_SERIALIZE_PROPERTIES
+
+
+class Point:
+    __slots__ = ("_x", "_y")
+
+    def __init__(self, x, y):
+        self._x = x
+        self._y = y
+
+    @property
+    def x(self):
+        return self._x
+
+    @property
+    def y(self):
+        return self._y
+
+    def norm(self):
+        return abs(self._x) + abs(self._y)
+
+
+class Point3(Point):
+    __slots__ = ("_z",)
+
+    def __init__(self, x, y, z):
+        super().__init__(x, y)
+        self._z = z
+
+    @property
+    def z(self):
+        return self._z
+
+
+class Counted:
+    def __init__(self):
+        self.calls = 0
+
+    @property
+    def value(self):
+        self.calls += 1
+        return self.calls
+
+
+class Raises:
+    @property
+    def ok(self):
+        return 1
+
+    @property
+    def fails(self):
+        raise ValueError("property failed")
+
+
+class Private:
+    @property
+    def _hidden(self):
+        return 1
+
+    @property
+    def shown(self):
+        return 2
+
+
+class Overridden(Point):
+    __slots__ = ()
+
+    x = 0
+
+    @property
+    def y(self):
+        return -self._y
+
+
+class Nested:
+    @property
+    def point(self):
+        return Point(1, 2)
+
+    @property
+    def items(self):
+        return [Point(3, 4), {"a": Point(5, 6)}]
+
+
+class NoProperties:
+    pass
+
+
 class TestDataclass:
     def test_dataclass(self):
         """
@@ -203,6 +361,39 @@ class TestDataclass:
         assert "__dict__" not in dir(obj)
         assert orjson.dumps(obj) == b'{"a":"a","b":1}'
 
+    def test_dataclass_slots_inheritance(self):
+        """
+        dumps() dataclass with __slots__ in each class of an inheritance
+        chain has the fields of base classes first
+        """
+        obj = SlotsLeaf(1, 2, 3)
+        assert "__dict__" not in dir(obj)
+        assert orjson.dumps(obj) == b'{"a":1,"b":2,"c":3}'
+        assert orjson.dumps(SlotsLeaf(c=3, b=2, a=1)) == b'{"a":1,"b":2,"c":3}'
+
+    def test_dataclass_slots_inheritance_dict(self):
+        """
+        dumps() dataclass without __slots__ includes the fields in the
+        __slots__ of base classes
+        """
+        obj = SlotsLeafDict(1, 2, 3)
+        assert obj.__dict__ == {"c": 3}
+        assert orjson.dumps(obj) == b'{"a":1,"b":2,"c":3}'
+
+    def test_dataclass_slots_inheritance_redeclared(self):
+        """
+        dumps() dataclass redeclaring a field of a base class keeps the
+        position of the field in the base class
+        """
+        assert orjson.dumps(SlotsRedeclared(1, 2, 3)) == b'{"a":1,"b":2,"c":3}'
+
+    def test_dataclass_empty_slots_base(self):
+        """
+        dumps() dataclass with a base class with empty __slots__, e.g.,
+        abc.ABC, includes attributes of __dict__ that are not fields
+        """
+        assert orjson.dumps(AbstractDataclass(1)) == b'{"a":1,"extra":1}'
+
     def test_dataclass_default(self):
         """
         dumps() dataclass with default
@@ -300,3 +491,175 @@ class TestAbstractDataclass:
     def test_dataclass_abc(self):
         obj = ConcreteAbc(1.0)
         assert orjson.dumps(obj) == b'{"attr":1.0}'
+
+
+class TestDataclassDictProperty:
+    def test_dataclass_dict_property(self):
+        """
+        dumps() dataclass with __dict__ as a property returning a dict
+        """
+        assert orjson.dumps(DictPropertyDataclass("a")) == b'{"name":"A"}'
+
+    def test_dataclass_dict_property_nested(self):
+        """
+        dumps() dataclass with __dict__ as a property in a list and dict
+        """
+        obj = DictPropertyDataclass("a")
+        assert orjson.dumps({"a": [obj]}) == b'{"a":[{"name":"A"}]}'
+
+    def test_dataclass_dict_property_mapping(self):
+        """
+        dumps() dataclass with __dict__ as a property returning a mapping
+        """
+        assert orjson.dumps(DictPropertyMappingDataclass("a")) == b'{"name":"a"}'
+
+    def test_dataclass_dict_property_invalid(self):
+        """
+        dumps() dataclass with __dict__ as a property returning a non-mapping
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(DictPropertyInvalidDataclass("a"))
+        assert str(exc_info.value) == "__dict__ is not a mapping"
+        assert isinstance(exc_info.value.__cause__, AttributeError)
+
+    def test_dataclass_dict_property_invalid_default(self):
+        """
+        dumps() dataclass with a non-mapping __dict__ does not call default
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(DictPropertyInvalidDataclass("a"), default=lambda _: None)
+
+
+class TestSerializeProperties:
+    def test_serialize_properties(self):
+        assert orjson.dumps(Point(1, 2), option=PROPERTIES) == b'{"x":1,"y":2}'
+
+    def test_serialize_properties_disabled(self):
+        """
+        properties are not evaluated without the option
+        """
+        obj = Counted()
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(obj)
+        assert obj.calls == 0
+        assert orjson.dumps(obj, option=PROPERTIES) == b'{"value":1}'
+        assert obj.calls == 1
+
+    def test_serialize_properties_default(self):
+        assert orjson.dumps(Point(1, 2), default=lambda obj: "default") == b'"default"'
+
+    def test_serialize_properties_subclass(self):
+        """
+        properties of base classes precede those of the subclass
+        """
+        assert (
+            orjson.dumps(Point3(1, 2, 3), option=PROPERTIES) == b'{"x":1,"y":2,"z":3}'
+        )
+
+    def test_serialize_properties_overridden(self):
+        """
+        a subclass attribute shadows a property and a property overridden
+        by a subclass is evaluated once
+        """
+        assert orjson.dumps(Overridden(1, 2), option=PROPERTIES) == b'{"y":-2}'
+
+    def test_serialize_properties_private(self):
+        assert orjson.dumps(Private(), option=PROPERTIES) == b'{"shown":2}'
+
+    def test_serialize_properties_sort_keys(self):
+        class Unsorted:
+            @property
+            def b(self):
+                return 1
+
+            @property
+            def a(self):
+                return 2
+
+        assert orjson.dumps(Unsorted(), option=PROPERTIES) == b'{"b":1,"a":2}'
+        assert (
+            orjson.dumps(Unsorted(), option=PROPERTIES | orjson.OPT_SORT_KEYS)
+            == b'{"a":2,"b":1}'
+        )
+
+    def test_serialize_properties_nested(self):
+        assert (
+            orjson.dumps([Nested()], option=PROPERTIES)
+            == b'[{"point":{"x":1,"y":2},"items":[{"x":3,"y":4},{"a":{"x":5,"y":6}}]}]'
+        )
+        assert (
+            orjson.dumps({"a": Point(1, 2)}, option=PROPERTIES | orjson.OPT_SORT_KEYS)
+            == b'{"a":{"x":1,"y":2}}'
+        )
+
+    def test_serialize_properties_raises(self):
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(Raises(), option=PROPERTIES)
+        assert "property raised an exception" in str(exc_info.value)
+        assert "Raises.fails" in str(exc_info.value)
+        assert isinstance(exc_info.value.__cause__, ValueError)
+
+    def test_serialize_properties_raises_skip(self):
+        """
+        OPT_SKIP_UNSERIALIZABLE omits a property that raises
+        """
+        assert (
+            orjson.dumps(Raises(), option=PROPERTIES | orjson.OPT_SKIP_UNSERIALIZABLE)
+            == b'{"ok":1}'
+        )
+
+    def test_serialize_properties_raises_on_skip(self):
+        skipped = []
+        assert (
+            orjson.dumps(
+                Raises(),
+                option=PROPERTIES | orjson.OPT_SKIP_UNSERIALIZABLE,
+                on_skip=lambda obj, exc: skipped.append((obj, exc)),
+            )
+            == b'{"ok":1}'
+        )
+        assert len(skipped) == 1
+        assert isinstance(skipped[0][0], Raises)
+        assert isinstance(skipped[0][1], orjson.JSONEncodeError)
+        assert isinstance(skipped[0][1].__cause__, ValueError)
+
+    def test_serialize_properties_unsupported_value(self):
+        class Value:
+            @property
+            def value(self):
+                return object()
+
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Value(), option=PROPERTIES)
+        assert (
+            orjson.dumps(Value(), option=PROPERTIES, default=lambda obj: None)
+            == b'{"value":null}'
+        )
+
+    def test_serialize_properties_no_properties(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(NoProperties(), option=PROPERTIES)
+
+    def test_serialize_properties_dataclass(self):
+        """
+        a dataclass is serialized by its fields
+        """
+
+        @dataclasses.dataclass
+        class Data:
+            a: int
+
+            @property
+            def b(self):
+                return 2
+
+        assert orjson.dumps(Data(1), option=PROPERTIES) == b'{"a":1}'
+
+    def test_serialize_properties_recursive(self):
+        class Recursive:
+            @property
+            def this(self):
+                return self
+
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Recursive(), option=PROPERTIES)
@@ -2,6 +2,7 @@
 # Copyright ijl (2019-2025)
 
 import datetime
+import sys
 
 import pytest
 
@@ -41,6 +42,62 @@ AMSTERDAM_1937_DATETIMES_WITH_Z = (
 )
 
 
+DATETIME = datetime.datetime(2020, 1, 2, 3, 4, 5, tzinfo=datetime.timezone.utc)
+
+
+class DatetimeSubclass(datetime.datetime):
+    pass
+
+
+def _reset_without_datetime():
+    """
+    Rebuild the interpreter state while the datetime module, and so its CAPI,
+    cannot be imported.
+    """
+    saved = sys.modules["datetime"]
+    sys.modules["datetime"] = None  # type: ignore
+    try:
+        orjson.reset_state()
+        # the state is rebuilt on the next call
+        assert orjson.dumps(None) == b"null"
+    finally:
+        sys.modules["datetime"] = saved
+
+
+SECONDS = orjson.OPT_DATETIME_AS_UNIX_SECONDS
+MILLIS = orjson.OPT_DATETIME_AS_UNIX_MILLIS
+
+UTC = datetime.timezone.utc
+
+EPOCH = datetime.datetime(1970, 1, 1, tzinfo=UTC)
+
+ADELAIDE = datetime.timezone(datetime.timedelta(hours=10, minutes=30))
+
+EST = datetime.timezone(datetime.timedelta(hours=-5))
+
+AWARE = (
+    datetime.datetime(1970, 1, 1, tzinfo=UTC),
+    datetime.datetime(2020, 1, 2, 3, 4, 5, 678, tzinfo=UTC),
+    datetime.datetime(2000, 2, 29, 23, 59, 59, 999999, tzinfo=UTC),
+    datetime.datetime(1969, 12, 31, 23, 59, 59, 500000, tzinfo=UTC),
+    datetime.datetime(1900, 3, 1, 12, tzinfo=UTC),
+    datetime.datetime(1, 1, 1, tzinfo=UTC),
+    datetime.datetime(9999, 12, 31, 23, 59, 59, tzinfo=UTC),
+    datetime.datetime(2020, 6, 1, 8, 30, 15, 250, tzinfo=ADELAIDE),
+    datetime.datetime(2020, 6, 1, 8, tzinfo=EST),
+)
+
+
+EPOCH_DAYS = orjson.OPT_DATE_AS_EPOCH_DAYS
+
+
+class DateSubclass(datetime.date):
+    pass
+
+
+STRICT = orjson.OPT_RFC3339_STRICT
+
+
 class TestDatetime:
     def test_datetime_naive(self):
         """
@@ -63,6 +120,54 @@ class TestDatetime:
             == b'["2000-01-01T02:03:04.000123+00:00"]'
         )
 
+    def test_datetime_naive_forbidden(self):
+        """
+        datetime.datetime naive raises with OPT_FORBID_NAIVE_DATETIME
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                [datetime.datetime(2000, 1, 1, 2, 3, 4, 123)],
+                option=orjson.OPT_FORBID_NAIVE_DATETIME,
+            )
+
+    def test_datetime_naive_forbidden_dict_key(self):
+        """
+        datetime.datetime naive dict key raises with OPT_FORBID_NAIVE_DATETIME
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                {datetime.datetime(2000, 1, 1, 2, 3, 4, 123): 1},
+                option=orjson.OPT_FORBID_NAIVE_DATETIME | orjson.OPT_NON_STR_KEYS,
+            )
+
+    def test_datetime_naive_forbidden_naive_utc(self):
+        """
+        datetime.datetime naive with OPT_FORBID_NAIVE_DATETIME and OPT_NAIVE_UTC
+        """
+        assert (
+            orjson.dumps(
+                [datetime.datetime(2000, 1, 1, 2, 3, 4, 123)],
+                option=orjson.OPT_FORBID_NAIVE_DATETIME | orjson.OPT_NAIVE_UTC,
+            )
+            == b'["2000-01-01T02:03:04.000123+00:00"]'
+        )
+
+    def test_datetime_naive_forbidden_aware(self):
+        """
+        datetime.datetime with tzinfo is unaffected by OPT_FORBID_NAIVE_DATETIME
+        """
+        assert (
+            orjson.dumps(
+                [
+                    datetime.datetime(
+                        2000, 1, 1, 2, 3, 4, 123, tzinfo=datetime.timezone.utc
+                    )
+                ],
+                option=orjson.OPT_FORBID_NAIVE_DATETIME,
+            )
+            == b'["2000-01-01T02:03:04.000123+00:00"]'
+        )
+
     def test_datetime_min(self):
         """
         datetime.datetime min range
@@ -157,6 +262,79 @@ class TestDatetime:
             == b'["2018-06-01T02:03:04+00:00"]'
         )
 
+    def test_datetime_timezone_fixed_offset(self):
+        """
+        datetime.timezone with a nonzero offset
+        """
+        for tz, ref in (
+            (datetime.timezone(datetime.timedelta(hours=-5)), b"-05:00"),
+            (datetime.timezone(datetime.timedelta(hours=-5), "EST"), b"-05:00"),
+            (datetime.timezone(datetime.timedelta(hours=9, minutes=30)), b"+09:30"),
+            (datetime.timezone.min, b"-23:59"),
+            (datetime.timezone.max, b"+23:59"),
+        ):
+            obj = datetime.datetime(2018, 6, 1, 2, 3, 4, tzinfo=tz)
+            assert orjson.dumps(obj) == b'"2018-06-01T02:03:04' + ref + b'"'
+            assert orjson.dumps(obj) == f'"{obj.isoformat()}"'.encode()
+
+    def test_datetime_timezone_utc_z(self):
+        """
+        datetime.timezone OPT_UTC_Z applies only to a zero offset
+        """
+        for tz in (
+            datetime.timezone.utc,
+            datetime.timezone(datetime.timedelta(0)),
+            datetime.timezone(datetime.timedelta(0), "GMT"),
+        ):
+            obj = datetime.datetime(2018, 6, 1, 2, 3, 4, tzinfo=tz)
+            assert (
+                orjson.dumps(obj, option=orjson.OPT_UTC_Z)
+                == b'"2018-06-01T02:03:04Z"'
+            )
+        obj = datetime.datetime(
+            2018, 6, 1, 2, 3, 4, tzinfo=datetime.timezone(datetime.timedelta(hours=-5))
+        )
+        assert (
+            orjson.dumps(obj, option=orjson.OPT_UTC_Z)
+            == b'"2018-06-01T02:03:04-05:00"'
+        )
+
+    def test_datetime_timezone_unix(self):
+        """
+        datetime.timezone offset with OPT_DATETIME_AS_UNIX_SECONDS
+        """
+        obj = datetime.datetime(
+            2018, 6, 1, 2, 3, 4, tzinfo=datetime.timezone(datetime.timedelta(hours=-5))
+        )
+        assert (
+            orjson.loads(orjson.dumps(obj, option=orjson.OPT_DATETIME_AS_UNIX_SECONDS))
+            == obj.timestamp()
+        )
+
+    def test_datetime_timezone_tzinfo_subclass(self):
+        """
+        datetime.timezone and an equivalent tzinfo subclass serialize the same
+        """
+
+        class FixedOffset(datetime.tzinfo):
+            def __init__(self, offset):
+                self.offset = offset
+
+            def utcoffset(self, dt):
+                return self.offset
+
+            def dst(self, dt):
+                return datetime.timedelta(0)
+
+        for offset in (
+            datetime.timedelta(0),
+            datetime.timedelta(hours=-5),
+            datetime.timedelta(hours=5, minutes=45),
+        ):
+            assert orjson.dumps(
+                datetime.datetime(2018, 6, 1, tzinfo=datetime.timezone(offset))
+            ) == orjson.dumps(datetime.datetime(2018, 6, 1, tzinfo=FixedOffset(offset)))
+
     @pytest.mark.skipif(pytz is None, reason="pytz optional")
     def test_datetime_pytz_utc(self):
         """
@@ -403,6 +581,107 @@ class TestDatetime:
             == b'["2018-12-01T02:03:04-05:00"]'
         )
 
+    @pytest.mark.skipif(pytz is None, reason="pytz optional")
+    def test_datetime_pytz_dst_boundary(self):
+        """
+        datetime.datetime pytz offset is normalized after arithmetic across DST
+        """
+        tz = pytz.timezone("America/New_York")
+        spring = tz.localize(datetime.datetime(2024, 3, 9, 12)) + datetime.timedelta(
+            days=1
+        )
+        fall = tz.localize(datetime.datetime(2024, 11, 2, 12)) + datetime.timedelta(
+            days=1
+        )
+        assert orjson.dumps([spring, fall]) == (
+            b'["2024-03-10T12:00:00-04:00","2024-11-03T12:00:00-05:00"]'
+        )
+
+    @pytest.mark.skipif(zoneinfo is None, reason="zoneinfo not available")
+    def test_datetime_zoneinfo_dst_boundary(self):
+        """
+        datetime.datetime zoneinfo offset after arithmetic across DST
+        """
+        tz = zoneinfo.ZoneInfo("America/New_York")
+        spring = datetime.datetime(2024, 3, 9, 12, tzinfo=tz) + datetime.timedelta(
+            days=1
+        )
+        fall = datetime.datetime(2024, 11, 2, 12, tzinfo=tz) + datetime.timedelta(
+            days=1
+        )
+        assert orjson.dumps([spring, fall]) == (
+            b'["2024-03-10T12:00:00-04:00","2024-11-03T12:00:00-05:00"]'
+        )
+
+    @pytest.mark.skipif(zoneinfo is None, reason="zoneinfo not available")
+    def test_datetime_zoneinfo_fold(self):
+        """
+        datetime.datetime fold selects the offset of an ambiguous local time
+        at a DST fall-back
+        """
+        tz = zoneinfo.ZoneInfo("America/New_York")
+        first = datetime.datetime(2024, 11, 3, 1, 30, tzinfo=tz, fold=0)
+        second = datetime.datetime(2024, 11, 3, 1, 30, tzinfo=tz, fold=1)
+        assert orjson.dumps([first, second]) == (
+            b'["2024-11-03T01:30:00-04:00","2024-11-03T01:30:00-05:00"]'
+        )
+        assert orjson.dumps(
+            [first, second], option=orjson.OPT_DATETIME_AS_UNIX_SECONDS
+        ) == f"[{first.timestamp()},{second.timestamp()}]".encode()
+        assert second.timestamp() - first.timestamp() == 3600
+        assert (
+            orjson.dumps({second: 1}, option=orjson.OPT_NON_STR_KEYS)
+            == b'{"2024-11-03T01:30:00-05:00":1}'
+        )
+
+    @pytest.mark.skipif(zoneinfo is None, reason="zoneinfo not available")
+    def test_datetime_zoneinfo_fold_gap(self):
+        """
+        datetime.datetime fold selects the offset of a nonexistent local time
+        at a DST spring-forward, as utcoffset() does
+        """
+        tz = zoneinfo.ZoneInfo("America/New_York")
+        for fold in (0, 1):
+            val = datetime.datetime(2024, 3, 10, 2, 30, tzinfo=tz, fold=fold)
+            assert orjson.dumps(val) == f'"{val.isoformat()}"'.encode()
+        assert orjson.dumps(
+            datetime.datetime(2024, 3, 10, 2, 30, tzinfo=tz, fold=0)
+        ) != orjson.dumps(datetime.datetime(2024, 3, 10, 2, 30, tzinfo=tz, fold=1))
+
+    @pytest.mark.skipif(tz is None, reason="dateutil optional")
+    def test_datetime_dateutil_fold(self):
+        """
+        datetime.datetime fold with a dateutil tzinfo
+        """
+        dateutil_tz = tz.gettz("America/New_York")
+        assert orjson.dumps(
+            [
+                datetime.datetime(2024, 11, 3, 1, 30, tzinfo=dateutil_tz, fold=0),
+                datetime.datetime(2024, 11, 3, 1, 30, tzinfo=dateutil_tz, fold=1),
+            ]
+        ) == (b'["2024-11-03T01:30:00-04:00","2024-11-03T01:30:00-05:00"]')
+
+    @pytest.mark.skipif(pytz is None, reason="pytz optional")
+    @pytest.mark.skipif(zoneinfo is None, reason="zoneinfo not available")
+    def test_datetime_pytz_zoneinfo_dst_boundary(self):
+        """
+        datetime.datetime pytz and zoneinfo have the same offset on either side
+        of a DST transition
+        """
+        pytz_tz = pytz.timezone("America/New_York")
+        zoneinfo_tz = zoneinfo.ZoneInfo("America/New_York")
+        for start in (
+            datetime.datetime(2024, 3, 10),
+            datetime.datetime(2024, 11, 3),
+        ):
+            for hours in range(6):
+                delta = datetime.timedelta(hours=hours)
+                if (start + delta) == datetime.datetime(2024, 3, 10, 2):
+                    continue  # does not exist
+                assert orjson.dumps(pytz_tz.localize(start) + delta) == orjson.dumps(
+                    start.replace(tzinfo=zoneinfo_tz) + delta
+                )
+
     @pytest.mark.skipif(pendulum is None, reason="pendulum not installed")
     def test_datetime_pendulum_negative_non_dst(self):
         """
@@ -729,6 +1008,63 @@ class TestDatetime:
             in AMSTERDAM_1937_DATETIMES_WITH_Z
         )
 
+    def test_datetime_tz_offset_no_colon(self):
+        """
+        datetime.datetime OPT_TZ_OFFSET_NO_COLON positive, negative, half-hour
+        """
+        for offset, ref_colon, ref_no_colon in (
+            (datetime.timedelta(hours=2), b"+02:00", b"+0200"),
+            (datetime.timedelta(hours=-5), b"-05:00", b"-0500"),
+            (datetime.timedelta(hours=5, minutes=30), b"+05:30", b"+0530"),
+            (datetime.timedelta(hours=-3, minutes=-30), b"-03:30", b"-0330"),
+        ):
+            obj = datetime.datetime(
+                2000, 1, 1, 2, 3, 4, tzinfo=datetime.timezone(offset)
+            )
+            assert orjson.dumps(obj) == b'"2000-01-01T02:03:04' + ref_colon + b'"'
+            assert (
+                orjson.dumps(obj, option=orjson.OPT_TZ_OFFSET_NO_COLON)
+                == b'"2000-01-01T02:03:04' + ref_no_colon + b'"'
+            )
+
+    def test_datetime_tz_offset_no_colon_utc(self):
+        """
+        datetime.datetime OPT_TZ_OFFSET_NO_COLON UTC
+        """
+        obj = datetime.datetime(2000, 1, 1, tzinfo=datetime.timezone.utc)
+        assert (
+            orjson.dumps(obj, option=orjson.OPT_TZ_OFFSET_NO_COLON)
+            == b'"2000-01-01T00:00:00+0000"'
+        )
+        assert (
+            orjson.dumps(
+                datetime.datetime(2000, 1, 1),
+                option=orjson.OPT_TZ_OFFSET_NO_COLON | orjson.OPT_NAIVE_UTC,
+            )
+            == b'"2000-01-01T00:00:00+0000"'
+        )
+
+    def test_datetime_tz_offset_no_colon_utc_z(self):
+        """
+        datetime.datetime OPT_TZ_OFFSET_NO_COLON and OPT_UTC_Z
+        """
+        obj = datetime.datetime(2000, 1, 1, tzinfo=datetime.timezone.utc)
+        assert (
+            orjson.dumps(
+                obj, option=orjson.OPT_TZ_OFFSET_NO_COLON | orjson.OPT_UTC_Z
+            )
+            == b'"2000-01-01T00:00:00Z"'
+        )
+        obj = datetime.datetime(
+            2000, 1, 1, tzinfo=datetime.timezone(datetime.timedelta(hours=5, minutes=30))
+        )
+        assert (
+            orjson.dumps(
+                obj, option=orjson.OPT_TZ_OFFSET_NO_COLON | orjson.OPT_UTC_Z
+            )
+            == b'"2000-01-01T00:00:00+0530"'
+        )
+
     @pytest.mark.skipif(pendulum is None, reason="pendulum not installed")
     def test_datetime_roundtrip(self):
         """
@@ -795,23 +1131,101 @@ class TestTime:
         assert orjson.dumps([datetime.time(12, 15, 59, 111)]) == b'["12:15:59.000111"]'
         assert orjson.dumps([datetime.time(12, 15, 59)]) == b'["12:15:59"]'
 
-    @pytest.mark.skipif(zoneinfo is None, reason="zoneinfo not available")
-    def test_time_tz(self):
+    def test_time_naive(self):
         """
-        datetime.time with tzinfo error
+        datetime.time without tzinfo has no offset regardless of options
         """
-        with pytest.raises(orjson.JSONEncodeError):
+        val = datetime.time(12, 0)
+        assert orjson.dumps(val) == b'"12:00:00"'
+        assert orjson.dumps(val, option=orjson.OPT_NAIVE_UTC) == b'"12:00:00"'
+
+    def test_time_tz_utc(self):
+        """
+        datetime.time with tzinfo of UTC
+        """
+        val = datetime.time(12, 0, tzinfo=datetime.timezone.utc)
+        assert orjson.dumps(val) == b'"12:00:00+00:00"'
+        assert orjson.dumps(val, option=orjson.OPT_UTC_Z) == b'"12:00:00Z"'
+        assert (
+            orjson.dumps(val, option=orjson.OPT_TZ_OFFSET_NO_COLON)
+            == b'"12:00:00+0000"'
+        )
+
+    def test_time_tz_offset(self):
+        """
+        datetime.time with tzinfo of a fixed offset
+        """
+        val = datetime.time(
+            12,
+            15,
+            59,
+            111,
+            tzinfo=datetime.timezone(datetime.timedelta(hours=-5, minutes=-30)),
+        )
+        assert orjson.dumps([val]) == b'["12:15:59.000111-05:30"]'
+        assert orjson.dumps([val]) == b'["' + val.isoformat().encode() + b'"]'
+        assert (
             orjson.dumps(
-                [
-                    datetime.time(
-                        12,
-                        15,
-                        59,
-                        111,
-                        tzinfo=zoneinfo.ZoneInfo("Asia/Shanghai"),
-                    ),
-                ],
+                val, option=orjson.OPT_OMIT_MICROSECONDS | orjson.OPT_TZ_OFFSET_NO_COLON
             )
+            == b'"12:15:59-0530"'
+        )
+        val = datetime.time(
+            23, 0, tzinfo=datetime.timezone(datetime.timedelta(hours=10, minutes=30))
+        )
+        assert orjson.dumps({"a": val}) == b'{"a":"23:00:00+10:30"}'
+
+    def test_time_tz_custom(self):
+        """
+        datetime.time with tzinfo of a tzinfo subclass
+        """
+
+        class Offset(datetime.tzinfo):
+            def utcoffset(self, dt):
+                assert dt is None
+                return datetime.timedelta(hours=2)
+
+        assert (
+            orjson.dumps(datetime.time(12, 0, tzinfo=Offset())) == b'"12:00:00+02:00"'
+        )
+
+    def test_time_tz_none(self):
+        """
+        datetime.time with tzinfo whose utcoffset() is None has no offset
+        """
+
+        class NoOffset(datetime.tzinfo):
+            def utcoffset(self, dt):
+                return None
+
+        assert orjson.dumps(datetime.time(12, 0, tzinfo=NoOffset())) == b'"12:00:00"'
+
+    @pytest.mark.skipif(zoneinfo is None, reason="zoneinfo not available")
+    def test_time_tz_zoneinfo(self):
+        """
+        datetime.time with tzinfo of zoneinfo has no offset, as with isoformat()
+        """
+        val = datetime.time(
+            12,
+            15,
+            59,
+            111,
+            tzinfo=zoneinfo.ZoneInfo("Asia/Shanghai"),
+        )
+        assert orjson.dumps([val]) == b'["12:15:59.000111"]'
+
+    def test_time_tz_err(self):
+        """
+        datetime.time with tzinfo whose utcoffset() raises
+        """
+
+        class Invalid(datetime.tzinfo):
+            def utcoffset(self, dt):
+                raise ValueError("invalid")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps([datetime.time(12, 0, tzinfo=Invalid())])
+        assert isinstance(exc_info.value.__cause__, ValueError)
 
     def test_time_microsecond_max(self):
         """
@@ -860,3 +1274,440 @@ class TestDateclassPassthrough:
             )
             == b'"Thu, 01 Jan 1970 00:00:00 GMT"'
         )
+
+
+DATETIME_OPTIONS = (
+    orjson.OPT_NAIVE_UTC,
+    orjson.OPT_OMIT_MICROSECONDS,
+    orjson.OPT_UTC_Z,
+    orjson.OPT_TZ_OFFSET_NO_COLON,
+)
+
+
+def datetime_isoformat(obj: datetime.datetime, option: int) -> str:
+    """
+    The expected serialization of obj given option, using isoformat()
+    """
+    if option & orjson.OPT_OMIT_MICROSECONDS:
+        obj = obj.replace(microsecond=0)
+    if obj.tzinfo is None and option & orjson.OPT_NAIVE_UTC:
+        obj = obj.replace(tzinfo=datetime.timezone.utc)
+    formatted = obj.isoformat()
+    if obj.tzinfo is None:
+        return formatted
+    if obj.utcoffset() == datetime.timedelta(0):
+        if option & orjson.OPT_UTC_Z:
+            return formatted[:-6] + "Z"
+        if option & orjson.OPT_TZ_OFFSET_NO_COLON:
+            return formatted[:-6] + "+0000"
+        return formatted
+    if option & orjson.OPT_TZ_OFFSET_NO_COLON:
+        return formatted[:-3] + formatted[-2:]
+    return formatted
+
+
+class TestDatetimeOptions:
+    def test_datetime_options_matrix(self):
+        """
+        each combination of datetime options over naive and aware datetimes
+        """
+        tzinfos = (
+            None,
+            datetime.timezone.utc,
+            datetime.timezone(datetime.timedelta(hours=5, minutes=30)),
+            datetime.timezone(-datetime.timedelta(hours=5)),
+            datetime.timezone(datetime.timedelta(hours=-9, minutes=-30)),
+        )
+        values = []
+        for tzinfo in tzinfos:
+            for microsecond in (0, 1, 123456, 999999):
+                values.append(
+                    datetime.datetime(2000, 1, 2, 3, 4, 5, microsecond, tzinfo=tzinfo),
+                )
+                values.append(
+                    datetime.datetime(1, 12, 31, 23, 59, 59, microsecond, tzinfo=tzinfo),
+                )
+        for mask in range(1 << len(DATETIME_OPTIONS)):
+            option = 0
+            for i, opt in enumerate(DATETIME_OPTIONS):
+                if mask & (1 << i):
+                    option |= opt
+            expected = [datetime_isoformat(value, option) for value in values]
+            assert orjson.loads(orjson.dumps(values, option=option)) == expected
+            for value, formatted in zip(values, expected):
+                assert orjson.dumps(value, option=option) == f'"{formatted}"'.encode()
+                assert orjson.dumps(
+                    {value: 1},
+                    option=option | orjson.OPT_NON_STR_KEYS,
+                ) == f'{{"{formatted}":1}}'.encode()
+
+
+class TestDatetimeUnavailable:
+    def test_datetime_unavailable(self):
+        """
+        datetime objects raise JSONEncodeError if the datetime CAPI could not
+        be imported, and other types are unaffected
+        """
+        _reset_without_datetime()
+        try:
+            for val in (
+                DATETIME,
+                DATETIME.date(),
+                DATETIME.time(),
+                DatetimeSubclass(2020, 1, 2),
+            ):
+                with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                    orjson.dumps([val])
+                assert str(exc_info.value).startswith("datetime support unavailable")
+            assert orjson.dumps({"a": [1, "b", None]}) == b'{"a":[1,"b",null]}'
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                orjson.dumps(object())
+            assert "not JSON serializable" in str(exc_info.value)
+        finally:
+            orjson.reset_state()
+
+    def test_datetime_unavailable_default(self):
+        """
+        default is called for datetime objects if the datetime CAPI could not
+        be imported
+        """
+        _reset_without_datetime()
+        try:
+            assert (
+                orjson.dumps(DATETIME, default=datetime.datetime.isoformat)
+                == b'"2020-01-02T03:04:05+00:00"'
+            )
+        finally:
+            orjson.reset_state()
+
+    def test_datetime_unavailable_recovers(self):
+        """
+        reset_state() retries importing the datetime CAPI
+        """
+        _reset_without_datetime()
+        try:
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(DATETIME)
+        finally:
+            orjson.reset_state()
+        assert orjson.dumps(DATETIME) == b'"2020-01-02T03:04:05+00:00"'
+
+
+class TestDatetimeUnix:
+    def test_datetime_unix_seconds_aware(self):
+        """
+        OPT_DATETIME_AS_UNIX_SECONDS matches timestamp()
+        """
+        for val in AWARE:
+            assert orjson.loads(orjson.dumps(val, option=SECONDS)) == val.timestamp()
+
+    def test_datetime_unix_millis_aware(self):
+        """
+        OPT_DATETIME_AS_UNIX_MILLIS matches timestamp()
+        """
+        for val in AWARE:
+            expected = (val - EPOCH) / datetime.timedelta(milliseconds=1)
+            assert orjson.loads(orjson.dumps(val, option=MILLIS)) == expected
+        val = datetime.datetime(2020, 1, 2, 3, 4, 5, 678000, tzinfo=UTC)
+        assert orjson.dumps(val, option=MILLIS) == b"1577934245678.0"
+
+    def test_datetime_unix_naive_utc(self):
+        """
+        a naive datetime with OPT_NAIVE_UTC is UTC
+        """
+        opt = SECONDS | orjson.OPT_NAIVE_UTC
+        for val in AWARE:
+            naive = val.astimezone(UTC).replace(tzinfo=None)
+            assert orjson.dumps(naive, option=opt) == orjson.dumps(val, option=SECONDS)
+            expected = naive.replace(tzinfo=UTC).timestamp()
+            assert orjson.loads(orjson.dumps(naive, option=opt)) == expected
+
+    def test_datetime_unix_naive(self):
+        """
+        a naive datetime without OPT_NAIVE_UTC raises
+        """
+        for opt in (SECONDS, MILLIS):
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                orjson.dumps(datetime.datetime(2020, 1, 1), option=opt)
+            assert "OPT_NAIVE_UTC" in str(exc_info.value)
+
+    def test_datetime_unix_omit_microseconds(self):
+        """
+        OPT_OMIT_MICROSECONDS drops the fraction, rounding down
+        """
+        val = datetime.datetime(2020, 1, 2, 3, 4, 5, 678901, tzinfo=UTC)
+        opt = orjson.OPT_OMIT_MICROSECONDS
+        assert orjson.dumps(val, option=SECONDS | opt) == b"1577934245"
+        assert orjson.dumps(val, option=MILLIS | opt) == b"1577934245678"
+        before = datetime.datetime(1969, 12, 31, 23, 59, 59, 500000, tzinfo=UTC)
+        assert orjson.dumps(before, option=SECONDS | opt) == b"-1"
+        assert orjson.dumps(before, option=MILLIS | opt) == b"-500"
+
+    def test_datetime_unix_fraction(self):
+        """
+        the fraction of a second is preserved
+        """
+        val = datetime.datetime(2020, 1, 2, 3, 4, 5, 678, tzinfo=UTC)
+        assert orjson.dumps(val, option=SECONDS) == b"1577934245.000678"
+        assert orjson.dumps(val, option=MILLIS) == b"1577934245000.678"
+        val = val.replace(microsecond=0)
+        assert orjson.dumps(val, option=SECONDS) == b"1577934245.0"
+
+    @pytest.mark.skipif(zoneinfo is None, reason="zoneinfo not available")
+    def test_datetime_unix_zoneinfo(self):
+        """
+        zoneinfo offsets, including across DST
+        """
+        tz = zoneinfo.ZoneInfo("America/New_York")
+        for val in (
+            datetime.datetime(2020, 1, 1, 12, tzinfo=tz),
+            datetime.datetime(2020, 7, 1, 12, tzinfo=tz),
+            datetime.datetime(2020, 11, 1, 1, 30, fold=1, tzinfo=tz),
+        ):
+            assert orjson.loads(orjson.dumps(val, option=SECONDS)) == val.timestamp()
+
+    @pytest.mark.skipif(pytz is None, reason="pytz optional")
+    def test_datetime_unix_pytz(self):
+        """
+        pytz offsets
+        """
+        val = pytz.timezone("Asia/Kolkata").localize(datetime.datetime(2020, 1, 1, 12))
+        assert orjson.loads(orjson.dumps(val, option=SECONDS)) == val.timestamp()
+
+    def test_datetime_unix_nested(self):
+        """
+        datetimes in a list and a dict; dates and times are unaffected
+        """
+        val = datetime.datetime(1970, 1, 2, tzinfo=UTC)
+        assert orjson.dumps(
+            {"a": [val, datetime.date(1970, 1, 2), datetime.time(1)]}, option=SECONDS
+        ) == b'{"a":[86400.0,"1970-01-02","01:00:00"]}'
+
+    def test_datetime_unix_key(self):
+        """
+        keys with OPT_NON_STR_KEYS are unaffected
+        """
+        val = datetime.datetime(1970, 1, 2, tzinfo=UTC)
+        assert orjson.dumps({val: val}, option=SECONDS | orjson.OPT_NON_STR_KEYS) == (
+            b'{"1970-01-02T00:00:00+00:00":86400.0}'
+        )
+
+    def test_datetime_unix_passthrough(self):
+        """
+        OPT_PASSTHROUGH_DATETIME takes precedence
+        """
+        val = datetime.datetime(1970, 1, 2, tzinfo=UTC)
+        assert orjson.dumps(
+            val, option=SECONDS | orjson.OPT_PASSTHROUGH_DATETIME, default=str
+        ) == b'"1970-01-02 00:00:00+00:00"'
+
+    def test_datetime_unix_exclusive(self):
+        """
+        OPT_DATETIME_AS_UNIX_SECONDS and OPT_DATETIME_AS_UNIX_MILLIS cannot be
+        combined
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(1, option=SECONDS | MILLIS)
+        with pytest.raises(ValueError):
+            orjson.Options(SECONDS, MILLIS)
+
+
+class TestDateAsEpochDays:
+    def test_date_epoch(self):
+        assert orjson.dumps(datetime.date(1970, 1, 1), option=EPOCH_DAYS) == b"0"
+        assert orjson.dumps(datetime.date(1970, 1, 2), option=EPOCH_DAYS) == b"1"
+
+    def test_date_before_epoch(self):
+        assert orjson.dumps(datetime.date(1969, 12, 31), option=EPOCH_DAYS) == b"-1"
+        assert orjson.dumps(datetime.date(1900, 3, 1), option=EPOCH_DAYS) == b"-25508"
+
+    def test_date_modern(self):
+        assert orjson.dumps(datetime.date(2024, 2, 29), option=EPOCH_DAYS) == b"19782"
+        assert orjson.dumps(datetime.date(2025, 6, 15), option=EPOCH_DAYS) == b"20254"
+
+    def test_date_limits(self):
+        assert orjson.dumps(datetime.date.min, option=EPOCH_DAYS) == b"-719162"
+        assert orjson.dumps(datetime.date.max, option=EPOCH_DAYS) == b"2932896"
+
+    def test_date_toordinal(self):
+        """
+        the day count is that of date.toordinal() relative to the epoch
+        """
+        epoch = datetime.date(1970, 1, 1).toordinal()
+        date = datetime.date(1, 1, 1)
+        while date.year < 9999:
+            assert orjson.dumps(date, option=EPOCH_DAYS) == str(
+                date.toordinal() - epoch
+            ).encode()
+            date += datetime.timedelta(days=113)
+
+    def test_date_subclass(self):
+        assert orjson.dumps(DateSubclass(1970, 1, 11), option=EPOCH_DAYS) == b"10"
+
+    def test_date_nested(self):
+        date = datetime.date(1970, 1, 3)
+        assert (
+            orjson.dumps({"a": date, "b": [date], "c": (date,)}, option=EPOCH_DAYS)
+            == b'{"a":2,"b":[2],"c":[2]}'
+        )
+
+    def test_date_datetime_unaffected(self):
+        """
+        OPT_DATE_AS_EPOCH_DAYS does not change datetime or time
+        """
+        assert (
+            orjson.dumps(datetime.datetime(1970, 1, 1), option=EPOCH_DAYS)
+            == b'"1970-01-01T00:00:00"'
+        )
+        assert orjson.dumps(datetime.time(12), option=EPOCH_DAYS) == b'"12:00:00"'
+        assert (
+            orjson.dumps(
+                [datetime.date(1970, 1, 2), datetime.datetime(1970, 1, 2)],
+                option=EPOCH_DAYS
+                | orjson.OPT_DATETIME_AS_UNIX_SECONDS
+                | orjson.OPT_NAIVE_UTC,
+            )
+            == b"[1,86400.0]"
+        )
+
+    def test_date_disabled(self):
+        assert orjson.dumps(datetime.date(1970, 1, 1)) == b'"1970-01-01"'
+
+    def test_date_non_str_keys(self):
+        """
+        dict keys are serialized as ISO dates
+        """
+        assert (
+            orjson.dumps(
+                {datetime.date(1970, 1, 1): datetime.date(1970, 1, 1)},
+                option=EPOCH_DAYS | orjson.OPT_NON_STR_KEYS,
+            )
+            == b'{"1970-01-01":0}'
+        )
+
+    def test_date_passthrough(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                datetime.date(1970, 1, 1),
+                option=EPOCH_DAYS | orjson.OPT_PASSTHROUGH_DATETIME,
+            )
+
+
+class TestRfc3339Strict:
+    def test_rfc3339_strict_aware(self):
+        """
+        OPT_RFC3339_STRICT aware datetime
+        """
+        assert (
+            orjson.dumps(
+                datetime.datetime(2000, 1, 1, 2, 3, 4, tzinfo=datetime.timezone.utc),
+                option=STRICT,
+            )
+            == b'"2000-01-01T02:03:04+00:00"'
+        )
+
+    def test_rfc3339_strict_aware_offset(self):
+        """
+        OPT_RFC3339_STRICT aware datetime with a non-UTC offset
+        """
+        tz = datetime.timezone(datetime.timedelta(hours=-5, minutes=-30))
+        assert (
+            orjson.dumps(
+                datetime.datetime(2000, 1, 1, 2, 3, 4, tzinfo=tz),
+                option=STRICT,
+            )
+            == b'"2000-01-01T02:03:04-05:30"'
+        )
+
+    def test_rfc3339_strict_naive(self):
+        """
+        OPT_RFC3339_STRICT raises on a naive datetime
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(datetime.datetime(2000, 1, 1, 2, 3, 4), option=STRICT)
+        assert "OPT_RFC3339_STRICT" in str(exc_info.value)
+
+    def test_rfc3339_strict_naive_key(self):
+        """
+        OPT_RFC3339_STRICT raises on a naive datetime dict key
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                {datetime.datetime(2000, 1, 1): 1},
+                option=STRICT | orjson.OPT_NON_STR_KEYS,
+            )
+
+    def test_rfc3339_strict_naive_utc(self):
+        """
+        OPT_RFC3339_STRICT serializes a naive datetime with OPT_NAIVE_UTC
+        """
+        assert (
+            orjson.dumps(
+                datetime.datetime(2000, 1, 1, 2, 3, 4),
+                option=STRICT | orjson.OPT_NAIVE_UTC,
+            )
+            == b'"2000-01-01T02:03:04+00:00"'
+        )
+
+    def test_rfc3339_strict_naive_utc_z(self):
+        """
+        OPT_RFC3339_STRICT with OPT_NAIVE_UTC and OPT_UTC_Z
+        """
+        assert (
+            orjson.dumps(
+                datetime.datetime(2000, 1, 1, 2, 3, 4),
+                option=STRICT | orjson.OPT_NAIVE_UTC | orjson.OPT_UTC_Z,
+            )
+            == b'"2000-01-01T02:03:04Z"'
+        )
+
+    def test_rfc3339_strict_microseconds(self):
+        """
+        OPT_RFC3339_STRICT serializes microseconds if present
+        """
+        tz = datetime.timezone.utc
+        assert (
+            orjson.dumps(
+                [
+                    datetime.datetime(2000, 1, 1, 2, 3, 4, 123, tzinfo=tz),
+                    datetime.datetime(2000, 1, 1, 2, 3, 4, 0, tzinfo=tz),
+                ],
+                option=STRICT | orjson.OPT_OMIT_MICROSECONDS,
+            )
+            == b'["2000-01-01T02:03:04.000123+00:00","2000-01-01T02:03:04+00:00"]'
+        )
+
+    def test_rfc3339_strict_offset_colon(self):
+        """
+        OPT_RFC3339_STRICT ignores OPT_TZ_OFFSET_NO_COLON
+        """
+        tz = datetime.timezone(datetime.timedelta(hours=10))
+        assert orjson.dumps(
+            [
+                datetime.datetime(2000, 1, 1, tzinfo=tz),
+                datetime.datetime(2000, 1, 1, tzinfo=datetime.timezone.utc),
+            ],
+            option=STRICT | orjson.OPT_TZ_OFFSET_NO_COLON,
+        ) == b'["2000-01-01T00:00:00+10:00","2000-01-01T00:00:00+00:00"]'
+
+    def test_rfc3339_strict_unix(self):
+        """
+        OPT_RFC3339_STRICT and OPT_DATETIME_AS_UNIX_SECONDS are exclusive
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                datetime.datetime(2000, 1, 1, tzinfo=datetime.timezone.utc),
+                option=STRICT | orjson.OPT_DATETIME_AS_UNIX_SECONDS,
+            )
+
+    def test_rfc3339_strict_date_time(self):
+        """
+        OPT_RFC3339_STRICT does not change datetime.date or datetime.time
+        """
+        assert (
+            orjson.dumps(
+                [datetime.date(2000, 1, 1), datetime.time(2, 3, 4)],
+                option=STRICT,
+            )
+            == b'["2000-01-01","02:03:04"]'
+        )
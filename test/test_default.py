@@ -1,7 +1,11 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2019-2025), Rami Chowdhury (2020), Marc Mueller (2023), Jack Amadeo (2023)
 
+import contextvars
+import dataclasses
 import datetime
+import decimal
+import inspect
 import sys
 import uuid
 
@@ -9,7 +13,7 @@ import pytest
 
 import orjson
 
-from .util import SUPPORTS_GETREFCOUNT, numpy
+from .util import SUPPORTS_GETREFCOUNT, numpy, run_in_subinterpreter
 
 
 class Custom:
@@ -36,6 +40,82 @@ def default_raises(obj):
     raise TypeError
 
 
+SKIP = orjson.OPT_SKIP_UNSERIALIZABLE
+
+
+class Bare:
+    pass
+
+
+@dataclasses.dataclass
+class Dataclass:
+    a: int
+    b: object
+
+
+REPR = orjson.OPT_REPR_FALLBACK
+
+
+class Opaque:
+    def __repr__(self):
+        return "<Opaque>"
+
+
+class Quoted:
+    def __repr__(self):
+        return 'Quoted("a\\b")'
+
+
+class RaisesRepr:
+    def __repr__(self):
+        raise ValueError("repr")
+
+
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+
+class Point3(Point):
+    def __init__(self, x, y, z):
+        super().__init__(x, y)
+        self.z = z
+
+
+class Mixin:
+    pass
+
+
+class Point3Mixin(Mixin, Point3):
+    pass
+
+
+def unregister():
+    for tp in (Point, Point3, Mixin, Point3Mixin, decimal.Decimal, datetime.datetime):
+        orjson.register_type(tp, None)
+
+
+JSON_DUNDER = orjson.OPT_USE_JSON_DUNDER
+
+
+class Coord:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def __json__(self):
+        return {"x": self.x, "y": self.y}
+
+
+class Raw:
+    def __init__(self, contents):
+        self.contents = contents
+
+    def __json__(self):
+        return self.contents
+
+
 class TestType:
     def test_default_not_callable(self):
         """
@@ -351,3 +431,766 @@ class TestType:
             raise TypeError
 
         assert orjson.dumps({1, 2}, default=default) == b"[1,2]"
+
+    def test_default_same_type_many(self):
+        """
+        dumps() default with many objects of one type, and others between them
+        """
+        objs = [Recursive(i) for i in range(1000)]
+        assert orjson.dumps(objs, default=lambda obj: obj.cur) == orjson.dumps(
+            list(range(1000))
+        )
+        mixed = [Recursive(1), {1}, Recursive(2), {2}, 3, Recursive(4)]
+
+        def default(obj):
+            if isinstance(obj, set):
+                return list(obj)
+            return obj.cur
+
+        assert orjson.dumps(mixed, default=default) == b"[1,[1],2,[2],3,4]"
+
+    def test_default_same_type_options(self):
+        """
+        a type passed to default with one option is serialized natively where
+        the option does not apply, e.g., as a key with OPT_PASSTHROUGH_DATETIME
+        """
+        val = datetime.date(2020, 1, 2)
+        assert (
+            orjson.dumps(
+                [val, {val: val}],
+                default=lambda obj: "default",
+                option=orjson.OPT_PASSTHROUGH_DATETIME | orjson.OPT_NON_STR_KEYS,
+            )
+            == b'["default",{"2020-01-02":"default"}]'
+        )
+
+    def test_default_same_type_nested_dumps(self):
+        """
+        dumps() called by default does not share what the outer call passed
+        to default
+        """
+        val = datetime.date(2020, 1, 2)
+
+        def default(obj):
+            return orjson.dumps(
+                [obj.cur, val],
+                default=lambda _: "inner",
+                option=orjson.OPT_PASSTHROUGH_DATETIME,
+            ).decode()
+
+        assert orjson.dumps([Recursive(1), val, Recursive(2), val], default=default) == (
+            b'["[1,\\"inner\\"]","2020-01-02","[2,\\"inner\\"]","2020-01-02"]'
+        )
+
+    def test_reference_cleanup_default_same_type(self):
+        """
+        references to the types of objects passed to default are released
+        """
+
+        class Local:
+            pass
+
+        if SUPPORTS_GETREFCOUNT:
+            refcount = sys.getrefcount(Local)
+        orjson.dumps([Local(), Local()], default=lambda obj: None)
+        if SUPPORTS_GETREFCOUNT:
+            assert sys.getrefcount(Local) == refcount
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([Local()])
+        if SUPPORTS_GETREFCOUNT:
+            assert sys.getrefcount(Local) == refcount
+
+    def test_default_sequence(self):
+        """
+        dumps() default list or tuple of callables tried in order
+        """
+
+        def first(obj):
+            if isinstance(obj, datetime.timedelta):
+                return obj.days
+            return NotImplemented
+
+        def second(obj):
+            return str(obj)
+
+        ref = Custom()
+        assert orjson.dumps(ref, default=[first, second]) == f'"{ref}"'.encode()
+        assert orjson.dumps(ref, default=(first, second)) == f'"{ref}"'.encode()
+        assert (
+            orjson.dumps(datetime.timedelta(days=2), default=[first, second]) == b"2"
+        )
+
+    def test_default_sequence_raises(self):
+        """
+        dumps() default sequence skips a callable that raises
+        """
+        ref = Custom()
+        assert orjson.dumps(ref, default=[default_raises, str]) == f'"{ref}"'.encode()
+
+    def test_default_sequence_last_exception(self):
+        """
+        dumps() default sequence chains the exception of the last callable
+        """
+
+        def last(obj):
+            raise ValueError("last")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(Custom(), default=[default_raises, last])
+        assert isinstance(exc_info.value.__cause__, ValueError)
+        assert str(exc_info.value.__cause__) == "last"
+
+    def test_default_sequence_not_implemented(self):
+        """
+        dumps() default sequence of callables that all return NotImplemented
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(
+                Custom(),
+                default=[default_raises, lambda obj: NotImplemented],
+            )
+        assert str(exc_info.value) == "Type is not JSON serializable: Custom"
+        assert exc_info.value.__cause__ is None
+
+    def test_default_sequence_empty(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Custom(), default=[])
+        assert orjson.dumps([1], default=()) == b"[1]"
+
+    def test_default_sequence_not_callable(self):
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(1, default=[str, 1])
+        assert (
+            str(exc_info.value)
+            == "default must be callable or a list or tuple of callables"
+        )
+
+    def test_default_sequence_nested(self):
+        """
+        dumps() default sequence handles what a callable returns
+        """
+
+        def to_set(obj):
+            if isinstance(obj, Custom):
+                return {1}
+            return NotImplemented
+
+        assert orjson.dumps(Custom(), default=[to_set, sorted]) == b"[1]"
+
+    def test_default_sequence_recursion(self):
+        """
+        dumps() default sequence recursion limit
+        """
+        assert (
+            orjson.dumps(Recursive(254), default=[default_raises, default_recursive])
+            == b"0"
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Recursive(255), default=[default_raises, default_recursive])
+
+    def test_default_sequence_mutated(self):
+        """
+        dumps() default list modified by a callable
+        """
+        callables: list = []
+
+        def clear(obj):
+            callables.clear()
+            raise TypeError
+
+        callables.extend([clear, str])
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Custom(), default=callables)
+
+
+class TestSkipUnserializable:
+    def test_skip_list(self):
+        """
+        an element of a list that cannot be serialized is null
+        """
+        assert orjson.dumps([1, Bare(), "a"], option=SKIP) == b'[1,null,"a"]'
+
+    def test_skip_tuple(self):
+        assert orjson.dumps((Bare(), 2), option=SKIP) == b"[null,2]"
+
+    def test_skip_top_level(self):
+        assert orjson.dumps(Bare(), option=SKIP) == b"null"
+
+    def test_skip_dict(self):
+        """
+        the entry of a dict value that cannot be serialized is omitted
+        """
+        assert (
+            orjson.dumps({"a": 1, "b": Bare(), "c": [Bare()]}, option=SKIP)
+            == b'{"a":1,"c":[null]}'
+        )
+
+    def test_skip_dict_all(self):
+        assert orjson.dumps({"a": Bare()}, option=SKIP) == b"{}"
+        assert (
+            orjson.dumps({"a": Bare()}, option=SKIP | orjson.OPT_INDENT_2)
+            == b"{}"
+        )
+
+    def test_skip_dict_indent(self):
+        assert (
+            orjson.dumps(
+                {"a": Bare(), "b": 1, "c": Bare()},
+                option=SKIP | orjson.OPT_INDENT_2,
+            )
+            == b'{\n  "b": 1\n}'
+        )
+
+    def test_skip_dict_sort_keys(self):
+        assert (
+            orjson.dumps(
+                {"c": 3, "b": Bare(), "a": 1}, option=SKIP | orjson.OPT_SORT_KEYS
+            )
+            == b'{"a":1,"c":3}'
+        )
+
+    def test_skip_dict_non_str_keys(self):
+        assert (
+            orjson.dumps(
+                {1: Bare(), 2: 2}, option=SKIP | orjson.OPT_NON_STR_KEYS
+            )
+            == b'{"2":2}'
+        )
+
+    def test_skip_dataclass(self):
+        assert orjson.dumps(Dataclass(1, Bare()), option=SKIP) == b'{"a":1}'
+
+    def test_skip_default(self):
+        """
+        an object is serialized by default if it can be
+        """
+
+        def default(obj):
+            if isinstance(obj, Bare):
+                return "custom"
+            raise TypeError
+
+        assert (
+            orjson.dumps([Bare(), {"a": Bare()}], default=default, option=SKIP)
+            == b'["custom",{"a":"custom"}]'
+        )
+        assert (
+            orjson.dumps([{1}, {"a": {1}}], default=default, option=SKIP)
+            == b'[null,{}]'
+        )
+
+    def test_skip_default_returns_unserializable(self):
+        """
+        what default returns is skipped in turn if it cannot be serialized
+        """
+
+        def default(obj):
+            if isinstance(obj, Bare):
+                return [1, {2}]
+            raise TypeError
+
+        assert (
+            orjson.dumps({"a": Bare()}, default=default, option=SKIP)
+            == b'{"a":[1,null]}'
+        )
+
+    def test_skip_default_recursion(self):
+        """
+        the default recursion limit is not skipped
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Bare(), default=lambda obj: Bare(), option=SKIP)
+
+    def test_skip_disabled(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1, Bare()])
+
+    def test_skip_other_errors(self):
+        """
+        errors other than an unsupported type are raised
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1, "\ud800"], option=SKIP)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({1: 1}, option=SKIP)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1 << 64], option=SKIP)
+
+    def test_on_skip(self):
+        """
+        on_skip is called with each object skipped and its error
+        """
+        skipped = []
+        first = Bare()
+        second = Bare()
+        assert (
+            orjson.dumps(
+                {"a": first, "b": [1, second]},
+                option=SKIP,
+                on_skip=lambda obj, err: skipped.append((obj, err)),
+            )
+            == b'{"b":[1,null]}'
+        )
+        assert [each[0] for each in skipped] == [first, second]
+        for _, err in skipped:
+            assert isinstance(err, orjson.JSONEncodeError)
+            assert str(err) == "Type is not JSON serializable: Bare"
+
+    def test_on_skip_default_cause(self):
+        """
+        the exception default raised is the cause of the error
+        """
+        skipped = []
+
+        def default(obj):
+            raise ValueError("unsupported")
+
+        assert (
+            orjson.dumps(
+                [Bare()],
+                default=default,
+                option=SKIP,
+                on_skip=lambda obj, err: skipped.append(err),
+            )
+            == b"[null]"
+        )
+        assert isinstance(skipped[0].__cause__, ValueError)
+
+    def test_on_skip_raises(self):
+        def on_skip(obj, err):
+            raise ValueError("abort")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps([Bare()], option=SKIP, on_skip=on_skip)
+        assert str(exc_info.value) == "on_skip raised an exception"
+        assert isinstance(exc_info.value.__cause__, ValueError)
+
+    def test_on_skip_without_option(self):
+        """
+        on_skip is not called without OPT_SKIP_UNSERIALIZABLE
+        """
+        skipped = []
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([Bare()], on_skip=lambda obj, err: skipped.append(obj))
+        assert skipped == []
+
+    def test_on_skip_none(self):
+        assert orjson.dumps([Bare()], option=SKIP, on_skip=None) == b"[null]"
+
+    def test_on_skip_invalid(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([], option=SKIP, on_skip=1)
+
+    def test_on_skip_dumps_str(self):
+        skipped = []
+        assert (
+            orjson.dumps_str(
+                [Bare()], option=SKIP, on_skip=lambda obj, err: skipped.append(obj)
+            )
+            == "[null]"
+        )
+        assert len(skipped) == 1
+
+
+class TestReprFallback:
+    def test_repr_fallback(self):
+        """
+        OPT_REPR_FALLBACK serializes an unsupported object as its repr()
+        """
+        assert orjson.dumps(Opaque(), option=REPR) == b'"<Opaque>"'
+
+    def test_repr_fallback_container(self):
+        """
+        OPT_REPR_FALLBACK in a dict and list
+        """
+        assert (
+            orjson.dumps({"a": Opaque(), "b": [1, Opaque()]}, option=REPR)
+            == b'{"a":"<Opaque>","b":[1,"<Opaque>"]}'
+        )
+
+    def test_repr_fallback_escaped(self):
+        """
+        OPT_REPR_FALLBACK escapes the repr() as a JSON string
+        """
+        assert orjson.dumps(Quoted(), option=REPR) == b'"Quoted(\\"a\\\\b\\")"'
+
+    def test_repr_fallback_object(self):
+        """
+        OPT_REPR_FALLBACK of an arbitrary object
+        """
+        obj = object()
+        assert orjson.dumps(obj, option=REPR) == orjson.dumps(repr(obj))
+
+    def test_repr_fallback_contextvars(self):
+        """
+        OPT_REPR_FALLBACK of a contextvars.Token
+        """
+        var: contextvars.ContextVar[int] = contextvars.ContextVar("var")
+        token = var.set(1)
+        try:
+            assert orjson.dumps(token, option=REPR) == orjson.dumps(repr(token))
+        finally:
+            var.reset(token)
+
+    def test_repr_fallback_default(self):
+        """
+        OPT_REPR_FALLBACK does not override default that succeeds
+        """
+        assert orjson.dumps(Opaque(), default=lambda _: 1, option=REPR) == b"1"
+
+    def test_repr_fallback_default_raises(self):
+        """
+        OPT_REPR_FALLBACK applies if default raises
+        """
+
+        def default(obj):
+            raise TypeError
+
+        assert orjson.dumps(Opaque(), default=default, option=REPR) == b'"<Opaque>"'
+
+    def test_repr_fallback_default_list(self):
+        """
+        OPT_REPR_FALLBACK applies if no callable of a default list serializes
+        """
+        assert (
+            orjson.dumps(Opaque(), default=[lambda _: NotImplemented], option=REPR)
+            == b'"<Opaque>"'
+        )
+
+    def test_repr_fallback_default_returns_unsupported(self):
+        """
+        OPT_REPR_FALLBACK applies to what default returns
+        """
+
+        def default(obj):
+            if isinstance(obj, complex):
+                return Opaque()
+            raise TypeError
+
+        assert orjson.dumps(1j, default=default, option=REPR) == b'"<Opaque>"'
+
+    def test_repr_fallback_supported(self):
+        """
+        OPT_REPR_FALLBACK does not change supported types
+        """
+        assert orjson.dumps([1, "a", None], option=REPR) == b'[1,"a",null]'
+
+    def test_repr_fallback_skip_unserializable(self):
+        """
+        OPT_REPR_FALLBACK takes precedence over OPT_SKIP_UNSERIALIZABLE
+        """
+        assert (
+            orjson.dumps(
+                {"a": Opaque(), "b": [Opaque()]},
+                option=REPR | orjson.OPT_SKIP_UNSERIALIZABLE,
+            )
+            == b'{"a":"<Opaque>","b":["<Opaque>"]}'
+        )
+
+    def test_repr_fallback_raises(self):
+        """
+        OPT_REPR_FALLBACK raises if repr() raises
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(RaisesRepr(), option=REPR)
+        assert isinstance(exc_info.value.__cause__, ValueError)
+
+    def test_repr_fallback_disabled(self):
+        """
+        unsupported objects raise without OPT_REPR_FALLBACK
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Opaque())
+
+
+class TestRegisterType:
+    def test_register_type(self):
+        """
+        register_type() handler is invoked for an instance of the type
+        """
+        try:
+            calls = []
+
+            def handler(obj):
+                calls.append(obj)
+                return [obj.x, obj.y]
+
+            val = Point(1, 2)
+            assert orjson.register_type(Point, handler) is None
+            assert orjson.dumps(val) == b"[1,2]"
+            assert orjson.dumps({"a": [val, val]}) == b'{"a":[[1,2],[1,2]]}'
+            assert calls == [val, val, val]
+        finally:
+            unregister()
+
+    def test_register_type_before_default(self):
+        """
+        a registered handler takes precedence over default, which still applies
+        to other types and to what the handler returns
+        """
+        try:
+            orjson.register_type(Point, lambda obj: {"x": obj.x, "d": decimal.Decimal("1.5")})
+            assert orjson.dumps([Point(1, 2), decimal.Decimal("2.5")], default=str) == (
+                b'[{"x":1,"d":"1.5"},"2.5"]'
+            )
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(Point(1, 2))
+        finally:
+            unregister()
+
+    def test_register_type_mro(self):
+        """
+        the exact type's handler is used, else that of the first type in the MRO
+        """
+        try:
+            orjson.register_type(Point, lambda _: "point")
+            assert orjson.dumps(Point3(1, 2, 3)) == b'"point"'
+            orjson.register_type(Point3, lambda _: "point3")
+            assert orjson.dumps([Point(1, 2), Point3(1, 2, 3)]) == b'["point","point3"]'
+            orjson.register_type(Mixin, lambda _: "mixin")
+            assert orjson.dumps(Point3Mixin(1, 2, 3)) == b'"mixin"'
+            orjson.register_type(Point3Mixin, lambda _: "exact")
+            assert orjson.dumps(Point3Mixin(1, 2, 3)) == b'"exact"'
+            orjson.register_type(Point3Mixin, None)
+            orjson.register_type(Mixin, None)
+            assert orjson.dumps(Point3Mixin(1, 2, 3)) == b'"point3"'
+        finally:
+            unregister()
+
+    def test_register_type_replace_and_remove(self):
+        """
+        registering again replaces the handler and None removes it
+        """
+        try:
+            orjson.register_type(decimal.Decimal, str)
+            assert orjson.dumps(decimal.Decimal("1.10")) == b'"1.10"'
+            orjson.register_type(decimal.Decimal, float)
+            assert orjson.dumps(decimal.Decimal("1.10")) == b"1.1"
+            orjson.register_type(decimal.Decimal, None)
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(decimal.Decimal("1.10"))
+            assert orjson.register_type(decimal.Decimal, None) is None
+        finally:
+            unregister()
+
+    def test_register_type_native(self):
+        """
+        natively serialized types are given to a handler only if passed through
+        """
+        try:
+            orjson.register_type(datetime.datetime, lambda _: "handler")
+            val = datetime.datetime(2020, 1, 2, 3, 4, 5)
+            assert orjson.dumps(val) == b'"2020-01-02T03:04:05"'
+            assert orjson.dumps(val, option=orjson.OPT_PASSTHROUGH_DATETIME) == b'"handler"'
+        finally:
+            unregister()
+
+    def test_register_type_raises(self):
+        """
+        an exception raised by a handler is chained to JSONEncodeError
+        """
+        try:
+
+            def handler(obj):
+                raise ValueError("invalid")
+
+            orjson.register_type(Point, handler)
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                orjson.dumps(Point(1, 2))
+            assert isinstance(exc_info.value.__cause__, ValueError)
+        finally:
+            unregister()
+
+    def test_register_type_unregister_during_call(self):
+        """
+        a handler that removes itself while called
+        """
+        try:
+
+            def handler(obj):
+                orjson.register_type(Point, None)
+                return "handled"
+
+            orjson.register_type(Point, handler)
+            del handler
+            assert orjson.dumps([Point(1, 2), Point(3, 4)], default=lambda _: "default") == (
+                b'["handled","default"]'
+            )
+        finally:
+            unregister()
+
+    def test_register_type_recursion(self):
+        """
+        a handler that returns an instance of its type is limited as default is
+        """
+        try:
+            orjson.register_type(Point, lambda obj: Point(obj.x, obj.y))
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                orjson.dumps(Point(1, 2))
+            assert "default serializer exceeds recursion limit" in str(exc_info.value)
+        finally:
+            unregister()
+
+    def test_register_type_invalid(self):
+        """
+        register_type() raises TypeError on invalid arguments
+        """
+        with pytest.raises(TypeError):
+            orjson.register_type(Point(1, 2), str)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.register_type(Point, "str")  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.register_type(Point)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.register_type(Point, str, str)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.register_type(tp=Point, handler=str)  # type: ignore
+
+    def test_register_type_reset_state(self):
+        """
+        reset_state() removes handlers
+        """
+        try:
+            orjson.register_type(Point, lambda _: "point")
+            orjson.reset_state()
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(Point(1, 2))
+        finally:
+            unregister()
+
+    def test_register_type_subinterpreter_isolated(self):
+        """
+        handlers are per-interpreter
+        """
+        try:
+            orjson.register_type(decimal.Decimal, str)
+            run_in_subinterpreter(
+                """
+                import decimal
+                import orjson
+                try:
+                    orjson.dumps(decimal.Decimal("1"))
+                except orjson.JSONEncodeError:
+                    pass
+                else:
+                    raise AssertionError("handler of the main interpreter used")
+                orjson.register_type(decimal.Decimal, float)
+                assert orjson.dumps(decimal.Decimal("1.5")) == b"1.5"
+                """,
+            )
+            assert orjson.dumps(decimal.Decimal("1.50")) == b'"1.50"'
+        finally:
+            unregister()
+
+    def test_register_type_signature(self):
+        """
+        register_type() valid __text_signature__
+        """
+        assert str(inspect.signature(orjson.register_type)) == "(tp, handler, /)"
+        assert orjson.register_type.__module__ == "orjson"
+
+
+class TestJsonDunder:
+    def test_json_dunder_dict(self):
+        """
+        OPT_USE_JSON_DUNDER serializes the value returned by __json__()
+        """
+        assert orjson.dumps(Coord(1, 2), option=JSON_DUNDER) == b'{"x":1,"y":2}'
+        assert orjson.dumps([Coord(1, [Coord(2, 3)])], option=JSON_DUNDER) == (
+            b'[{"x":1,"y":[{"x":2,"y":3}]}]'
+        )
+        assert (
+            orjson.dumps({"a": Coord(1, 2)}, option=JSON_DUNDER)
+            == b'{"a":{"x":1,"y":2}}'
+        )
+
+    def test_json_dunder_fragment(self):
+        """
+        OPT_USE_JSON_DUNDER writes bytes or str returned by __json__() as is
+        """
+        assert orjson.dumps(Raw(b'{"a":[1]}'), option=JSON_DUNDER) == b'{"a":[1]}'
+        assert orjson.dumps([Raw('"é"')], option=JSON_DUNDER) == b'["\xc3\xa9"]'
+        assert (
+            orjson.dumps({"a": Raw(orjson.Fragment(b"[]"))}, option=JSON_DUNDER)
+            == b'{"a":[]}'
+        )
+
+    def test_json_dunder_disabled(self):
+        """
+        __json__() is not called without OPT_USE_JSON_DUNDER
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Coord(1, 2))
+        assert orjson.dumps(Coord(1, 2), default=lambda _: "default") == b'"default"'
+
+    def test_json_dunder_before_default(self):
+        """
+        __json__() is used instead of default, which is used for its result
+        """
+        assert (
+            orjson.dumps(Raw(object()), option=JSON_DUNDER, default=lambda _: "default")
+            == b'"default"'
+        )
+        assert (
+            orjson.dumps(object(), option=JSON_DUNDER, default=lambda _: "default")
+            == b'"default"'
+        )
+
+    def test_json_dunder_supported_type(self):
+        """
+        __json__() is not called for a natively supported type
+        """
+
+        @dataclasses.dataclass
+        class Dataclass:
+            a: int
+
+            def __json__(self):
+                raise AssertionError
+
+        class DictSubclass(dict):
+            def __json__(self):
+                raise AssertionError
+
+        assert orjson.dumps(Dataclass(1), option=JSON_DUNDER) == b'{"a":1}'
+        assert orjson.dumps(DictSubclass(a=1), option=JSON_DUNDER) == b'{"a":1}'
+
+    def test_json_dunder_exception(self):
+        """
+        an exception raised by __json__() is chained to JSONEncodeError
+        """
+
+        class Failing:
+            def __json__(self):
+                raise ValueError("failing")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps([Failing()], option=JSON_DUNDER)
+        assert isinstance(exc_info.value.__cause__, ValueError)
+        assert "__json__" in str(exc_info.value)
+
+    def test_json_dunder_recursion(self):
+        """
+        __json__() returning itself raises JSONEncodeError
+        """
+
+        class Recursive:
+            def __json__(self):
+                return self
+
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Recursive(), option=JSON_DUNDER)
+
+    def test_json_dunder_invalid_str(self):
+        """
+        __json__() returning a str that is not valid UTF-8
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Raw("\ud800"), option=JSON_DUNDER)
+
+    def test_json_dunder_non_str_key(self):
+        """
+        an object with __json__() is not a valid key with OPT_NON_STR_KEYS
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({Raw(b"1"): 1}, option=JSON_DUNDER | orjson.OPT_NON_STR_KEYS)
@@ -1,11 +1,41 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2018-2025), J. Nick Koston (2022), Anders Kaseorg (2022)
 
+import collections
+import dataclasses
+import datetime
+import types
+import uuid
+
 import pytest
 
 import orjson
 
 
+@dataclasses.dataclass
+class Point:
+    x: int
+    y: int
+
+
+def mappings(data):
+    """
+    ChainMap and MappingProxyType with the entries of data
+    """
+    yield collections.ChainMap(data)
+    yield types.MappingProxyType(data)
+
+
+@dataclasses.dataclass
+class Dataclass:
+    x_a: int
+    b: int
+
+
+def redact(key):
+    return None if key.startswith("_") else key
+
+
 class TestDict:
     def test_dict(self):
         """
@@ -19,6 +49,99 @@ class TestDict:
     def test_dict_duplicate_loads(self):
         assert orjson.loads(b'{"1":true,"1":false}') == {"1": False}
 
+    def test_dict_duplicate_loads_as_list(self):
+        """
+        loads() OPT_DUPLICATE_KEYS_AS_LIST two occurrences
+        """
+        assert orjson.loads(
+            b'{"1":true,"2":null,"1":false}',
+            option=orjson.OPT_DUPLICATE_KEYS_AS_LIST,
+        ) == {"1": [True, False], "2": None}
+
+    def test_dict_duplicate_loads_as_list_three(self):
+        """
+        loads() OPT_DUPLICATE_KEYS_AS_LIST three occurrences
+        """
+        assert orjson.loads(
+            '{"a":1,"a":[2],"b":"c","a":{"d":3}}',
+            option=orjson.OPT_DUPLICATE_KEYS_AS_LIST,
+        ) == {"a": [1, [2], {"d": 3}], "b": "c"}
+
+    def test_dict_duplicate_loads_as_list_nested(self):
+        """
+        loads() OPT_DUPLICATE_KEYS_AS_LIST nested objects
+        """
+        assert orjson.loads(
+            '[{"a":{"b":1,"b":2}},{"a":[{"b":[],"b":[]}]}]',
+            option=orjson.OPT_DUPLICATE_KEYS_AS_LIST,
+        ) == [{"a": {"b": [1, 2]}}, {"a": [{"b": [[], []]}]}]
+
+    def test_dict_duplicate_loads_as_list_unique(self):
+        """
+        loads() OPT_DUPLICATE_KEYS_AS_LIST keys occurring once stay scalar
+        """
+        obj = {"a": 1, "b": [2], "c": {"d": None}, "e": "f", "g": 1.5}
+        assert (
+            orjson.loads(orjson.dumps(obj), option=orjson.OPT_DUPLICATE_KEYS_AS_LIST)
+            == obj
+        )
+        assert orjson.loads("1", option=orjson.OPT_DUPLICATE_KEYS_AS_LIST) == 1
+
+    def test_dict_duplicate_loads_as_list_forbidden(self):
+        """
+        loads() OPT_FORBID_DUPLICATE_KEYS takes precedence
+        """
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads(
+                '{"a":1,"a":2}',
+                option=orjson.OPT_DUPLICATE_KEYS_AS_LIST
+                | orjson.OPT_FORBID_DUPLICATE_KEYS,
+            )
+
+    def test_dict_duplicate_loads_forbidden(self):
+        """
+        loads() OPT_FORBID_DUPLICATE_KEYS
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads(
+                b'{"1":true,"1":false}', option=orjson.OPT_FORBID_DUPLICATE_KEYS
+            )
+        assert exc_info.value.msg == 'Duplicate object key: "1"'
+        assert exc_info.value.pos == 10
+
+    def test_dict_duplicate_loads_forbidden_nested(self):
+        """
+        loads() OPT_FORBID_DUPLICATE_KEYS nested object
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads(
+                '[{"a":{"b":1}},{"a":{"b":[],"c":2,"b":{}}}]',
+                option=orjson.OPT_FORBID_DUPLICATE_KEYS,
+            )
+        assert exc_info.value.msg == 'Duplicate object key: "b"'
+        assert exc_info.value.pos == 34
+
+    def test_dict_duplicate_loads_forbidden_escaped(self):
+        """
+        loads() OPT_FORBID_DUPLICATE_KEYS compares unescaped keys
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads(
+                '{"\\u00e5":1,"å":2}', option=orjson.OPT_FORBID_DUPLICATE_KEYS
+            )
+        assert exc_info.value.msg == 'Duplicate object key: "å"'
+        assert exc_info.value.pos == 12
+
+    def test_dict_duplicate_loads_forbidden_unique(self):
+        """
+        loads() OPT_FORBID_DUPLICATE_KEYS same key in different objects
+        """
+        obj = [{"a": {"a": 1}}, {"a": [{"a": 2}]}]
+        assert (
+            orjson.loads(orjson.dumps(obj), option=orjson.OPT_FORBID_DUPLICATE_KEYS)
+            == obj
+        )
+
     def test_dict_empty(self):
         obj = [{"key": [{}] * 4096}] * 4096  # type:ignore
         assert orjson.loads(orjson.dumps(obj)) == obj
@@ -168,3 +291,625 @@ class TestDict:
                 self.b = 1
 
         assert orjson.dumps(C().__dict__) == b'{"a":0,"b":1}'
+
+
+class TestDictView:
+    def test_dict_keys(self):
+        obj = {"a": 1, "b": 2, "c": 3}
+        assert orjson.dumps(obj.keys()) == b'["a","b","c"]'
+
+    def test_dict_values(self):
+        obj = {"a": 1, "b": [2], "c": None}
+        assert orjson.dumps(obj.values()) == b"[1,[2],null]"
+
+    def test_dict_items(self):
+        obj = {"a": 1, "b": [2], "c": None}
+        assert orjson.dumps(obj.items()) == b'[["a",1],["b",[2]],["c",null]]'
+
+    def test_dict_view_empty(self):
+        obj: dict = {}
+        for view in (obj.keys(), obj.values(), obj.items()):
+            assert orjson.dumps(view) == b"[]"
+            assert orjson.dumps(view, option=orjson.OPT_INDENT_2) == b"[]"
+
+    def test_dict_view_non_str_keys(self):
+        """
+        keys that are not str are elements, so do not need OPT_NON_STR_KEYS
+        """
+        obj = {1: "a", None: "b", (1, 2): "c"}
+        assert orjson.dumps(obj.keys()) == b"[1,null,[1,2]]"
+        assert orjson.dumps(obj.items()) == b'[[1,"a"],[null,"b"],[[1,2],"c"]]'
+
+    def test_dict_view_types(self):
+        obj = {
+            "a": datetime.date(2000, 1, 2),
+            "b": Point(1, 2),
+            "c": {"d": 1}.values(),
+        }
+        assert orjson.dumps(obj.values()) == b'["2000-01-02",{"x":1,"y":2},[1]]'
+
+    def test_dict_view_nested(self):
+        obj = {"a": 1}
+        assert orjson.dumps([obj.keys()]) == b'[["a"]]'
+        assert orjson.dumps({"b": obj.items()}) == b'{"b":[["a",1]]}'
+        assert orjson.dumps((obj.values(),)) == b"[[1]]"
+
+    def test_dict_view_order(self):
+        """
+        elements are in the order of the dict, irrespective of OPT_SORT_KEYS
+        """
+        obj = {"b": 1, "a": 2}
+        assert orjson.dumps(obj.keys(), option=orjson.OPT_SORT_KEYS) == b'["b","a"]'
+        assert (
+            orjson.dumps(obj.items(), option=orjson.OPT_SORT_KEYS)
+            == b'[["b",1],["a",2]]'
+        )
+
+    def test_dict_view_indent(self):
+        obj = {"a": 1}
+        assert (
+            orjson.dumps(obj.items(), option=orjson.OPT_INDENT_2)
+            == b'[\n  [\n    "a",\n    1\n  ]\n]'
+        )
+
+    def test_dict_view_default(self):
+        obj = {"a": object()}
+        assert orjson.dumps(obj.values(), default=lambda _: "x") == b'["x"]'
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(obj.items())
+
+    def test_dict_view_changed_size(self):
+        """
+        a dict that changes size while its view is serialized raises
+        """
+        obj = {"a": object(), "b": 1}
+
+        def default(_):
+            obj["c"] = 2
+            return None
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(obj.values(), default=default)
+        assert isinstance(exc_info.value.__cause__, RuntimeError)
+
+    def test_dict_view_recursion(self):
+        obj: dict = {}
+        obj["a"] = obj.values()
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(obj)
+
+    def test_dict_view_dict_key(self):
+        obj = {"a": 1}
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({obj.values(): 1}, option=orjson.OPT_NON_STR_KEYS)
+
+    def test_dict_view_subclass(self):
+        """
+        the views of a dict subclass are serialized unless the subclass has its
+        own view types, as OrderedDict, which are passed to default
+        """
+        class Subclass(dict):
+            pass
+
+        obj = Subclass(b=1, a=2)
+        assert orjson.dumps(obj.keys()) == b'["b","a"]'
+        ordered = collections.OrderedDict(obj)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(ordered.keys())
+        assert orjson.dumps(ordered.items(), default=list) == b'[["b",1],["a",2]]'
+
+
+class TestMapping:
+    def test_chainmap(self):
+        """
+        collections.ChainMap, with the first mapping taking precedence
+        """
+        val = collections.ChainMap({"a": 1, "b": 2}, {"b": 3, "c": 4})
+        assert orjson.loads(orjson.dumps(val)) == {"a": 1, "b": 2, "c": 4}
+        assert orjson.dumps(collections.ChainMap()) == b"{}"
+
+    def test_mappingproxy(self):
+        """
+        types.MappingProxyType
+        """
+        assert orjson.dumps(types.MappingProxyType({"a": 1, "b": [2]})) == b'{"a":1,"b":[2]}'
+        assert orjson.dumps(types.MappingProxyType({})) == b"{}"
+
+    def test_mappingproxy_class_dict(self):
+        """
+        a class's __dict__ is a MappingProxyType
+        """
+
+        class Namespace:
+            a = 1
+            b = "c"
+
+            def method(self):
+                pass
+
+        val = orjson.loads(orjson.dumps(Namespace.__dict__, default=lambda _: None))
+        assert val["a"] == 1
+        assert val["b"] == "c"
+        assert val["method"] is None
+        assert val["__module__"] == __name__
+
+    def test_simple_namespace(self):
+        """
+        types.SimpleNamespace
+        """
+        assert orjson.dumps(types.SimpleNamespace(a=1, b="c")) == b'{"a":1,"b":"c"}'
+        assert orjson.dumps(types.SimpleNamespace()) == b"{}"
+
+    def test_nested(self):
+        """
+        mappings in a list, as a dict value, and in each other
+        """
+        inner = types.SimpleNamespace(x=types.MappingProxyType({"y": 1}))
+        assert orjson.dumps([inner]) == b'[{"x":{"y":1}}]'
+        assert orjson.dumps({"a": collections.ChainMap({"b": inner})}) == (
+            b'{"a":{"b":{"x":{"y":1}}}}'
+        )
+
+    def test_sort_keys(self):
+        """
+        OPT_SORT_KEYS
+        """
+        data = {"c": 1, "a": 2, "b": 3}
+        for val in mappings(data):
+            assert orjson.dumps(val, option=orjson.OPT_SORT_KEYS) == b'{"a":2,"b":3,"c":1}'
+        assert (
+            orjson.dumps(types.SimpleNamespace(**data), option=orjson.OPT_SORT_KEYS)
+            == b'{"a":2,"b":3,"c":1}'
+        )
+
+    def test_non_str_keys(self):
+        """
+        OPT_NON_STR_KEYS
+        """
+        data = {
+            1: "int",
+            2.5: "float",
+            None: "none",
+            True: "bool",
+            datetime.date(2020, 1, 2): "date",
+            uuid.UUID("7202d115-7ff3-4c81-a7c1-2a1f067b1ece"): "uuid",
+        }
+        expected = orjson.dumps(data, option=orjson.OPT_NON_STR_KEYS)
+        for val in mappings(data):
+            assert orjson.dumps(val, option=orjson.OPT_NON_STR_KEYS) == expected
+
+    def test_non_str_keys_sorted(self):
+        """
+        OPT_NON_STR_KEYS and OPT_SORT_KEYS
+        """
+        opt = orjson.OPT_NON_STR_KEYS | orjson.OPT_SORT_KEYS
+        data = {"b": 1, 3: 2, "a": 3}
+        expected = orjson.dumps(data, option=opt)
+        assert expected == b'{"3":2,"a":3,"b":1}'
+        for val in mappings(data):
+            assert orjson.dumps(val, option=opt) == expected
+
+    def test_non_str_keys_disabled(self):
+        """
+        non-str keys raise JSONEncodeError without OPT_NON_STR_KEYS, as for dict
+        """
+        for val in mappings({1: 1}):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(val)
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(val, option=orjson.OPT_SORT_KEYS)
+
+    def test_non_str_keys_invalid_type(self):
+        """
+        an unsupported key type raises JSONEncodeError with OPT_NON_STR_KEYS
+        """
+        for val in mappings({(1,): 1}):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(val, option=orjson.OPT_NON_STR_KEYS)
+
+    def test_mapping_error(self):
+        """
+        an exception raised by the mapping is chained to JSONEncodeError
+        """
+
+        class Failing(dict):
+            def __getitem__(self, key):
+                raise RuntimeError
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(collections.ChainMap(Failing(a=1)))
+        assert isinstance(exc_info.value.__cause__, RuntimeError)
+
+    def test_subclass(self):
+        """
+        subclasses are passed to default
+        """
+
+        class ChainMapSubclass(collections.ChainMap):
+            pass
+
+        assert orjson.dumps(ChainMapSubclass({"a": 1}), default=lambda _: "default") == (
+            b'"default"'
+        )
+
+
+class TestTupleFields:
+    def test_tuple_fields(self):
+        """
+        dumps() tuple_fields
+        """
+        assert (
+            orjson.dumps((1, "a", None), tuple_fields=["id", "name", "parent"])
+            == b'{"id":1,"name":"a","parent":null}'
+        )
+
+    def test_tuple_fields_tuple(self):
+        """
+        dumps() tuple_fields given as tuple
+        """
+        assert orjson.dumps((1, 2), tuple_fields=("x", "y")) == b'{"x":1,"y":2}'
+
+    def test_tuple_fields_nested_values(self):
+        """
+        dumps() tuple_fields elements are serialized as usual
+        """
+        assert (
+            orjson.dumps(
+                ([1, (2,)], {"b": 2}),
+                tuple_fields=["a", "b"],
+                option=orjson.OPT_SORT_KEYS,
+            )
+            == b'{"a":[1,[2]],"b":{"b":2}}'
+        )
+
+    def test_tuple_fields_empty(self):
+        """
+        dumps() tuple_fields empty
+        """
+        assert orjson.dumps((), tuple_fields=[]) == b"{}"
+
+    def test_tuple_fields_envelope(self):
+        """
+        dumps() tuple_fields with envelope
+        """
+        assert (
+            orjson.dumps((1, "a"), tuple_fields=["id", "name"], envelope="data")
+            == b'{"data":{"id":1,"name":"a"}}'
+        )
+
+    def test_tuple_fields_indent(self):
+        """
+        dumps() tuple_fields OPT_INDENT_2
+        """
+        assert (
+            orjson.dumps((1, [2]), tuple_fields=["a", "b"], option=orjson.OPT_INDENT_2)
+            == b'{\n  "a": 1,\n  "b": [\n    2\n  ]\n}'
+        )
+
+    def test_tuple_fields_none(self):
+        """
+        dumps() tuple_fields=None serializes tuple as array
+        """
+        assert orjson.dumps((1, 2), tuple_fields=None) == b"[1,2]"
+
+    def test_tuple_fields_length_mismatch(self):
+        """
+        dumps() tuple_fields length does not match tuple
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps((1, 2), tuple_fields=["a"])
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps((1,), tuple_fields=["a", "b"])
+
+    def test_tuple_fields_not_tuple(self):
+        """
+        dumps() tuple_fields requires a tuple
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps([1, 2], tuple_fields=["a", "b"])
+
+    def test_tuple_fields_invalid(self):
+        """
+        dumps() tuple_fields must be a list or tuple of str
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps((1,), tuple_fields="a")  # type: ignore
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps((1,), tuple_fields=[1])  # type: ignore
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps((1,), tuple_fields=["\ud800"])
+
+    def test_tuple_fields_error(self):
+        """
+        dumps() tuple_fields propagates errors from serializing elements
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps((object(),), tuple_fields=["a"])
+
+    def test_tuple_fields_namedtuple(self):
+        """
+        dumps() tuple_fields of a namedtuple subclass is by its elements
+        """
+        Point = collections.namedtuple("Point", ["x", "y"])
+        assert orjson.dumps(Point(1, 2), tuple_fields=Point._fields) == (
+            b'{"x":1,"y":2}'
+        )
+        assert orjson.dumps(Point(1, 2), tuple_fields=["a", "b"]) == b'{"a":1,"b":2}'
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Point(1, 2), tuple_fields=["x"])
+
+
+class TestSkipKeyPrefix:
+    def test_skip_key_prefix(self):
+        """
+        skip_key_prefix omits entries whose keys start with the prefix
+        """
+        assert (
+            orjson.dumps({"a": 1, "_b": 2, "c_": 3}, skip_key_prefix="_")
+            == b'{"a":1,"c_":3}'
+        )
+
+    def test_skip_key_prefix_nested(self):
+        """
+        skip_key_prefix applies to nested dicts
+        """
+        obj = {"a": {"_b": 1, "c": {"_d": 2, "e": 3}}, "f": [{"_g": 4, "h": 5}]}
+        assert (
+            orjson.dumps(obj, skip_key_prefix="_")
+            == b'{"a":{"c":{"e":3}},"f":[{"h":5}]}'
+        )
+
+    def test_skip_key_prefix_all(self):
+        """
+        skip_key_prefix omitting every entry serializes an empty object
+        """
+        assert orjson.dumps({"_a": 1, "_b": 2}, skip_key_prefix="_") == b"{}"
+        assert (
+            orjson.dumps(
+                {"a": {"_b": 1}}, skip_key_prefix="_", option=orjson.OPT_INDENT_2
+            )
+            == b'{\n  "a": {}\n}'
+        )
+
+    def test_skip_key_prefix_multibyte(self):
+        """
+        skip_key_prefix compares the UTF-8 of keys
+        """
+        assert (
+            orjson.dumps({"été": 1, "étang": 2, "et": 3}, skip_key_prefix="ét")
+            == b'{"et":3}'
+        )
+
+    def test_skip_key_prefix_empty(self):
+        """
+        skip_key_prefix of an empty str omits every entry
+        """
+        assert orjson.dumps({"a": 1, "": 2}, skip_key_prefix="") == b"{}"
+
+    def test_skip_key_prefix_none(self):
+        """
+        skip_key_prefix of None omits nothing
+        """
+        assert orjson.dumps({"_a": 1}, skip_key_prefix=None) == b'{"_a":1}'
+
+    def test_skip_key_prefix_sort_keys(self):
+        """
+        skip_key_prefix with OPT_SORT_KEYS
+        """
+        assert (
+            orjson.dumps(
+                {"c": 1, "_b": 2, "a": {"_d": 3, "e": 4}},
+                skip_key_prefix="_",
+                option=orjson.OPT_SORT_KEYS,
+            )
+            == b'{"a":{"e":4},"c":1}'
+        )
+
+    def test_skip_key_prefix_non_str_keys(self):
+        """
+        skip_key_prefix with OPT_NON_STR_KEYS compares only str keys
+        """
+        assert (
+            orjson.dumps(
+                {"_a": 1, 1: 2, "b": 3, b"_c": 4},
+                skip_key_prefix="_",
+                option=orjson.OPT_NON_STR_KEYS,
+            )
+            == b'{"1":2,"b":3,"_c":4}'
+        )
+
+    def test_skip_key_prefix_dataclass(self):
+        """
+        skip_key_prefix does not apply to dataclass fields
+        """
+        assert (
+            orjson.dumps(Dataclass(1, 2), skip_key_prefix="x_")
+            == b'{"x_a":1,"b":2}'
+        )
+
+    def test_skip_key_prefix_key_filter(self):
+        """
+        skip_key_prefix applies to the keys returned by key_filter
+        """
+        assert (
+            orjson.dumps(
+                {"a": 1, "b": 2},
+                key_filter=lambda key: "_" + key if key == "a" else key,
+                skip_key_prefix="_",
+            )
+            == b'{"b":2}'
+        )
+
+    def test_skip_key_prefix_dumps_many(self):
+        """
+        skip_key_prefix applies to each object of dumps_many()
+        """
+        assert orjson.dumps_many([{"_a": 1}, {"b": 2}], skip_key_prefix="_") == [
+            b"{}",
+            b'{"b":2}',
+        ]
+
+    def test_skip_key_prefix_restored(self):
+        """
+        skip_key_prefix does not apply to dumps() called from default
+        """
+
+        def default(obj):
+            return orjson.Fragment(orjson.dumps({"_a": 1}))
+
+        assert (
+            orjson.dumps({"_a": 1, "b": object()}, default=default, skip_key_prefix="_")
+            == b'{"b":{"_a":1}}'
+        )
+
+    def test_skip_key_prefix_invalid(self):
+        """
+        skip_key_prefix must be str
+        """
+        with pytest.raises(orjson.JSONEncodeError, match="skip_key_prefix must be str"):
+            orjson.dumps({}, skip_key_prefix=b"_")
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({}, skip_key_prefix="\ud800")
+
+
+class TestKeyFilter:
+    def test_key_filter_upper(self):
+        """
+        key_filter replaces each key, including those of nested dicts
+        """
+        obj = {"a": 1, "b": [{"c": 2}], "d": {"e": {"f": 3}}}
+        assert orjson.dumps(obj, key_filter=str.upper) == (
+            b'{"A":1,"B":[{"C":2}],"D":{"E":{"F":3}}}'
+        )
+
+    def test_key_filter_drop(self):
+        """
+        key_filter returning None omits the entry
+        """
+        obj = {"a": 1, "_b": 2, "c": {"_d": 3, "e": 4}}
+        assert orjson.dumps(obj, key_filter=redact) == b'{"a":1,"c":{"e":4}}'
+
+    def test_key_filter_none(self):
+        """
+        key_filter returning None for every key
+        """
+        assert orjson.dumps({"a": 1, "b": 2}, key_filter=lambda _: None) == b"{}"
+        assert orjson.dumps([{"a": 1}], key_filter=lambda _: None) == b"[{}]"
+
+    def test_key_filter_called(self):
+        """
+        key_filter is called once per key of non-empty dicts only
+        """
+        calls = []
+
+        def key_filter(key):
+            calls.append(key)
+            return key
+
+        obj = {"a": [1, {}], "b": ("c", {"d": None})}
+        assert orjson.dumps(obj, key_filter=key_filter) == orjson.dumps(obj)
+        assert sorted(calls) == ["a", "b", "d"]
+
+    def test_key_filter_absent(self):
+        """
+        key_filter=None is the same as not specifying it
+        """
+        obj = {"b": 1, "a": {"c": 2}}
+        assert orjson.dumps(obj, key_filter=None) == orjson.dumps(obj)
+
+    def test_key_filter_sort_keys(self):
+        """
+        key_filter with OPT_SORT_KEYS sorts by the replaced keys
+        """
+        obj = {"a": 1, "b": 2, "c": 3}
+        assert (
+            orjson.dumps(
+                obj,
+                key_filter={"a": "z", "b": "y", "c": "x"}.get,
+                option=orjson.OPT_SORT_KEYS,
+            )
+            == b'{"x":3,"y":2,"z":1}'
+        )
+
+    def test_key_filter_non_str_keys(self):
+        """
+        key_filter receives and may return non-str keys with OPT_NON_STR_KEYS
+        """
+        obj = {1: "a", 2: "b", "c": "d"}
+        assert (
+            orjson.dumps(
+                obj,
+                key_filter=lambda key: key * 10 if isinstance(key, int) else key,
+                option=orjson.OPT_NON_STR_KEYS,
+            )
+            == b'{"10":"a","20":"b","c":"d"}'
+        )
+
+    def test_key_filter_non_str_result(self):
+        """
+        key_filter returning a non-str key without OPT_NON_STR_KEYS
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({"a": 1}, key_filter=lambda _: 1)
+
+    def test_key_filter_collision(self):
+        """
+        keys mapped to the same key serialize the later entry
+        """
+        assert orjson.dumps({"a": 1, "b": 2}, key_filter=lambda _: "k") == b'{"k":2}'
+
+    def test_key_filter_dataclass(self):
+        """
+        key_filter is not called for dataclass fields
+        """
+
+        @dataclasses.dataclass
+        class Dataclass:
+            a: int
+            b: dict
+
+        assert orjson.dumps(Dataclass(1, {"_c": 2}), key_filter=str.upper) == (
+            b'{"a":1,"b":{"_C":2}}'
+        )
+
+    def test_key_filter_default(self):
+        """
+        key_filter applies to dicts returned by default
+        """
+
+        class Custom:
+            pass
+
+        assert (
+            orjson.dumps(
+                [Custom()],
+                default=lambda _: {"_a": 1, "b": 2},
+                key_filter=redact,
+            )
+            == b'[{"b":2}]'
+        )
+
+    def test_key_filter_exception(self):
+        """
+        an exception raised by key_filter is chained to JSONEncodeError
+        """
+
+        def key_filter(key):
+            raise ZeroDivisionError
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps({"a": 1}, key_filter=key_filter)
+        assert isinstance(exc_info.value.__cause__, ZeroDivisionError)
+
+    def test_key_filter_not_callable(self):
+        """
+        key_filter that is not callable raises JSONEncodeError
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({"a": 1}, key_filter="a")  # type: ignore
+
+    def test_key_filter_dumps_str(self):
+        """
+        key_filter with dumps_str()
+        """
+        assert orjson.dumps_str({"a": 1}, key_filter=str.upper) == '{"A":1}'
@@ -1,6 +1,7 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2020-2025)
 
+import dataclasses
 import datetime
 import enum
 
@@ -57,6 +58,22 @@ class UnspecifiedEnum(enum.Enum):
     F = datetime.datetime(1970, 1, 1)
 
 
+@dataclasses.dataclass
+class Point:
+    x: int
+    y: int
+
+
+class Unserializable:
+    pass
+
+
+class ComplexEnum(enum.Enum):
+    POINT = Point(1, 2)
+    NESTED = [Point(3, 4), {"a": Unserializable()}]
+    OBJECT = Unserializable()
+
+
 class TestEnum:
     def test_cannot_subclass(self):
         """
@@ -119,3 +136,34 @@ class TestEnum:
         assert (
             orjson.dumps({IntEnum.ONE: 1}, option=orjson.OPT_NON_STR_KEYS) == b'{"1":1}'
         )
+
+    def test_enum_value_dataclass(self):
+        assert orjson.dumps(ComplexEnum.POINT) == b'{"x":1,"y":2}'
+        assert orjson.dumps([ComplexEnum.POINT]) == b'[{"x":1,"y":2}]'
+
+    def test_enum_value_unserializable(self):
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(ComplexEnum.OBJECT)
+        msg = str(exc_info.value)
+        assert "Type is not JSON serializable: Unserializable" in msg
+        assert "(in value of enum member ComplexEnum.OBJECT)" in msg
+
+    def test_enum_value_unserializable_nested(self):
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps({"key": [ComplexEnum.NESTED]})
+        assert "(in value of enum member ComplexEnum.NESTED)" in str(exc_info.value)
+
+    def test_enum_value_unserializable_default_cause(self):
+        def raising(obj):
+            raise ValueError("custom")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(ComplexEnum.OBJECT, default=raising)
+        assert "(in value of enum member ComplexEnum.OBJECT)" in str(exc_info.value)
+        assert isinstance(exc_info.value.__cause__, ValueError)
+
+    def test_enum_value_unserializable_default(self):
+        assert (
+            orjson.dumps(ComplexEnum.OBJECT, default=lambda obj: "handled")
+            == b'"handled"'
+        )
@@ -2,6 +2,10 @@
 # Copyright ijl (2021-2025), Eric Jolibois (2021), o.ermakov (2023)
 
 import json
+import os
+import subprocess
+import sys
+import textwrap
 
 import pytest
 
@@ -24,6 +28,72 @@ MULTILINE_EMOJI = """[
 """
 
 
+LAZY_SCRIPT = textwrap.dedent(
+    """
+    import sys
+
+    import orjson
+
+    def created():
+        return "JSONDecodeError" in vars(orjson)
+
+    orjson.dumps({"a": [1, 2.5, None]})
+    orjson.loads('{"a": [1, 2.5, null]}')
+    print("json" in sys.modules, created())
+    try:
+        orjson.loads("[")
+    except ValueError as exc:
+        print(type(exc).__name__, "json" in sys.modules, created())
+        print(isinstance(exc, orjson.JSONDecodeError))
+    """
+)
+
+
+def _run_lazy(script):
+    env = dict(os.environ)
+    env["PYTHONPATH"] = os.pathsep.join(path for path in sys.path if path)
+    return subprocess.run(
+        [sys.executable, "-c", script],
+        capture_output=True,
+        check=True,
+        env=env,
+        text=True,
+        timeout=60,
+    ).stdout.split()
+
+
+UNAVAILABLE_SCRIPT = textwrap.dedent(
+    """
+    import sys
+
+    # importing json raises ImportError
+    sys.modules["json"] = None
+
+    import orjson
+
+    print(issubclass(orjson.JSONDecodeError, ValueError))
+    try:
+        orjson.loads("[")
+    except orjson.JSONDecodeError as exc:
+        print(type(exc).__name__, exc.args[0].replace(" ", "_"))
+    print(orjson.loads("[1]") == [1])
+    """
+)
+
+
+def _run_unavailable():
+    env = dict(os.environ)
+    env["PYTHONPATH"] = os.pathsep.join(path for path in sys.path if path)
+    return subprocess.run(
+        [sys.executable, "-c", UNAVAILABLE_SCRIPT],
+        capture_output=True,
+        check=True,
+        env=env,
+        text=True,
+        timeout=60,
+    ).stdout.split()
+
+
 class TestJsonDecodeError:
     def _get_error_infos(self, json_decode_error_exc_info):
         return {
@@ -103,6 +173,61 @@ class TestJsonDecodeError:
             "colno": 7,
         }
 
+    def _test_invalid(self, data, expected_err_infos, expected_doc):
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads(data)
+        assert self._get_error_infos(exc_info) == expected_err_infos
+        assert exc_info.value.doc == expected_doc
+        assert str(exc_info.value).startswith("str is not valid UTF-8")
+
+    def test_parse_error_position(self):
+        """
+        pos, lineno, and colno of a parse error
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads('{\n  "a": [1,\n    2,]\n}')
+        assert self._get_error_infos(exc_info) == {"pos": 18, "lineno": 3, "colno": 6}
+        assert exc_info.value.doc == '{\n  "a": [1,\n    2,]\n}'
+        assert str(exc_info.value).endswith(": line 3 column 6 (char 18)")
+
+    def test_invalid_utf8_bytes(self):
+        """
+        pos, lineno, and colno of invalid UTF-8 in bytes
+        """
+        self._test_invalid(b'["\xff"]', {"pos": 2, "lineno": 1, "colno": 3}, '["\ufffd"]')
+        self._test_invalid(
+            b'[\n  "\xe6\x9d\xb1",\n  "\xed\xa0\x80"]',
+            {"pos": 12, "lineno": 3, "colno": 4},
+            '[\n  "\u6771",\n  "\ufffd\ufffd\ufffd"]',
+        )
+
+    def test_invalid_utf8_bytearray_memoryview(self):
+        """
+        pos, lineno, and colno of invalid UTF-8 in bytearray and memoryview
+        """
+        self._test_invalid(
+            bytearray(b'\n["\xc3"]'),
+            {"pos": 3, "lineno": 2, "colno": 3},
+            '\n["\ufffd"]',
+        )
+        self._test_invalid(
+            memoryview(b"[1, 2]\x80"),
+            {"pos": 6, "lineno": 1, "colno": 7},
+            "[1, 2]\ufffd",
+        )
+
+    def test_invalid_str_surrogate(self):
+        """
+        pos, lineno, and colno of a surrogate in str, with doc having each
+        surrogate replaced by "?"
+        """
+        self._test_invalid('["\ud800"]', {"pos": 2, "lineno": 1, "colno": 3}, '["?"]')
+        self._test_invalid(
+            '{"é":\n  "😊\udc00\ud800"}',
+            {"pos": 10, "lineno": 2, "colno": 5},
+            '{"é":\n  "😊??"}',
+        )
+
 
 class Custom:
     pass
@@ -190,3 +315,75 @@ class TestJsonEncodeError:
         with pytest.raises(orjson.JSONEncodeError) as exc_info:
             orjson.dumps(10**60)
         assert exc_info.type == orjson.JSONEncodeError
+
+
+class TestJsonDecodeErrorLazy:
+    def test_json_decode_error_lazy(self):
+        """
+        JSONDecodeError and the json module it derives from are not imported
+        until the first error
+        """
+        assert _run_lazy(LAZY_SCRIPT) == [
+            "False",
+            "False",
+            "JSONDecodeError",
+            "True",
+            "True",
+            "True",
+        ]
+
+    def test_json_decode_error_lazy_attribute(self):
+        """
+        accessing JSONDecodeError creates it before any error
+        """
+        script = (
+            "import sys, orjson; "
+            "print(orjson.JSONDecodeError.__name__, 'json' in sys.modules)"
+        )
+        assert _run_lazy(script) == ["JSONDecodeError", "True"]
+
+    def test_json_decode_error_identity(self):
+        """
+        the JSONDecodeError raised is the one the module exports
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads("[")
+        assert type(exc_info.value) is orjson.JSONDecodeError
+        assert vars(orjson)["JSONDecodeError"] is orjson.JSONDecodeError
+
+    def test_json_decode_error_reset_state(self):
+        """
+        reset_state() keeps the JSONDecodeError already created
+        """
+        json_decode_error = orjson.JSONDecodeError
+        orjson.reset_state()
+        with pytest.raises(json_decode_error):
+            orjson.loads("[")
+        assert orjson.JSONDecodeError is json_decode_error
+
+    def test_module_getattr(self):
+        """
+        other missing attributes raise AttributeError
+        """
+        with pytest.raises(AttributeError):
+            orjson.JSONDecodeErrors  # noqa: B018
+        assert not hasattr(orjson, "missing")
+
+
+class TestJsonUnavailable:
+    def test_json_decode_error_base(self):
+        """
+        JSONDecodeError derives from json.JSONDecodeError
+        """
+        assert issubclass(orjson.JSONDecodeError, json.JSONDecodeError)
+
+    def test_json_unavailable(self):
+        """
+        JSONDecodeError derives from ValueError if json cannot be imported
+        """
+        assert _run_unavailable() == [
+            "True",
+            "JSONDecodeError",
+            "unexpected_end_of_data",
+            "True",
+        ]
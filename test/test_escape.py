@@ -1,8 +1,21 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2025)
 
+import json
+
+import pytest
+
 import orjson
 
+FORWARD_SLASH = orjson.OPT_ESCAPE_FORWARD_SLASH
+
+
+ESCAPE = orjson.OPT_SURROGATES_ESCAPE
+
+
+class SubStr(str):
+    pass
+
 
 def test_issue565():
     assert (
@@ -125,3 +138,264 @@ def test_backslash():
 
 def test_quote():
     assert orjson.dumps('"') == b'"\\""'
+
+
+class TestEscapeForwardSlash:
+    def test_escape_forward_slash_default(self):
+        """
+        "/" is not escaped by default
+        """
+        assert orjson.dumps("</script>") == b'"</script>"'
+
+    def test_escape_forward_slash(self):
+        assert orjson.dumps("/", option=FORWARD_SLASH) == b'"\\/"'
+        assert orjson.dumps("a/b//c", option=FORWARD_SLASH) == b'"a\\/b\\/\\/c"'
+        assert orjson.dumps("</script>", option=FORWARD_SLASH) == b'"<\\/script>"'
+
+    def test_escape_forward_slash_unchanged(self):
+        """
+        strings without "/" are unchanged
+        """
+        for val in ("", "a", "abc def", '"\\\n\t\x00\x1f\x7f', "café 😀"):
+            assert orjson.dumps(val, option=FORWARD_SLASH) == orjson.dumps(val)
+
+    def test_escape_forward_slash_script(self):
+        """
+        the output can be embedded in a <script> element and parses back to the
+        original
+        """
+        obj = {
+            "html": "<b>bold</b></script><script>alert(1)</script>",
+            "</script>": ["</SCRIPT>", "< /script>", "<\\/script>"],
+            "url": "https://example.com/a/b",
+        }
+        res = orjson.dumps(obj, option=FORWARD_SLASH)
+        assert b"</" not in res
+        assert orjson.loads(res) == obj
+        assert json.loads(res) == obj
+
+    def test_escape_forward_slash_key(self):
+        assert (
+            orjson.dumps({"a/b": "c/d"}, option=FORWARD_SLASH) == b'{"a\\/b":"c\\/d"}'
+        )
+
+    def test_escape_forward_slash_non_str_key(self):
+        assert (
+            orjson.dumps({1: "/"}, option=FORWARD_SLASH | orjson.OPT_NON_STR_KEYS)
+            == b'{"1":"\\/"}'
+        )
+
+    def test_escape_forward_slash_non_ascii(self):
+        """
+        non-ASCII characters are written as UTF-8 without OPT_ENSURE_ASCII
+        """
+        assert orjson.dumps("é/中", option=FORWARD_SLASH) == '"é\\/中"'.encode()
+
+    def test_escape_forward_slash_ensure_ascii(self):
+        assert (
+            orjson.dumps("</é>", option=FORWARD_SLASH | orjson.OPT_ENSURE_ASCII)
+            == b'"<\\/\\u00e9>"'
+        )
+
+    def test_escape_forward_slash_indent(self):
+        assert (
+            orjson.dumps({"a": ["/"]}, option=FORWARD_SLASH | orjson.OPT_INDENT_2)
+            == b'{\n  "a": [\n    "\\/"\n  ]\n}'
+        )
+
+    def test_escape_forward_slash_surrogates(self):
+        assert (
+            orjson.dumps(
+                "\ud800</", option=FORWARD_SLASH | orjson.OPT_SURROGATES_ESCAPE
+            )
+            == b'"\\ud800<\\/"'
+        )
+
+    def test_escape_forward_slash_fragment(self):
+        """
+        the contents of a Fragment are not modified
+        """
+        assert orjson.dumps(orjson.Fragment(b'"/"'), option=FORWARD_SLASH) == b'"/"'
+
+
+class TestEnsureAscii:
+    def test_ensure_ascii_ascii(self):
+        """
+        OPT_ENSURE_ASCII does not change ASCII strings
+        """
+        for val in ("", "a", "abc def", '"\\\n\t\x00\x1f\x7f', "/"):
+            assert orjson.dumps(val, option=orjson.OPT_ENSURE_ASCII) == orjson.dumps(val)
+
+    def test_ensure_ascii_bmp(self):
+        """
+        OPT_ENSURE_ASCII escapes BMP code points
+        """
+        assert orjson.dumps("é", option=orjson.OPT_ENSURE_ASCII) == b'"\\u00e9"'
+        assert orjson.dumps("a中b", option=orjson.OPT_ENSURE_ASCII) == b'"a\\u4e2db"'
+        assert orjson.dumps("\x80￿", option=orjson.OPT_ENSURE_ASCII) == b'"\\u0080\\uffff"'
+
+    def test_ensure_ascii_astral(self):
+        """
+        OPT_ENSURE_ASCII escapes code points outside the BMP as surrogate pairs
+        """
+        assert orjson.dumps("\U0001f600", option=orjson.OPT_ENSURE_ASCII) == b'"\\ud83d\\ude00"'
+        assert orjson.dumps("\U00010000\U0010ffff", option=orjson.OPT_ENSURE_ASCII) == (
+            b'"\\ud800\\udc00\\udbff\\udfff"'
+        )
+
+    def test_ensure_ascii_json(self):
+        """
+        OPT_ENSURE_ASCII matches json.dumps(ensure_ascii=True)
+        """
+        obj = {
+            "café": ["naïve", "中文", "\U0001f600 emoji", "\n\"\\", "plain"],
+            "ключ": {"é": "  "},
+        }
+        res = orjson.dumps(obj, option=orjson.OPT_ENSURE_ASCII)
+        assert res == json.dumps(obj, ensure_ascii=True, separators=(",", ":")).encode()
+        assert all(byte < 128 for byte in res)
+        assert orjson.loads(res) == obj
+
+    def test_ensure_ascii_indent(self):
+        """
+        OPT_ENSURE_ASCII with OPT_INDENT_2
+        """
+        obj = {"é": ["ü"]}
+        assert orjson.dumps(
+            obj,
+            option=orjson.OPT_ENSURE_ASCII | orjson.OPT_INDENT_2,
+        ) == json.dumps(obj, ensure_ascii=True, indent=2).encode()
+
+    def test_ensure_ascii_long(self):
+        """
+        OPT_ENSURE_ASCII output greater than the initial buffer
+        """
+        val = "\U0001f600" * 4096
+        assert orjson.dumps(val, option=orjson.OPT_ENSURE_ASCII) == json.dumps(val).encode()
+
+    def test_ensure_ascii_non_str_keys(self):
+        """
+        OPT_ENSURE_ASCII with OPT_NON_STR_KEYS and str subclasses
+        """
+
+        class StrSubclass(str):
+            pass
+
+        assert (
+            orjson.dumps(
+                {1: StrSubclass("é")},
+                option=orjson.OPT_ENSURE_ASCII | orjson.OPT_NON_STR_KEYS,
+            )
+            == b'{"1":"\\u00e9"}'
+        )
+
+    def test_ensure_ascii_dumps_str(self):
+        """
+        OPT_ENSURE_ASCII with dumps_str()
+        """
+        assert orjson.dumps_str(["é"], option=orjson.OPT_ENSURE_ASCII) == '["\\u00e9"]'
+
+
+class TestSurrogates:
+    def test_surrogate_default_raises(self):
+        """
+        a str with a lone surrogate raises JSONEncodeError by default
+        """
+        for val in ("\ud800", "a\udfff", "\ud83d\ude00", SubStr("\ud800")):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(val)
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps([val])
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps({"a": val})
+
+    def test_surrogate_escape_high(self):
+        """
+        OPT_SURROGATES_ESCAPE escapes a lone high surrogate
+        """
+        assert orjson.dumps("\ud800", option=ESCAPE) == b'"\\ud800"'
+        assert orjson.dumps("a\udbffb", option=ESCAPE) == b'"a\\udbffb"'
+        assert orjson.dumps("é\ud800😊", option=ESCAPE) == '"é\\ud800😊"'.encode()
+
+    def test_surrogate_escape_low(self):
+        """
+        OPT_SURROGATES_ESCAPE escapes a lone low surrogate
+        """
+        assert orjson.dumps("\udc00", option=ESCAPE) == b'"\\udc00"'
+
+    def test_surrogate_escape_pair(self):
+        """
+        a pair of surrogates in a str is escaped and decodes as the character
+        the pair encodes in UTF-16
+        """
+        val = "\ud83d\ude00"
+        assert orjson.dumps(val, option=ESCAPE) == b'"\\ud83d\\ude00"'
+        assert orjson.loads(orjson.dumps(val, option=ESCAPE)) == "\U0001f600"
+        assert json.loads(orjson.dumps(val, option=ESCAPE)) == json.loads(
+            json.dumps(val)
+        )
+
+    def test_surrogate_escape_roundtrip(self):
+        """
+        OPT_SURROGATES_ESCAPE output is decoded by json to the same str
+        """
+        for val in (
+            "\ud800",
+            'a"b\\c\n\t\x00\x1f\x7f\ud800',
+            "\udfff\ud800",
+            "東\udc80",
+            "😊\udc80😊",
+        ):
+            assert json.loads(orjson.dumps(val, option=ESCAPE)) == val
+        assert orjson.dumps('a"\n\x00\ud800', option=ESCAPE) == (
+            b'"a\\"\\n\\u0000\\ud800"'
+        )
+
+    def test_surrogate_escape_ensure_ascii(self):
+        """
+        OPT_SURROGATES_ESCAPE with OPT_ENSURE_ASCII matches json
+        """
+        for val in ("\ud800", "é\udc00😊", 'a"\n\ud83d'):
+            assert orjson.dumps(
+                val, option=ESCAPE | orjson.OPT_ENSURE_ASCII
+            ) == json.dumps(val).encode()
+
+    def test_surrogate_escape_nested(self):
+        """
+        OPT_SURROGATES_ESCAPE applies to values of lists, dicts, and subclasses
+        """
+        obj = {"a": ["\ud800", SubStr("\udc00")], "b": SubStr("x\ud800")}
+        assert orjson.dumps(obj, option=ESCAPE) == (
+            b'{"a":["\\ud800","\\udc00"],"b":"x\\ud800"}'
+        )
+        assert orjson.dumps(obj, option=ESCAPE | orjson.OPT_INDENT_2) == (
+            b'{\n  "a": [\n    "\\ud800",\n    "\\udc00"\n  ],\n  "b": "x\\ud800"\n}'
+        )
+
+    def test_surrogate_escape_surrogateescape(self):
+        """
+        a str decoded with surrogateescape
+        """
+        val = b"a\xffb".decode("utf-8", "surrogateescape")
+        assert orjson.dumps(val, option=ESCAPE) == b'"a\\udcffb"'
+
+    def test_surrogate_escape_valid(self):
+        """
+        OPT_SURROGATES_ESCAPE does not change valid str
+        """
+        for val in ("", "a", "é", "😊", 'a"\\\n'):
+            assert orjson.dumps(val, option=ESCAPE) == orjson.dumps(val)
+
+    def test_surrogate_escape_key_raises(self):
+        """
+        a dict key with a surrogate still raises
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({"\ud800": 1}, option=ESCAPE)
+
+    def test_surrogate_escape_long(self):
+        """
+        OPT_SURROGATES_ESCAPE of a str longer than the initial buffer
+        """
+        val = ("\ud800" + "a" * 100) * 100
+        assert orjson.dumps(val, option=ESCAPE) == json.dumps(val).encode()
@@ -12,6 +12,16 @@ except ImportError:
 
 from .util import needs_data, read_fixture_bytes
 
+VALIDATE = orjson.OPT_VALIDATE_FRAGMENTS
+
+
+class JsonDunder:
+    def __json__(self):
+        return b"{"
+
+
+from .util import needs_data, read_fixture_bytes
+
 
 class TestFragment:
     def test_fragment_fragment_eq(self):
@@ -1059,3 +1069,69 @@ class TestFragmentParsing:
 
     def test_fragment_i_structure_UTF_8_BOM_empty_object(self):
         self._run_test("i_structure_UTF-8_BOM_empty_object.json")
+
+
+class TestValidateFragments:
+    def test_validate_fragments_valid(self):
+        """
+        OPT_VALIDATE_FRAGMENTS writes a valid fragment as is
+        """
+        assert (
+            orjson.dumps(
+                {"a": orjson.Fragment(b'{"b": [1, 2]}'), "c": orjson.Fragment("1")},
+                option=VALIDATE,
+            )
+            == b'{"a":{"b": [1, 2]},"c":1}'
+        )
+
+    def test_validate_fragments_invalid(self):
+        """
+        OPT_VALIDATE_FRAGMENTS raises on an invalid fragment
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps([1, orjson.Fragment(b'{"a": ')], option=VALIDATE)
+        assert "not valid JSON" in str(exc_info.value)
+
+    @pytest.mark.parametrize(
+        "contents", [b"", b"1 2", b"[1,]", b"nul", '"a', b"\xff", b"{} x"]
+    )
+    def test_validate_fragments_invalid_contents(self, contents):
+        """
+        OPT_VALIDATE_FRAGMENTS raises on contents that are not one document
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(orjson.Fragment(contents), option=VALIDATE)
+
+    def test_validate_fragments_dict_value(self):
+        """
+        OPT_VALIDATE_FRAGMENTS applies to dict values
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({"a": orjson.Fragment(b"[")}, option=VALIDATE)
+
+    def test_validate_fragments_disabled(self):
+        """
+        an invalid fragment is written as is without OPT_VALIDATE_FRAGMENTS
+        """
+        assert orjson.dumps([orjson.Fragment(b'{"a": ')]) == b'[{"a": ]'
+
+    def test_validate_fragments_default_options(self):
+        """
+        OPT_VALIDATE_FRAGMENTS as the default of the interpreter
+        """
+        try:
+            orjson.set_default_options(VALIDATE)
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(orjson.Fragment(b"{"))
+        finally:
+            orjson.set_default_options(None)
+        assert orjson.dumps(orjson.Fragment(b"{")) == b"{"
+
+    def test_validate_fragments_json_dunder(self):
+        """
+        OPT_VALIDATE_FRAGMENTS does not apply to __json__
+        """
+        assert (
+            orjson.dumps(JsonDunder(), option=VALIDATE | orjson.OPT_USE_JSON_DUNDER)
+            == b"{"
+        )
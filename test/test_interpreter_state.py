@@ -0,0 +1,511 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+# Copyright ijl (2025)
+
+import ctypes
+import sys
+import threading
+import uuid
+from concurrent.futures import ThreadPoolExecutor
+
+import pytest
+
+import orjson
+
+from .util import run_in_subinterpreter
+
+
+def _call_in_new_thread(func):
+    """
+    Run func in a fresh thread so the per-thread state cache is empty.
+    """
+    result = {}
+
+    def target():
+        try:
+            result["value"] = func()
+        except BaseException as exc:
+            result["exc"] = exc
+
+    thread = threading.Thread(target=target)
+    thread.start()
+    thread.join()
+    return result
+
+
+class Custom:
+    pass
+
+
+def shutdown_fn(release_gil):
+    # the extension module, which may be a submodule of the package
+    path = sys.modules[orjson.dumps.__module__].__file__
+    lib = ctypes.CDLL(path) if release_gil else ctypes.PyDLL(path)
+    fn = lib.orjson_shutdown
+    fn.argtypes = ()
+    fn.restype = None
+    return fn
+
+
+def configure_state():
+    orjson.set_default_options(orjson.OPT_SORT_KEYS)
+    orjson.register_type(Custom, lambda obj: "custom")
+    orjson.warm_keys(["a", "b"])
+
+
+def assert_configured():
+    assert orjson.dumps({"b": Custom(), "a": 1}) == b'{"a":1,"b":"custom"}'
+
+
+def assert_default():
+    with pytest.raises(orjson.JSONEncodeError):
+        orjson.dumps(Custom())
+    assert orjson.dumps({"b": 2, "a": 1}) == b'{"b":2,"a":1}'
+    assert orjson.loads('{"a":[1,{"b":null}]}') == {"a": [1, {"b": None}]}
+
+
+class TestInterpreterState:
+    def test_state_unavailable(self):
+        """
+        dumps(), loads(), Fragment() raise RuntimeError if the module can't be
+        imported to resolve the interpreter state
+        """
+        name = orjson.dumps.__module__
+        saved = sys.modules[name]
+        # the interpreter's state is resolved again after reset_state()
+        orjson.reset_state()
+        sys.modules[name] = None  # type: ignore
+        try:
+            for func in (
+                lambda: orjson.dumps([]),
+                lambda: orjson.loads("[]"),
+                lambda: orjson.Fragment(b"[]"),
+            ):
+                result = _call_in_new_thread(func)
+                assert isinstance(result.get("exc"), RuntimeError)
+        finally:
+            sys.modules[name] = saved
+
+    def test_state_recovers(self):
+        """
+        a failed import is not cached and a later call in the same thread works
+        """
+        name = orjson.dumps.__module__
+        saved = sys.modules[name]
+
+        def func():
+            orjson.reset_state()
+            sys.modules[name] = None  # type: ignore
+            try:
+                with pytest.raises(RuntimeError):
+                    orjson.dumps([])
+            finally:
+                sys.modules[name] = saved
+            return orjson.dumps([])
+
+        result = _call_in_new_thread(func)
+        assert "exc" not in result
+        assert result["value"] == b"[]"
+
+    def test_state_new_thread_no_import(self):
+        """
+        a new thread uses the state already resolved for the interpreter
+        without importing the module
+        """
+        orjson.dumps([])
+        name = orjson.dumps.__module__
+        saved = sys.modules[name]
+        sys.modules[name] = None  # type: ignore
+        try:
+            result = _call_in_new_thread(lambda: orjson.dumps({"a": [1]}))
+        finally:
+            sys.modules[name] = saved
+        assert result == {"value": b'{"a":[1]}'}
+
+    def test_state_thread_pool(self):
+        """
+        many new threads resolve the same state
+        """
+        with ThreadPoolExecutor(max_workers=8) as executor:
+            for _ in range(4):
+                results = list(
+                    executor.map(lambda n: orjson.loads(orjson.dumps([n])), range(64))
+                )
+                assert results == [[n] for n in range(64)]
+        results = [_call_in_new_thread(lambda: orjson.dumps(uuid.UUID(int=1))) for _ in range(8)]
+        assert all(
+            result == {"value": b'"00000000-0000-0000-0000-000000000001"'}
+            for result in results
+        )
+
+    def test_module_name(self):
+        """
+        functions and JSONDecodeError are qualified by the module's __name__
+        """
+        name = orjson.dumps.__module__
+        assert sys.modules[name].dumps is orjson.dumps
+        assert orjson.loads.__module__ == name
+        assert orjson.JSONDecodeError.__module__ == name
+        assert orjson.JSONDecodeError.__qualname__ == "JSONDecodeError"
+
+    def test_module_name_loads_error(self):
+        """
+        loads() raises JSONDecodeError qualified by the module's __name__
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads("[")
+        assert type(exc_info.value).__module__ == orjson.dumps.__module__
+
+    def test_module_name_new_thread(self):
+        """
+        a thread without cached state resolves the module by its __name__
+        """
+        result = _call_in_new_thread(lambda: orjson.loads("["))
+        assert isinstance(result.get("exc"), orjson.JSONDecodeError)
+
+    def test_reset_state(self):
+        """
+        reset_state() rebuilds the state using the types looked up at that time
+        """
+
+        class UUIDSubclass(uuid.UUID):
+            pass
+
+        val = UUIDSubclass("7202d115-7ff3-4c81-a7c1-2a1f067b1ece")
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(val)
+
+        saved = uuid.UUID
+        uuid.UUID = UUIDSubclass  # type: ignore
+        try:
+            assert orjson.reset_state() is None
+            assert orjson.dumps(val) == b'"7202d115-7ff3-4c81-a7c1-2a1f067b1ece"'
+            result = _call_in_new_thread(lambda: orjson.dumps(val))
+            assert result["value"] == b'"7202d115-7ff3-4c81-a7c1-2a1f067b1ece"'
+        finally:
+            uuid.UUID = saved  # type: ignore
+            orjson.reset_state()
+
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(val)
+        assert orjson.dumps(saved(str(val))) == b'"7202d115-7ff3-4c81-a7c1-2a1f067b1ece"'
+
+    def test_reset_state_other_thread(self):
+        """
+        reset_state() invalidates the state cached by other threads
+        """
+        ready = threading.Event()
+        done = threading.Event()
+        result = {}
+
+        def target():
+            orjson.dumps([])
+            ready.set()
+            done.wait()
+            try:
+                result["value"] = orjson.loads('{"a":[1]}')
+            except BaseException as exc:
+                result["exc"] = exc
+
+        thread = threading.Thread(target=target)
+        thread.start()
+        ready.wait()
+        orjson.reset_state()
+        done.set()
+        thread.join()
+        assert result == {"value": {"a": [1]}}
+
+    def test_reset_state_in_callback(self):
+        """
+        reset_state() from a callback during dumps() and loads()
+        """
+
+        def default(obj):
+            orjson.reset_state()
+            return {"key": str(obj), "uuid": uuid.UUID(int=1)}
+
+        obj = [{"a": 1}, object, {"b": [1.5, "c"]}, object, {"a": 2}]
+        expected = orjson.dumps(
+            [default(each) if each is object else each for each in obj]
+        )
+        for _ in range(10):
+            assert orjson.dumps(obj, default=default) == expected
+
+        def hook(obj):
+            orjson.reset_state()
+            return obj
+
+        doc = '[{"a": 1}, {"b": {"c": [1, 2]}}, {"a": "d"}]'
+        for _ in range(10):
+            assert orjson.loads(doc, object_hook=hook) == orjson.loads(doc)
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads('[{"a": 1}, ]', object_hook=hook)
+
+    def test_reset_state_in_callback_raises(self):
+        """
+        the exception raised by a callback that calls reset_state() is kept
+        """
+
+        def callback(obj):
+            orjson.reset_state()
+            raise ValueError("callback")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps([1j], default=callback)
+        assert isinstance(exc_info.value.__cause__, ValueError)
+        with pytest.raises(ValueError):
+            orjson.loads("[{}]", object_hook=callback)
+
+    def test_reset_state_exports(self):
+        """
+        reset_state() keeps Fragment and JSONDecodeError
+        """
+        fragment = orjson.Fragment(b"[]")
+        orjson.reset_state()
+        assert orjson.dumps(fragment) == b"[]"
+        assert orjson.dumps(orjson.Fragment(b"{}")) == b"{}"
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads("[")
+
+    def test_reset_state_releases_handler(self):
+        """
+        reset_state() releases the handlers of the state it drops
+        """
+
+        def handler(obj):
+            return str(obj)
+
+        ref = sys.getrefcount(handler)
+        orjson.register_type(complex, handler)
+        assert sys.getrefcount(handler) == ref + 1
+        orjson.reset_state()
+        assert sys.getrefcount(handler) == ref
+
+    def test_reset_state_in_callback_releases_handler(self):
+        """
+        reset_state() from a callback releases the handlers of the state it
+        drops once the call returns
+        """
+
+        def handler(obj):
+            orjson.reset_state()
+            return str(obj)
+
+        ref = sys.getrefcount(handler)
+        orjson.register_type(complex, handler)
+        assert orjson.dumps([1j]) == b'["1j"]'
+        assert sys.getrefcount(handler) == ref
+
+    def test_state_subinterpreter_same_thread(self):
+        """
+        a subinterpreter on a thread that has used the state of the main
+        interpreter resolves its own
+        """
+        try:
+            orjson.register_type(complex, str)
+            assert orjson.dumps(1j) == b'"1j"'
+            assert orjson.loads("[1]") == [1]
+            run_in_subinterpreter(
+                """
+                import orjson
+                try:
+                    orjson.dumps(1j)
+                except orjson.JSONEncodeError:
+                    pass
+                else:
+                    raise AssertionError("state of the main interpreter used")
+                assert orjson.loads("[1]") == [1]
+                """,
+            )
+            assert orjson.dumps(1j) == b'"1j"'
+        finally:
+            orjson.register_type(complex, None)
+
+
+class TestShutdown:
+    def test_shutdown(self):
+        """
+        shutdown() drops the state and the next call rebuilds it
+        """
+        try:
+            configure_state()
+            assert_configured()
+            assert orjson.shutdown() is None
+            assert_default()
+        finally:
+            orjson.reset_state()
+
+    def test_shutdown_cycles(self):
+        """
+        the state is built, dropped, and built again repeatedly
+        """
+        try:
+            for _ in range(10):
+                configure_state()
+                assert_configured()
+                orjson.shutdown()
+                assert_default()
+        finally:
+            orjson.reset_state()
+
+    def test_shutdown_twice(self):
+        orjson.shutdown()
+        orjson.shutdown()
+        assert_default()
+
+    def test_shutdown_then_reset_state(self):
+        orjson.shutdown()
+        orjson.reset_state()
+        orjson.shutdown()
+        assert_default()
+
+    def test_shutdown_in_callback(self):
+        """
+        shutdown() from a callback during dumps() and loads()
+        """
+
+        def default(obj):
+            orjson.shutdown()
+            return {"key": "custom"}
+
+        def hook(obj):
+            orjson.shutdown()
+            return obj
+
+        try:
+            for _ in range(10):
+                obj = [{"a": 1}, Custom(), {"a": 2}]
+                assert orjson.dumps(obj, default=default) == (
+                    b'[{"a":1},{"key":"custom"},{"a":2}]'
+                )
+                doc = '[{"a": 1}, {"b": [1, {"c": 2}]}]'
+                assert orjson.loads(doc, object_hook=hook) == orjson.loads(doc)
+            assert_default()
+        finally:
+            orjson.reset_state()
+
+    def test_shutdown_exports(self):
+        """
+        shutdown() keeps Fragment, Options, and JSONDecodeError
+        """
+        fragment = orjson.Fragment(b"[]")
+        options = orjson.Options(orjson.OPT_SORT_KEYS)
+        orjson.shutdown()
+        assert orjson.dumps(fragment) == b"[]"
+        assert orjson.dumps({"b": 1, "a": 2}, option=options) == b'{"a":2,"b":1}'
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads("[")
+
+    def test_shutdown_other_thread(self):
+        """
+        shutdown() invalidates the state cached by other threads
+        """
+        ready = threading.Event()
+        done = threading.Event()
+        result = {}
+
+        def target():
+            configure_state()
+            assert_configured()
+            ready.set()
+            done.wait()
+            try:
+                result["value"] = orjson.dumps({"b": 2, "a": 1})
+            except BaseException as exc:
+                result["exc"] = exc
+
+        try:
+            thread = threading.Thread(target=target)
+            thread.start()
+            ready.wait()
+            orjson.shutdown()
+            done.set()
+            thread.join()
+        finally:
+            orjson.reset_state()
+        assert result == {"value": b'{"b":2,"a":1}'}
+
+    def test_shutdown_releases_handler(self):
+        """
+        shutdown() releases the handlers of the state of the current
+        interpreter
+        """
+
+        def handler(obj):
+            return "custom"
+
+        ref = sys.getrefcount(handler)
+        orjson.register_type(Custom, handler)
+        assert sys.getrefcount(handler) == ref + 1
+        orjson.shutdown()
+        assert sys.getrefcount(handler) == ref
+
+    def test_shutdown_c_api_without_gil_releases_handler(self):
+        """
+        orjson_shutdown() without the GIL held releases the handlers of a state
+        on the next call of its interpreter
+        """
+
+        def handler(obj):
+            return "custom"
+
+        ref = sys.getrefcount(handler)
+        orjson.register_type(Custom, handler)
+        shutdown_fn(release_gil=True)()
+        assert sys.getrefcount(handler) == ref + 1
+        assert orjson.dumps([]) == b"[]"
+        assert sys.getrefcount(handler) == ref
+
+    def test_shutdown_c_api(self):
+        """
+        orjson_shutdown() with the GIL held is as shutdown()
+        """
+        try:
+            configure_state()
+            assert_configured()
+            shutdown_fn(release_gil=False)()
+            assert_default()
+        finally:
+            orjson.reset_state()
+
+    def test_shutdown_c_api_without_gil(self):
+        """
+        orjson_shutdown() without the GIL held drops the states, each released
+        on the next call of its interpreter
+        """
+        try:
+            configure_state()
+            assert_configured()
+            shutdown_fn(release_gil=True)()
+            assert_default()
+        finally:
+            orjson.reset_state()
+
+    def test_shutdown_c_api_cycles(self):
+        try:
+            for release_gil in (False, True) * 5:
+                configure_state()
+                assert_configured()
+                shutdown_fn(release_gil)()
+                assert_default()
+        finally:
+            orjson.reset_state()
+
+    def test_shutdown_subinterpreter(self):
+        """
+        shutdown() in a subinterpreter drops the state of the main interpreter,
+        released on its next call
+        """
+        try:
+            configure_state()
+            assert_configured()
+            run_in_subinterpreter(
+                """
+                import orjson
+                assert orjson.dumps([1]) == b"[1]"
+                orjson.shutdown()
+                assert orjson.dumps({"b": 2, "a": 1}) == b'{"b":2,"a":1}'
+                """
+            )
+            assert_default()
+        finally:
+            orjson.reset_state()
@@ -4,7 +4,10 @@
 import dataclasses
 import datetime
 import gc
+import json
+import os
 import random
+import tracemalloc
 
 from .util import numpy, pandas
 
@@ -22,7 +25,7 @@ import pytest
 
 import orjson
 
-from .util import IS_FREETHREADING
+from .util import IS_FREETHREADING, run_in_subinterpreter
 
 FIXTURE = '{"a":[81891289, 8919812.190129012], "b": false, "c": null, "d": "東京"}'
 
@@ -66,6 +69,9 @@ class Unsupported:
     pass
 
 
+ORJSON_FEATURE_PYMALLOC = os.getenv("ORJSON_FEATURE_PYMALLOC", "")
+
+
 class TestMemory:
     @pytest.mark.skipif(psutil is None, reason="psutil not installed")
     def test_memory_loads(self):
@@ -290,3 +296,156 @@ class TestMemory:
             orjson.dumps(orjson.Fragment(str(i)))
         gc.collect()
         assert proc.memory_info().rss <= mem + MAX_INCREASE
+
+    @pytest.mark.skipif(psutil is None, reason="psutil not installed")
+    def test_memory_loads_intern_values(self):
+        """
+        loads() OPT_INTERN_VALUES memory leak and shared values
+        """
+        proc = psutil.Process()
+        gc.collect()
+        fixture = orjson.dumps(
+            [{"id": idx, "country": ("NZ", "AU", "FR")[idx % 3]} for idx in range(10000)]
+        )
+        loaded = orjson.loads(fixture, option=orjson.OPT_INTERN_VALUES)
+        assert len({id(each["country"]) for each in loaded}) == 3
+        mem = proc.memory_info().rss
+        for _ in range(100):
+            loaded = orjson.loads(fixture, option=orjson.OPT_INTERN_VALUES)
+            assert loaded
+        gc.collect()
+        assert proc.memory_info().rss <= mem + MAX_INCREASE
+
+
+class TestBufferPool:
+    def test_reuse(self):
+        """
+        dumps_str() output is correct when reusing buffers of varying capacity
+        """
+        for size in (1, 100, 5000, 1, 70000, 10, 2000, 0):
+            obj = ["a" * size, {"b": list(range(size % 50))}]
+            expected = json.dumps(obj, separators=(",", ":"))
+            for _ in range(3):
+                assert orjson.dumps_str(obj) == expected
+                assert orjson.dumps(obj) == expected.encode()
+
+    def test_reuse_after_error(self):
+        """
+        a buffer released on error is reused
+        """
+        for _ in range(3):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps_str(["a" * 2000, object()])
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(["a" * 20], max_output_bytes=10)
+            assert orjson.dumps_str([1, "b"]) == '[1,"b"]'
+            assert orjson.dumps([1, "b"]) == b'[1,"b"]'
+
+    def test_reentrant(self):
+        """
+        dumps_str() called by default while a buffer is checked out
+        """
+
+        class Custom:
+            def __init__(self, depth):
+                self.depth = depth
+
+        def default(obj):
+            if isinstance(obj, Custom):
+                if obj.depth == 0:
+                    return "leaf"
+                return orjson.dumps_str([obj.depth, Custom(obj.depth - 1)], default=default)
+            raise TypeError
+
+        assert orjson.dumps_str(Custom(2), default=default) == (
+            '"[2,\\"[1,\\\\\\"leaf\\\\\\"]\\"]"'
+        )
+        assert orjson.loads(orjson.loads(orjson.dumps(Custom(1), default=default))) == [
+            1,
+            "leaf",
+        ]
+
+    def test_configure_size(self):
+        """
+        configure(buffer_pool_size=...)
+        """
+        try:
+            for size in (0, 1, 100, 4):
+                assert orjson.configure(buffer_pool_size=size) is None
+                for _ in range(3):
+                    assert orjson.dumps_str({"a": [1, 2]}) == '{"a":[1,2]}'
+            assert orjson.configure(buffer_pool_size=None) is None
+        finally:
+            orjson.configure(buffer_pool_size=4)
+
+    def test_configure_invalid(self):
+        """
+        configure() raises on an invalid buffer_pool_size
+        """
+        with pytest.raises(TypeError):
+            orjson.configure(buffer_pool_size="4")  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.configure(buffer_pool_size=4.0)  # type: ignore
+        with pytest.raises(ValueError):
+            orjson.configure(buffer_pool_size=-1)
+        with pytest.raises(ValueError, match="non-negative"):
+            orjson.configure(buffer_pool_size=-(1 << 64))
+
+    def test_configure_too_large(self):
+        """
+        configure() raises giving the maximum on a too large buffer_pool_size
+        """
+        try:
+            orjson.configure(buffer_pool_size=1024)
+            with pytest.raises(ValueError, match="at most 1024"):
+                orjson.configure(buffer_pool_size=1025)
+            with pytest.raises(ValueError, match="at most 1024"):
+                orjson.configure(buffer_pool_size=1 << 64)
+        finally:
+            orjson.configure(buffer_pool_size=4)
+
+    def test_reset_state(self):
+        """
+        reset_state() releases pooled buffers
+        """
+        for _ in range(3):
+            orjson.dumps_str(["a" * 1000])
+        orjson.reset_state()
+        assert orjson.dumps_str(["a"]) == '["a"]'
+
+    def test_subinterpreter(self):
+        """
+        pooled buffers are released when a subinterpreter exits
+        """
+        run_in_subinterpreter(
+            """
+            import orjson
+            orjson.configure(buffer_pool_size=8)
+            for size in (10, 5000, 10):
+                assert orjson.dumps_str(["a" * size]) == '["' + "a" * size + '"]'
+            """,
+        )
+        assert orjson.dumps_str([1]) == "[1]"
+
+
+@pytest.mark.skipif(
+    not ORJSON_FEATURE_PYMALLOC,
+    reason="ORJSON_FEATURE_PYMALLOC not defined",
+)
+def test_pymalloc_tracemalloc():
+    """
+    with the pymalloc feature, allocations other than the output are traced
+    """
+    obj = {i: 0 for i in range(100000)}
+    output_len = len(orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS))
+    tracemalloc.start()
+    try:
+        tracemalloc.reset_peak()
+        before, _ = tracemalloc.get_traced_memory()
+        res = orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SORT_KEYS)
+        _, peak = tracemalloc.get_traced_memory()
+    finally:
+        tracemalloc.stop()
+    assert len(res) == output_len
+    # the keys coerced and sorted in addition to the output
+    assert peak - before > 3 * output_len
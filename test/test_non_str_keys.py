@@ -3,6 +3,7 @@
 
 import dataclasses
 import datetime
+import sys
 import uuid
 
 import pytest
@@ -21,6 +22,9 @@ class SubStr(str):
     pass
 
 
+STRICT = orjson.OPT_NON_STR_KEYS | orjson.OPT_NON_STR_KEYS_STRICT
+
+
 class TestNonStrKeyTests:
     def test_dict_keys_duplicate(self):
         """
@@ -37,6 +41,84 @@ class TestNonStrKeyTests:
             == b'{"1":true,"2":false}'
         )
 
+    def test_dict_keys_int_repeated(self):
+        """
+        OPT_NON_STR_KEYS many dicts sharing int keys
+        """
+        keys = [2**40 + i for i in range(100)]
+        obj = [{key: i for i, key in enumerate(keys)} for _ in range(100)]
+        expected = b"{" + b",".join(b'"%d":%d' % (key, i) for i, key in enumerate(keys)) + b"}"
+        assert orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS) == (
+            b"[" + b",".join([expected] * 100) + b"]"
+        )
+        assert orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS) == (
+            b"[" + b",".join([expected] * 100) + b"]"
+        )
+
+    def test_dict_keys_cached_reference(self):
+        """
+        OPT_NON_STR_KEYS coerces a key object once and releases it on eviction
+        """
+        key = 2.0**40 + 7.5
+        refcount = sys.getrefcount(key)
+        for _ in range(10):
+            assert (
+                orjson.dumps({key: True}, option=orjson.OPT_NON_STR_KEYS)
+                == b'{"1099511627783.5":true}'
+            )
+        assert sys.getrefcount(key) == refcount + 1
+        orjson.dumps(
+            {2.0**41 + i: True for i in range(10000)},
+            option=orjson.OPT_NON_STR_KEYS,
+        )
+        assert sys.getrefcount(key) == refcount
+
+    def test_dict_keys_int_not_cached(self):
+        """
+        OPT_NON_STR_KEYS formats int keys directly rather than caching them
+        """
+        key = 2**40 + 7
+        refcount = sys.getrefcount(key)
+        assert (
+            orjson.dumps({key: True}, option=orjson.OPT_NON_STR_KEYS)
+            == b'{"1099511627783":true}'
+        )
+        assert sys.getrefcount(key) == refcount
+
+    def test_dict_keys_int_many(self):
+        """
+        OPT_NON_STR_KEYS ten thousand int keys, negative and at the limits
+        of i64 and u64
+        """
+        keys = list(range(-5000, 5000)) + [
+            -9223372036854775808,
+            9223372036854775807,
+            9223372036854775808,
+            18446744073709551615,
+        ]
+        obj = {key: key % 7 for key in keys}
+        expected = b"{" + b",".join(b'"%d":%d' % (key, key % 7) for key in keys) + b"}"
+        assert orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS) == expected
+        assert orjson.dumps(
+            obj,
+            option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SORT_KEYS,
+        ) == orjson.dumps(
+            {str(key): val for key, val in obj.items()},
+            option=orjson.OPT_SORT_KEYS,
+        )
+
+    def test_dict_keys_cached_float_uuid(self):
+        """
+        OPT_NON_STR_KEYS float and uuid.UUID keys through the cache
+        """
+        val = uuid.UUID("7202d115-7ff3-4c81-a7c1-2a1f067b1ece")
+        obj = {1.5: 1, val: 2}
+        for _ in range(3):
+            assert (
+                orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS)
+                == b'{"1.5":1,"7202d115-7ff3-4c81-a7c1-2a1f067b1ece":2}'
+            )
+
     def test_dict_keys_substr(self):
         assert (
             orjson.dumps({SubStr("aaa"): True}, option=orjson.OPT_NON_STR_KEYS)
@@ -59,6 +141,85 @@ class TestNonStrKeyTests:
         with pytest.raises(orjson.JSONEncodeError):
             orjson.dumps({SubStr("\ud800"): True}, option=orjson.OPT_NON_STR_KEYS)
 
+    def test_dict_keys_bytes(self):
+        """
+        a bytes key is decoded as UTF-8
+        """
+        assert (
+            orjson.dumps({b"a": 1, "b": 2}, option=orjson.OPT_NON_STR_KEYS)
+            == b'{"a":1,"b":2}'
+        )
+        assert (
+            orjson.dumps({"\u00e9".encode(): 1}, option=orjson.OPT_NON_STR_KEYS)
+            == '{"\u00e9":1}'.encode()
+        )
+        assert orjson.dumps({b"": 1}, option=orjson.OPT_NON_STR_KEYS) == b'{"":1}'
+
+    def test_dict_keys_bytes_sort_keys(self):
+        assert (
+            orjson.dumps(
+                {b"b": 1, "a": 2},
+                option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SORT_KEYS,
+            )
+            == b'{"a":2,"b":1}'
+        )
+
+    def test_dict_keys_bytes_invalid_utf8(self):
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps({b"\xff\x00": 1}, option=orjson.OPT_NON_STR_KEYS)
+        assert "not valid UTF-8" in str(exc_info.value)
+
+    def test_dict_keys_bytes_base64(self):
+        """
+        a bytes key is encoded as base64, including one that is not UTF-8
+        """
+        assert (
+            orjson.dumps(
+                {b"\xff\x00\x01": 1, b"abcd": 2},
+                option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SERIALIZE_BYTES_BASE64,
+            )
+            == b'{"/wAB":1,"YWJjZA==":2}'
+        )
+
+    def test_dict_keys_bytes_base64_value(self):
+        assert (
+            orjson.dumps(
+                {b"\xff": b"\xfe"},
+                option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SERIALIZE_BYTES_BASE64,
+            )
+            == b'{"/w==":"/g=="}'
+        )
+
+    def test_dict_keys_bytes_hex_latin1(self):
+        assert (
+            orjson.dumps(
+                {b"\xff\x00": 1},
+                option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SERIALIZE_BYTES_HEX,
+            )
+            == b'{"ff00":1}'
+        )
+        assert (
+            orjson.dumps(
+                {b"\xe9": 1},
+                option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SERIALIZE_BYTES_LATIN1,
+            )
+            == '{"\u00e9":1}'.encode()
+        )
+
+    def test_dict_keys_bytes_configure(self):
+        orjson.configure(bytes_mode="base64")
+        try:
+            assert (
+                orjson.dumps({b"\xff": 1}, option=orjson.OPT_NON_STR_KEYS)
+                == b'{"/w==":1}'
+            )
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+    def test_dict_keys_bytes_without_option(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({b"a": 1})
+
     def test_dict_keys_strict(self):
         """
         OPT_NON_STR_KEYS does not respect OPT_STRICT_INTEGER
@@ -229,14 +390,37 @@ class TestNonStrKeyTests:
             == b'{"1970-01-03":3,"1970-01-05":2,"other":1}'
         )
 
-    @pytest.mark.skipif(pytz is None, reason="pytz optional")
+    def test_dict_keys_time_tz(self):
+        """
+        OPT_NON_STR_KEYS has datetime.time with the offset of tzinfo
+        """
+        val = datetime.time(
+            12, 15, 59, 111, tzinfo=datetime.timezone(datetime.timedelta(hours=8))
+        )
+        assert (
+            orjson.dumps({val: True}, option=orjson.OPT_NON_STR_KEYS)
+            == b'{"12:15:59.000111+08:00":true}'
+        )
+
     def test_dict_keys_time_err(self):
         """
         OPT_NON_STR_KEYS propagates errors in types
         """
-        val = datetime.time(12, 15, 59, 111, tzinfo=pytz.timezone("Asia/Shanghai"))
+
+        class Invalid(datetime.tzinfo):
+            valid = True
+
+            def utcoffset(self, dt):
+                # hashing the key calls utcoffset()
+                if self.valid:
+                    return None
+                raise ValueError("invalid")
+
+        tzinfo = Invalid()
+        obj = {datetime.time(12, 15, 59, 111, tzinfo=tzinfo): True}
+        tzinfo.valid = False
         with pytest.raises(orjson.JSONEncodeError):
-            orjson.dumps({val: True}, option=orjson.OPT_NON_STR_KEYS)
+            orjson.dumps(obj, option=orjson.OPT_NON_STR_KEYS)
 
     def test_dict_keys_str(self):
         assert (
@@ -303,3 +487,121 @@ class TestNonStrKeyTests:
         val = Obj()
         with pytest.raises(orjson.JSONEncodeError):
             orjson.dumps({val: True}, option=orjson.OPT_NON_STR_KEYS)
+
+
+class TestNonStrKeysStrict:
+    def test_strict_float_int_same_key(self):
+        """
+        {1.0: "a", 1: "b"} is a single key in Python and serializes
+        """
+        obj = {1.0: "a", 1: "b"}
+        assert len(obj) == 1
+        assert orjson.dumps(obj, option=STRICT) == b'{"1.0":"b"}'
+
+    def test_strict_collision(self):
+        """
+        keys that serialize to the same str raise
+        """
+        for obj in (
+            {1: "a", "1": "b"},
+            {1.5: "a", "1.5": "b"},
+            {None: "a", "null": "b"},
+            {True: "a", "true": "b"},
+            {datetime.date(1970, 1, 1): "a", "1970-01-01": "b"},
+        ):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(obj, option=STRICT)
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(obj, option=STRICT | orjson.OPT_SORT_KEYS)
+
+    def test_strict_no_collision(self):
+        """
+        distinct keys serialize as with OPT_NON_STR_KEYS
+        """
+        obj = {"b": 1, 2: 2, None: 3, True: 4, 0.5: 5, "a": 6}
+        for option in (0, orjson.OPT_SORT_KEYS):
+            assert orjson.dumps(obj, option=STRICT | option) == orjson.dumps(
+                obj, option=orjson.OPT_NON_STR_KEYS | option
+            )
+
+    def test_strict_float_inexact(self):
+        """
+        a float key that is not exactly its decimal representation raises
+        """
+        for key in (0.1, 0.3, 1 / 3, 2.0**70, 1e23, 5e-324):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps({key: 1}, option=STRICT)
+
+    def test_strict_float_nonfinite(self):
+        """
+        nan and inf keys raise as both serialize to null
+        """
+        for key in (float("nan"), float("inf"), float("-inf")):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps({key: 1}, option=STRICT)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({float("nan"): 1, float("inf"): 2}, option=STRICT)
+
+    def test_strict_float_exact(self):
+        """
+        a float key that is exactly its decimal representation serializes
+        """
+        for key, expected in (
+            (0.5, b'{"0.5":1}'),
+            (1.0, b'{"1.0":1}'),
+            (-2.25, b'{"-2.25":1}'),
+            (0.0, b'{"0.0":1}'),
+            (-0.0, b'{"-0.0":1}'),
+            (1e22, b'{"1e22":1}'),
+            (2.0**-10, b'{"0.0009765625":1}'),
+            (123456.125, b'{"123456.125":1}'),
+        ):
+            assert orjson.dumps({key: 1}, option=STRICT) == expected
+
+    def test_strict_float_cached(self):
+        """
+        a float key is checked when it has been serialized before
+        """
+        option = STRICT | orjson.OPT_SORT_KEYS
+        assert orjson.dumps({0.5: 1}, option=option) == b'{"0.5":1}'
+        orjson.dumps({0.1: 1}, option=orjson.OPT_NON_STR_KEYS)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({0.1: 1}, option=STRICT)
+
+    def test_strict_requires_non_str_keys(self):
+        """
+        OPT_NON_STR_KEYS_STRICT has no effect without OPT_NON_STR_KEYS
+        """
+        assert orjson.dumps({"a": 1}, option=orjson.OPT_NON_STR_KEYS_STRICT) == (
+            b'{"a":1}'
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({1: 1}, option=orjson.OPT_NON_STR_KEYS_STRICT)
+
+    def test_strict_not_set(self):
+        """
+        OPT_NON_STR_KEYS alone serializes colliding and inexact keys
+        """
+        assert (
+            orjson.dumps({1: "a", "1": "b"}, option=orjson.OPT_NON_STR_KEYS)
+            == b'{"1":"a","1":"b"}'
+        )
+        assert orjson.dumps({0.1: 1}, option=orjson.OPT_NON_STR_KEYS) == b'{"0.1":1}'
+
+    def test_strict_nested(self):
+        """
+        nested dict keys are checked
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({"a": [{1: 1, "1": 2}]}, option=STRICT)
+        assert orjson.dumps({"a": [{1: 1, "2": 2}]}, option=STRICT) == (
+            b'{"a":[{"1":1,"2":2}]}'
+        )
+
+    def test_strict_value(self):
+        """
+        OPT_NON_STR_KEYS_STRICT is a distinct bit
+        """
+        assert orjson.OPT_NON_STR_KEYS_STRICT == 1 << 31
+        assert orjson.OPT_NON_STR_KEYS_STRICT & orjson.OPT_NON_STR_KEYS == 0
+        assert int(orjson.Options(STRICT)) == STRICT
@@ -2,13 +2,34 @@
 # Copyright ijl (2020-2025), Ben Sully (2021), Nazar Kostetskyi (2022), Aviram Hassan (2020-2021), Marco Ribeiro (2020), Eric Jolibois (2021)
 # mypy: ignore-errors
 
+import datetime
+import os
+import subprocess
 import sys
+import textwrap
+import zoneinfo
 
 import pytest
 
 import orjson
 
-from .util import numpy
+from .util import numpy, pandas
+
+
+FLOAT32_VALUES = [
+    0.1,
+    0.2,
+    1 / 3,
+    123.456,
+    16777217.0,
+    1e-7,
+    1e20,
+    3.4028235e38,
+    1.1754944e-38,
+    1e-45,
+    -2.5,
+    0.0,
+]
 
 
 def numpy_default(obj):
@@ -17,6 +38,47 @@ def numpy_default(obj):
     raise TypeError
 
 
+SCRIPT = textwrap.dedent(
+    """
+    import sys
+
+    attempts = []
+
+    class Finder:
+        def find_spec(self, name, path=None, target=None):
+            if name.partition(".")[0] == "numpy":
+                attempts.append(name)
+            return None
+
+    sys.meta_path.insert(0, Finder())
+
+    import orjson
+
+    if sys.argv[1] == "disable":
+        orjson.disable_numpy()
+    orjson.dumps([1, object()], option=orjson.OPT_SERIALIZE_NUMPY, default=str)
+    orjson.build_info()
+    print(len(attempts), "numpy" in sys.modules)
+    """
+)
+
+
+def _run(arg):
+    env = dict(os.environ)
+    env["PYTHONPATH"] = os.pathsep.join(path for path in sys.path if path)
+    return subprocess.run(
+        [sys.executable, "-c", SCRIPT, arg],
+        capture_output=True,
+        check=True,
+        env=env,
+        text=True,
+    ).stdout.split()
+
+
+class DatetimeSubclass(datetime.datetime):
+    pass
+
+
 @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
 class TestNumpy:
     def test_numpy_array_d1_uintp(self):
@@ -166,6 +228,18 @@ class TestNumpy:
             == b"[null,null,null,-0.0,0.0,3.140625]"
         )
 
+    def test_numpy_array_f16_subnormal(self):
+        assert (
+            orjson.dumps(
+                numpy.array(
+                    [2**-24, -(2**-24), 1023 * 2**-24, 2**-14],
+                    numpy.float16,
+                ),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            )
+            == b"[5.9604645e-8,-5.9604645e-8,0.00006097555,0.000061035156]"
+        )
+
     def test_numpy_array_f32_edge(self):
         assert (
             orjson.dumps(
@@ -396,6 +470,66 @@ class TestNumpy:
             == b"[[1.0,2.0,3.0],[4.0,5.0,6.0]]"
         )
 
+    def test_numpy_masked_array_d1_i64(self):
+        assert (
+            orjson.dumps(
+                numpy.ma.masked_array(
+                    [1, 2, 3, 4],
+                    mask=[False, True, False, True],
+                    dtype=numpy.int64,
+                ),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            )
+            == b"[1,null,3,null]"
+        )
+
+    def test_numpy_masked_array_d2_f64(self):
+        assert (
+            orjson.dumps(
+                numpy.ma.masked_array(
+                    [[1.5, 2.5, 3.5], [4.5, 5.5, 6.5]],
+                    mask=[[False, True, False], [True, False, True]],
+                    dtype=numpy.float64,
+                ),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            )
+            == b"[[1.5,null,3.5],[null,5.5,null]]"
+        )
+
+    def test_numpy_masked_array_nomask(self):
+        arr = numpy.ma.masked_array([[1, 2], [3, 4]], dtype=numpy.int32)
+        assert arr.mask is numpy.ma.nomask
+        assert (
+            orjson.dumps(arr, option=orjson.OPT_SERIALIZE_NUMPY) == b"[[1,2],[3,4]]"
+        )
+
+    def test_numpy_masked_array_masked_values(self):
+        arr = numpy.ma.masked_equal(numpy.array([0, 1, 0, 2], numpy.uint8), 0)
+        assert (
+            orjson.dumps({"a": [arr]}, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'{"a":[[null,1,null,2]]}'
+        )
+
+    def test_numpy_masked_array_d3_bool(self):
+        arr = numpy.ma.masked_array(
+            [[[True, False], [True, True]], [[False, False], [True, False]]],
+            mask=[[[0, 1], [0, 0]], [[0, 0], [1, 1]]],
+            dtype=numpy.bool_,
+        )
+        assert (
+            orjson.dumps(arr, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b"[[[true,null],[true,true]],[[false,false],[null,null]]]"
+        )
+
+    def test_numpy_masked_array_empty(self):
+        arr = numpy.ma.masked_array(numpy.zeros((0,), numpy.float32), mask=[])
+        assert orjson.dumps(arr, option=orjson.OPT_SERIALIZE_NUMPY) == b"[]"
+
+    def test_numpy_masked_array_disabled(self):
+        arr = numpy.ma.masked_array([1, 2], mask=[False, True], dtype=numpy.int64)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(arr)
+
     def test_numpy_array_d3_i8(self):
         assert (
             orjson.dumps(
@@ -447,25 +581,93 @@ class TestNumpy:
     def test_numpy_array_fortran(self):
         array = numpy.array([[1, 2], [3, 4]], order="F")
         assert array.flags["F_CONTIGUOUS"] is True
-        with pytest.raises(orjson.JSONEncodeError):
+        assert orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY) == b"[[1,2],[3,4]]"
+
+    def test_numpy_array_transposed(self):
+        array = numpy.arange(6, dtype=numpy.int64).reshape(2, 3).T
+        assert array.flags["C_CONTIGUOUS"] is False
+        assert (
             orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
-        assert orjson.dumps(
-            array,
-            default=numpy_default,
-            option=orjson.OPT_SERIALIZE_NUMPY,
-        ) == orjson.dumps(array.tolist())
+            == b"[[0,3],[1,4],[2,5]]"
+        )
+        array = numpy.random.rand(3, 4, 5).transpose(2, 0, 1)
+        assert orjson.loads(
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+        ) == array.tolist()
 
-    def test_numpy_array_non_contiguous_message(self):
-        array = numpy.array([[1, 2], [3, 4]], order="F")
-        assert array.flags["F_CONTIGUOUS"] is True
-        try:
+    def test_numpy_array_strided_slice(self):
+        array = numpy.arange(10, dtype=numpy.int32)[::2]
+        assert array.flags["C_CONTIGUOUS"] is False
+        assert orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY) == b"[0,2,4,6,8]"
+        array = numpy.arange(10, dtype=numpy.float64)[::-3]
+        assert (
             orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
-            raise AssertionError()
-        except TypeError as exc:
-            assert (
-                str(exc)
-                == "numpy array is not C contiguous; use ndarray.tolist() in default"
-            )
+            == b"[9.0,6.0,3.0,0.0]"
+        )
+        array = numpy.arange(24, dtype=numpy.uint8).reshape(4, 6)[1::2, ::3]
+        assert orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY) == (
+            b"[[6,9],[18,21]]"
+        )
+
+    def test_numpy_array_strided_types(self):
+        for dtype in (
+            numpy.bool_,
+            numpy.float16,
+            numpy.float32,
+            numpy.float64,
+            numpy.int8,
+            numpy.int16,
+            numpy.int32,
+            numpy.int64,
+            numpy.uint8,
+            numpy.uint16,
+            numpy.uint32,
+            numpy.uint64,
+        ):
+            array = numpy.arange(12).astype(dtype).reshape(3, 4).T[:, ::2]
+            assert orjson.dumps(
+                array,
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            ) == orjson.dumps(array.tolist()), dtype
+
+    def test_numpy_array_strided_datetime64(self):
+        array = numpy.array(
+            ["2021-01-01", "2021-01-02", "2021-01-03", "2021-01-04"],
+            dtype="datetime64[D]",
+        )[::2]
+        assert orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY) == (
+            b'["2021-01-01T00:00:00","2021-01-03T00:00:00"]'
+        )
+
+    def test_numpy_array_broadcast(self):
+        array = numpy.broadcast_to(numpy.array([1, 2], numpy.int64), (3, 2))
+        assert array.strides == (0, 8)
+        assert orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY) == (
+            b"[[1,2],[1,2],[1,2]]"
+        )
+        array = numpy.broadcast_to(numpy.array([[1], [2]], numpy.int64), (2, 3))
+        assert orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY) == (
+            b"[[1,1,1],[2,2,2]]"
+        )
+
+    def test_numpy_array_unaligned(self):
+        buf = b"\x00" + numpy.array([1.0, 2.5, -3.0, 4.0], numpy.float64).tobytes()
+        array = numpy.frombuffer(buf, dtype=numpy.float64, offset=1)
+        assert array.flags["ALIGNED"] is False
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b"[1.0,2.5,-3.0,4.0]"
+        )
+
+    def test_numpy_masked_array_transposed(self):
+        array = numpy.ma.masked_array(
+            numpy.arange(6, dtype=numpy.int64).reshape(2, 3),
+            mask=[[0, 1, 0], [1, 0, 0]],
+        ).T
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b"[[0,null],[null,4],[2,5]]"
+        )
 
     def test_numpy_array_unsupported_dtype(self):
         array = numpy.array([[1, 2], [3, 4]], numpy.csingle)  # type: ignore
@@ -473,6 +675,70 @@ class TestNumpy:
             orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
         assert "unsupported datatype in numpy array" in str(cm)
 
+    def test_numpy_array_object_dicts(self):
+        array = numpy.array([{"a": 1}, {"b": [2, 3]}, {}], dtype=object)
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'[{"a":1},{"b":[2,3]},{}]'
+        )
+
+    def test_numpy_array_object_mixed(self):
+        array = numpy.array(
+            [1, 2.5, "a", None, True, numpy.int32(3), numpy.float64(0.5)],
+            dtype=object,
+        )
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'[1,2.5,"a",null,true,3,0.5]'
+        )
+
+    def test_numpy_array_object_shape(self):
+        array = numpy.array([[1, "a"], [None, [2]]], dtype=object)
+        assert array.shape == (2, 2)
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'[[1,"a"],[null,[2]]]'
+        )
+        assert (
+            orjson.dumps(array.T, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'[[1,null],["a",[2]]]'
+        )
+        assert (
+            orjson.dumps(numpy.empty((2, 0), dtype=object), option=orjson.OPT_SERIALIZE_NUMPY)
+            == b"[[],[]]"
+        )
+
+    def test_numpy_array_object_default(self):
+        array = numpy.array([1, object(), 2], dtype=object)
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY, default=lambda _: "x")
+            == b'[1,"x",2]'
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+
+    def test_numpy_array_object_default_replaces_item(self):
+        array = numpy.array([object(), "b"], dtype=object)
+
+        def default(obj):
+            array[0] = None
+            return "a"
+
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY, default=default)
+            == b'["a","b"]'
+        )
+
+    def test_numpy_array_object_masked(self):
+        array = numpy.ma.masked_array(
+            numpy.array([{"a": 1}, "b", 3], dtype=object),
+            mask=[0, 1, 0],
+        )
+        assert (
+            orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'[{"a":1},null,3]'
+        )
+
     def test_numpy_array_d1(self):
         array = numpy.array([1])
         assert (
@@ -695,18 +961,85 @@ class TestNumpy:
             == b"18446744073709551615"
         )
 
+    def test_numpy_integer_limits_native(self):
+        """
+        the limits of int64 and uint64 serialize as the native int would, in
+        scalars and in contiguous and strided arrays, and parse back exactly
+        """
+        for dtype, value in (
+            (numpy.uint64, 2**64 - 1),
+            (numpy.uint64, 2**63),
+            (numpy.int64, -(2**63)),
+            (numpy.int64, 2**63 - 1),
+        ):
+            output = orjson.dumps(dtype(value), option=orjson.OPT_SERIALIZE_NUMPY)
+            assert output == orjson.dumps(value)
+            assert orjson.loads(output) == value
+            assert orjson.dumps(
+                {"a": [dtype(value)]}, option=orjson.OPT_SERIALIZE_NUMPY
+            ) == orjson.dumps({"a": [value]})
+            array = numpy.array([value, 0, value], dtype)
+            assert orjson.dumps(
+                array, option=orjson.OPT_SERIALIZE_NUMPY
+            ) == orjson.dumps([value, 0, value])
+            assert orjson.dumps(
+                array[::2], option=orjson.OPT_SERIALIZE_NUMPY
+            ) == orjson.dumps([value, value])
+
     def test_numpy_scalar_float16(self):
         assert (
             orjson.dumps(numpy.float16(1.0), option=orjson.OPT_SERIALIZE_NUMPY)
             == b"1.0"
         )
 
+    def test_numpy_scalar_float16_edge(self):
+        for val, expected in (
+            (0.0, b"0.0"),
+            (-0.0, b"-0.0"),
+            (2**-24, b"5.9604645e-8"),
+            (65504.0, b"65504.0"),
+            (-65504.0, b"-65504.0"),
+            (numpy.inf, b"null"),
+            (-numpy.inf, b"null"),
+            (numpy.nan, b"null"),
+        ):
+            assert (
+                orjson.dumps(numpy.float16(val), option=orjson.OPT_SERIALIZE_NUMPY)
+                == expected
+            )
+
     def test_numpy_scalar_float32(self):
         assert (
             orjson.dumps(numpy.float32(1.0), option=orjson.OPT_SERIALIZE_NUMPY)
             == b"1.0"
         )
 
+    def test_numpy_scalar_float32_shortest(self):
+        """
+        float32 is the shortest decimal that round-trips as float32, as numpy
+        formats it, and not widened to float64
+        """
+        assert (
+            orjson.dumps(numpy.float32(0.1), option=orjson.OPT_SERIALIZE_NUMPY)
+            == b"0.1"
+        )
+        for val in FLOAT32_VALUES:
+            obj = numpy.float32(val)
+            res = orjson.dumps(obj, option=orjson.OPT_SERIALIZE_NUMPY)
+            assert numpy.float32(res.decode()) == obj
+            # str() is the digits of repr() without the np.float32() of numpy 2
+            assert float(res) == float(str(obj))
+
+    def test_numpy_array_float32_shortest(self):
+        """
+        float32 arrays, contiguous or not, are formatted like the scalar
+        """
+        arr = numpy.array(FLOAT32_VALUES, numpy.float32)
+        for obj in (arr, arr[::2], arr[::-1], arr.reshape(2, -1)):
+            res = orjson.dumps(obj, option=orjson.OPT_SERIALIZE_NUMPY)
+            expected = numpy.vectorize(lambda each: float(str(each)))(obj)
+            assert orjson.loads(res) == expected.tolist()
+
     def test_numpy_scalar_float64(self):
         assert (
             orjson.dumps(numpy.float64(123.123), option=orjson.OPT_SERIALIZE_NUMPY)
@@ -722,6 +1055,38 @@ class TestNumpy:
             == b'{"a":true,"b":false}'
         )
 
+    def test_numpy_bool_native(self):
+        """
+        numpy.bool_ serializes as True and False, not as an int
+        """
+        for value in (True, False):
+            assert orjson.dumps(
+                numpy.bool_(value),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            ) == orjson.dumps(value)
+            assert orjson.dumps(
+                [numpy.bool_(value)],
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            ) == orjson.dumps([value])
+            assert orjson.dumps(
+                {"a": numpy.bool_(value)},
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            ) == orjson.dumps({"a": value})
+        assert (
+            orjson.dumps(
+                [numpy.bool_(True), numpy.int64(1), numpy.bool_(False), numpy.int64(0)],
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            )
+            == b"[true,1,false,0]"
+        )
+        assert (
+            orjson.dumps(
+                numpy.array([True, False]),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            )
+            == b"[true,false]"
+        )
+
     def test_numpy_datetime_year(self):
         assert (
             orjson.dumps(numpy.datetime64("2021"), option=orjson.OPT_SERIALIZE_NUMPY)
@@ -1059,10 +1424,81 @@ class TestNumpy:
         )
 
     def test_numpy_datetime_nat(self):
-        with pytest.raises(orjson.JSONEncodeError):
+        """
+        numpy.datetime64("NaT") of generic unit is serialized as null
+        """
+        assert (
             orjson.dumps(numpy.datetime64("NaT"), option=orjson.OPT_SERIALIZE_NUMPY)
-        with pytest.raises(orjson.JSONEncodeError):
+            == b"null"
+        )
+        assert (
             orjson.dumps([numpy.datetime64("NaT")], option=orjson.OPT_SERIALIZE_NUMPY)
+            == b"[null]"
+        )
+
+    @pytest.mark.parametrize(
+        "unit", ["Y", "M", "W", "D", "h", "m", "s", "ms", "us", "ns"]
+    )
+    def test_numpy_datetime_nat_unit(self, unit):
+        """
+        NaT is serialized as null in each unit rather than converted
+        """
+        nat = numpy.iinfo(numpy.int64).min
+        data = numpy.array([0, nat, 0], numpy.int64).view(f"datetime64[{unit}]")
+        assert (
+            orjson.dumps(data, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'["1970-01-01T00:00:00",null,"1970-01-01T00:00:00"]'
+        )
+        assert orjson.dumps(data[1], option=orjson.OPT_SERIALIZE_NUMPY) == b"null"
+
+    def test_numpy_datetime_nat_mixed(self):
+        """
+        arrays mixing valid timestamps and NaT in several units
+        """
+        assert (
+            orjson.dumps(
+                [
+                    numpy.array(["2021-01-01T00:00:00.172", "NaT"], "datetime64[ns]"),
+                    numpy.array(["NaT", "2021-01-01T00:00:00.172"], "datetime64[us]"),
+                    numpy.array(["2021-01-01T00:00:01", "NaT"], "datetime64[ms]"),
+                    numpy.array(["NaT", "NaT"], "datetime64[s]"),
+                    numpy.array(["2021-01-01", "NaT"], "datetime64[D]"),
+                    numpy.array(["NaT", "2021"], "datetime64[Y]"),
+                ],
+                option=orjson.OPT_SERIALIZE_NUMPY,
+            )
+            == b'[["2021-01-01T00:00:00.172000",null],'
+            b'[null,"2021-01-01T00:00:00.172000"],'
+            b'["2021-01-01T00:00:01",null],'
+            b"[null,null],"
+            b'["2021-01-01T00:00:00",null],'
+            b'[null,"2021-01-01T00:00:00"]]'
+        )
+
+    def test_numpy_datetime_nat_d2(self):
+        """
+        NaT in a 2-dimensional array
+        """
+        data = numpy.array(
+            [["2021-01-01T00:00:00", "NaT"], ["NaT", "2021-01-02T00:00:00"]],
+            "datetime64[s]",
+        )
+        assert (
+            orjson.dumps(data, option=orjson.OPT_SERIALIZE_NUMPY)
+            == b'[["2021-01-01T00:00:00",null],[null,"2021-01-02T00:00:00"]]'
+        )
+
+    def test_numpy_datetime_nat_naive_utc(self):
+        """
+        NaT is null rather than a datetime with an offset with OPT_NAIVE_UTC
+        """
+        assert (
+            orjson.dumps(
+                numpy.array(["NaT", "1970-01-01"], "datetime64[ns]"),
+                option=orjson.OPT_SERIALIZE_NUMPY | orjson.OPT_NAIVE_UTC,
+            )
+            == b'[null,"1970-01-01T00:00:00+00:00"]'
+        )
 
     def test_numpy_repeated(self):
         data = numpy.array([[[1, 2], [3, 4], [5, 6], [7, 8]]], numpy.int64)  # type: ignore
@@ -1075,6 +1511,82 @@ class TestNumpy:
                 == b"[[[1,2],[3,4],[5,6],[7,8]]]"
             )
 
+    def test_numpy_passthrough_array(self):
+        """
+        OPT_PASSTHROUGH_NUMPY passes arrays to default while other types are
+        serialized natively
+        """
+        calls = []
+
+        def default(obj):
+            calls.append(obj)
+            if isinstance(obj, numpy.ndarray) and obj.dtype == numpy.float32:
+                return [round(float(val), 2) for val in obj]
+            if isinstance(obj, numpy.ndarray):
+                return obj.tolist()
+            raise TypeError
+
+        data = {
+            "a": numpy.array([1.1, 2.2], numpy.float32),
+            "b": 1,
+            "c": [2, 3],
+        }
+        assert (
+            orjson.dumps(
+                data,
+                default=default,
+                option=orjson.OPT_SERIALIZE_NUMPY | orjson.OPT_PASSTHROUGH_NUMPY,
+            )
+            == b'{"a":[1.1,2.2],"b":1,"c":[2,3]}'
+        )
+        assert len(calls) == 1
+        assert (
+            orjson.dumps(
+                [numpy.array([1, 2], numpy.int64), 3],
+                default=default,
+                option=orjson.OPT_SERIALIZE_NUMPY | orjson.OPT_PASSTHROUGH_NUMPY,
+            )
+            == b"[[1,2],3]"
+        )
+        assert len(calls) == 2
+
+    def test_numpy_passthrough_scalar(self):
+        """
+        OPT_PASSTHROUGH_NUMPY passes scalars to default
+        """
+
+        def default(obj):
+            if isinstance(obj, numpy.float32):
+                return "float32"
+            if isinstance(obj, numpy.int32):
+                return int(obj)
+            raise TypeError
+
+        assert (
+            orjson.dumps(
+                [numpy.float32(1.5), numpy.int32(2)],
+                default=default,
+                option=orjson.OPT_SERIALIZE_NUMPY | orjson.OPT_PASSTHROUGH_NUMPY,
+            )
+            == b'["float32",2]'
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                numpy.uint8(1),
+                default=default,
+                option=orjson.OPT_SERIALIZE_NUMPY | orjson.OPT_PASSTHROUGH_NUMPY,
+            )
+
+    def test_numpy_passthrough_no_default(self):
+        """
+        OPT_PASSTHROUGH_NUMPY without default raises JSONEncodeError
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                numpy.array([1, 2]),
+                option=orjson.OPT_SERIALIZE_NUMPY | orjson.OPT_PASSTHROUGH_NUMPY,
+            )
+
 
 @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
 class TestNumpyEquivalence:
@@ -1131,6 +1643,13 @@ class TestNumpyEquivalence:
         )
 
 
+    def test_numpy_float64_shortest(self):
+        values = [0.1, 0.3, 0.1 + 0.2, 1e20, 1e-7, 5e-324, 1 / 3]
+        self._test(numpy.array(values, numpy.float64))
+        assert orjson.dumps(
+            [numpy.float64(val) for val in values], option=orjson.OPT_SERIALIZE_NUMPY
+        ) == orjson.dumps(values)
+
 @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
 class NumpyEndianness:
     def test_numpy_array_dimension_zero(self):
@@ -1138,3 +1657,144 @@ class NumpyEndianness:
         array = numpy.array([0, 1, 0.4, 5.7], dtype=f"{wrong_endianness}f8")
         with pytest.raises(orjson.JSONEncodeError):
             orjson.dumps(array, option=orjson.OPT_SERIALIZE_NUMPY)
+
+
+class TestDisableNumpy:
+    def test_disable_numpy_no_import(self):
+        """
+        disable_numpy() prevents importing numpy on serializing with
+        OPT_SERIALIZE_NUMPY
+        """
+        assert _run("disable") == ["0", "False"]
+
+    def test_disable_numpy_import_otherwise(self):
+        """
+        numpy is imported, or an import attempted, without disable_numpy()
+        """
+        attempts, _ = _run("enable")
+        assert int(attempts) > 0
+
+    def test_disable_numpy_build_info(self):
+        try:
+            orjson.disable_numpy()
+            assert orjson.build_info()["numpy_available"] is False
+        finally:
+            orjson.reset_state()
+
+    def test_disable_numpy_reset_state(self):
+        orjson.disable_numpy()
+        orjson.reset_state()
+        assert orjson.build_info()["numpy_available"] is (numpy is not None)
+
+    def test_disable_numpy_returns_none(self):
+        try:
+            assert orjson.disable_numpy() is None
+            assert orjson.disable_numpy() is None
+        finally:
+            orjson.reset_state()
+
+    def test_disable_numpy_args(self):
+        with pytest.raises(TypeError):
+            orjson.disable_numpy(True)  # type: ignore
+
+    @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
+    def test_disable_numpy_default(self):
+        """
+        numpy types are passed to default after disable_numpy()
+        """
+        arr = numpy.array([1, 2], numpy.int64)
+        try:
+            orjson.disable_numpy()
+            assert (
+                orjson.dumps(
+                    arr,
+                    option=orjson.OPT_SERIALIZE_NUMPY,
+                    default=lambda obj: obj.tolist(),
+                )
+                == b"[1,2]"
+            )
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(numpy.float64(1.0), option=orjson.OPT_SERIALIZE_NUMPY)
+        finally:
+            orjson.reset_state()
+        assert orjson.dumps(arr, option=orjson.OPT_SERIALIZE_NUMPY) == b"[1,2]"
+
+
+def test_pandas_not_imported():
+    """
+    datetime subclasses do not import pandas
+    """
+    if "pandas" in sys.modules:
+        pytest.skip("pandas is already imported")
+    assert orjson.dumps(DatetimeSubclass(2000, 1, 1)) == b'"2000-01-01T00:00:00"'
+    assert "pandas" not in sys.modules
+
+
+@pytest.mark.skipif(pandas is None, reason="pandas is not installed")
+class TestPandas:
+    def test_pandas_nat(self):
+        """
+        pandas.NaT is serialized as null
+        """
+        assert orjson.dumps(pandas.NaT) == b"null"
+        assert orjson.dumps([pandas.NaT, 1]) == b"[null,1]"
+        assert orjson.dumps({"a": pandas.NaT}) == b'{"a":null}'
+
+    def test_pandas_nat_non_str_keys(self):
+        """
+        pandas.NaT as a key with OPT_NON_STR_KEYS
+        """
+        assert (
+            orjson.dumps({pandas.NaT: 1}, option=orjson.OPT_NON_STR_KEYS)
+            == b'{"null":1}'
+        )
+
+    def test_pandas_nat_passthrough_datetime(self):
+        """
+        pandas.NaT is passed to default with OPT_PASSTHROUGH_DATETIME
+        """
+        assert (
+            orjson.dumps(
+                pandas.NaT,
+                option=orjson.OPT_PASSTHROUGH_DATETIME,
+                default=lambda _: "nat",
+            )
+            == b'"nat"'
+        )
+
+    def test_pandas_timestamp(self):
+        """
+        pandas.Timestamp
+        """
+        assert (
+            orjson.dumps(pandas.Timestamp("2018-12-01 02:03:04.000005"))
+            == b'"2018-12-01T02:03:04.000005"'
+        )
+
+    def test_pandas_timestamp_tz(self):
+        """
+        pandas.Timestamp with a timezone
+        """
+        val = pandas.Timestamp("2018-12-01 02:03:04", tz="Asia/Shanghai")
+        assert orjson.dumps(val) == b'"2018-12-01T02:03:04+08:00"'
+        val = pandas.Timestamp(
+            datetime.datetime(
+                2018, 12, 1, 2, 3, 4, tzinfo=zoneinfo.ZoneInfo("America/New_York")
+            )
+        )
+        assert orjson.dumps(val) == b'"2018-12-01T02:03:04-05:00"'
+        val = pandas.Timestamp("2018-12-01 02:03:04", tz="UTC")
+        assert orjson.dumps(val, option=orjson.OPT_UTC_Z) == b'"2018-12-01T02:03:04Z"'
+
+    def test_pandas_timestamp_naive_utc(self):
+        """
+        pandas.Timestamp with OPT_NAIVE_UTC and OPT_OMIT_MICROSECONDS
+        """
+        val = pandas.Timestamp("2018-12-01 02:03:04.000005")
+        assert (
+            orjson.dumps(
+                val,
+                option=orjson.OPT_NAIVE_UTC | orjson.OPT_OMIT_MICROSECONDS,
+            )
+            == b'"2018-12-01T02:03:04+00:00"'
+        )
@@ -1,11 +1,79 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2018-2025)
 
+import collections
+import inspect
+import itertools
+import math
+import os
+import pathlib
+import sys
+import tempfile
+import uuid
+
 import pytest
 
 import orjson
 
-from .util import SUPPORTS_MEMORYVIEW, needs_data, read_fixture_bytes
+from .util import (
+    SUPPORTS_MEMORYVIEW,
+    needs_data,
+    read_fixture_bytes,
+    run_in_subinterpreter,
+)
+
+RELAXED = orjson.OPT_PARSE_RELAXED
+
+
+JSON5 = orjson.OPT_JSON5_NONFINITE
+
+try:
+    import numpy
+except ImportError:
+    numpy = None  # type: ignore
+
+
+RECORDS = orjson.dumps(
+    [
+        {"id": idx, "country": ("NZ", "AU", "FR")[idx % 3], "status": "active"}
+        for idx in range(1000)
+    ]
+)
+
+
+class AttrDict(dict):
+    pass
+
+
+def write_temp(contents: bytes) -> str:
+    with tempfile.NamedTemporaryFile(suffix=".json", delete=False) as fp:
+        fp.write(contents)
+    return fp.name
+
+
+KEY_CACHE = orjson.build_info()["key_cache"]
+
+needs_key_cache = pytest.mark.skipif(
+    not KEY_CACHE, reason="keys are not cached in this build"
+)
+
+
+def new_str(val: str) -> str:
+    """
+    a str that is not the same object as any other
+    """
+    return "".join(list(val))
+
+
+def unique_key() -> str:
+    """
+    a key not already cached by another test
+    """
+    return f"stats_{uuid.uuid4().hex}"
+
+
+def reset():
+    orjson.key_cache_stats(reset=True)
 
 
 @needs_data
@@ -1948,3 +2016,1256 @@ class TestJSONTestSuiteParsing:
         i_structure_UTF-8_BOM_empty_object.json
         """
         self._run_fail_json("i_structure_UTF-8_BOM_empty_object.json")
+
+
+class TestParseRelaxed:
+    def test_parse_relaxed_disabled(self):
+        """
+        comments and trailing commas are rejected without OPT_PARSE_RELAXED
+        """
+        for val in ("[1,]", '{"a":1,}', "[1] // a", "/* a */ [1]"):
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads(val)
+
+    def test_parse_relaxed_standard(self):
+        val = '{"a":[1,2.5,"b",null,true,false],"c":{"d":"é"}}'
+        assert orjson.loads(val, option=RELAXED) == orjson.loads(val)
+
+    def test_parse_relaxed_trailing_comma_array(self):
+        assert orjson.loads("[1,2,]", option=RELAXED) == [1, 2]
+        assert orjson.loads("[1 , \n ]", option=RELAXED) == [1]
+        assert orjson.loads('[[1,],{"a":[],},]', option=RELAXED) == [[1], {"a": []}]
+
+    def test_parse_relaxed_trailing_comma_object(self):
+        assert orjson.loads('{"a":1,"b":2,}', option=RELAXED) == {"a": 1, "b": 2}
+        assert orjson.loads('{"a":{"b":"c",},\n}', option=RELAXED) == {"a": {"b": "c"}}
+
+    def test_parse_relaxed_line_comment(self):
+        val = """
+        // leading
+        {
+            "a": 1, // after a value
+            // on its own line
+            "b": [2, 3] // before the end
+        }
+        // trailing"""
+        assert orjson.loads(val, option=RELAXED) == {"a": 1, "b": [2, 3]}
+
+    def test_parse_relaxed_block_comment(self):
+        val = """/* leading */ {
+            "a": /* inline */ 1,
+            /*
+             * multiline
+             */
+            "b": [2, /* é */ 3]
+        } /**/"""
+        assert orjson.loads(val, option=RELAXED) == {"a": 1, "b": [2, 3]}
+
+    def test_parse_relaxed_comment_and_trailing_comma(self):
+        val = """{
+            "a": [
+                1, // one
+                2, /* two */
+            ],
+        }"""
+        assert orjson.loads(val, option=RELAXED) == {"a": [1, 2]}
+
+    def test_parse_relaxed_string(self):
+        """
+        comment delimiters and commas in a string are unchanged
+        """
+        val = r'["//", "/* a */", ",]", "\"//", "\\", "a,}"]'
+        assert orjson.loads(val, option=RELAXED) == [
+            "//",
+            "/* a */",
+            ",]",
+            '"//',
+            "\\",
+            "a,}",
+        ]
+
+    def test_parse_relaxed_bytes(self):
+        assert orjson.loads(b"[1, // a\n 2,]", option=RELAXED) == [1, 2]
+        assert orjson.loads(bytearray(b"{/**/}"), option=RELAXED) == {}
+
+    def test_parse_relaxed_only_comment(self):
+        for val in ("// a", "/* a */", " "):
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads(val, option=RELAXED)
+
+    def test_parse_relaxed_invalid(self):
+        """
+        input that is malformed other than by comments and trailing commas
+        is rejected
+        """
+        for val in (
+            "[,]",
+            "{,}",
+            "[1,,]",
+            '{"a",}',
+            '{"a":,}',
+            "[1 2,]",
+            "[1,] 2",
+            "[1",
+            "[1,",
+            "/ [1]",
+            "[1] /",
+            "# a\n[1]",
+        ):
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads(val, option=RELAXED)
+
+    def test_parse_relaxed_invalid_pos(self):
+        """
+        the position of an error is in the input
+        """
+        val = '{\n  // a\n  "a": 1,\n  "b": tru\n}'
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads(val, option=RELAXED)
+        assert exc_info.value.doc == val
+        assert exc_info.value.lineno == 4
+        assert val[exc_info.value.pos :].startswith("tru")
+
+    def test_parse_relaxed_invalid_pos_multibyte(self):
+        """
+        the position counts characters of a comment, as the standard library
+        """
+        val = "[/* é\U0001f600 */ 1 x]"
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads(val, option=RELAXED)
+        assert exc_info.value.doc == val
+        assert val[exc_info.value.pos] == "x"
+
+    def test_parse_relaxed_unclosed_comment(self):
+        val = "[1] /* a"
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads(val, option=RELAXED)
+        assert exc_info.value.pos == 4
+
+    def test_parse_relaxed_option(self):
+        """
+        OPT_PARSE_RELAXED with other options
+        """
+        val = '{"a":1, /* a */ "a":2,}'
+        assert orjson.loads(val, option=RELAXED) == {"a": 2}
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads(val, option=RELAXED | orjson.OPT_FORBID_DUPLICATE_KEYS)
+        assert orjson.loads(val, option=RELAXED, object_pairs_hook=list) == [
+            ("a", 1),
+            ("a", 2),
+        ]
+
+    def test_parse_relaxed_load_file(self):
+        with tempfile.NamedTemporaryFile(suffix=".json", delete=False) as fp:
+            fp.write(b'{\n  // a\n  "a": [1, 2,],\n}\n')
+        try:
+            assert orjson.load_file(fp.name, RELAXED) == {"a": [1, 2]}
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.load_file(fp.name)
+        finally:
+            os.unlink(fp.name)
+
+
+class TestJson5Nonfinite:
+    def test_json5_nonfinite_dumps(self):
+        """
+        OPT_JSON5_NONFINITE writes NaN, Infinity, and -Infinity
+        """
+        assert (
+            orjson.dumps([float("nan"), float("inf"), float("-inf")], option=JSON5)
+            == b"[NaN,Infinity,-Infinity]"
+        )
+
+    @pytest.mark.parametrize(
+        "obj,expected",
+        [
+            (float("nan"), b"NaN"),
+            (float("inf"), b"Infinity"),
+            (float("-inf"), b"-Infinity"),
+        ],
+    )
+    def test_json5_nonfinite_dumps_toplevel(self, obj, expected):
+        """
+        OPT_JSON5_NONFINITE of a non-finite float
+        """
+        assert orjson.dumps(obj, option=JSON5) == expected
+        assert orjson.dumps({"a": obj}, option=JSON5) == b'{"a":' + expected + b"}"
+
+    def test_json5_nonfinite_finite(self):
+        """
+        OPT_JSON5_NONFINITE does not change finite floats
+        """
+        assert orjson.dumps([1.5, -0.0, 1e300], option=JSON5) == orjson.dumps(
+            [1.5, -0.0, 1e300]
+        )
+
+    def test_json5_nonfinite_disabled(self):
+        """
+        non-finite floats are null without OPT_JSON5_NONFINITE
+        """
+        assert orjson.dumps([float("nan"), float("inf")]) == b"[null,null]"
+
+    @pytest.mark.parametrize("obj", [float("inf"), float("-inf")])
+    def test_json5_nonfinite_roundtrip_infinity(self, obj):
+        """
+        OPT_JSON5_NONFINITE round-trips infinity with OPT_PARSE_RELAXED
+        """
+        assert orjson.loads(orjson.dumps(obj, option=JSON5), option=RELAXED) == obj
+        assert orjson.loads(
+            orjson.dumps([obj, {"a": [obj]}], option=JSON5), option=RELAXED
+        ) == [obj, {"a": [obj]}]
+
+    def test_json5_nonfinite_roundtrip_nan(self):
+        """
+        OPT_JSON5_NONFINITE round-trips NaN with OPT_PARSE_RELAXED
+        """
+        assert math.isnan(
+            orjson.loads(orjson.dumps(float("nan"), option=JSON5), option=RELAXED)
+        )
+        val = orjson.loads(
+            orjson.dumps({"a": [1, float("nan")]}, option=JSON5), option=RELAXED
+        )
+        assert val["a"][0] == 1
+        assert math.isnan(val["a"][1])
+
+    def test_json5_nonfinite_self_check(self):
+        """
+        OPT_JSON5_NONFINITE with OPT_SELF_CHECK
+        """
+        assert (
+            orjson.dumps(
+                [float("nan"), float("inf")], option=JSON5 | orjson.OPT_SELF_CHECK
+            )
+            == b"[NaN,Infinity]"
+        )
+
+    def test_json5_nonfinite_non_str_keys(self):
+        """
+        OPT_JSON5_NONFINITE does not apply to dict keys
+        """
+        assert (
+            orjson.dumps({float("inf"): 1}, option=JSON5 | orjson.OPT_NON_STR_KEYS)
+            == b'{"null":1}'
+        )
+
+    def test_json5_nonfinite_indent(self):
+        """
+        OPT_JSON5_NONFINITE with OPT_INDENT_2
+        """
+        assert (
+            orjson.dumps([float("-inf")], option=JSON5 | orjson.OPT_INDENT_2)
+            == b"[\n  -Infinity\n]"
+        )
+
+    def test_json5_nonfinite_cache_tuples(self):
+        """
+        OPT_CACHE_TUPLES output is not reused across OPT_JSON5_NONFINITE
+        """
+        cache = orjson.OPT_CACHE_TUPLES
+        obj = (float("nan"), float("inf"))
+        assert orjson.dumps(obj, option=cache) == b"[null,null]"
+        assert orjson.dumps(obj, option=cache | JSON5) == b"[NaN,Infinity]"
+        assert orjson.dumps(obj, option=cache) == b"[null,null]"
+        obj = (float("-inf"),)
+        assert orjson.dumps(obj, option=cache | JSON5) == b"[-Infinity]"
+        assert orjson.dumps(obj, option=cache) == b"[null]"
+        assert orjson.dumps(obj, option=cache | JSON5) == b"[-Infinity]"
+
+    def test_json5_nonfinite_numpy(self):
+        """
+        OPT_JSON5_NONFINITE of numpy floats
+        """
+        if numpy is None:
+            pytest.skip("numpy is not installed")
+        assert (
+            orjson.dumps(
+                numpy.array([numpy.nan, numpy.inf], numpy.float32),
+                option=JSON5 | orjson.OPT_SERIALIZE_NUMPY,
+            )
+            == b"[NaN,Infinity]"
+        )
+
+    def test_parse_relaxed_nonfinite(self):
+        """
+        OPT_PARSE_RELAXED reads NaN, Infinity, +Infinity, and -Infinity
+        """
+        val = orjson.loads(
+            "[NaN, Infinity, +Infinity, -Infinity, /* a */ 1,]", option=RELAXED
+        )
+        assert math.isnan(val[0])
+        assert val[1:] == [float("inf"), float("inf"), float("-inf"), 1]
+
+    def test_parse_relaxed_nonfinite_string(self):
+        """
+        OPT_PARSE_RELAXED does not change the tokens in a string
+        """
+        assert orjson.loads('["NaN", "Infinity", "   "]', option=RELAXED) == [
+            "NaN",
+            "Infinity",
+            "   ",
+        ]
+
+    def test_parse_relaxed_nonfinite_disabled(self):
+        """
+        NaN and Infinity are rejected without OPT_PARSE_RELAXED
+        """
+        for val in ("NaN", "[Infinity]", '{"a":-Infinity}'):
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads(val)
+
+    @pytest.mark.parametrize(
+        "val",
+        [
+            "{NaN: 1}",
+            "[NaNa]",
+            "[Infinity1]",
+            "[nan]",
+            "[inf]",
+            "[- Infinity]",
+            "[1 NaN]",
+        ],
+    )
+    def test_parse_relaxed_nonfinite_invalid(self, val):
+        """
+        OPT_PARSE_RELAXED rejects the tokens elsewhere
+        """
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads(val, option=RELAXED)
+
+    def test_parse_relaxed_nonfinite_key(self):
+        """
+        OPT_PARSE_RELAXED rejects a token as a key with its position
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads('{"a": NaN, Infinity: 1}', option=RELAXED)
+        assert exc_info.value.pos == 11
+
+
+class TestInternValues:
+    def test_intern_values(self):
+        """
+        OPT_INTERN_VALUES shares equal string values
+        """
+        val = orjson.loads(RECORDS, option=orjson.OPT_INTERN_VALUES)
+        assert val == orjson.loads(RECORDS)
+        assert len({id(each["country"]) for each in val}) == 3
+        assert len({id(each["status"]) for each in val}) == 1
+
+    def test_intern_values_disabled(self):
+        """
+        string values are not shared without OPT_INTERN_VALUES
+        """
+        val = orjson.loads(RECORDS)
+        assert len({id(each["country"]) for each in val}) == len(val)
+
+    def test_intern_values_across_calls(self):
+        """
+        values are shared across calls and with keys
+        """
+        first = orjson.loads('["status"]', option=orjson.OPT_INTERN_VALUES)[0]
+        second = orjson.loads('{"a":"status"}', option=orjson.OPT_INTERN_VALUES)["a"]
+        key = next(iter(orjson.loads('{"status":1}')))
+        assert first is second is key
+
+    def test_intern_values_length(self):
+        """
+        values longer than the key cache limit are not shared
+        """
+        for length, shared in ((1, True), (64, True), (65, False), (1000, False)):
+            text = "a" * length
+            doc = orjson.dumps([text, {"b": text}, [text]])
+            val = orjson.loads(doc, option=orjson.OPT_INTERN_VALUES)
+            assert val == [text, {"b": text}, [text]]
+            assert (val[0] is val[1]["b"] is val[2][0]) == shared
+
+    def test_intern_values_nonascii(self):
+        """
+        non-ASCII and escaped values
+        """
+        doc = '["東京","\\u6771\\u4eac","🐈","",""]'
+        val = orjson.loads(doc, option=orjson.OPT_INTERN_VALUES)
+        assert val == ["東京", "東京", "🐈", "", ""]
+        assert val[0] is val[1]
+        assert val[3] is val[4]
+
+    def test_intern_values_root(self):
+        """
+        a document that is a single string
+        """
+        first = orjson.loads('"value"', option=orjson.OPT_INTERN_VALUES)
+        second = orjson.loads('"value"', option=orjson.OPT_INTERN_VALUES)
+        assert first == "value"
+        assert first is second
+
+    def test_intern_values_slow_path(self):
+        """
+        OPT_INTERN_VALUES with OPT_DUPLICATE_KEYS_AS_LIST and object_hook
+        """
+        val = orjson.loads(
+            '{"a":"x","a":"x","b":["x"]}',
+            option=orjson.OPT_INTERN_VALUES | orjson.OPT_DUPLICATE_KEYS_AS_LIST,
+        )
+        assert val == {"a": ["x", "x"], "b": ["x"]}
+        assert val["a"][0] is val["a"][1] is val["b"][0]
+        val = orjson.loads(
+            '[{"a":"x"},{"a":"x"}]',
+            option=orjson.OPT_INTERN_VALUES,
+            object_pairs_hook=list,
+        )
+        assert val[0][0][1] is val[1][0][1]
+
+    def test_intern_values_refcount(self):
+        """
+        shared values are not leaked
+        """
+        val = orjson.loads(RECORDS, option=orjson.OPT_INTERN_VALUES)
+        refcount = sys.getrefcount(val[0]["country"])
+        for _ in range(10):
+            orjson.loads(RECORDS, option=orjson.OPT_INTERN_VALUES)
+        assert sys.getrefcount(val[0]["country"]) == refcount
+
+
+class TestObjectHook:
+    def test_object_hook(self):
+        """
+        object_hook is called with each object and its return value is used
+        """
+        val = orjson.loads('{"a":1,"b":[2,{"c":3}]}', object_hook=AttrDict)
+        assert isinstance(val, AttrDict)
+        assert val == {"a": 1, "b": [2, {"c": 3}]}
+        assert isinstance(val["b"][1], AttrDict)
+
+    def test_object_hook_nested(self):
+        """
+        object_hook is called innermost first
+        """
+        calls = []
+
+        def hook(obj):
+            calls.append(dict(obj))
+            return len(calls)
+
+        val = orjson.loads('{"a":{"b":{"c":1}},"d":{}}', object_hook=hook)
+        assert calls == [{"c": 1}, {"b": 1}, {}, {"a": 2, "d": 3}]
+        assert val == 4
+
+    def test_object_hook_array_root(self):
+        """
+        object_hook with an array or scalar document
+        """
+
+        def hook(obj):
+            return sorted(obj)
+
+        assert orjson.loads('[{"b":1,"a":2},[{}],1]', object_hook=hook) == [
+            ["a", "b"],
+            [[]],
+            1,
+        ]
+        assert orjson.loads("{}", object_hook=hook) == []
+        assert orjson.loads("[]", object_hook=hook) == []
+        assert orjson.loads('"a"', object_hook=hook) == "a"
+        assert orjson.loads("1", object_hook=hook) == 1
+
+    def test_object_pairs_hook(self):
+        """
+        object_pairs_hook is called with a list of (key, value) tuples
+        """
+        val = orjson.loads('{"b":1,"a":{"c":[{"d":null}]}}', object_pairs_hook=list)
+        assert val == [("b", 1), ("a", [("c", [[("d", None)]])])]
+        assert orjson.loads("{}", object_pairs_hook=list) == []
+
+    def test_object_pairs_hook_duplicates(self):
+        """
+        object_pairs_hook receives keys that occur more than once
+        """
+        val = orjson.loads('{"a":1,"b":2,"a":3}', object_pairs_hook=list)
+        assert val == [("a", 1), ("b", 2), ("a", 3)]
+
+    def test_object_pairs_hook_ordereddict(self):
+        """
+        object_pairs_hook=collections.OrderedDict
+        """
+        val = orjson.loads('{"z":1,"y":{"x":2}}', object_pairs_hook=collections.OrderedDict)
+        assert isinstance(val, collections.OrderedDict)
+        assert isinstance(val["y"], collections.OrderedDict)
+        assert list(val.items()) == [("z", 1), ("y", collections.OrderedDict(x=2))]
+
+    def test_object_pairs_hook_priority(self):
+        """
+        object_pairs_hook is used if both hooks are given
+        """
+        val = orjson.loads(
+            '{"a":{"b":1}}',
+            object_hook=lambda _: pytest.fail("object_hook called"),
+            object_pairs_hook=tuple,
+        )
+        assert val == (("a", (("b", 1),)),)
+
+    def test_object_hook_none(self):
+        """
+        None is no hook
+        """
+        doc = '{"a":[{"b":1}]}'
+        assert orjson.loads(doc, object_hook=None) == orjson.loads(doc)
+        assert orjson.loads(doc, object_pairs_hook=None) == orjson.loads(doc)
+        assert orjson.loads(doc, None, object_hook=None, object_pairs_hook=None) == (
+            orjson.loads(doc)
+        )
+
+    def test_object_hook_not_callable(self):
+        """
+        a hook that is not callable raises TypeError
+        """
+        with pytest.raises(TypeError):
+            orjson.loads("{}", object_hook=1)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.loads("{}", object_pairs_hook="a")  # type: ignore
+
+    def test_object_hook_exception(self):
+        """
+        an exception raised by a hook is propagated
+        """
+
+        def hook(obj):
+            raise KeyError("hook")
+
+        with pytest.raises(KeyError):
+            orjson.loads('[1,{"a":{"b":2}}]', object_hook=hook)
+        with pytest.raises(KeyError):
+            orjson.loads('[1,{"a":{"b":2}}]', object_pairs_hook=hook)
+        assert orjson.loads('[1,{"a":{"b":2}}]') == [1, {"a": {"b": 2}}]
+
+    def test_object_hook_invalid_document(self):
+        """
+        an invalid document raises JSONDecodeError without calling a hook
+        """
+        calls = []
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads('{"a":{"b":1}', object_hook=calls.append)
+        assert calls == []
+
+    def test_object_hook_duplicate_keys_as_list(self):
+        """
+        OPT_DUPLICATE_KEYS_AS_LIST applies to the dict given to object_hook
+        """
+        val = orjson.loads(
+            '{"a":1,"a":{"b":2}}',
+            option=orjson.OPT_DUPLICATE_KEYS_AS_LIST,
+            object_hook=AttrDict,
+        )
+        assert isinstance(val, AttrDict)
+        assert val == {"a": [1, {"b": 2}]}
+        assert isinstance(val["a"][1], AttrDict)
+
+    def test_object_hook_keys_cached(self):
+        """
+        keys are cached as without a hook
+        """
+        first = orjson.loads('{"key":1}', object_pairs_hook=list)[0][0]
+        second = orjson.loads('{"key":2}', object_hook=dict)
+        third = orjson.loads('{"key":3}')
+        assert first is next(iter(second)) is next(iter(third))
+
+    def test_object_hook_refcount(self):
+        """
+        hook results and arguments are not leaked
+        """
+        sentinel = object()
+        for _ in range(100):
+            orjson.loads('[{"a":1},{"b":2}]', object_hook=lambda _: sentinel)
+            orjson.loads('[{"a":1},{"b":2}]', object_pairs_hook=lambda _: sentinel)
+        assert sys.getrefcount(sentinel) == 2
+
+
+class TestLoadsMany:
+    def test_loads_many(self):
+        """
+        loads_many() returns the loads() of each element
+        """
+        docs = [
+            b'{"a": [1, 2.5, null, "\\u00e9"]}',
+            "[]",
+            bytearray(b'"' + b"a" * 5000 + b'"'),
+            memoryview(b"1"),
+            "null",
+            orjson.dumps({"b": {"c": list(range(1000))}}),
+            "{}",
+            '""',
+        ]
+        res = orjson.loads_many(docs)
+        assert isinstance(res, list)
+        assert res == [orjson.loads(doc) for doc in docs]
+
+    def test_loads_many_empty(self):
+        """
+        loads_many() of an empty list
+        """
+        assert orjson.loads_many([]) == []
+
+    def test_loads_many_keys(self):
+        """
+        loads_many() shares the key cache across documents
+        """
+        docs = [orjson.dumps({"id": idx, "name": str(idx)}) for idx in range(100)]
+        res = orjson.loads_many(docs)
+        assert res == [{"id": idx, "name": str(idx)} for idx in range(100)]
+        if orjson.build_info()["key_cache"]:
+            keys = [next(iter(each)) for each in res]
+            assert all(key is keys[0] for key in keys)
+
+    def test_loads_many_options(self):
+        """
+        loads_many() applies option and hooks to each
+        """
+        docs = ['{"a": 1, "a": 2}', '{"b": [1,]}']
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads_many(docs[:1], orjson.OPT_FORBID_DUPLICATE_KEYS)
+        assert orjson.loads_many(docs[1:], option=orjson.OPT_PARSE_RELAXED) == [
+            {"b": [1]}
+        ]
+        assert orjson.loads_many(['{"a": 1}', "[{}]"], object_pairs_hook=list) == [
+            [("a", 1)],
+            [[]],
+        ]
+
+    def test_loads_many_error_index(self):
+        """
+        loads_many() raises on the first element that fails with its index
+        and position
+        """
+        docs = ["1", '{"a": 2}', '[1, {"a": }]', "["]
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads_many(docs)
+        assert exc_info.value.msg.endswith("(at index 2)")
+        assert exc_info.value.pos == 10
+        assert exc_info.value.doc == '[1, {"a": }]'
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads_many([b"\xff"])
+        assert exc_info.value.msg.endswith("(at index 0)")
+
+    def test_loads_many_error_type(self):
+        """
+        loads_many() raises on an element of an invalid type with its index
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            orjson.loads_many(["1", 2])
+        assert exc_info.value.msg.endswith("(at index 1)")
+
+    def test_loads_many_error_hook(self):
+        """
+        an exception raised by a hook is propagated
+        """
+
+        def hook(obj):
+            raise ValueError("hook")
+
+        with pytest.raises(ValueError, match="hook"):
+            orjson.loads_many(["1", "{}"], object_hook=hook)
+
+    def test_loads_many_not_list(self):
+        """
+        loads_many() of other than a list raises JSONDecodeError
+        """
+        for val in (("1", "2"), {"a": 1}, "[1]", b"[1]", None, iter(["1"])):
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads_many(val)  # type: ignore
+
+    def test_loads_many_list_subclass(self):
+        """
+        loads_many() of a list subclass
+        """
+
+        class Subclass(list):
+            pass
+
+        assert orjson.loads_many(Subclass(["1", '"a"'])) == [1, "a"]
+
+    def test_loads_many_args_invalid(self):
+        """
+        loads_many() invalid arguments raise naming loads_many()
+        """
+        with pytest.raises(TypeError) as exc_info:
+            orjson.loads_many()  # type: ignore
+        assert "loads_many()" in str(exc_info.value)
+        with pytest.raises(TypeError):
+            orjson.loads_many(["1"], opt=0)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.loads_many(["1"], option=1 << 50)
+
+    def test_loads_many_modified_by_hook(self):
+        """
+        a hook that shrinks the list does not read past its end
+        """
+        docs = ["{}", "1", "2", "3"]
+
+        def hook(obj):
+            del docs[1:]
+            return obj
+
+        assert orjson.loads_many(docs, object_hook=hook) == [{}]
+
+    def test_loads_many_refcount(self):
+        """
+        loads_many() does not leak the elements or the output
+        """
+        doc = b'{"a": [1, 2]}'
+        docs = [doc] * 10
+        before = sys.getrefcount(doc)
+        for _ in range(100):
+            res = orjson.loads_many(docs)
+            assert sys.getrefcount(res[0]) == 2
+            del res
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.loads_many([*docs, "["])
+        assert sys.getrefcount(doc) == before
+
+    def test_loads_many_signature(self):
+        """
+        loads_many() valid __text_signature__ and __module__
+        """
+        assert str(inspect.signature(orjson.loads_many)) == (
+            "(objs, /, option=None, *, object_hook=None, object_pairs_hook=None)"
+        )
+        assert orjson.loads_many.__module__ == "orjson"
+
+
+class TestLoadsArrayStream:
+    def test_loads_array_stream(self):
+        """
+        loads_array_stream() yields each element
+        """
+        doc = b' [1, "a,]\\"", {"b": [1, 2]}, [[]], null , true, 1.5] '
+        assert list(orjson.loads_array_stream(doc)) == orjson.loads(doc)
+
+    def test_loads_array_stream_iter(self):
+        """
+        loads_array_stream() returns an iterator of type ArrayStream
+        """
+        stream = orjson.loads_array_stream("[1]")
+        assert isinstance(stream, orjson.ArrayStream)
+        assert iter(stream) is stream
+        assert next(stream) == 1
+        with pytest.raises(StopIteration):
+            next(stream)
+        with pytest.raises(StopIteration):
+            next(stream)
+
+    def test_loads_array_stream_empty(self):
+        """
+        loads_array_stream() of an empty array
+        """
+        assert list(orjson.loads_array_stream("[]")) == []
+        assert list(orjson.loads_array_stream(" [ ]\n")) == []
+
+    def test_loads_array_stream_large(self):
+        """
+        loads_array_stream() of a large array
+        """
+        doc = orjson.dumps([{"id": idx, "name": str(idx)} for idx in range(100_000)])
+        count = 0
+        for idx, element in enumerate(orjson.loads_array_stream(doc)):
+            assert element == {"id": idx, "name": str(idx)}
+            count += 1
+        assert count == 100_000
+
+    def test_loads_array_stream_lazy(self):
+        """
+        loads_array_stream() deserializes only the elements consumed
+        """
+        seen = []
+
+        def hook(obj):
+            seen.append(obj["id"])
+            return obj
+
+        doc = orjson.dumps([{"id": idx} for idx in range(1000)])
+        stream = orjson.loads_array_stream(doc, object_hook=hook)
+        assert list(itertools.islice(stream, 2)) == [{"id": 0}, {"id": 1}]
+        assert seen == [0, 1]
+
+    def test_loads_array_stream_lazy_invalid(self):
+        """
+        loads_array_stream() does not validate elements after those consumed
+        """
+        stream = orjson.loads_array_stream("[1, 2, oops]")
+        assert next(stream) == 1
+        assert next(stream) == 2
+        with pytest.raises(orjson.JSONDecodeError):
+            next(stream)
+
+    def test_loads_array_stream_invalid_element(self):
+        """
+        loads_array_stream() raises with the position in the document
+        """
+        stream = orjson.loads_array_stream('[1, {"a": }]')
+        assert next(stream) == 1
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            next(stream)
+        assert exc_info.value.pos == 10
+        assert exc_info.value.doc == '[1, {"a": }]'
+        with pytest.raises(StopIteration):
+            next(stream)
+
+    @pytest.mark.parametrize(
+        "doc,pos",
+        [
+            ("{}", 0),
+            ("1", 0),
+            ("[", 1),
+            ("[1,", 3),
+            ("[1 2]", 3),
+            ("[1,]", 2),
+            ("[1,,2]", 3),
+            ("[1] x", 4),
+            ("[1", 2),
+        ],
+    )
+    def test_loads_array_stream_invalid(self, doc, pos):
+        """
+        loads_array_stream() raises JSONDecodeError on an invalid array
+        """
+        with pytest.raises(orjson.JSONDecodeError) as exc_info:
+            list(orjson.loads_array_stream(doc))
+        assert exc_info.value.pos == pos
+
+    def test_loads_array_stream_invalid_utf8(self):
+        """
+        loads_array_stream() raises on invalid UTF-8 when called
+        """
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads_array_stream(b"[1, \xff]")
+
+    def test_loads_array_stream_bytearray(self):
+        """
+        loads_array_stream() copies a bytearray
+        """
+        doc = bytearray(b"[1, 2]")
+        stream = orjson.loads_array_stream(doc)
+        doc[4] = ord("3")
+        assert list(stream) == [1, 2]
+
+    def test_loads_array_stream_memoryview(self):
+        """
+        loads_array_stream() of a memoryview
+        """
+        assert list(orjson.loads_array_stream(memoryview(b"[1, 2]"))) == [1, 2]
+
+    def test_loads_array_stream_reentrant(self):
+        """
+        next() of the stream from its hook raises ValueError
+        """
+        errors = []
+
+        def hook(obj):
+            try:
+                next(stream)
+            except ValueError as exc:
+                errors.append(exc)
+            return obj
+
+        stream = orjson.loads_array_stream('[{"a": 1}, 2, {"b": 3}]', object_hook=hook)
+        assert list(stream) == [{"a": 1}, 2, {"b": 3}]
+        assert len(errors) == 2
+        assert "already executing" in str(errors[0])
+
+    def test_loads_array_stream_reentrant_raises(self):
+        """
+        the ValueError of next() from a hook propagates and ends iteration
+        """
+
+        def hook(obj):
+            return next(stream)
+
+        stream = orjson.loads_array_stream('[{"a": 1}, {"b": 2}]', object_hook=hook)
+        with pytest.raises(ValueError, match="already executing"):
+            next(stream)
+        with pytest.raises(StopIteration):
+            next(stream)
+
+    def test_loads_array_stream_option(self):
+        """
+        loads_array_stream() applies option to each element
+        """
+        doc = '[{"a": 1, "a": 2}]'
+        stream = orjson.loads_array_stream(doc, option=orjson.OPT_FORBID_DUPLICATE_KEYS)
+        with pytest.raises(orjson.JSONDecodeError):
+            next(stream)
+
+    def test_loads_array_stream_pairs_hook(self):
+        """
+        loads_array_stream() object_pairs_hook
+        """
+        assert list(
+            orjson.loads_array_stream('[{"a": 1}]', object_pairs_hook=list)
+        ) == [[("a", 1)]]
+
+    def test_loads_array_stream_relaxed(self):
+        """
+        loads_array_stream() does not support OPT_PARSE_RELAXED
+        """
+        with pytest.raises(ValueError):
+            orjson.loads_array_stream("[1]", option=orjson.OPT_PARSE_RELAXED)
+
+    def test_loads_array_stream_type(self):
+        """
+        loads_array_stream() invalid type
+        """
+        with pytest.raises(orjson.JSONDecodeError):
+            orjson.loads_array_stream([1])
+        with pytest.raises(TypeError):
+            orjson.ArrayStream()
+
+    def test_loads_array_stream_signature(self):
+        """
+        loads_array_stream() valid __text_signature__
+        """
+        assert str(inspect.signature(orjson.loads_array_stream)) == (
+            "(obj, /, option=None, *, object_hook=None, object_pairs_hook=None)"
+        )
+
+
+class TestLoadFile:
+    def test_load_file(self):
+        contents = b'{"a":[1,2.5,"b",null,true],"c":{"d":"\xc3\xa9"}}'
+        path = write_temp(contents)
+        try:
+            assert orjson.load_file(path) == orjson.loads(contents)
+        finally:
+            os.unlink(path)
+
+    def test_load_file_pathlike(self):
+        path = write_temp(b"[1,2,3]")
+        try:
+            assert orjson.load_file(pathlib.Path(path)) == [1, 2, 3]
+        finally:
+            os.unlink(path)
+
+    def test_load_file_bytes_path(self):
+        path = write_temp(b"[1,2,3]")
+        try:
+            assert orjson.load_file(os.fsencode(path)) == [1, 2, 3]
+        finally:
+            os.unlink(path)
+
+    @needs_data
+    def test_load_file_fixture(self):
+        contents = read_fixture_bytes("twitter.json.xz")
+        path = write_temp(contents)
+        try:
+            assert orjson.load_file(path) == orjson.loads(contents)
+        finally:
+            os.unlink(path)
+
+    def test_load_file_option(self):
+        path = write_temp(b'{"a":1,"a":2}')
+        try:
+            assert orjson.load_file(path) == {"a": 2}
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.load_file(path, orjson.OPT_FORBID_DUPLICATE_KEYS)
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.load_file(path, option=orjson.OPT_FORBID_DUPLICATE_KEYS)
+        finally:
+            os.unlink(path)
+
+    def test_load_file_object_hook(self):
+        path = write_temp(b'{"a":{"b":1}}')
+        try:
+            assert orjson.load_file(path, object_hook=len) == 1
+            assert orjson.load_file(path, object_pairs_hook=list) == [
+                ("a", [("b", 1)]),
+            ]
+        finally:
+            os.unlink(path)
+
+    def test_load_file_not_found(self):
+        with tempfile.TemporaryDirectory() as tmpdir:
+            path = os.path.join(tmpdir, "missing.json")
+            with pytest.raises(FileNotFoundError) as exc_info:
+                orjson.load_file(path)
+            assert exc_info.value.filename == path
+            assert exc_info.value.errno is not None
+
+    def test_load_file_directory(self):
+        with tempfile.TemporaryDirectory() as tmpdir:
+            with pytest.raises(OSError):
+                orjson.load_file(tmpdir)
+
+    @pytest.mark.skipif(
+        sys.platform == "win32" or os.geteuid() == 0,
+        reason="permissions do not apply",
+    )
+    def test_load_file_permission(self):
+        path = write_temp(b"[]")
+        try:
+            os.chmod(path, 0)
+            with pytest.raises(PermissionError):
+                orjson.load_file(path)
+        finally:
+            os.unlink(path)
+
+    def test_load_file_invalid(self):
+        path = write_temp(b'{"a":}')
+        try:
+            with pytest.raises(orjson.JSONDecodeError) as exc_info:
+                orjson.load_file(path)
+            assert exc_info.value.doc == '{"a":}'
+            assert exc_info.value.pos == 5
+        finally:
+            os.unlink(path)
+
+    def test_load_file_invalid_utf8(self):
+        path = write_temp(b'["a\xff"]')
+        try:
+            with pytest.raises(orjson.JSONDecodeError) as exc_info:
+                orjson.load_file(path)
+            assert exc_info.value.pos == 3
+        finally:
+            os.unlink(path)
+
+    def test_load_file_empty(self):
+        path = write_temp(b"")
+        try:
+            with pytest.raises(orjson.JSONDecodeError):
+                orjson.load_file(path)
+        finally:
+            os.unlink(path)
+
+    def test_load_file_type(self):
+        with pytest.raises(TypeError):
+            orjson.load_file(1)  # type: ignore
+
+    def test_load_file_args(self):
+        with pytest.raises(TypeError) as exc_info:
+            orjson.load_file()  # type: ignore
+        assert "'path'" in str(exc_info.value)
+        with pytest.raises(TypeError):
+            orjson.load_file("a", None, None)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.load_file("a", foo=1)  # type: ignore
+
+
+class TestWarmKeys:
+    @needs_key_cache
+    def test_warm_keys(self):
+        keys = [new_str("warm_id"), new_str("warm_name"), new_str("warm_é\U0001f600")]
+        assert orjson.warm_keys(keys) is None
+        obj = orjson.loads(
+            '[{"warm_id":1,"warm_name":"a","warm_é\U0001f600":null},{"warm_id":2}]'
+        )
+        for each in obj:
+            for key in each:
+                assert any(key is warmed for warmed in keys)
+        assert list(obj[0])[0] is keys[0]
+        assert list(obj[1])[0] is keys[0]
+
+    @needs_key_cache
+    def test_warm_keys_replaces(self):
+        """
+        a str given replaces the str already cached for the key
+        """
+        cached = list(orjson.loads('{"warm_replace":1}'))[0]
+        assert list(orjson.loads('{"warm_replace":1}'))[0] is cached
+        key = new_str("warm_replace")
+        orjson.warm_keys([key])
+        assert list(orjson.loads('{"warm_replace":1}'))[0] is key
+
+    @needs_key_cache
+    def test_warm_keys_hash(self):
+        key = new_str("warm_hash")
+        orjson.warm_keys([key])
+        obj = orjson.loads('{"warm_hash":1}')
+        assert obj["warm_hash"] == 1
+        assert hash(list(obj)[0]) == hash("warm_hash")
+
+    @needs_key_cache
+    def test_warm_keys_too_long(self):
+        """
+        keys longer than 64 bytes are not cached
+        """
+        key = new_str("a" * 65)
+        orjson.warm_keys([key])
+        loaded = list(orjson.loads(f'{{"{key}":1}}'))[0]
+        assert loaded == key
+        assert loaded is not key
+        key = new_str("b" * 64)
+        orjson.warm_keys([key])
+        assert list(orjson.loads(f'{{"{key}":1}}'))[0] is key
+
+    def test_warm_keys_values(self):
+        """
+        warmed keys are also used by OPT_INTERN_VALUES
+        """
+        key = new_str("warm_value")
+        orjson.warm_keys([key])
+        obj = orjson.loads('["warm_value"]', option=orjson.OPT_INTERN_VALUES)
+        assert obj == ["warm_value"]
+        if KEY_CACHE:
+            assert obj[0] is key
+
+    def test_warm_keys_empty(self):
+        assert orjson.warm_keys([]) is None
+        orjson.warm_keys([""])
+        assert orjson.loads('{"":1}') == {"": 1}
+
+    def test_warm_keys_reset_state(self):
+        key = new_str("warm_reset")
+        orjson.warm_keys([key])
+        orjson.reset_state()
+        loaded = list(orjson.loads('{"warm_reset":1}'))[0]
+        assert loaded == key
+        assert loaded is not key
+
+    def test_warm_keys_type(self):
+        for val in (("a",), {"a"}, "a", None, [1], ["a", b"b"]):
+            with pytest.raises(TypeError):
+                orjson.warm_keys(val)  # type: ignore
+
+    def test_warm_keys_str_subclass(self):
+        class Key(str):
+            pass
+
+        with pytest.raises(TypeError):
+            orjson.warm_keys([Key("a")])
+
+    def test_warm_keys_surrogate(self):
+        with pytest.raises(TypeError):
+            orjson.warm_keys(["\ud800"])
+
+    def test_warm_keys_args(self):
+        with pytest.raises(TypeError):
+            orjson.warm_keys()  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.warm_keys([], [])  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.warm_keys(keys=[])  # type: ignore
+        assert str(inspect.signature(orjson.warm_keys)) == "(keys, /)"
+
+    def test_warm_keys_subinterpreter_isolated(self):
+        """
+        keys are warmed only in the interpreter that warms them
+        """
+        key = new_str("warm_isolated")
+        orjson.warm_keys([key])
+        run_in_subinterpreter(
+            """
+import orjson
+key = "".join(list("warm_isolated"))
+orjson.warm_keys([key])
+assert list(orjson.loads('{"warm_isolated":1}'))[0] is key
+"""
+        )
+        if KEY_CACHE:
+            assert list(orjson.loads('{"warm_isolated":1}'))[0] is key
+
+
+class TestKeyCacheStats:
+    def test_key_cache_stats(self):
+        stats = orjson.key_cache_stats()
+        assert set(stats) == {"hits", "misses", "bypasses"}
+        for val in stats.values():
+            assert isinstance(val, int)
+            assert val >= 0
+
+    @needs_key_cache
+    def test_key_cache_stats_miss_then_hit(self):
+        key = unique_key()
+        reset()
+        orjson.loads(f'{{"{key}":1}}')
+        assert orjson.key_cache_stats() == {"hits": 0, "misses": 1, "bypasses": 0}
+        orjson.loads(f'{{"{key}":1}}')
+        assert orjson.key_cache_stats() == {"hits": 1, "misses": 1, "bypasses": 0}
+
+    @needs_key_cache
+    def test_key_cache_stats_array_of_objects(self):
+        """
+        the first of repeated keys misses and the rest hit
+        """
+        key = unique_key()
+        reset()
+        orjson.loads("[" + ",".join(f'{{"{key}":{i}}}' for i in range(10)) + "]")
+        assert orjson.key_cache_stats() == {"hits": 9, "misses": 1, "bypasses": 0}
+
+    @needs_key_cache
+    def test_key_cache_stats_bypass(self):
+        """
+        keys longer than 64 bytes are not cached
+        """
+        reset()
+        orjson.loads(f'{{"{"a" * 64}":1}}')
+        orjson.loads(f'{{"{"b" * 65}":1,"{"c" * 100}":2}}')
+        stats = orjson.key_cache_stats()
+        assert stats["bypasses"] == 2
+        assert stats["hits"] + stats["misses"] == 1
+
+    @needs_key_cache
+    def test_key_cache_stats_warm_keys(self):
+        key = unique_key()
+        orjson.warm_keys([key])
+        reset()
+        orjson.loads(f'{{"{key}":1}}')
+        assert orjson.key_cache_stats() == {"hits": 1, "misses": 0, "bypasses": 0}
+
+    @needs_key_cache
+    def test_key_cache_stats_reset(self):
+        """
+        reset=True returns the counts before resetting them
+        """
+        orjson.loads(f'{{"{unique_key()}":1}}')
+        stats = orjson.key_cache_stats(reset=True)
+        assert stats["misses"] >= 1
+        assert orjson.key_cache_stats() == {"hits": 0, "misses": 0, "bypasses": 0}
+        assert orjson.key_cache_stats(reset=False) == {
+            "hits": 0,
+            "misses": 0,
+            "bypasses": 0,
+        }
+
+    def test_key_cache_stats_values_not_counted(self):
+        """
+        strs that are values are not looked up in the key cache
+        """
+        reset()
+        orjson.loads(f'["{unique_key()}"]')
+        assert orjson.key_cache_stats() == {"hits": 0, "misses": 0, "bypasses": 0}
+
+    @needs_key_cache
+    def test_key_cache_stats_intern_values(self):
+        """
+        values interned with OPT_INTERN_VALUES share the key cache
+        """
+        val = unique_key()
+        reset()
+        orjson.loads(f'["{val}","{val}"]', option=orjson.OPT_INTERN_VALUES)
+        assert orjson.key_cache_stats() == {"hits": 1, "misses": 1, "bypasses": 0}
+
+    @needs_key_cache
+    def test_key_cache_stats_reset_state(self):
+        orjson.loads(f'{{"{unique_key()}":1}}')
+        orjson.reset_state()
+        assert orjson.key_cache_stats() == {"hits": 0, "misses": 0, "bypasses": 0}
+
+    def test_key_cache_stats_invalid(self):
+        with pytest.raises(TypeError):
+            orjson.key_cache_stats(True)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.key_cache_stats(reset=1)  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.key_cache_stats(clear=True)  # type: ignore
+
+    @needs_key_cache
+    def test_key_cache_stats_subinterpreter(self):
+        """
+        each interpreter counts its own lookups
+        """
+        reset()
+        run_in_subinterpreter(
+            """
+import orjson
+orjson.key_cache_stats(reset=True)
+orjson.loads('{"a":1,"b":2}')
+stats = orjson.key_cache_stats()
+assert stats["hits"] + stats["misses"] == 2, stats
+"""
+        )
+        assert orjson.key_cache_stats() == {"hits": 0, "misses": 0, "bypasses": 0}
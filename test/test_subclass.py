@@ -2,7 +2,9 @@
 # Copyright ijl (2018-2022)
 
 import collections
+import datetime
 import json
+from typing import NamedTuple, Optional
 
 import pytest
 
@@ -29,10 +31,28 @@ class SubFloat(float):
     pass
 
 
+class Record(NamedTuple):
+    id: int
+    name: Optional[str] = None
+    parent: Optional[int] = 0
+
+
 class SubTuple(tuple):
     pass
 
 
+class SubDatetime(datetime.datetime):
+    pass
+
+
+class SubDate(datetime.date):
+    pass
+
+
+class SubTime(datetime.time):
+    pass
+
+
 class TestSubclass:
     def test_subclass_str(self):
         assert orjson.dumps(SubStr("zxc")) == b'"zxc"'
@@ -71,11 +91,60 @@ class TestSubclass:
             orjson.dumps(SubTuple((1, 2)))
         assert json.dumps(SubTuple((1, 2))) == "[1, 2]"
 
+    def test_subclass_datetime(self):
+        assert (
+            orjson.dumps(
+                SubDatetime(2018, 6, 1, 2, 3, 4, 123, tzinfo=datetime.timezone.utc)
+            )
+            == b'"2018-06-01T02:03:04.000123+00:00"'
+        )
+
+    def test_subclass_date(self):
+        assert orjson.dumps(SubDate(2018, 6, 1)) == b'"2018-06-01"'
+
+    def test_subclass_time(self):
+        assert orjson.dumps(SubTime(2, 3, 4)) == b'"02:03:04"'
+
+    def test_subclass_datetime_dict_key(self):
+        assert (
+            orjson.dumps(
+                {SubDatetime(2018, 6, 1, 2, 3, 4): 1},
+                option=orjson.OPT_NON_STR_KEYS,
+            )
+            == b'{"2018-06-01T02:03:04":1}'
+        )
+
+    def test_subclass_datetime_passthrough_datetime(self):
+        assert (
+            orjson.dumps(
+                SubDatetime(2018, 6, 1),
+                option=orjson.OPT_PASSTHROUGH_DATETIME,
+                default=lambda obj: type(obj).__name__,
+            )
+            == b'"SubDatetime"'
+        )
+
     def test_namedtuple(self):
         Point = collections.namedtuple("Point", ["x", "y"])
         with pytest.raises(orjson.JSONEncodeError):
             orjson.dumps(Point(1, 2))
 
+    def test_namedtuple_typing_defaults(self):
+        """
+        typing.NamedTuple is not serialized natively and default is given the
+        instance, whose values are those set or defaulted, in _fields order
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(Record(1))
+        for obj, expected in (
+            (Record(1), b'{"id":1,"name":null,"parent":0}'),
+            (Record(1, "a"), b'{"id":1,"name":"a","parent":0}'),
+            (Record(1, parent=None), b'{"id":1,"name":null,"parent":null}'),
+            (Record(1, "a", 2), b'{"id":1,"name":"a","parent":2}'),
+        ):
+            assert orjson.dumps(obj, default=Record._asdict) == expected
+            assert orjson.dumps(obj, tuple_fields=Record._fields) == expected
+
     def test_subclass_circular_dict(self):
         obj = SubDict({})
         obj["obj"] = obj
@@ -111,3 +180,12 @@ class TestSubclassPassthrough:
     def test_subclass_list(self):
         with pytest.raises(orjson.JSONEncodeError):
             orjson.dumps(SubList(["a", "b"]), option=orjson.OPT_PASSTHROUGH_SUBCLASS)
+
+    def test_subclass_datetime(self):
+        assert (
+            orjson.dumps(
+                [SubDatetime(2018, 6, 1, 2, 3, 4), SubDate(2018, 6, 1), SubTime(2, 3)],
+                option=orjson.OPT_PASSTHROUGH_SUBCLASS,
+            )
+            == b'["2018-06-01T02:03:04","2018-06-01","02:03:00"]'
+        )
@@ -1,14 +1,133 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2018-2025)
 
+import array
+import dataclasses
+import enum
+import fractions
+import gc
 import io
+import ipaddress
+import itertools
+import os
+import pathlib
 import sys
+import warnings
+import weakref
 
 import pytest
 
 import orjson
 
-from .util import SUPPORTS_MEMORYVIEW
+from .util import (
+    SUPPORTS_GETREFCOUNT,
+    SUPPORTS_MEMORYVIEW,
+    numpy,
+    run_in_subinterpreter,
+)
+
+INT_TYPECODES = "bBhHiIlLqQ"
+
+
+def unicode_array(typecode, val):
+    # typecode "u" is deprecated as of Python 3.13
+    with warnings.catch_warnings():
+        warnings.simplefilter("ignore", DeprecationWarning)
+        return array.array(typecode, val)
+
+
+BIG_NUMBER = orjson.OPT_BIG_NUMBER_AS_STRING
+
+MAX_SAFE = 2**53 - 1
+
+
+class IntSubclass(int):
+    def __str__(self):
+        return "subclass"
+
+
+class Big(enum.IntEnum):
+    A = 2**60
+
+
+@dataclasses.dataclass
+class Record:
+    id: int
+
+
+FRACTION = orjson.OPT_SERIALIZE_FRACTION
+FRACTION_FLOAT = orjson.OPT_SERIALIZE_FRACTION | orjson.OPT_FRACTION_AS_FLOAT
+
+
+AS_LIST = orjson.OPT_SERIALIZE_RANGE | orjson.OPT_RANGE_AS_LIST
+
+
+class Referent(dict):
+    pass
+
+
+class Callable:
+    def __call__(self):
+        pass
+
+
+ITERABLES = orjson.OPT_SERIALIZE_ITERABLES
+
+
+@dataclasses.dataclass
+class Point:
+    x: int
+    y: int
+
+
+class Countdown:
+    def __init__(self, start):
+        self.current = start
+
+    def __iter__(self):
+        return self
+
+    def __next__(self):
+        if self.current == 0:
+            raise StopIteration
+        self.current -= 1
+        return self.current + 1
+
+
+class CustomPath(os.PathLike):
+    def __init__(self, path):
+        self.path = path
+
+    def __fspath__(self):
+        return self.path
+
+
+class DuckPath:
+    def __fspath__(self):
+        return "/duck"
+
+
+class StrPath(str):
+    pass
+
+
+NESTED = orjson.OPT_MEMORYVIEW_AS_NESTED
+
+
+def _view(typecode, values, shape):
+    return memoryview(array.array(typecode, values)).cast("B").cast(typecode, shape)
+
+
+CACHE_TUPLES = orjson.OPT_CACHE_TUPLES
+
+needs_cache = pytest.mark.skipif(
+    not orjson.build_info()["key_cache"],
+    reason="tuples are not cached on the free-threaded build",
+)
+
+
+class TupleSubclass(tuple):
+    pass
 
 
 class TestType:
@@ -517,6 +636,61 @@ class TestType:
         assert orjson.loads("1.234567890E+34") == 1.23456789e34
         assert orjson.loads("23456789012E66") == 2.3456789012e76
 
+    def test_float_shortest(self):
+        """
+        float is serialized as the shortest representation that round-trips
+        """
+        for val, expected in (
+            (0.1, b"0.1"),
+            (0.2, b"0.2"),
+            (0.3, b"0.3"),
+            (0.1 + 0.2, b"0.30000000000000004"),
+            (1 / 3, b"0.3333333333333333"),
+            (2 / 3, b"0.6666666666666666"),
+            (0.015, b"0.015"),
+            (1.005, b"1.005"),
+            (1.0, b"1.0"),
+            (-0.0, b"-0.0"),
+            (100.0, b"100.0"),
+            (1e15, b"1000000000000000.0"),
+            (1e16, b"1e16"),
+            (1e20, b"1e20"),
+            (1e22, b"1e22"),
+            (1e23, b"1e23"),
+            (1e-6, b"1e-6"),
+            (1e-7, b"1e-7"),
+            (9007199254740993.0, b"9007199254740992.0"),
+            (123456789012345680.0, b"1.2345678901234568e17"),
+            (6.02214076e23, b"6.02214076e23"),
+            (9.109e-31, b"9.109e-31"),
+            (5e-324, b"5e-324"),
+            (2.2250738585072014e-308, b"2.2250738585072014e-308"),
+            (2.225073858507201e-308, b"2.225073858507201e-308"),
+            (1.7976931348623157e308, b"1.7976931348623157e308"),
+        ):
+            assert orjson.dumps(val) == expected
+            assert orjson.loads(expected) == val
+
+    def test_float_shortest_roundtrip(self):
+        """
+        float round-trips with no more significant digits than repr()
+        """
+        import random
+        import struct
+
+        rng = random.Random(0)
+        for _ in range(10000):
+            val = struct.unpack("<d", rng.getrandbits(64).to_bytes(8, "little"))[0]
+            if val != val or val in (float("inf"), float("-inf")):
+                continue
+            output = orjson.dumps(val)
+            assert orjson.loads(output) == val
+            digits = output.split(b"e")[0].lstrip(b"-").replace(b".", b"").strip(b"0")
+            expected = (
+                repr(val).split("e")[0].lstrip("-").replace(".", "").strip("0")
+            )
+            assert len(digits) <= len(expected)
+
     def test_float_notation(self):
         """
         float notation
@@ -550,3 +724,1577 @@ class TestType:
         """
         with pytest.raises(orjson.JSONEncodeError):
             orjson.dumps(object())
+
+
+class TestArray:
+    def test_array_int(self):
+        """
+        array.array("i")
+        """
+        val = array.array("i", [0, 1, -1, 2147483647, -2147483648])
+        assert orjson.dumps(val) == b"[0,1,-1,2147483647,-2147483648]"
+
+    def test_array_double(self):
+        """
+        array.array("d")
+        """
+        val = array.array("d", [0.0, 1.5, -2.25, 1e300, 5e-324])
+        assert orjson.dumps(val) == b"[0.0,1.5,-2.25,1e300,5e-324]"
+        assert orjson.loads(orjson.dumps(val)) == val.tolist()
+
+    def test_array_float(self):
+        """
+        array.array("f") is serialized as 32-bit floats
+        """
+        val = array.array("f", [0.1, 1.5, -3.0])
+        assert orjson.dumps(val) == b"[0.1,1.5,-3.0]"
+
+    def test_array_nonfinite(self):
+        """
+        NaN and infinity are null
+        """
+        for typecode in "fd":
+            val = array.array(typecode, [float("nan"), float("inf"), float("-inf")])
+            assert orjson.dumps(val) == b"[null,null,null]"
+
+    def test_array_typecodes(self):
+        """
+        every numeric typecode, at its limits
+        """
+        for typecode in INT_TYPECODES:
+            bits = array.array(typecode).itemsize * 8
+            if typecode.isupper():
+                limits = [0, 2**bits - 1]
+            else:
+                limits = [-(2 ** (bits - 1)), 2 ** (bits - 1) - 1]
+            val = array.array(typecode, [*limits, 1])
+            assert orjson.dumps(val) == orjson.dumps(val.tolist())
+        for typecode in "fd":
+            val = array.array(typecode, [1.0, -0.5])
+            assert orjson.dumps(val) == b"[1.0,-0.5]"
+
+    def test_array_empty(self):
+        """
+        empty arrays
+        """
+        for typecode in INT_TYPECODES + "fd":
+            assert orjson.dumps(array.array(typecode)) == b"[]"
+        assert orjson.dumps(unicode_array("u", "")) == b'""'
+
+    def test_array_nested(self):
+        """
+        arrays in a list and as a dict value
+        """
+        val = {"a": [array.array("B", [1, 2]), array.array("d", [0.5])]}
+        assert orjson.dumps(val) == b'{"a":[[1,2],[0.5]]}'
+        assert orjson.dumps(val, option=orjson.OPT_INDENT_2) == orjson.dumps(
+            {"a": [[1, 2], [0.5]]}, option=orjson.OPT_INDENT_2
+        )
+
+    def test_array_unicode(self):
+        """
+        array.array("u") is serialized as a str
+        """
+        val = unicode_array("u", "aé東🐈")
+        assert orjson.dumps(val) == orjson.dumps("aé東🐈")
+        assert orjson.dumps(val, option=orjson.OPT_ENSURE_ASCII) == (
+            b'"a\\u00e9\\u6771\\ud83d\\udc08"'
+        )
+
+    @pytest.mark.skipif(sys.version_info < (3, 13), reason="typecode w is new in 3.13")
+    def test_array_unicode_ucs4(self):
+        """
+        array.array("w") is serialized as a str
+        """
+        assert orjson.dumps(array.array("w", "aé🐈")) == orjson.dumps("aé🐈")
+
+    def test_array_unicode_surrogate(self):
+        """
+        an array.array("u") with a lone surrogate raises
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(unicode_array("u", "\ud800"))
+
+    def test_array_subclass(self):
+        """
+        subclasses are passed to default
+        """
+
+        class ArraySubclass(array.array):
+            pass
+
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(ArraySubclass("i", [1]))
+        assert orjson.dumps(ArraySubclass("i", [1]), default=list) == b"[1]"
+
+    def test_array_resize(self):
+        """
+        the array can be resized after serializing
+        """
+        val = array.array("i", [1])
+        orjson.dumps(val)
+        val.append(2)
+        assert orjson.dumps(val) == b"[1,2]"
+
+
+class TestBigNumberAsString:
+    def test_big_number_as_string_disabled(self):
+        assert orjson.dumps(2**53) == b"9007199254740992"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(2**64)
+
+    def test_big_number_as_string_boundary(self):
+        assert orjson.dumps(MAX_SAFE, option=BIG_NUMBER) == b"9007199254740991"
+        assert orjson.dumps(MAX_SAFE + 1, option=BIG_NUMBER) == b'"9007199254740992"'
+        assert orjson.dumps(-MAX_SAFE, option=BIG_NUMBER) == b"-9007199254740991"
+        assert orjson.dumps(-MAX_SAFE - 1, option=BIG_NUMBER) == b'"-9007199254740992"'
+
+    def test_big_number_as_string_small(self):
+        assert orjson.dumps([0, 1, -1, 2**31, -(2**31) - 1], option=BIG_NUMBER) == (
+            b"[0,1,-1,2147483648,-2147483649]"
+        )
+        assert orjson.dumps([True, False], option=BIG_NUMBER) == b"[true,false]"
+
+    def test_big_number_as_string_64_bit(self):
+        assert (
+            orjson.dumps([2**63 - 1, 2**64 - 1, -(2**63)], option=BIG_NUMBER)
+            == b'["9223372036854775807","18446744073709551615","-9223372036854775808"]'
+        )
+
+    def test_big_number_as_string_beyond_64_bit(self):
+        assert orjson.dumps(2**64, option=BIG_NUMBER) == b'"18446744073709551616"'
+        assert (
+            orjson.dumps(-(2**63) - 1, option=BIG_NUMBER) == b'"-9223372036854775809"'
+        )
+        assert orjson.dumps(10**100, option=BIG_NUMBER) == b'"1' + b"0" * 100 + b'"'
+
+    def test_big_number_as_string_max_str_digits(self):
+        """
+        an int with more digits than sys.get_int_max_str_digits() raises
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(10**5000, option=BIG_NUMBER)
+
+    def test_big_number_as_string_roundtrip(self):
+        for val in (MAX_SAFE + 1, -MAX_SAFE - 1, 2**64 - 1, 2**100):
+            assert int(orjson.loads(orjson.dumps(val, option=BIG_NUMBER))) == val
+
+    def test_big_number_as_string_subclass(self):
+        """
+        int subclasses are serialized by value, irrespective of __str__
+        """
+        assert (
+            orjson.dumps(IntSubclass(MAX_SAFE), option=BIG_NUMBER)
+            == b"9007199254740991"
+        )
+        assert (
+            orjson.dumps(IntSubclass(2**53), option=BIG_NUMBER) == b'"9007199254740992"'
+        )
+        assert (
+            orjson.dumps(IntSubclass(2**64), option=BIG_NUMBER)
+            == b'"18446744073709551616"'
+        )
+
+    def test_big_number_as_string_enum(self):
+        assert orjson.dumps(Big.A, option=BIG_NUMBER) == b'"1152921504606846976"'
+
+    def test_big_number_as_string_nested(self):
+        obj = {"a": [2**53], "b": Record(2**53), "c": (1, 2**53)}
+        assert (
+            orjson.dumps(obj, option=BIG_NUMBER)
+            == b'{"a":["9007199254740992"],"b":{"id":"9007199254740992"},"c":[1,"9007199254740992"]}'
+        )
+
+    def test_big_number_as_string_non_str_keys(self):
+        """
+        int keys are serialized as they are without the option
+        """
+        assert (
+            orjson.dumps({2**53: 2**53}, option=BIG_NUMBER | orjson.OPT_NON_STR_KEYS)
+            == b'{"9007199254740992":"9007199254740992"}'
+        )
+
+    def test_big_number_as_string_strict_integer(self):
+        """
+        OPT_BIG_NUMBER_AS_STRING takes precedence over OPT_STRICT_INTEGER
+        """
+        opt = BIG_NUMBER | orjson.OPT_STRICT_INTEGER
+        assert orjson.dumps(2**53, option=opt) == b'"9007199254740992"'
+        assert orjson.dumps(MAX_SAFE, option=opt) == b"9007199254740991"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(2**53, option=orjson.OPT_STRICT_INTEGER)
+
+    def test_big_number_as_string_float(self):
+        """
+        floats are not affected
+        """
+        assert orjson.dumps(2.0**60, option=BIG_NUMBER) == b"1.152921504606847e18"
+
+    @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
+    def test_big_number_as_string_numpy_scalar(self):
+        opt = BIG_NUMBER | orjson.OPT_SERIALIZE_NUMPY
+        assert orjson.dumps(numpy.int64(MAX_SAFE), option=opt) == b"9007199254740991"
+        assert orjson.dumps(numpy.int64(2**53), option=opt) == b'"9007199254740992"'
+        assert orjson.dumps(numpy.int64(-(2**53)), option=opt) == b'"-9007199254740992"'
+        assert orjson.dumps(numpy.uint64(2**64 - 1), option=opt) == (
+            b'"18446744073709551615"'
+        )
+        assert orjson.dumps(numpy.int32(2**31 - 1), option=opt) == b"2147483647"
+        assert orjson.dumps(
+            numpy.uint64(2**64 - 1), option=orjson.OPT_SERIALIZE_NUMPY
+        ) == (b"18446744073709551615")
+
+    @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
+    def test_big_number_as_string_numpy_array(self):
+        opt = BIG_NUMBER | orjson.OPT_SERIALIZE_NUMPY
+        arr = numpy.array([MAX_SAFE, 2**53, -(2**53)], numpy.int64)
+        expected = b'[9007199254740991,"9007199254740992","-9007199254740992"]'
+        assert orjson.dumps(arr, option=opt) == expected
+        assert orjson.dumps(arr[::-1][::-1], option=opt) == expected
+        assert orjson.dumps(arr[::2], option=opt) == (
+            b'[9007199254740991,"-9007199254740992"]'
+        )
+        arr = numpy.array([[1, 2**64 - 1]], numpy.uint64)
+        assert orjson.dumps(arr, option=opt) == b'[[1,"18446744073709551615"]]'
+
+
+class TestFraction:
+    def test_fraction_disabled(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(fractions.Fraction(1, 3))
+        assert orjson.dumps(fractions.Fraction(1, 2), default=float) == b"0.5"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(fractions.Fraction(1, 3), option=orjson.OPT_FRACTION_AS_FLOAT)
+
+    def test_fraction(self):
+        assert orjson.dumps(fractions.Fraction(1, 3), option=FRACTION) == b"[1,3]"
+
+    def test_fraction_normalized(self):
+        assert orjson.dumps(fractions.Fraction(4, 2), option=FRACTION) == b"[2,1]"
+        assert orjson.dumps(fractions.Fraction(6, 4), option=FRACTION) == b"[3,2]"
+        assert orjson.dumps(fractions.Fraction(0, 5), option=FRACTION) == b"[0,1]"
+
+    def test_fraction_negative(self):
+        assert orjson.dumps(fractions.Fraction(-1, 3), option=FRACTION) == b"[-1,3]"
+        assert orjson.dumps(fractions.Fraction(1, -3), option=FRACTION) == b"[-1,3]"
+        assert orjson.dumps(fractions.Fraction(-4, -6), option=FRACTION) == b"[2,3]"
+
+    def test_fraction_from_str(self):
+        assert orjson.dumps(fractions.Fraction("0.125"), option=FRACTION) == b"[1,8]"
+
+    def test_fraction_as_float(self):
+        assert orjson.dumps(
+            fractions.Fraction(1, 3), option=FRACTION_FLOAT
+        ) == orjson.dumps(1 / 3)
+        assert orjson.dumps(fractions.Fraction(4, 2), option=FRACTION_FLOAT) == b"2.0"
+        assert (
+            orjson.dumps(fractions.Fraction(-1, 4), option=FRACTION_FLOAT) == b"-0.25"
+        )
+
+    def test_fraction_as_float_rounding(self):
+        """
+        the float is the nearest to the exact value, not the quotient of the
+        numerator and denominator as floats
+        """
+        val = fractions.Fraction(2**60 + 1, 2**60)
+        assert orjson.dumps(val, option=FRACTION_FLOAT) == orjson.dumps(float(val))
+
+    def test_fraction_as_float_overflow(self):
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(fractions.Fraction(10**400, 3), option=FRACTION_FLOAT)
+        assert isinstance(exc_info.value.__cause__, OverflowError)
+
+    def test_fraction_64_bit(self):
+        assert (
+            orjson.dumps(fractions.Fraction(2**64 - 1, 2**63 - 1), option=FRACTION)
+            == b"[18446744073709551615,9223372036854775807]"
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(fractions.Fraction(2**64 + 1, 3), option=FRACTION)
+
+    def test_fraction_strict_integer(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                fractions.Fraction(2**53 + 1, 2),
+                option=FRACTION | orjson.OPT_STRICT_INTEGER,
+            )
+
+    def test_fraction_containers(self):
+        obj = {"a": [fractions.Fraction(1, 2), (fractions.Fraction(-3, 4),)]}
+        assert orjson.dumps(obj, option=FRACTION) == b'{"a":[[1,2],[[-3,4]]]}'
+        assert orjson.dumps(obj, option=FRACTION_FLOAT) == b'{"a":[0.5,[-0.75]]}'
+
+    def test_fraction_subclass(self):
+        class MyFraction(fractions.Fraction):
+            pass
+
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(MyFraction(1, 2), option=FRACTION)
+
+    def test_fraction_dict_key(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                {fractions.Fraction(1, 2): 1},
+                option=FRACTION | orjson.OPT_NON_STR_KEYS,
+            )
+
+
+class TestIpAddress:
+    def test_ipv4_address(self):
+        """
+        ipaddress.IPv4Address
+        """
+        val = ipaddress.IPv4Address("192.168.0.1")
+        res = orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS)
+        assert res == b'"192.168.0.1"'
+        assert ipaddress.ip_address(orjson.loads(res)) == val
+
+    def test_ipv6_address(self):
+        """
+        ipaddress.IPv6Address in its compressed form
+        """
+        val = ipaddress.IPv6Address("2001:0db8:0000:0000:0000:0000:0000:0001")
+        res = orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS)
+        assert res == b'"2001:db8::1"'
+        assert ipaddress.ip_address(orjson.loads(res)) == val
+
+    def test_ipv6_address_ipv4_mapped(self):
+        """
+        IPv4-mapped ipaddress.IPv6Address
+        """
+        val = ipaddress.IPv6Address("::ffff:192.168.0.1")
+        res = orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS)
+        assert res == orjson.dumps(str(val))
+        parsed = ipaddress.ip_address(orjson.loads(res))
+        assert parsed == val
+        assert parsed.ipv4_mapped == ipaddress.IPv4Address("192.168.0.1")
+
+    def test_ipv6_address_scope_id(self):
+        """
+        ipaddress.IPv6Address with a scope ID
+        """
+        val = ipaddress.IPv6Address("fe80::1%eth0")
+        res = orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS)
+        assert res == b'"fe80::1%eth0"'
+        assert ipaddress.ip_address(orjson.loads(res)) == val
+
+    def test_ipv4_network(self):
+        """
+        ipaddress.IPv4Network
+        """
+        val = ipaddress.IPv4Network("10.0.0.0/8")
+        res = orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS)
+        assert res == b'"10.0.0.0/8"'
+        assert ipaddress.ip_network(orjson.loads(res)) == val
+
+    def test_ipv6_network(self):
+        """
+        ipaddress.IPv6Network
+        """
+        val = ipaddress.IPv6Network("2001:db8::/32")
+        res = orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS)
+        assert res == b'"2001:db8::/32"'
+        assert ipaddress.ip_network(orjson.loads(res)) == val
+
+    def test_nested(self):
+        """
+        ipaddress objects in a list and as dict values
+        """
+        obj = {
+            "a": [ipaddress.IPv4Address("127.0.0.1"), ipaddress.IPv6Address("::1")],
+            "b": ipaddress.IPv4Network("127.0.0.0/8"),
+        }
+        assert (
+            orjson.dumps(obj, option=orjson.OPT_SERIALIZE_IPADDRESS)
+            == b'{"a":["127.0.0.1","::1"],"b":"127.0.0.0/8"}'
+        )
+
+    def test_non_str_keys(self):
+        """
+        ipaddress objects as dict keys with OPT_NON_STR_KEYS
+        """
+        obj = {
+            ipaddress.IPv4Address("127.0.0.1"): 1,
+            ipaddress.IPv6Network("::/0"): 2,
+        }
+        assert (
+            orjson.dumps(
+                obj,
+                option=orjson.OPT_SERIALIZE_IPADDRESS | orjson.OPT_NON_STR_KEYS,
+            )
+            == b'{"127.0.0.1":1,"::/0":2}'
+        )
+
+    def test_disabled(self):
+        """
+        ipaddress objects are passed to default without OPT_SERIALIZE_IPADDRESS
+        """
+        val = ipaddress.IPv4Address("127.0.0.1")
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(val)
+        assert orjson.dumps(val, default=lambda _: "default") == b'"default"'
+
+    def test_interface(self):
+        """
+        ipaddress.IPv4Interface, a subclass of IPv4Address, is passed to default
+        """
+        val = ipaddress.IPv4Interface("192.168.0.1/24")
+        assert (
+            orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS, default=str)
+            == b'"192.168.0.1/24"'
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(val, option=orjson.OPT_SERIALIZE_IPADDRESS)
+
+    def test_subclass(self):
+        """
+        subclasses are passed to default
+        """
+
+        class Address(ipaddress.IPv4Address):
+            pass
+
+        assert (
+            orjson.dumps(
+                Address("127.0.0.1"),
+                option=orjson.OPT_SERIALIZE_IPADDRESS,
+                default=lambda _: "default",
+            )
+            == b'"default"'
+        )
+
+    def test_same_name(self):
+        """
+        an unrelated type of the same name is not serialized as an address
+        """
+
+        class IPv4Address:
+            pass
+
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(IPv4Address(), option=orjson.OPT_SERIALIZE_IPADDRESS)
+        assert (
+            orjson.dumps(
+                ipaddress.IPv4Address("127.0.0.1"),
+                option=orjson.OPT_SERIALIZE_IPADDRESS,
+            )
+            == b'"127.0.0.1"'
+        )
+
+
+class TestRange:
+    def test_range_triple(self):
+        """
+        OPT_SERIALIZE_RANGE serializes [start,stop,step]
+        """
+        assert orjson.dumps(range(3), option=orjson.OPT_SERIALIZE_RANGE) == b"[0,3,1]"
+        assert orjson.dumps(range(1, 10, 3), option=orjson.OPT_SERIALIZE_RANGE) == b"[1,10,3]"
+        assert orjson.dumps(range(5, -5, -2), option=orjson.OPT_SERIALIZE_RANGE) == (
+            b"[5,-5,-2]"
+        )
+
+    def test_range_triple_large(self):
+        """
+        OPT_SERIALIZE_RANGE does not materialize a large range
+        """
+        assert orjson.dumps(range(10**15), option=orjson.OPT_SERIALIZE_RANGE) == (
+            b"[0,1000000000000000,1]"
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(range(2**64), option=orjson.OPT_SERIALIZE_RANGE)
+
+    def test_range_empty(self):
+        """
+        an empty range
+        """
+        assert orjson.dumps(range(0), option=AS_LIST) == b"[]"
+        assert orjson.dumps(range(5, 5), option=AS_LIST) == b"[]"
+        assert orjson.dumps(range(0, 5, -1), option=AS_LIST) == b"[]"
+        assert orjson.dumps(range(0), option=orjson.OPT_SERIALIZE_RANGE) == b"[0,0,1]"
+
+    def test_range_step(self):
+        """
+        a range with a step greater than 1
+        """
+        val = range(1, 20, 4)
+        assert orjson.dumps(val, option=AS_LIST) == b"[1,5,9,13,17]"
+        assert orjson.loads(orjson.dumps(val, option=AS_LIST)) == list(val)
+
+    def test_range_negative_step(self):
+        """
+        a range with a negative step
+        """
+        val = range(10, -10, -3)
+        assert orjson.dumps(val, option=AS_LIST) == b"[10,7,4,1,-2,-5,-8]"
+        assert orjson.loads(orjson.dumps(val, option=AS_LIST)) == list(val)
+
+    def test_range_nested(self):
+        """
+        a range in a list, as a dict value, and with OPT_INDENT_2
+        """
+        obj = {"a": [range(2)], "b": range(1, 3)}
+        assert orjson.dumps(obj, option=AS_LIST) == b'{"a":[[0,1]],"b":[1,2]}'
+        assert orjson.dumps(obj, option=orjson.OPT_SERIALIZE_RANGE) == (
+            b'{"a":[[0,2,1]],"b":[1,3,1]}'
+        )
+        assert orjson.dumps(range(2), option=AS_LIST | orjson.OPT_INDENT_2) == (
+            b"[\n  0,\n  1\n]"
+        )
+
+    def test_range_strict_integer(self):
+        """
+        OPT_STRICT_INTEGER applies to elements
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(range(2**53, 2**53 + 1), option=AS_LIST | orjson.OPT_STRICT_INTEGER)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(range(2**53), option=orjson.OPT_SERIALIZE_RANGE | orjson.OPT_STRICT_INTEGER)
+
+    def test_range_max_output_bytes(self):
+        """
+        a materialized range longer than max_output_bytes allows raises before
+        serializing its elements
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(range(10**15), option=AS_LIST, max_output_bytes=1024)
+        assert str(exc_info.value) == "Output exceeds max_output_bytes of 1024"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(range(10), option=AS_LIST, max_output_bytes=20)
+        assert orjson.dumps(range(10), option=AS_LIST, max_output_bytes=21) == (
+            b"[0,1,2,3,4,5,6,7,8,9]"
+        )
+
+    def test_range_max_output_bytes_nested_call(self):
+        """
+        the max_output_bytes of an outer call does not apply to a call made by
+        default
+        """
+
+        def default(obj):
+            return len(orjson.dumps(range(1000), option=AS_LIST))
+
+        assert orjson.dumps(object(), default=default, max_output_bytes=100) == b"3891"
+
+    def test_range_too_large(self):
+        """
+        a range longer than sys.maxsize cannot be materialized
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(range(2**64), option=AS_LIST)
+        assert str(exc_info.value) == "range is too large to serialize as a list"
+        assert exc_info.value.__cause__ is None
+
+    def test_range_disabled(self):
+        """
+        range is passed to default without OPT_SERIALIZE_RANGE
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(range(3))
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(range(3), option=orjson.OPT_RANGE_AS_LIST)
+        assert orjson.dumps(range(3), default=list) == b"[0,1,2]"
+
+    def test_range_dict_key(self):
+        """
+        range is not a valid dict key with OPT_NON_STR_KEYS
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({range(1): 1}, option=AS_LIST | orjson.OPT_NON_STR_KEYS)
+
+
+class TestWeakref:
+    def test_weakref_live(self):
+        """
+        weakref.ref and weakref.proxy serialize as their referent
+        """
+        value = Referent(a=[1, 2])
+        assert orjson.dumps(weakref.ref(value)) == b'{"a":[1,2]}'
+        assert orjson.dumps(weakref.proxy(value)) == b'{"a":[1,2]}'
+        assert orjson.dumps({"x": weakref.proxy(value)}) == b'{"x":{"a":[1,2]}}'
+        assert orjson.dumps([weakref.ref(value)]) == b'[{"a":[1,2]}]'
+
+    def test_weakref_callable_proxy(self):
+        """
+        a proxy of a callable serializes as its referent
+        """
+        value = Callable()
+        assert (
+            orjson.dumps(weakref.proxy(value), default=lambda obj: type(obj).__name__)
+            == b'"Callable"'
+        )
+
+    def test_weakref_dead(self):
+        """
+        a dead reference raises JSONEncodeError
+        """
+        value = Referent()
+        ref = weakref.ref(value)
+        proxy = weakref.proxy(value)
+        del value
+        gc.collect()
+        for val in (ref, proxy, [ref], {"a": proxy}):
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                orjson.dumps(val)
+            assert str(exc_info.value) == "weakly-referenced object no longer exists"
+
+    def test_weakref_dead_as_null(self):
+        """
+        OPT_WEAKREF_DEAD_AS_NULL serializes a dead reference as null
+        """
+        value = Referent()
+        ref = weakref.ref(value)
+        proxy = weakref.proxy(value)
+        live = Referent(b=1)
+        del value
+        gc.collect()
+        opt = orjson.OPT_WEAKREF_DEAD_AS_NULL
+        assert orjson.dumps(ref, option=opt) == b"null"
+        assert orjson.dumps(proxy, option=opt) == b"null"
+        assert (
+            orjson.dumps([ref, weakref.ref(live), {"a": proxy}], option=opt)
+            == b'[null,{"b":1},{"a":null}]'
+        )
+
+    def test_weakref_default(self):
+        """
+        a referent that is not natively serialized is passed to default
+        """
+
+        class Custom:
+            pass
+
+        value = Custom()
+        assert orjson.dumps(weakref.ref(value), default=lambda obj: type(obj).__name__) == (
+            b'"Custom"'
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(weakref.proxy(value))
+
+    def test_weakref_dropped_by_default(self):
+        """
+        the referent is held while serialized, even if default drops the last
+        other reference to it
+        """
+        holder = [Referent(a=object())]
+        ref = weakref.ref(holder[0])
+
+        def default(obj):
+            holder.clear()
+            return "x"
+
+        assert orjson.dumps(ref, default=default) == b'{"a":"x"}'
+        gc.collect()
+        assert ref() is None
+
+    def test_weakref_subclass(self):
+        """
+        subclasses of weakref.ref are passed to default
+        """
+
+        class Method:
+            def method(self):
+                pass
+
+        value = Method()
+        assert (
+            orjson.dumps(weakref.WeakMethod(value.method), default=lambda _: "default")
+            == b'"default"'
+        )
+
+    def test_weakref_key(self):
+        """
+        references are not dict keys with OPT_NON_STR_KEYS
+        """
+        value = Referent()
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({weakref.ref(value): 1}, option=orjson.OPT_NON_STR_KEYS)
+
+
+class TestIterables:
+    def test_iterables_disabled(self):
+        """
+        iterators are not serialized without OPT_SERIALIZE_ITERABLES
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(x for x in range(3))
+        assert orjson.dumps(iter([1]), default=list) == b"[1]"
+
+    def test_iterables_generator(self):
+        assert orjson.dumps((x * 2 for x in range(4)), option=ITERABLES) == b"[0,2,4,6]"
+
+    def test_iterables_map(self):
+        assert orjson.dumps(map(str, [1, 2, 3]), option=ITERABLES) == b'["1","2","3"]'
+
+    def test_iterables_empty(self):
+        assert orjson.dumps(iter(()), option=ITERABLES) == b"[]"
+        assert orjson.dumps((x for x in ()), option=ITERABLES) == b"[]"
+        assert orjson.dumps(iter(()), option=ITERABLES | orjson.OPT_INDENT_2) == b"[]"
+
+    def test_iterables_consumed(self):
+        gen = (x for x in range(3))
+        assert orjson.dumps(gen, option=ITERABLES) == b"[0,1,2]"
+        assert orjson.dumps(gen, option=ITERABLES) == b"[]"
+
+    def test_iterables_class(self):
+        assert orjson.dumps(Countdown(3), option=ITERABLES) == b"[3,2,1]"
+
+    def test_iterables_nested(self):
+        obj = {
+            "a": (Point(x, x) for x in range(2)),
+            "b": [zip("ab", itertools.count())],
+            "c": (iter([n]) for n in range(2)),
+        }
+        assert (
+            orjson.dumps(obj, option=ITERABLES)
+            == b'{"a":[{"x":0,"y":0},{"x":1,"y":1}],"b":[[["a",0],["b",1]]],"c":[[0],[1]]}'
+        )
+
+    def test_iterables_indent(self):
+        assert (
+            orjson.dumps(iter([1, 2]), option=ITERABLES | orjson.OPT_INDENT_2)
+            == b"[\n  1,\n  2\n]"
+        )
+
+    def test_iterables_containers(self):
+        """
+        OPT_SERIALIZE_ITERABLES does not change how containers are serialized
+        """
+        assert orjson.dumps([1, 2], option=ITERABLES) == b"[1,2]"
+        assert orjson.dumps({"a": 1}, option=ITERABLES) == b'{"a":1}'
+        assert orjson.dumps((1, 2), option=ITERABLES) == b"[1,2]"
+        assert orjson.dumps("ab", option=ITERABLES) == b'"ab"'
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({1, 2}, option=ITERABLES)
+
+    def test_iterables_default(self):
+        """
+        an element that is not supported is passed to default
+        """
+        obj = iter([object()])
+        assert orjson.dumps(obj, option=ITERABLES, default=lambda _: "x") == b'["x"]'
+
+    def test_iterables_exception(self):
+        def gen():
+            yield 1
+            raise ValueError("stop")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(gen(), option=ITERABLES)
+        assert isinstance(exc_info.value.__cause__, ValueError)
+
+    def test_iterables_infinite(self):
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(itertools.count(), option=ITERABLES, max_output_bytes=1024)
+        assert str(exc_info.value) == "Output exceeds max_output_bytes of 1024"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                {"a": itertools.repeat("a" * 100)},
+                option=ITERABLES,
+                max_output_bytes=1024,
+            )
+
+    def test_iterables_max_output_bytes(self):
+        assert (
+            orjson.dumps(iter([1, 2, 3]), option=ITERABLES, max_output_bytes=7)
+            == b"[1,2,3]"
+        )
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(iter([1, 2, 3]), option=ITERABLES, max_output_bytes=6)
+
+    def test_iterables_dict_key(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                {iter(()): 1},
+                option=ITERABLES | orjson.OPT_NON_STR_KEYS,
+            )
+
+    def test_iterables_recursion(self):
+        def nest(depth):
+            if depth:
+                yield nest(depth - 1)
+
+        assert orjson.dumps(nest(3), option=ITERABLES) == b"[[[[]]]]"
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(nest(300), option=ITERABLES)
+
+
+class TestFsPath:
+    def test_fspath_custom(self):
+        """
+        OPT_SERIALIZE_FSPATH serializes an os.PathLike as its path
+        """
+        assert (
+            orjson.dumps(CustomPath("/tmp/a.json"), option=orjson.OPT_SERIALIZE_FSPATH)
+            == b'"/tmp/a.json"'
+        )
+
+    def test_fspath_duck_typed(self):
+        """
+        OPT_SERIALIZE_FSPATH serializes any object with __fspath__
+        """
+        assert orjson.dumps(DuckPath(), option=orjson.OPT_SERIALIZE_FSPATH) == (
+            b'"/duck"'
+        )
+
+    def test_fspath_pathlib(self):
+        """
+        OPT_SERIALIZE_FSPATH serializes pathlib paths
+        """
+        obj = [pathlib.PurePosixPath("/a/b"), pathlib.PureWindowsPath("C:/a/b")]
+        assert (
+            orjson.dumps(obj, option=orjson.OPT_SERIALIZE_FSPATH)
+            == b'["/a/b","C:\\\\a\\\\b"]'
+        )
+
+    def test_fspath_nested(self):
+        """
+        OPT_SERIALIZE_FSPATH in a dict and list
+        """
+        obj = {"a": [CustomPath("é"), {"b": CustomPath("c")}]}
+        assert (
+            orjson.dumps(obj, option=orjson.OPT_SERIALIZE_FSPATH)
+            == '{"a":["é",{"b":"c"}]}'.encode()
+        )
+
+    def test_fspath_bytes(self):
+        """
+        OPT_SERIALIZE_FSPATH decodes a bytes path with the filesystem encoding
+        """
+        path = os.fsencode("/tmp/é")
+        assert orjson.dumps(CustomPath(path), option=orjson.OPT_SERIALIZE_FSPATH) == (
+            orjson.dumps(os.fsdecode(path))
+        )
+
+    def test_fspath_str_subclass(self):
+        """
+        OPT_SERIALIZE_FSPATH with __fspath__ returning a str subclass
+        """
+        assert (
+            orjson.dumps(CustomPath(StrPath("/a")), option=orjson.OPT_SERIALIZE_FSPATH)
+            == b'"/a"'
+        )
+
+    def test_fspath_not_enabled(self):
+        """
+        os.PathLike without OPT_SERIALIZE_FSPATH is passed to default
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(CustomPath("/a"))
+        assert orjson.dumps(CustomPath("/a"), default=lambda obj: "default") == (
+            b'"default"'
+        )
+
+    def test_fspath_invalid_return(self):
+        """
+        __fspath__ returning neither str nor bytes raises JSONEncodeError
+        """
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(CustomPath(1), option=orjson.OPT_SERIALIZE_FSPATH)
+        assert isinstance(exc_info.value.__cause__, TypeError)
+
+    def test_fspath_exception(self):
+        """
+        an exception raised by __fspath__ is the cause of JSONEncodeError
+        """
+
+        class Raises(os.PathLike):
+            def __fspath__(self):
+                raise ValueError("fspath")
+
+        with pytest.raises(orjson.JSONEncodeError) as exc_info:
+            orjson.dumps(Raises(), option=orjson.OPT_SERIALIZE_FSPATH)
+        assert isinstance(exc_info.value.__cause__, ValueError)
+
+    def test_fspath_json_dunder_first(self):
+        """
+        OPT_USE_JSON_DUNDER takes precedence over OPT_SERIALIZE_FSPATH
+        """
+
+        class Both(CustomPath):
+            def __json__(self):
+                return {"path": self.path}
+
+        assert (
+            orjson.dumps(
+                Both("/a"),
+                option=orjson.OPT_SERIALIZE_FSPATH | orjson.OPT_USE_JSON_DUNDER,
+            )
+            == b'{"path":"/a"}'
+        )
+
+    def test_fspath_native_types(self):
+        """
+        OPT_SERIALIZE_FSPATH does not affect natively serialized types
+        """
+
+        class DictPath(dict):
+            def __fspath__(self):
+                return "/dict"
+
+        obj = [DictPath(a=1), "b"]
+        assert orjson.dumps(obj, option=orjson.OPT_SERIALIZE_FSPATH) == b'[{"a":1},"b"]'
+
+    def test_fspath_non_str_key(self):
+        """
+        os.PathLike is not a valid dict key with OPT_NON_STR_KEYS
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                {CustomPath("/a"): 1},
+                option=orjson.OPT_SERIALIZE_FSPATH | orjson.OPT_NON_STR_KEYS,
+            )
+
+
+class TestFloatPrecision:
+    def test_float_precision(self):
+        assert orjson.dumps(0.1, float_precision=2) == b"0.10"
+        assert orjson.dumps(1.0, float_precision=2) == b"1.00"
+        assert orjson.dumps(-1.005, float_precision=2) == b"-1.00"
+        assert orjson.dumps(2.675, float_precision=2) == b"2.67"
+
+    def test_float_precision_large(self):
+        assert orjson.dumps(1e21, float_precision=2) == b"1000000000000000000000.00"
+        assert orjson.dumps(-123456789.987, float_precision=2) == b"-123456789.99"
+        assert orjson.loads(orjson.dumps(1.7976931348623157e308, float_precision=2)) == (
+            1.7976931348623157e308
+        )
+
+    def test_float_precision_small(self):
+        assert orjson.dumps(5e-324, float_precision=2) == b"0.00"
+        assert orjson.dumps(-0.001, float_precision=2) == b"-0.00"
+        assert orjson.dumps(1e-20, float_precision=20) == b"0.00000000000000000001"
+
+    def test_float_precision_zero(self):
+        assert orjson.dumps([0.4, 1.0, 2.5], float_precision=0) == b"[0,1,2]"
+
+    def test_float_precision_none(self):
+        assert orjson.dumps(0.1, float_precision=None) == b"0.1"
+        assert orjson.dumps(0.1) == b"0.1"
+
+    def test_float_precision_nonfinite(self):
+        assert (
+            orjson.dumps([float("nan"), float("inf"), float("-inf")], float_precision=2)
+            == b"[null,null,null]"
+        )
+
+    def test_float_precision_nested(self):
+        obj = {"a": [0.5, {"b": (1.25,)}], "c": 1, "d": "1.0"}
+        assert (
+            orjson.dumps(obj, float_precision=1)
+            == b'{"a":[0.5,{"b":[1.2]}],"c":1,"d":"1.0"}'
+        )
+        assert (
+            orjson.dumps([1.5], float_precision=3, option=orjson.OPT_INDENT_2)
+            == b"[\n  1.500\n]"
+        )
+
+    def test_float_precision_non_str_keys(self):
+        assert (
+            orjson.dumps(
+                {0.5: 0.5},
+                float_precision=2,
+                option=orjson.OPT_NON_STR_KEYS,
+            )
+            == b'{"0.5":0.50}'
+        )
+
+    def test_float_precision_default(self):
+        """
+        float_precision applies to what default returns, and a nested dumps()
+        does not inherit it
+        """
+
+        def default(obj):
+            return [0.5, orjson.dumps(0.5).decode()]
+
+        assert orjson.dumps(object(), default=default, float_precision=2) == (
+            b'[0.50,"0.5"]'
+        )
+
+    def test_float_precision_cache_tuples(self):
+        obj = (0.5,)
+        option = orjson.OPT_CACHE_TUPLES
+        assert orjson.dumps(obj, option=option) == b"[0.5]"
+        assert orjson.dumps(obj, option=option, float_precision=2) == b"[0.50]"
+        assert orjson.dumps(obj, option=option) == b"[0.5]"
+
+    def test_float_precision_functions(self):
+        assert orjson.dumps_str([0.5], float_precision=2) == "[0.50]"
+        assert bytes(orjson.dumps_view([0.5], float_precision=2)) == b"[0.50]"
+        assert orjson.dumps_many([0.5, 1.0], float_precision=1) == [b"0.5", b"1.0"]
+        fp = io.BytesIO()
+        orjson.dump([0.5], fp, float_precision=2)
+        assert fp.getvalue() == b"[0.50]"
+
+    def test_float_precision_invalid(self):
+        for val in (-1, 21, 1 << 64, 1.0, "2", True):
+            with pytest.raises(orjson.JSONEncodeError) as exc_info:
+                orjson.dumps(0.1, float_precision=val)
+            assert str(exc_info.value) == "float_precision must be an int from 0 to 20"
+
+    @pytest.mark.skipif(numpy is None, reason="numpy is not installed")
+    def test_float_precision_numpy(self):
+        assert (
+            orjson.dumps(
+                numpy.array([0.1, 1.0], numpy.float64),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+                float_precision=2,
+            )
+            == b"[0.10,1.00]"
+        )
+        assert (
+            orjson.dumps(
+                numpy.array([0.1, 1.0], numpy.float32),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+                float_precision=2,
+            )
+            == b"[0.10,1.00]"
+        )
+        assert (
+            orjson.dumps(
+                numpy.float64(0.1),
+                option=orjson.OPT_SERIALIZE_NUMPY,
+                float_precision=2,
+            )
+            == b"0.10"
+        )
+
+
+class TestMemoryviewNested:
+    def test_memoryview_nested_2d(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED 2-D memoryview of an array.array buffer
+        """
+        view = _view("i", range(6), (2, 3))
+        assert orjson.dumps(view, option=NESTED) == b"[[0,1,2],[3,4,5]]"
+        assert orjson.loads(orjson.dumps(view, option=NESTED)) == view.tolist()
+
+    def test_memoryview_nested_2d_transposed(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED 2-D memoryview of other shape
+        """
+        view = _view("i", range(6), (3, 2))
+        assert orjson.dumps(view, option=NESTED) == b"[[0,1],[2,3],[4,5]]"
+
+    def test_memoryview_nested_3d(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED 3-D memoryview of float
+        """
+        view = _view("d", [0.5, 1, 2, 3, 4, 5, 6, 7], (2, 2, 2))
+        assert (
+            orjson.dumps(view, option=NESTED)
+            == b"[[[0.5,1.0],[2.0,3.0]],[[4.0,5.0],[6.0,7.0]]]"
+        )
+
+    @pytest.mark.parametrize("typecode", ["b", "B", "h", "H", "i", "I", "q", "Q"])
+    def test_memoryview_nested_typecode(self, typecode):
+        """
+        OPT_MEMORYVIEW_AS_NESTED integer formats
+        """
+        view = _view(typecode, [1, 2, 3, 4], (2, 2))
+        assert orjson.dumps(view, option=NESTED) == b"[[1,2],[3,4]]"
+
+    def test_memoryview_nested_negative(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED signed and unsigned limits
+        """
+        assert (
+            orjson.dumps(_view("q", [-(2**63), 2**63 - 1], (1, 2)), option=NESTED)
+            == b"[[-9223372036854775808,9223372036854775807]]"
+        )
+        assert (
+            orjson.dumps(_view("Q", [0, 2**64 - 1], (2, 1)), option=NESTED)
+            == b"[[0],[18446744073709551615]]"
+        )
+
+    def test_memoryview_nested_1d(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED 1-D memoryview of bytes
+        """
+        assert orjson.dumps(memoryview(b"ab"), option=NESTED) == b"[97,98]"
+
+    def test_memoryview_nested_strided(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED follows strides of a non-contiguous view
+        """
+        view = _view("i", range(12), (3, 4))
+        assert (
+            orjson.dumps(memoryview(b"abcdef")[::2], option=NESTED) == b"[97,99,101]"
+        )
+        assert orjson.dumps(view[::2], option=NESTED) == b"[[0,1,2,3],[8,9,10,11]]"
+        assert orjson.dumps(view[::-1], option=NESTED) == orjson.dumps(
+            view[::-1].tolist()
+        )
+
+    def test_memoryview_nested_zero_dimensional(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED 0-D memoryview is its element
+        """
+        assert orjson.dumps(_view("q", [7], ()), option=NESTED) == b"7"
+
+    def test_memoryview_nested_empty(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED empty memoryview
+        """
+        assert orjson.dumps(memoryview(b""), option=NESTED) == b"[]"
+        assert orjson.dumps(_view("i", range(6), (2, 3))[0:0], option=NESTED) == b"[]"
+
+    def test_memoryview_nested_container(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED in a dict and list
+        """
+        view = _view("i", range(4), (2, 2))
+        assert orjson.dumps({"a": [view]}, option=NESTED) == b'{"a":[[[0,1],[2,3]]]}'
+
+    def test_memoryview_nested_unicode(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED memoryview of a Unicode typecode is a str
+        """
+        assert (
+            orjson.dumps(memoryview(array.array("u", "abc")), option=NESTED)
+            == b'"abc"'
+        )
+
+    def test_memoryview_nested_unsupported_format(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED raises on a format array.array does not have
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(memoryview(b"ab").cast("c"), option=NESTED)
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(memoryview(b"ab").cast("?"), option=NESTED)
+
+    def test_memoryview_nested_base64(self):
+        """
+        a 2-D memoryview is flattened to its bytes with a bytes mode
+        """
+        view = _view("B", b"abcd", (2, 2))
+        assert (
+            orjson.dumps(view, option=orjson.OPT_SERIALIZE_BYTES_BASE64)
+            == b'"YWJjZA=="'
+        )
+
+    def test_memoryview_nested_base64_precedence(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED takes precedence over the bytes mode for
+        memoryview
+        """
+        view = _view("B", b"abcd", (2, 2))
+        assert (
+            orjson.dumps(
+                [view, b"abcd"],
+                option=NESTED | orjson.OPT_SERIALIZE_BYTES_BASE64,
+            )
+            == b'[[[97,98],[99,100]],"YWJjZA=="]'
+        )
+
+    def test_memoryview_nested_disabled(self):
+        """
+        memoryview is not serialized without an option
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(_view("i", range(6), (2, 3)))
+
+    def test_memoryview_nested_array(self):
+        """
+        OPT_MEMORYVIEW_AS_NESTED does not change array.array
+        """
+        assert orjson.dumps(array.array("i", [1, 2]), option=NESTED) == b"[1,2]"
+
+
+class TestBytesMode:
+    def test_default_passthrough(self):
+        """
+        bytes are not serialized by default
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(b"abc")
+
+    def test_option_base64(self):
+        """
+        OPT_SERIALIZE_BYTES_BASE64
+        """
+        for val, expected in (
+            (b"", b'""'),
+            (b"a", b'"YQ=="'),
+            (b"ab", b'"YWI="'),
+            (b"abc", b'"YWJj"'),
+            (b"\xff\xfe\x00\x01", b'"//4AAQ=="'),
+        ):
+            assert orjson.dumps(val, option=orjson.OPT_SERIALIZE_BYTES_BASE64) == expected
+
+    def test_option_hex(self):
+        """
+        OPT_SERIALIZE_BYTES_HEX
+        """
+        assert orjson.dumps(b"", option=orjson.OPT_SERIALIZE_BYTES_HEX) == b'""'
+        assert (
+            orjson.dumps(b"\x00\x0f\xab\xff", option=orjson.OPT_SERIALIZE_BYTES_HEX)
+            == b'"000fabff"'
+        )
+
+    def test_option_bytearray_memoryview(self):
+        """
+        bytearray and memoryview are serialized like bytes
+        """
+        opt = orjson.OPT_SERIALIZE_BYTES_HEX
+        assert orjson.dumps(bytearray(b"\x01\x02"), option=opt) == b'"0102"'
+        assert orjson.dumps(memoryview(b"\x01\x02"), option=opt) == b'"0102"'
+
+    def test_option_memoryview_not_contiguous(self):
+        """
+        a non-contiguous memoryview raises JSONEncodeError
+        """
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps(
+                memoryview(b"abcd")[::2],
+                option=orjson.OPT_SERIALIZE_BYTES_HEX,
+            )
+
+    def test_option_latin1(self):
+        """
+        OPT_SERIALIZE_BYTES_LATIN1
+        """
+        for val, expected in (
+            (b"", b'""'),
+            (b"abc", b'"abc"'),
+            (b"caf\xe9", b'"caf\xc3\xa9"'),
+            (b'"\\/', b'"\\"\\\\/"'),
+        ):
+            assert orjson.dumps(val, option=orjson.OPT_SERIALIZE_BYTES_LATIN1) == expected
+
+    def test_option_latin1_control(self):
+        """
+        OPT_SERIALIZE_BYTES_LATIN1 escapes control characters, including null
+        """
+        val = b"a\x00b\x01\n\t\x1f\x7f"
+        res = orjson.dumps(val, option=orjson.OPT_SERIALIZE_BYTES_LATIN1)
+        assert res == b'"a\\u0000b\\u0001\\n\\t\\u001f\x7f"'
+        assert orjson.loads(res).encode("latin-1") == val
+
+    def test_option_latin1_roundtrip(self):
+        """
+        OPT_SERIALIZE_BYTES_LATIN1 round-trips every byte value, including
+        0x80-0xff, for bytes, bytearray, and memoryview
+        """
+        val = bytes(range(256))
+        for obj in (val, bytearray(val), memoryview(val)):
+            res = orjson.dumps([obj], option=orjson.OPT_SERIALIZE_BYTES_LATIN1)
+            assert orjson.loads(res)[0].encode("latin-1") == val
+        res = orjson.dumps(b"\x80\xff", option=orjson.OPT_SERIALIZE_BYTES_LATIN1)
+        assert res == b'"\xc2\x80\xc3\xbf"'
+
+    def test_option_latin1_overrides_configure(self):
+        """
+        OPT_SERIALIZE_BYTES_LATIN1 takes precedence over configure()
+        """
+        try:
+            orjson.configure(bytes_mode="hex")
+            assert (
+                orjson.dumps(b"\xe9", option=orjson.OPT_SERIALIZE_BYTES_LATIN1)
+                == b'"\xc3\xa9"'
+            )
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+    def test_option_nested(self):
+        """
+        bytes in a list and as a dict value
+        """
+        assert (
+            orjson.dumps(
+                {"a": [b"\x01", b"\x02"]},
+                option=orjson.OPT_SERIALIZE_BYTES_HEX,
+            )
+            == b'{"a":["01","02"]}'
+        )
+
+    def test_option_dict_key(self):
+        """
+        a bytes dict key with OPT_NON_STR_KEYS is encoded in the mode
+        """
+        assert (
+            orjson.dumps(
+                {b"a": 1},
+                option=orjson.OPT_NON_STR_KEYS | orjson.OPT_SERIALIZE_BYTES_HEX,
+            )
+            == b'{"61":1}'
+        )
+
+    def test_option_subclass(self):
+        """
+        bytes subclasses are passed to default
+        """
+
+        class BytesSubclass(bytes):
+            pass
+
+        assert (
+            orjson.dumps(
+                BytesSubclass(b"a"),
+                option=orjson.OPT_SERIALIZE_BYTES_HEX,
+                default=lambda obj: "default",
+            )
+            == b'"default"'
+        )
+
+    def test_option_mutually_exclusive(self):
+        """
+        only one bytes mode option may be given
+        """
+        for opt in (
+            orjson.OPT_SERIALIZE_BYTES_BASE64 | orjson.OPT_SERIALIZE_BYTES_HEX,
+            orjson.OPT_SERIALIZE_BYTES_BASE64 | orjson.OPT_PASSTHROUGH_BYTES,
+            orjson.OPT_SERIALIZE_BYTES_HEX | orjson.OPT_PASSTHROUGH_BYTES,
+            orjson.OPT_SERIALIZE_BYTES_LATIN1 | orjson.OPT_SERIALIZE_BYTES_BASE64,
+            orjson.OPT_SERIALIZE_BYTES_LATIN1 | orjson.OPT_SERIALIZE_BYTES_HEX,
+            orjson.OPT_SERIALIZE_BYTES_LATIN1 | orjson.OPT_PASSTHROUGH_BYTES,
+        ):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(b"a", option=opt)
+
+    def test_configure_default(self):
+        """
+        configure(bytes_mode=...) applies when no option is given
+        """
+        try:
+            assert orjson.configure(bytes_mode="base64") is None
+            assert orjson.dumps([b"abc"]) == b'["YWJj"]'
+            orjson.configure(bytes_mode="hex")
+            assert orjson.dumps([b"abc"]) == b'["616263"]'
+            orjson.configure(bytes_mode="latin1")
+            assert orjson.dumps(b"a\xe5\x00") == b'"a\xc3\xa5\\u0000"'
+            orjson.configure(bytes_mode="passthrough")
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(b"abc")
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+    def test_configure_passthrough_default(self):
+        """
+        configure(bytes_mode="passthrough") passes bytes to default
+        """
+        orjson.configure(bytes_mode="passthrough")
+        assert orjson.dumps(b"abc", default=bytes.decode) == b'"abc"'
+
+    def test_configure_option_overrides(self):
+        """
+        a per-call option takes precedence over the configured default
+        """
+        try:
+            orjson.configure(bytes_mode="base64")
+            assert orjson.dumps(b"abc", option=orjson.OPT_SERIALIZE_BYTES_HEX) == b'"616263"'
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(b"abc", option=orjson.OPT_PASSTHROUGH_BYTES)
+            assert (
+                orjson.dumps(
+                    b"abc",
+                    option=orjson.OPT_PASSTHROUGH_BYTES,
+                    default=bytes.decode,
+                )
+                == b'"abc"'
+            )
+            assert orjson.dumps(b"abc") == b'"YWJj"'
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+    def test_configure_none(self):
+        """
+        configure() and configure(bytes_mode=None) leave the setting unchanged
+        """
+        try:
+            orjson.configure(bytes_mode="hex")
+            assert orjson.configure() is None
+            assert orjson.configure(bytes_mode=None) is None
+            assert orjson.dumps(b"a") == b'"61"'
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+    def test_configure_invalid(self):
+        """
+        configure() raises on invalid arguments
+        """
+        with pytest.raises(TypeError):
+            orjson.configure("hex")  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.configure(mode="hex")  # type: ignore
+        with pytest.raises(TypeError):
+            orjson.configure(bytes_mode=b"hex")  # type: ignore
+        with pytest.raises(ValueError):
+            orjson.configure(bytes_mode="utf-8")
+        assert orjson.dumps(b"a", default=bytes.decode) == b'"a"'
+
+    def test_configure_reset_state(self):
+        """
+        reset_state() restores the default bytes mode
+        """
+        try:
+            orjson.configure(bytes_mode="hex")
+            orjson.reset_state()
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(b"a")
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+    def test_configure_subinterpreter_isolated(self):
+        """
+        the configured bytes mode is per-interpreter
+        """
+        try:
+            orjson.configure(bytes_mode="hex")
+            run_in_subinterpreter(
+                """
+                import orjson
+                try:
+                    orjson.dumps(b"abc")
+                except orjson.JSONEncodeError:
+                    pass
+                else:
+                    raise AssertionError("bytes serialized by default")
+                orjson.configure(bytes_mode="base64")
+                assert orjson.dumps(b"abc") == b'"YWJj"'
+                assert orjson.dumps(b"abc", option=orjson.OPT_SERIALIZE_BYTES_HEX) == b'"616263"'
+                """,
+            )
+            assert orjson.dumps(b"abc") == b'"616263"'
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+
+class TestCacheTuples:
+    def test_cache_tuples(self):
+        obj = ("a", 1, 2.5, True, None, ("b", (3,)))
+        ref = orjson.dumps(obj)
+        assert ref == b'["a",1,2.5,true,null,["b",[3]]]'
+        assert orjson.dumps(obj, option=CACHE_TUPLES) == ref
+        assert orjson.dumps(obj, option=CACHE_TUPLES) == ref
+        assert orjson.dumps({"a": [obj, obj]}, option=CACHE_TUPLES) == (
+            b'{"a":[' + ref + b"," + ref + b"]}"
+        )
+
+    def test_cache_tuples_empty(self):
+        assert orjson.dumps((), option=CACHE_TUPLES) == b"[]"
+        assert orjson.dumps([(), ((),)], option=CACHE_TUPLES) == b"[[],[[]]]"
+
+    def test_cache_tuples_mutable(self):
+        """
+        a tuple containing a mutable object is serialized again each time
+        """
+        inner = [1]
+        obj = ("a", inner)
+        assert orjson.dumps(obj, option=CACHE_TUPLES) == b'["a",[1]]'
+        inner.append(2)
+        assert orjson.dumps(obj, option=CACHE_TUPLES) == b'["a",[1,2]]'
+        data = {"b": 1}
+        obj2 = (data,)
+        assert orjson.dumps(obj2, option=CACHE_TUPLES) == b'[{"b":1}]'
+        data["b"] = 2
+        assert orjson.dumps(obj2, option=CACHE_TUPLES) == b'[{"b":2}]'
+
+    def test_cache_tuples_options(self):
+        """
+        the output cached with other options is not used
+        """
+        obj = ("a/é", 1.0)
+        assert orjson.dumps(obj, option=CACHE_TUPLES) == '["a/é",1.0]'.encode()
+        assert (
+            orjson.dumps(obj, option=CACHE_TUPLES | orjson.OPT_ENSURE_ASCII)
+            == b'["a/\\u00e9",1.0]'
+        )
+        assert (
+            orjson.dumps(obj, option=CACHE_TUPLES | orjson.OPT_ESCAPE_FORWARD_SLASH)
+            == '["a\\/é",1.0]'.encode()
+        )
+        assert orjson.dumps(obj, option=CACHE_TUPLES) == '["a/é",1.0]'.encode()
+
+    def test_cache_tuples_indent(self):
+        obj = (1, (2,))
+        assert (
+            orjson.dumps(obj, option=CACHE_TUPLES | orjson.OPT_INDENT_2)
+            == b"[\n  1,\n  [\n    2\n  ]\n]"
+        )
+        assert (
+            orjson.dumps([obj], option=CACHE_TUPLES | orjson.OPT_INDENT_2)
+            == b"[\n  [\n    1,\n    [\n      2\n    ]\n  ]\n]"
+        )
+
+    def test_cache_tuples_subclass(self):
+        obj = (TupleSubclass((1,)),)
+        assert orjson.dumps(obj, option=CACHE_TUPLES, default=list) == b"[[1]]"
+        assert orjson.dumps(obj, option=CACHE_TUPLES, default=lambda _: 2) == b"[2]"
+
+    def test_cache_tuples_default(self):
+        """
+        a tuple containing a type passed to default is not cached
+        """
+        calls = []
+
+        def default(obj):
+            calls.append(obj)
+            return len(calls)
+
+        obj = (object(),)
+        assert orjson.dumps(obj, option=CACHE_TUPLES, default=default) == b"[1]"
+        assert orjson.dumps(obj, option=CACHE_TUPLES, default=default) == b"[2]"
+
+    def test_cache_tuples_error(self):
+        obj = ("\ud800",)
+        for _ in range(2):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(obj, option=CACHE_TUPLES)
+        obj2 = (1 << 64,)
+        for _ in range(2):
+            with pytest.raises(orjson.JSONEncodeError):
+                orjson.dumps(obj2, option=CACHE_TUPLES)
+
+    def test_cache_tuples_many(self):
+        """
+        more tuples than are cached are each serialized correctly
+        """
+        objs = [(str(i), i) for i in range(1000)]
+        for _ in range(2):
+            for i, obj in enumerate(objs):
+                assert orjson.dumps(obj, option=CACHE_TUPLES) == b'["%d",%d]' % (i, i)
+
+    def test_cache_tuples_same_address(self):
+        """
+        a tuple created after another is freed is not given its output
+        """
+        for i in range(100):
+            assert (
+                orjson.dumps((str(i), i), option=CACHE_TUPLES) == b'["%d",%d]' % (i, i)
+            )
+
+    @needs_cache
+    def test_cache_tuples_reference(self):
+        """
+        a cached tuple is kept alive until the state is reset
+        """
+        if not hasattr(sys, "getrefcount"):
+            pytest.skip("sys.getrefcount() is not supported")
+        obj = ("a", 1)
+        refcount = sys.getrefcount(obj)
+        orjson.dumps(obj, option=CACHE_TUPLES)
+        assert sys.getrefcount(obj) == refcount + 1
+        orjson.dumps(obj, option=CACHE_TUPLES)
+        assert sys.getrefcount(obj) == refcount + 1
+        orjson.reset_state()
+        assert sys.getrefcount(obj) == refcount
+
+    def test_cache_tuples_disabled(self):
+        obj = ("a", 1)
+        if SUPPORTS_GETREFCOUNT:
+            refcount = sys.getrefcount(obj)
+        orjson.dumps(obj)
+        if SUPPORTS_GETREFCOUNT:
+            assert sys.getrefcount(obj) == refcount
+
+    def test_cache_tuples_reset_state(self):
+        obj = ("a", 1)
+        orjson.dumps(obj, option=CACHE_TUPLES)
+        orjson.reset_state()
+        assert orjson.dumps(obj, option=CACHE_TUPLES) == b'["a",1]'
+
+    def test_cache_tuples_dict_key(self):
+        with pytest.raises(orjson.JSONEncodeError):
+            orjson.dumps({(1,): 1}, option=CACHE_TUPLES | orjson.OPT_NON_STR_KEYS)
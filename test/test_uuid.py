@@ -1,12 +1,26 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 # Copyright ijl (2020-2025), Rami Chowdhury (2020)
 
+import base64
 import uuid
 
 import pytest
 
 import orjson
 
+AS_BYTES = orjson.OPT_UUID_AS_BYTES
+
+VAL = uuid.UUID("7202d115-7ff3-4c81-a7c1-2a1f067b1ece")
+
+VALUES = (
+    VAL,
+    uuid.UUID(int=0),
+    uuid.UUID(int=2**128 - 1),
+    uuid.UUID(int=1),
+    uuid.UUID(int=1 << 120),
+    uuid.uuid5(uuid.NAMESPACE_DNS, "python.org"),
+)
+
 
 class TestUUID:
     def test_uuid_immutable(self):
@@ -100,3 +114,91 @@ class TestUUID:
         )
         for val in uuids:
             assert orjson.dumps(val) == f'"{val}"'.encode("utf-8")
+
+
+class TestUUIDAsBytes:
+    def test_uuid_as_bytes_array(self):
+        """
+        without a bytes option, a UUID is an array of its 16 bytes
+        """
+        for val in VALUES:
+            assert orjson.loads(orjson.dumps(val, option=AS_BYTES)) == list(val.bytes)
+        assert (
+            orjson.dumps(uuid.UUID(int=1), option=AS_BYTES)
+            == b"[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1]"
+        )
+
+    def test_uuid_as_bytes_base64(self):
+        for val in VALUES:
+            output = orjson.loads(
+                orjson.dumps(val, option=AS_BYTES | orjson.OPT_SERIALIZE_BYTES_BASE64)
+            )
+            assert base64.b64decode(output) == val.bytes
+        assert (
+            orjson.dumps(VAL, option=AS_BYTES | orjson.OPT_SERIALIZE_BYTES_BASE64)
+            == b'"cgLRFX/zTIGnwSofBnsezg=="'
+        )
+
+    def test_uuid_as_bytes_hex(self):
+        for val in VALUES:
+            output = orjson.loads(
+                orjson.dumps(val, option=AS_BYTES | orjson.OPT_SERIALIZE_BYTES_HEX)
+            )
+            assert bytes.fromhex(output) == val.bytes
+            assert output == val.hex
+
+    def test_uuid_as_bytes_latin1(self):
+        for val in VALUES:
+            output = orjson.loads(
+                orjson.dumps(val, option=AS_BYTES | orjson.OPT_SERIALIZE_BYTES_LATIN1)
+            )
+            assert output.encode("latin1") == val.bytes
+
+    def test_uuid_as_bytes_passthrough(self):
+        """
+        OPT_PASSTHROUGH_BYTES serializes the array of bytes
+        """
+        assert orjson.loads(
+            orjson.dumps(VAL, option=AS_BYTES | orjson.OPT_PASSTHROUGH_BYTES)
+        ) == list(VAL.bytes)
+
+    def test_uuid_as_bytes_configure(self):
+        """
+        the bytes_mode of configure() applies
+        """
+        orjson.configure(bytes_mode="hex")
+        try:
+            assert orjson.dumps(VAL, option=AS_BYTES) == f'"{VAL.hex}"'.encode()
+        finally:
+            orjson.configure(bytes_mode="passthrough")
+
+    def test_uuid_as_bytes_nested(self):
+        option = AS_BYTES | orjson.OPT_SERIALIZE_BYTES_HEX
+        expected = f'"{VAL.hex}"'
+        assert (
+            orjson.dumps({"a": VAL, "b": [VAL], "c": (VAL,)}, option=option)
+            == f'{{"a":{expected},"b":[{expected}],"c":[{expected}]}}'.encode()
+        )
+        assert (
+            orjson.dumps({"a": VAL}, option=option | orjson.OPT_SORT_KEYS)
+            == f'{{"a":{expected}}}'.encode()
+        )
+
+    def test_uuid_as_bytes_dict_key(self):
+        """
+        a dict key is serialized as a str
+        """
+        assert (
+            orjson.dumps(
+                {VAL: VAL},
+                option=AS_BYTES | orjson.OPT_NON_STR_KEYS | orjson.OPT_SERIALIZE_BYTES_HEX,
+            )
+            == f'{{"{VAL}":"{VAL.hex}"}}'.encode()
+        )
+
+    def test_uuid_as_bytes_disabled(self):
+        assert orjson.dumps(VAL) == f'"{VAL}"'.encode()
+        assert (
+            orjson.dumps(VAL, option=orjson.OPT_SERIALIZE_BYTES_BASE64)
+            == f'"{VAL}"'.encode()
+        )
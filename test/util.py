@@ -67,3 +67,60 @@ needs_data = pytest.mark.skipif(
     not Path(data_dir).exists(),
     reason="Test depends on ./data dir that contains fixtures",
 )
+
+
+def run_in_subinterpreter(code: str) -> None:
+    """
+    Run code in a new subinterpreter on the calling thread, raising AssertionError
+    if it raises. Skips the test if subinterpreters are unavailable or the
+    module can't be imported in one or was built with the pymalloc feature.
+    """
+    import textwrap
+
+    if os.getenv("ORJSON_FEATURE_PYMALLOC", ""):
+        pytest.skip("the pymalloc feature supports only a single interpreter")
+
+    try:
+        from concurrent import interpreters  # type: ignore
+
+        def run_string(source):
+            interp = interpreters.create()
+            try:
+                interp.exec(source)
+            except interpreters.ExecutionFailed as exc:
+                return str(exc)
+            finally:
+                interp.close()
+            return None
+
+    except ImportError:
+        try:
+            import _interpreters as subinterpreters  # type: ignore
+        except ImportError:
+            try:
+                import _xxsubinterpreters as subinterpreters  # type: ignore
+            except ImportError:
+                pytest.skip("subinterpreters are not available")
+
+        def run_string(source):
+            interp = subinterpreters.create()
+            try:
+                if hasattr(subinterpreters, "run_string"):
+                    subinterpreters.run_string(interp, source)
+                    return None
+                excinfo = subinterpreters.exec(interp, source)
+                return None if excinfo is None else str(excinfo)
+            except Exception as exc:
+                return str(exc)
+            finally:
+                subinterpreters.destroy(interp)
+
+    prelude = f"import sys\nsys.path[:] = {sys.path!r}\n"
+
+    if run_string(prelude + "import orjson\n") is not None:
+        pytest.skip("module can't be imported in a subinterpreter")
+    # on the calling thread, so that the state of the main interpreter is
+    # cached for it already
+    err = run_string(prelude + textwrap.dedent(code))
+    if err is not None:
+        raise AssertionError(err)